@@ -0,0 +1,288 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::network::prelude::*,
+    prelude::{Address, PrivateKey},
+};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    aead::{Aead, Payload},
+    ChaCha20Poly1305,
+    Key,
+    KeyInit,
+    Nonce,
+};
+use core::fmt;
+use serde::{Deserialize, Serialize};
+use std::{fs, io::Write as _, ops::RangeInclusive, path::Path};
+use zeroize::Zeroizing;
+
+/// The version of the keystore format.
+const KEYSTORE_VERSION: u8 = 1;
+/// The key derivation function of the keystore.
+const KDF_ALGORITHM: &str = "argon2id";
+/// The cipher of the keystore.
+const CIPHER_ALGORITHM: &str = "chacha20poly1305";
+/// The Argon2 memory cost, in KiB.
+const ARGON2_MEMORY_COST: u32 = 64 * 1024;
+/// The Argon2 number of iterations.
+const ARGON2_TIME_COST: u32 = 3;
+/// The Argon2 degree of parallelism.
+const ARGON2_PARALLELISM: u32 = 1;
+/// The range of Argon2 memory costs accepted from a keystore, in KiB.
+const ARGON2_MEMORY_COST_RANGE: RangeInclusive<u32> = (8 * 1024)..=(1024 * 1024);
+/// The range of Argon2 numbers of iterations accepted from a keystore.
+const ARGON2_TIME_COST_RANGE: RangeInclusive<u32> = 1..=16;
+/// The range of Argon2 degrees of parallelism accepted from a keystore.
+const ARGON2_PARALLELISM_RANGE: RangeInclusive<u32> = 1..=16;
+/// The number of bytes in the salt.
+const SALT_LENGTH: usize = 16;
+/// The number of bytes in the nonce.
+const NONCE_LENGTH: usize = 12;
+/// The number of bytes in the encryption key.
+const KEY_LENGTH: usize = 32;
+
+/// A password-encrypted private key, stored as a JSON file.
+///
+/// The encryption key is derived from the password with Argon2id, and the private key
+/// is encrypted with ChaCha20-Poly1305, using the address as associated data.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    /// The version of the keystore format.
+    version: u8,
+    /// The address of the encrypted private key.
+    address: String,
+    /// The key derivation parameters.
+    kdf: KdfParams,
+    /// The cipher parameters.
+    cipher: CipherParams,
+}
+
+/// The key derivation parameters of a keystore.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct KdfParams {
+    /// The key derivation function.
+    algorithm: String,
+    /// The hex-encoded salt.
+    salt: String,
+    /// The memory cost, in KiB.
+    memory_cost: u32,
+    /// The number of iterations.
+    time_cost: u32,
+    /// The degree of parallelism.
+    parallelism: u32,
+}
+
+/// The cipher parameters of a keystore.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct CipherParams {
+    /// The cipher.
+    algorithm: String,
+    /// The hex-encoded nonce.
+    nonce: String,
+    /// The hex-encoded ciphertext of the private key.
+    ciphertext: String,
+}
+
+impl Keystore {
+    /// Encrypts the given private key with the given password.
+    pub fn encrypt<N: Network, R: Rng + CryptoRng>(
+        private_key: &PrivateKey<N>,
+        password: &str,
+        rng: &mut R,
+    ) -> Result<Self> {
+        // Sample the salt and nonce.
+        let salt: [u8; SALT_LENGTH] = rng.gen();
+        let nonce: [u8; NONCE_LENGTH] = rng.gen();
+        // Prepare the key derivation parameters.
+        let kdf = KdfParams {
+            algorithm: KDF_ALGORITHM.to_string(),
+            salt: hex::encode(salt),
+            memory_cost: ARGON2_MEMORY_COST,
+            time_cost: ARGON2_TIME_COST,
+            parallelism: ARGON2_PARALLELISM,
+        };
+        // Derive the encryption key.
+        let key = kdf.derive_key(password)?;
+        // Encrypt the private key, bound to its address.
+        let address = Address::try_from(private_key)?.to_string();
+        let plaintext = Zeroizing::new(private_key.to_string());
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext.as_bytes(), aad: address.as_bytes() })
+            .map_err(|_| anyhow!("Failed to encrypt the private key"))?;
+        // Construct the keystore.
+        let cipher = CipherParams {
+            algorithm: CIPHER_ALGORITHM.to_string(),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        };
+        Ok(Self { version: KEYSTORE_VERSION, address, kdf, cipher })
+    }
+
+    /// Decrypts the private key with the given password.
+    pub fn decrypt<N: Network>(&self, password: &str) -> Result<PrivateKey<N>> {
+        // Ensure the keystore is supported.
+        ensure!(self.version == KEYSTORE_VERSION, "Unsupported keystore version '{}'", self.version);
+        ensure!(self.cipher.algorithm == CIPHER_ALGORITHM, "Unsupported keystore cipher '{}'", self.cipher.algorithm);
+        // Derive the encryption key.
+        let key = self.kdf.derive_key(password)?;
+        // Decrypt the private key.
+        let nonce = hex::decode(&self.cipher.nonce)?;
+        ensure!(nonce.len() == NONCE_LENGTH, "Invalid keystore nonce");
+        let ciphertext = hex::decode(&self.cipher.ciphertext)?;
+        let plaintext = Zeroizing::new(
+            ChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+                .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: self.address.as_bytes() })
+                .map_err(|_| anyhow!("Failed to decrypt the keystore (incorrect password)"))?,
+        );
+        // Parse the private key.
+        let private_key = PrivateKey::<N>::from_str(std::str::from_utf8(&plaintext)?)?;
+        // Ensure the private key matches the address.
+        ensure!(
+            Address::try_from(&private_key)?.to_string() == self.address,
+            "The keystore private key does not match its address"
+        );
+        Ok(private_key)
+    }
+
+    /// Returns the address of the encrypted private key.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Loads the keystore from the given path.
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_str(&fs::read_to_string(path)?)
+    }
+
+    /// Saves the keystore to the given path, which must not exist.
+    /// On Unix, the file is readable only by the owner (0600).
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(target_family = "unix")]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(path).map_err(|e| anyhow!("Failed to create the keystore {path:?} - {e}"))?;
+        file.write_all(self.to_string().as_bytes())?;
+        Ok(())
+    }
+}
+
+impl KdfParams {
+    /// Derives the encryption key from the given password.
+    fn derive_key(&self, password: &str) -> Result<Zeroizing<[u8; KEY_LENGTH]>> {
+        ensure!(self.algorithm == KDF_ALGORITHM, "Unsupported keystore key derivation function '{}'", self.algorithm);
+        // Ensure the parameters are within bounds, as a keystore may request an arbitrary amount of work.
+        ensure!(
+            ARGON2_MEMORY_COST_RANGE.contains(&self.memory_cost),
+            "Invalid keystore memory cost '{}' (expected {ARGON2_MEMORY_COST_RANGE:?} KiB)",
+            self.memory_cost
+        );
+        ensure!(
+            ARGON2_TIME_COST_RANGE.contains(&self.time_cost),
+            "Invalid keystore time cost '{}' (expected {ARGON2_TIME_COST_RANGE:?})",
+            self.time_cost
+        );
+        ensure!(
+            ARGON2_PARALLELISM_RANGE.contains(&self.parallelism),
+            "Invalid keystore parallelism '{}' (expected {ARGON2_PARALLELISM_RANGE:?})",
+            self.parallelism
+        );
+        let salt = hex::decode(&self.salt)?;
+        let params = Params::new(self.memory_cost, self.time_cost, self.parallelism, Some(KEY_LENGTH))
+            .map_err(|e| anyhow!("Invalid keystore parameters - {e}"))?;
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password.as_bytes(), &salt, key.as_mut_slice())
+            .map_err(|e| anyhow!("Failed to derive the keystore key - {e}"))?;
+        Ok(key)
+    }
+}
+
+impl FromStr for Keystore {
+    type Err = Error;
+
+    /// Initializes the keystore from a JSON string.
+    fn from_str(keystore: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_str(keystore)?)
+    }
+}
+
+impl Display for Keystore {
+    /// Renders the keystore as a JSON string.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", serde_json::to_string_pretty(self).map_err(|_| fmt::Error)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        // Initialize the RNG.
+        let mut rng = TestRng::default();
+        // Encrypt the private key.
+        let private_key = PrivateKey::<CurrentNetwork>::new(&mut rng).unwrap();
+        let keystore = Keystore::encrypt(&private_key, "password", &mut rng).unwrap();
+        assert_eq!(keystore.address(), Address::try_from(&private_key).unwrap().to_string());
+        assert!(!keystore.to_string().contains(&private_key.to_string()));
+
+        // Decrypt the private key.
+        let keystore = Keystore::from_str(&keystore.to_string()).unwrap();
+        assert_eq!(keystore.decrypt::<CurrentNetwork>("password").unwrap(), private_key);
+        assert!(keystore.decrypt::<CurrentNetwork>("wrong password").is_err());
+
+        // Ensure a tampered keystore cannot be decrypted.
+        let mut tampered = keystore.clone();
+        tampered.address = Address::try_from(PrivateKey::<CurrentNetwork>::new(&mut rng).unwrap()).unwrap().to_string();
+        assert!(tampered.decrypt::<CurrentNetwork>("password").is_err());
+    }
+
+    #[test]
+    fn test_kdf_params_bounds() {
+        // Initialize the RNG.
+        let mut rng = TestRng::default();
+        // Encrypt the private key.
+        let private_key = PrivateKey::<CurrentNetwork>::new(&mut rng).unwrap();
+        let keystore = Keystore::encrypt(&private_key, "password", &mut rng).unwrap();
+
+        // Ensure a keystore with out-of-bounds parameters is rejected before deriving the key.
+        for (memory_cost, time_cost, parallelism) in [
+            (*ARGON2_MEMORY_COST_RANGE.start() - 1, ARGON2_TIME_COST, ARGON2_PARALLELISM),
+            (*ARGON2_MEMORY_COST_RANGE.end() + 1, ARGON2_TIME_COST, ARGON2_PARALLELISM),
+            (u32::MAX, ARGON2_TIME_COST, ARGON2_PARALLELISM),
+            (ARGON2_MEMORY_COST, 0, ARGON2_PARALLELISM),
+            (ARGON2_MEMORY_COST, u32::MAX, ARGON2_PARALLELISM),
+            (ARGON2_MEMORY_COST, ARGON2_TIME_COST, 0),
+            (ARGON2_MEMORY_COST, ARGON2_TIME_COST, u32::MAX),
+        ] {
+            let mut tampered = keystore.clone();
+            tampered.kdf.memory_cost = memory_cost;
+            tampered.kdf.time_cost = time_cost;
+            tampered.kdf.parallelism = parallelism;
+            let error = tampered.decrypt::<CurrentNetwork>("password").unwrap_err();
+            assert!(error.to_string().starts_with("Invalid keystore"), "{error}");
+        }
+    }
+}
@@ -14,6 +14,9 @@
 
 #![forbid(unsafe_code)]
 
+mod keystore;
+pub use keystore::*;
+
 use snarkvm::{
     console::{network::prelude::*, types::Field},
     prelude::*,
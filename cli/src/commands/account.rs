@@ -12,22 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::{Developer, Scan};
+use crate::helpers::read_password;
+use snarkos_account::Keystore;
 use snarkvm::console::{
-    account::PrivateKey,
+    account::{Address, PrivateKey, Signature, ViewKey},
     prelude::{Environment, Uniform},
+    program::{Entry, Identifier, Literal, Plaintext},
     types::Field,
 };
 
 use anyhow::{anyhow, bail, Result};
-use clap::Parser;
+use clap::{Args, Parser};
 use colored::Colorize;
 use core::str::FromStr;
 use crossterm::ExecutableCommand;
 use rand::SeedableRng;
 use rand_chacha::ChaChaRng;
 use rayon::prelude::*;
-use std::io::{Read, Write};
-use zeroize::Zeroize;
+use std::{
+    io::{Read, Write},
+    path::PathBuf,
+};
+use zeroize::{Zeroize, Zeroizing};
 
 type Network = snarkvm::prelude::MainnetV0;
 
@@ -46,6 +53,91 @@ pub enum Account {
         #[clap(long)]
         discreet: bool,
     },
+    /// Imports a private key into a password-encrypted keystore file
+    Import {
+        /// Specify the private key to import (if omitted, it is read from a prompt)
+        #[clap(long)]
+        private_key: Option<String>,
+        /// Specify the path of the keystore file to create
+        #[clap(long)]
+        #[zeroize(skip)]
+        keystore: PathBuf,
+        /// Specify the path to a file containing the keystore password (if omitted, it is read from a prompt)
+        #[clap(long)]
+        #[zeroize(skip)]
+        password_file: Option<PathBuf>,
+    },
+    /// Signs a message with the private key of an account
+    Sign {
+        #[clap(flatten)]
+        key: AccountKey,
+        /// Specify the message to sign
+        #[clap(long)]
+        message: String,
+    },
+    /// Verifies the signature of a message for an address
+    Verify {
+        /// Specify the address of the signer
+        #[clap(long)]
+        address: String,
+        /// Specify the signature to verify
+        #[clap(long)]
+        signature: String,
+        /// Specify the message that was signed
+        #[clap(long)]
+        message: String,
+    },
+    /// Prints the balance of an account, in microcredits
+    Balance {
+        /// Specify the address of the account (for the public balance only)
+        #[clap(long)]
+        address: Option<String>,
+        #[clap(flatten)]
+        key: AccountKey,
+        /// Scan the blocks from the given height for unspent records, to compute the private balance (requires the private key)
+        #[clap(long)]
+        scan_from: Option<u32>,
+        /// Specify the endpoint of the node to query
+        #[clap(long, default_value = "http://localhost:3030")]
+        endpoint: String,
+    },
+}
+
+/// The private key of an account, given directly or unlocked from a password-encrypted keystore file.
+#[derive(Debug, Args, Zeroize)]
+pub struct AccountKey {
+    /// Specify the private key of the account
+    #[clap(long)]
+    private_key: Option<String>,
+    /// Specify the path to the keystore file of the account
+    #[clap(long)]
+    #[zeroize(skip)]
+    keystore: Option<PathBuf>,
+    /// Specify the path to a file containing the keystore password
+    /// (if omitted, it is read from the `SNARKOS_KEYSTORE_PASSWORD` environment variable, or a prompt)
+    #[clap(long)]
+    #[zeroize(skip)]
+    password_file: Option<PathBuf>,
+}
+
+impl AccountKey {
+    /// Returns the private key of the account, if one is given.
+    fn private_key(&self) -> Result<Option<PrivateKey<Network>>> {
+        match (&self.private_key, &self.keystore) {
+            (Some(private_key), None) => Ok(Some(PrivateKey::from_str(private_key)?)),
+            (None, Some(path)) => {
+                // Load the keystore.
+                let keystore = Keystore::load(path)?;
+                // Unlock the keystore with the password.
+                let password = read_password(self.password_file.as_deref(), false)?;
+                Ok(Some(keystore.decrypt(&password)?))
+            }
+            (None, None) => Ok(None),
+            (Some(_), Some(_)) => {
+                bail!("Cannot use '--private-key' and '--keystore' simultaneously, please use only one")
+            }
+        }
+    }
 }
 
 impl Account {
@@ -66,6 +158,10 @@ impl Account {
                     Self::new_seeded(seed, discreet)
                 }
             }
+            Self::Import { private_key, keystore, password_file } => Self::import(private_key, keystore, password_file),
+            Self::Sign { key, message } => Self::sign(key, message),
+            Self::Verify { address, signature, message } => Self::verify(address, signature, message),
+            Self::Balance { address, key, scan_from, endpoint } => Self::balance(address, key, scan_from, endpoint),
         }
     }
 
@@ -176,6 +272,85 @@ impl Account {
         );
         Ok(account_info)
     }
+
+    /// Imports the given private key into a password-encrypted keystore file.
+    fn import(private_key: Option<String>, keystore: PathBuf, password_file: Option<PathBuf>) -> Result<String> {
+        // Read the private key, from a prompt if it is not given.
+        let private_key = match private_key {
+            Some(private_key) => Zeroizing::new(private_key),
+            None => Zeroizing::new(rpassword::prompt_password("Enter the private key: ")?),
+        };
+        let private_key = PrivateKey::<Network>::from_str(private_key.trim())?;
+        // Read the password.
+        let password = read_password(password_file.as_deref(), true)?;
+        // Encrypt the private key, and save the keystore.
+        let keystore_file = Keystore::encrypt(&private_key, &password, &mut rand::thread_rng())?;
+        keystore_file.save(&keystore)?;
+
+        // Prepare the path string.
+        let path_string = format!("(in \"{}\")", keystore.display()).dimmed();
+        Ok(format!("✅ Imported the account {} into a keystore {path_string}", keystore_file.address()))
+    }
+
+    /// Signs the given message with the private key of the account.
+    fn sign(key: AccountKey, message: String) -> Result<String> {
+        let Some(private_key) = key.private_key()? else {
+            bail!("Missing the '--private-key' or '--keystore' argument");
+        };
+        let account = snarkos_account::Account::<Network>::try_from(private_key)?;
+        Ok(account.sign_bytes(message.as_bytes(), &mut rand::thread_rng())?.to_string())
+    }
+
+    /// Verifies the signature of the given message for the given address.
+    fn verify(address: String, signature: String, message: String) -> Result<String> {
+        let address = Address::<Network>::from_str(&address)?;
+        let signature = Signature::<Network>::from_str(&signature)?;
+        match signature.verify_bytes(&address, message.as_bytes()) {
+            true => Ok("✅ The signature is valid".to_string()),
+            false => bail!("The signature is invalid for the address {address}"),
+        }
+    }
+
+    /// Returns the public balance of the account, and the private balance if a block height to scan from is given.
+    fn balance(address: Option<String>, key: AccountKey, scan_from: Option<u32>, endpoint: String) -> Result<String> {
+        // Determine the address of the account.
+        let private_key = key.private_key()?;
+        let address = match (address, &private_key) {
+            (Some(address), None) => Address::<Network>::from_str(&address)?,
+            (None, Some(private_key)) => Address::try_from(private_key)?,
+            (None, None) => bail!("Missing the '--address', '--private-key' or '--keystore' argument"),
+            (Some(_), Some(_)) => bail!("Cannot use '--address' with a private key, please use only one"),
+        };
+
+        // Fetch the public balance.
+        let public_balance = Developer::get_public_balance(&address, &endpoint)?;
+        let mut balance = format!(" {:>15}  {public_balance} microcredits", "Public Balance".cyan().bold());
+
+        // Compute the private balance from the unspent records.
+        if let Some(start_height) = scan_from {
+            let Some(private_key) = private_key else {
+                bail!("The '--scan-from' argument requires the '--private-key' or '--keystore' argument");
+            };
+            // Request the latest block height from the endpoint.
+            let latest_height =
+                u32::from_str(&ureq::get(&format!("{endpoint}/mainnet/latest/height")).call()?.into_string()?)?;
+            // Fetch the unspent records.
+            let view_key = ViewKey::try_from(&private_key)?;
+            let records = Scan::fetch_records(Some(private_key), &view_key, &endpoint, start_height, latest_height)?;
+            // Sum the microcredits of the records.
+            let microcredits = Identifier::<Network>::from_str("microcredits")?;
+            let private_balance = records
+                .iter()
+                .filter_map(|record| match record.data().get(&microcredits) {
+                    Some(Entry::Private(Plaintext::Literal(Literal::U64(amount), _))) => Some(**amount),
+                    _ => None,
+                })
+                .sum::<u64>();
+            balance += &format!("\n {:>15}  {private_balance} microcredits", "Private Balance".cyan().bold());
+        }
+
+        Ok(balance)
+    }
 }
 
 // Print the string to an alternate screen, so that the string won't been printed to the terminal.
@@ -201,7 +376,7 @@ fn wait_for_keypress() {
 
 #[cfg(test)]
 mod tests {
-    use crate::commands::Account;
+    use crate::commands::{Account, AccountKey};
 
     use colored::Colorize;
 
@@ -264,4 +439,53 @@ mod tests {
         let actual = account.parse().unwrap();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let private_key = "APrivateKey1zkp2n22c19hNdGF8wuEoQcuiyuWbquY6up4CtG5DYKqPX2X".to_string();
+        let address = "aleo1uxl69laseuv3876ksh8k0nd7tvpgjt6ccrgccedpjk9qwyfensxst9ftg5".to_string();
+        let message = "hello world".to_string();
+
+        // Sign the message.
+        let key = AccountKey { private_key: Some(private_key), keystore: None, password_file: None };
+        let signature = Account::Sign { key, message: message.clone() }.parse().unwrap();
+
+        // Verify the signature.
+        let account = Account::Verify { address: address.clone(), signature: signature.clone(), message };
+        assert!(account.parse().is_ok());
+        let account = Account::Verify { address, signature, message: "goodbye world".to_string() };
+        assert!(account.parse().is_err());
+    }
+
+    #[test]
+    fn test_import_and_sign_with_keystore() {
+        let directory = tempfile::tempdir().unwrap();
+        let keystore = directory.path().join("keystore.json");
+        let password_file = directory.path().join("password");
+        std::fs::write(&password_file, "password\n").unwrap();
+
+        // Import the private key into a keystore.
+        let private_key = "APrivateKey1zkp2n22c19hNdGF8wuEoQcuiyuWbquY6up4CtG5DYKqPX2X".to_string();
+        let account = Account::Import {
+            private_key: Some(private_key),
+            keystore: keystore.clone(),
+            password_file: Some(password_file.clone()),
+        };
+        assert!(account.parse().is_ok());
+
+        // Ensure the keystore is not overwritten.
+        let account = Account::Import {
+            private_key: Some("APrivateKey1zkp61PAYmrYEKLtRWeWhUoDpFnGLNuHrCciSqN49T86dw3p".to_string()),
+            keystore: keystore.clone(),
+            password_file: Some(password_file.clone()),
+        };
+        assert!(account.parse().is_err());
+
+        // Sign a message with the keystore, and verify the signature.
+        let key = AccountKey { private_key: None, keystore: Some(keystore), password_file: Some(password_file) };
+        let message = "hello world".to_string();
+        let signature = Account::Sign { key, message: message.clone() }.parse().unwrap();
+        let address = "aleo1uxl69laseuv3876ksh8k0nd7tvpgjt6ccrgccedpjk9qwyfensxst9ftg5".to_string();
+        assert!(Account::Verify { address, signature, message }.parse().is_ok());
+    }
 }
@@ -0,0 +1,445 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::Start;
+
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::{parser::ValueSource, ArgAction, ArgMatches, CommandFactory, FromArgMatches, Parser};
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+/// The prefix of the environment variables of the options of `snarkos start`.
+const ENV_PREFIX: &str = "SNARKOS_";
+
+/// The options that cannot be set in the configuration file.
+/// Note: The private key must be given in a file, through `private-key-file`, instead.
+const EXCLUDED_OPTIONS: &[&str] = &["config", "private-key"];
+
+/// The options that select the node type, which are overridden as a group.
+/// Note: This way, e.g. `--client` on the command line overrides `validator = true` in the configuration file.
+const NODE_TYPE_OPTIONS: &[&str] = &["validator", "prover", "client"];
+
+/// The options whose values are secret, or point to a secret, and are hidden from the help.
+const SECRET_OPTIONS: &[&str] =
+    &["private-key", "private-key-file", "keystore-password-file", "pool-token-file", "jwt-secret-file"];
+
+/// The sections of the configuration file, and the options that may be set in each section.
+/// Note: These options may also be set at the top level of the configuration file.
+const SECTIONS: &[(&str, &[&str])] = &[
+    ("router", &[
+        "node",
+        "peers",
+        "min-peers",
+        "max-peers",
+        "max-provers",
+        "heartbeat-interval",
+        "radio-silence",
+        "max-connection-failures",
+        "message-limit",
+        "message-limit-time-frame",
+    ]),
+    ("bft", &["bft", "validators", "workers", "graceful-shutdown"]),
+    ("prover", &["pool-server", "pool", "pool-token-file", "puzzle-instances", "puzzle-cores"]),
+    ("rest", &[
+        "rest",
+        "rest-rps",
+        "norest",
+        "rest-index",
+        "rest-mapping-history",
+        "jwt-secret-file",
+        "jwt-revocation-file",
+    ]),
+];
+
+/// The header of the template of the configuration file.
+const TEMPLATE_HEADER: &str = "\
+# The configuration file of a snarkOS node, for `snarkos start --config <PATH>`.
+#
+# Each option corresponds to the flag of `snarkos start` with the same name,
+# e.g. `rest-rps = 10` for `--rest-rps 10`, or `norest = true` for `--norest`.
+# A flag given on the command line takes precedence over its environment variable,
+# which takes precedence over this file.
+# A list of IP addresses (e.g. `peers`) may be given as an array of strings.
+# The options of the router, the BFT, the prover, and the REST server may be grouped
+# in their `[router]`, `[bft]`, `[prover]`, and `[rest]` sections, or set at the top level.
+# Note: The private key cannot be set in this file, use `private-key-file` instead.
+# Uncomment an option to set it.
+";
+/// Commands to manage the configuration file of the node
+#[derive(Debug, Parser)]
+pub enum Config {
+    /// Generates a documented template of the configuration file, for `snarkos start --config <PATH>`
+    Init {
+        /// Specify the path to write the configuration file to
+        #[clap(default_value = "snarkos.toml")]
+        path: PathBuf,
+        /// If the flag is set, an existing file at the path is overwritten
+        #[clap(long)]
+        force: bool,
+    },
+    /// Validates the configuration file, without starting the node
+    Check {
+        /// Specify the path to the configuration file
+        path: PathBuf,
+    },
+}
+
+impl Config {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Init { path, force } => {
+                // Ensure an existing file is not overwritten by accident.
+                if !force && path.exists() {
+                    bail!("The file '{}' already exists (use '--force' to overwrite it)", path.display())
+                }
+                std::fs::write(&path, NodeConfig::template())?;
+                Ok(format!("✅ Wrote the configuration template to '{}'", path.display()))
+            }
+            Self::Check { path } => {
+                NodeConfig::load(&path)?.check()?;
+                Ok(format!("✅ The configuration file '{}' is valid", path.display()))
+            }
+        }
+    }
+}
+
+/// The configuration file of the node, which provides the options of `snarkos start`
+/// that are not given on the command line or in the environment.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NodeConfig {
+    /// The options, as the IDs of the arguments of `snarkos start` and their command-line values.
+    options: Vec<(String, String)>,
+}
+
+impl NodeConfig {
+    /// Loads the configuration file from the given path.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| anyhow!("Unable to read the configuration file '{}' - {error}", path.display()))?;
+        Self::from_toml(&contents).map_err(|error| anyhow!("Invalid configuration file '{}' - {error}", path.display()))
+    }
+
+    /// Parses the configuration file from the given TOML.
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        let table: toml::Table = contents.parse()?;
+        let command = Start::command();
+
+        // Flatten the sections into their options.
+        let mut entries = Vec::with_capacity(table.len());
+        for (key, value) in table {
+            match value {
+                toml::Value::Table(section) => {
+                    let Some((_, options)) = SECTIONS.iter().find(|(name, _)| *name == key) else {
+                        bail!("Unknown section '[{key}]'")
+                    };
+                    for (option, value) in section {
+                        ensure!(options.contains(&option.as_str()), "The option '{option}' cannot be set in '[{key}]'");
+                        entries.push((option, value));
+                    }
+                }
+                value => entries.push((key, value)),
+            }
+        }
+
+        let mut options = Vec::with_capacity(entries.len());
+        for (key, value) in entries {
+            // Retrieve the argument of the option.
+            let Some(arg) = command.get_arguments().find(|arg| arg.get_long() == Some(key.as_str())) else {
+                bail!("Unknown option '{key}'")
+            };
+            ensure!(
+                !EXCLUDED_OPTIONS.contains(&key.as_str()),
+                "The option '{key}' cannot be set in the configuration file"
+            );
+            ensure!(
+                !options.iter().any(|(id, _)| id == arg.get_id().as_str()),
+                "The option '{key}' is set more than once"
+            );
+            // Convert the value into its command-line form.
+            let value = match value {
+                toml::Value::String(value) => value,
+                toml::Value::Integer(value) => value.to_string(),
+                toml::Value::Float(value) => value.to_string(),
+                toml::Value::Boolean(value) => value.to_string(),
+                // Note: A list is given to the command line as comma-separated values.
+                toml::Value::Array(values) => values
+                    .into_iter()
+                    .map(|value| match value {
+                        toml::Value::String(value) => Ok(value),
+                        toml::Value::Integer(value) => Ok(value.to_string()),
+                        _ => bail!("The option '{key}' must be a list of strings or integers"),
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .join(","),
+                _ => bail!("The option '{key}' must be a string, a number, a boolean, or a list"),
+            };
+            options.push((arg.get_id().to_string(), value));
+        }
+        Ok(Self { options })
+    }
+
+    /// Returns the command-line arguments of the options of the configuration file,
+    /// which are not already given on the command line or in the environment of the given matches.
+    pub fn to_args(&self, matches: &ArgMatches) -> Vec<OsString> {
+        let command = Start::command();
+        let is_given =
+            |id: &str| matches!(matches.value_source(id), Some(ValueSource::CommandLine | ValueSource::EnvVariable));
+        // Determine if the node type is already given.
+        let is_node_type_given = NODE_TYPE_OPTIONS.iter().any(|id| is_given(id) && matches.get_flag(id));
+
+        let mut args = Vec::new();
+        for (id, value) in &self.options {
+            // Skip the options that are already given, and the node type if another one is already given.
+            if is_given(id) || (is_node_type_given && NODE_TYPE_OPTIONS.contains(&id.as_str())) {
+                continue;
+            }
+            let Some(arg) = command.get_arguments().find(|arg| arg.get_id() == id) else { continue };
+            let Some(long) = arg.get_long() else { continue };
+            match arg.get_action() {
+                // Note: A flag is only given if it is enabled.
+                ArgAction::SetTrue => {
+                    if value == "true" {
+                        args.push(format!("--{long}").into());
+                    }
+                }
+                _ => args.push(format!("--{long}={value}").into()),
+            }
+        }
+        args
+    }
+
+    /// Ensures the configuration file is valid, without starting the node.
+    pub fn check(&self) -> Result<()> {
+        let matches = Start::command().try_get_matches_from(["snarkos"])?;
+        let args = std::iter::once(OsString::from("snarkos")).chain(self.to_args(&matches));
+        Start::from_arg_matches(&Start::command().try_get_matches_from(args)?)?.check()
+    }
+
+    /// Returns a documented template of the configuration file, with every option commented out.
+    pub fn template() -> String {
+        let command = Start::command();
+        let mut template = TEMPLATE_HEADER.to_string();
+        // Document the options at the top level, and then the options of each section.
+        let top_level = command
+            .get_arguments()
+            .filter(|arg| {
+                arg.get_long().map_or(false, |long| SECTIONS.iter().all(|(_, options)| !options.contains(&long)))
+            })
+            .collect::<Vec<_>>();
+        let sections = SECTIONS.iter().map(|(name, options)| {
+            let args =
+                options.iter().filter_map(|option| command.get_arguments().find(|arg| arg.get_long() == Some(*option)));
+            (Some(*name), args.collect::<Vec<_>>())
+        });
+        for (section, args) in std::iter::once((None, top_level)).chain(sections) {
+            if let Some(section) = section {
+                template.push_str(&format!("\n[{section}]\n"));
+            }
+            for arg in args {
+                // Skip the options that cannot be set in the configuration file.
+                let Some(long) = arg.get_long() else { continue };
+                if EXCLUDED_OPTIONS.contains(&long) {
+                    continue;
+                }
+                // Document the option.
+                template.push('\n');
+                if let Some(help) = arg.get_help() {
+                    for line in help.to_string().lines() {
+                        template.push_str(&format!("# {line}\n"));
+                    }
+                }
+                template.push_str(&format!("# (environment variable: {})\n", env_name(long)));
+                // Write the option with its default value, or a placeholder.
+                let value = match (arg.get_action(), arg.get_default_values().first()) {
+                    (ArgAction::SetTrue, _) => "false".to_string(),
+                    (_, Some(default)) => toml_value(&default.to_string_lossy()),
+                    (_, None) => {
+                        let name = arg.get_value_names().and_then(|names| names.first()).map(|name| name.to_string());
+                        toml_value(&format!("<{}>", name.unwrap_or_else(|| "VALUE".to_string())))
+                    }
+                };
+                template.push_str(&format!("# {long} = {value}\n"));
+            }
+        }
+        template
+    }
+}
+
+/// Returns the given `snarkos start` command, with its options falling back to the `SNARKOS_*` environment variables.
+/// The values of the secret options are hidden from the help.
+pub fn with_environment(command: clap::Command) -> clap::Command {
+    command.mut_args(|arg| match arg.get_long() {
+        Some(long) => {
+            let is_secret = SECRET_OPTIONS.contains(&long);
+            let name = env_name(long);
+            arg.env(name).hide_env_values(is_secret)
+        }
+        None => arg,
+    })
+}
+
+/// Returns the name of the environment variable of the given option.
+fn env_name(long: &str) -> String {
+    format!("{ENV_PREFIX}{}", long.to_uppercase().replace('-', "_"))
+}
+
+/// Returns the given value as a TOML value, which is unquoted if it is a boolean or an integer.
+fn toml_value(value: &str) -> String {
+    match value.parse::<bool>().is_ok() || value.parse::<i64>().is_ok() {
+        true => value.to_string(),
+        false => toml::Value::String(value.to_string()).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Command, CLI};
+
+    use std::io::Write;
+
+    /// Parses `snarkos start` with the given configuration file and arguments.
+    fn parse(config: &NodeConfig, args: &[&str]) -> Start {
+        let matches = Start::command().ignore_errors(true).try_get_matches_from(args).unwrap();
+        let args = args.iter().map(OsString::from).chain(config.to_args(&matches));
+        Start::from_arg_matches(&Start::command().try_get_matches_from(args).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_template() {
+        // Ensure the template is a valid configuration file, which sets no options.
+        let template = NodeConfig::template();
+        assert_eq!(NodeConfig::from_toml(&template).unwrap(), NodeConfig::default());
+        assert!(template.contains("# rest-rps = 10\n"));
+        assert!(template.contains("# norest = false\n"));
+        assert!(template.contains("\n[bft]\n"));
+        assert!(template.contains("# (environment variable: SNARKOS_REST_RPS)\n"));
+        assert!(!template.contains("# config = "));
+        assert!(!template.contains("# private-key = "));
+
+        // Ensure every uncommented option is accepted, except the placeholders.
+        let uncommented = template
+            .lines()
+            .filter_map(|line| line.strip_prefix("# "))
+            .filter(|line| line.contains(" = ") && !line.contains("\"<"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        NodeConfig::from_toml(&uncommented).unwrap();
+    }
+
+    #[test]
+    fn test_precedence() {
+        let config = NodeConfig::from_toml(
+            r#"
+            peers = ["1.2.3.4:4130", "5.6.7.8:4130"]
+            norest = true
+
+            [router]
+            message-limit = 600
+
+            [rest]
+            rest-rps = 20
+            "#,
+        )
+        .unwrap();
+
+        // Ensure the configuration file overrides the defaults.
+        let start = parse(&config, &["snarkos"]);
+        assert_eq!(start.peers, "1.2.3.4:4130,5.6.7.8:4130");
+        assert_eq!(start.rest_rps, 20);
+        assert_eq!(start.message_limit, Some(600));
+        assert!(start.norest);
+
+        // Ensure the command line overrides the configuration file.
+        let start = parse(&config, &["snarkos", "--rest-rps", "30", "--message-limit", "800"]);
+        assert_eq!(start.rest_rps, 30);
+        assert_eq!(start.message_limit, Some(800));
+        assert_eq!(start.peers, "1.2.3.4:4130,5.6.7.8:4130");
+
+        // Ensure the options of the file satisfy the requirements of the other options.
+        let config = NodeConfig::from_toml("prover = true\n[prover]\npool = \"1.2.3.4:4130\"").unwrap();
+        assert!(parse(&config, &["snarkos"]).pool.is_some());
+    }
+
+    #[test]
+    fn test_node_type_precedence() {
+        // Parses the CLI with the given configuration file and arguments of `snarkos start`.
+        fn parse_cli(contents: &str, args: &[&str]) -> Result<Start> {
+            let mut file = tempfile::NamedTempFile::new().unwrap();
+            file.write_all(contents.as_bytes()).unwrap();
+            let path = file.path().to_str().unwrap();
+            let args = ["snarkos", "start", "--config", path].iter().chain(args).map(OsString::from).collect();
+            let command = CLI::command();
+            let args = CLI::args_with_config(&command, args)?;
+            match CLI::from_arg_matches(&command.try_get_matches_from(args)?)?.command {
+                Command::Start(start) => Ok(*start),
+                _ => unreachable!(),
+            }
+        }
+
+        // Ensure the node type of the command line overrides the node type of the configuration file.
+        let start = parse_cli("validator = true", &["--client"]).unwrap();
+        assert!(start.client);
+        assert!(!start.validator);
+        start.check().unwrap();
+        let start = parse_cli("validator = true", &[]).unwrap();
+        assert!(start.validator);
+
+        // Ensure the node type of the configuration file satisfies the requirements of the command line.
+        let start = parse_cli("validator = true", &["--workers", "2", "--graceful-shutdown", "30"]).unwrap();
+        assert_eq!(start.workers, 2);
+        assert_eq!(start.graceful_shutdown, Some(30));
+        assert!(parse_cli("prover = true", &["--workers", "2"]).is_err());
+        assert!(parse_cli("validator = true", &["--client", "--workers", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_help() {
+        // Ensure the values of the configuration file are not shown in the help.
+        let config = NodeConfig::from_toml("rest-rps = 12345").unwrap();
+        let matches = Start::command().try_get_matches_from(["snarkos"]).unwrap();
+        assert_eq!(config.to_args(&matches), vec![OsString::from("--rest-rps=12345")]);
+        assert!(!with_environment(Start::command()).render_long_help().to_string().contains("12345"));
+
+        // Ensure the values of the secret options in the environment are hidden from the help.
+        let command = with_environment(Start::command());
+        for arg in command.get_arguments().filter(|arg| arg.get_long().is_some()) {
+            let is_secret = SECRET_OPTIONS.contains(&arg.get_long().unwrap());
+            assert_eq!(arg.is_hide_env_values_set(), is_secret, "{}", arg.get_id());
+        }
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        // Ensure unknown options and sections, misplaced options, and the excluded options are rejected.
+        assert!(NodeConfig::from_toml("unknown = 1").is_err());
+        assert!(NodeConfig::from_toml("[unknown]\nmax-peers = 1").is_err());
+        assert!(NodeConfig::from_toml("[bft]\nmax-peers = 1").is_err());
+        assert!(NodeConfig::from_toml("[router.nested]\nmax-peers = 1").is_err());
+        assert!(NodeConfig::from_toml("max-peers = 1\n[router]\nmax-peers = 2").is_err());
+        assert!(NodeConfig::from_toml("config = \"snarkos.toml\"").is_err());
+        assert!(NodeConfig::from_toml("private-key = \"APrivateKey1\"").is_err());
+        NodeConfig::from_toml("[router]\nmax-peers = 1").unwrap();
+
+        // Ensure malformed and inconsistent values are rejected by the check.
+        NodeConfig::from_toml("rest-rps = 20").unwrap().check().unwrap();
+        assert!(NodeConfig::from_toml("rest-rps = \"fast\"").unwrap().check().is_err());
+        assert!(NodeConfig::from_toml("peers = [\"1.2.3.4\"]").unwrap().check().is_err());
+        assert!(NodeConfig::from_toml("max-peers = 5\nmin-peers = 10").unwrap().check().is_err());
+        assert!(NodeConfig::from_toml("validator = true\nprover = true").unwrap().check().is_err());
+        assert!(NodeConfig::from_toml("network = 5").unwrap().check().is_err());
+    }
+}
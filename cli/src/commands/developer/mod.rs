@@ -136,7 +136,7 @@ impl Developer {
     }
 
     /// Fetch the public balance in microcredits associated with the address from the given endpoint.
-    fn get_public_balance(address: &Address<CurrentNetwork>, endpoint: &str) -> Result<u64> {
+    pub(crate) fn get_public_balance(address: &Address<CurrentNetwork>, endpoint: &str) -> Result<u64> {
         // Initialize the program id and account identifier.
         let credits = ProgramID::<CurrentNetwork>::from_str("credits.aleo")?;
         let account_mapping = Identifier::<CurrentNetwork>::from_str("account")?;
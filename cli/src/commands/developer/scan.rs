@@ -146,7 +146,7 @@ impl Scan {
     }
 
     /// Fetch owned ciphertext records from the endpoint.
-    fn fetch_records(
+    pub(crate) fn fetch_records(
         private_key: Option<PrivateKey<CurrentNetwork>>,
         view_key: &ViewKey<CurrentNetwork>,
         endpoint: &str,
@@ -0,0 +1,82 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::store::{helpers::rocksdb::ConsensusDB, ConsensusStore};
+
+use aleo_std::StorageMode;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::path::PathBuf;
+
+type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+/// Commands to manage the ledger of the node.
+#[derive(Debug, Parser)]
+pub enum Ledger {
+    /// Exports the blocks of the ledger into a directory, in the CDN bundle format (the node must not be running)
+    Export {
+        /// Specify the directory to export the blocks to (usable with `snarkos start --cdn file://<DIRECTORY>`)
+        #[clap(long)]
+        output: PathBuf,
+        /// Specify the starting block height, rounded down to the nearest bundle
+        #[clap(default_value = "0", long)]
+        start: u32,
+        /// Specify the ending block height (exclusive), rounded down to the nearest bundle (default: the latest block)
+        #[clap(long)]
+        end: Option<u32>,
+        /// Enables development mode, specify the unique ID of the local node to export from
+        #[clap(long)]
+        dev: Option<u16>,
+        /// Specify the path to a directory containing the ledger
+        #[clap(long = "path")]
+        path: Option<PathBuf>,
+    },
+}
+
+impl Ledger {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Export { output, start, end, dev, path } => {
+                let storage_mode = match path {
+                    Some(path) => StorageMode::Custom(path),
+                    None => StorageMode::from(dev),
+                };
+                Self::export(output, start, end, storage_mode)
+            }
+        }
+    }
+
+    /// Exports the blocks in the given range from the ledger into the given directory.
+    fn export(output: PathBuf, start: u32, end: Option<u32>, storage_mode: StorageMode) -> Result<String> {
+        // Open the ledger storage.
+        let store = ConsensusStore::<CurrentNetwork, ConsensusDB<CurrentNetwork>>::open(storage_mode)?;
+        let block_store = store.block_store();
+
+        // Determine the ending block height, which may not exceed the latest block.
+        let latest_height =
+            block_store.max_height().ok_or_else(|| anyhow!("The ledger does not contain any blocks"))?;
+        let end = end.map_or(latest_height + 1, |end| end.min(latest_height + 1));
+
+        // Export the blocks.
+        let exported_height = snarkos_node_cdn::export_blocks(&output, start, end, |height| {
+            let hash = block_store.get_block_hash(height)?.ok_or_else(|| anyhow!("Missing hash for block {height}"))?;
+            block_store.get_block(&hash)?.ok_or_else(|| anyhow!("Missing block {height}"))
+        })?;
+
+        // Prepare the path string.
+        let path_string = format!("(in \"{}\")", output.display()).dimmed();
+        Ok(format!("✅ Exported the ledger up to block {} {path_string}", exported_height - 1))
+    }
+}
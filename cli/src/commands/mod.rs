@@ -18,18 +18,28 @@ pub use account::*;
 mod clean;
 pub use clean::*;
 
+mod config;
+pub use config::*;
+
 mod developer;
 pub use developer::*;
 
+mod ledger;
+pub use ledger::*;
+
 mod start;
 pub use start::*;
 
+mod token;
+pub use token::*;
+
 mod update;
 pub use update::*;
 
 use anstyle::{AnsiColor, Color, Style};
 use anyhow::Result;
-use clap::{builder::Styles, Parser};
+use clap::{builder::Styles, CommandFactory, FromArgMatches, Parser};
+use std::{ffi::OsString, path::PathBuf};
 
 const HEADER_COLOR: Option<Color> = Some(Color::Ansi(AnsiColor::Yellow));
 const LITERAL_COLOR: Option<Color> = Some(Color::Ansi(AnsiColor::Green));
@@ -49,6 +59,31 @@ pub struct CLI {
     pub command: Command,
 }
 
+impl CLI {
+    /// Parses the given arguments, with the options of `snarkos start` falling back to
+    /// the `SNARKOS_*` environment variables, and then to the configuration file in `--config`.
+    pub fn parse_with_config() -> Result<Self> {
+        let command = Self::command().mut_subcommand("start", with_environment);
+        let args = Self::args_with_config(&command, std::env::args_os().collect())?;
+        Ok(Self::from_arg_matches(&command.get_matches_from(args))?)
+    }
+
+    /// Returns the given arguments, with the options of the configuration file in `--config` appended,
+    /// if they are not given on the command line or in the environment.
+    /// Note: The options of `snarkos start` are the last arguments, so the options of the file are appended.
+    fn args_with_config(command: &clap::Command, mut args: Vec<OsString>) -> Result<Vec<OsString>> {
+        // Parse the arguments once, to retrieve the path to the configuration file.
+        // Note: The errors are ignored here, as the options of the file may be required by the given options
+        // (e.g. `--workers` requires the node type, which may be set in the file), and are reported by the next parse.
+        let matches = command.clone().ignore_errors(true).try_get_matches_from(&args).ok();
+        let start = matches.as_ref().and_then(|matches| matches.subcommand_matches("start"));
+        if let Some((start, path)) = start.and_then(|start| Some((start, start.get_one::<PathBuf>("config")?))) {
+            args.extend(NodeConfig::load(path)?.to_args(start));
+        }
+        Ok(args)
+    }
+}
+
 #[derive(Debug, Parser)]
 pub enum Command {
     #[clap(subcommand)]
@@ -56,9 +91,15 @@ pub enum Command {
     #[clap(name = "clean")]
     Clean(Clean),
     #[clap(subcommand)]
+    Config(Config),
+    #[clap(subcommand)]
     Developer(Developer),
+    #[clap(subcommand)]
+    Ledger(Ledger),
     #[clap(name = "start")]
     Start(Box<Start>),
+    #[clap(subcommand)]
+    Token(Token),
     #[clap(name = "update")]
     Update(Update),
 }
@@ -69,8 +110,11 @@ impl Command {
         match self {
             Self::Account(command) => command.parse(),
             Self::Clean(command) => command.parse(),
+            Self::Config(command) => command.parse(),
             Self::Developer(command) => command.parse(),
+            Self::Ledger(command) => command.parse(),
             Self::Start(command) => command.parse(),
+            Self::Token(command) => command.parse(),
             Self::Update(command) => command.parse(),
         }
     }
@@ -83,7 +127,6 @@ mod tests {
     // As per the official clap recommendation.
     #[test]
     fn verify_cli() {
-        use clap::CommandFactory;
         CLI::command().debug_assert()
     }
 }
@@ -12,9 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use snarkos_account::Account;
+use crate::helpers::read_password;
+use snarkos_account::{Account, Keystore};
 use snarkos_display::Display;
-use snarkos_node::{bft::MEMORY_POOL_PORT, router::messages::NodeType, Node};
+use snarkos_node::{
+    bft::{MAX_WORKERS, MEMORY_POOL_PORT},
+    router::{messages::NodeType, RouterConfig},
+    Node,
+    PoolMode,
+};
 use snarkvm::{
     console::{
         account::{Address, PrivateKey},
@@ -32,7 +38,7 @@ use snarkvm::{
 };
 
 use aleo_std::StorageMode;
-use anyhow::{bail, ensure, Result};
+use anyhow::{anyhow, bail, ensure, Result};
 use clap::Parser;
 use colored::Colorize;
 use core::str::FromStr;
@@ -40,8 +46,9 @@ use indexmap::IndexMap;
 use rand::SeedableRng;
 use rand_chacha::ChaChaRng;
 use serde::{Deserialize, Serialize};
-use std::{net::SocketAddr, path::PathBuf};
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
 use tokio::runtime::{self, Runtime};
+use zeroize::{Zeroize, Zeroizing};
 
 /// The recommended minimum number of 'open files' limit for a validator.
 /// Validators should be able to handle at least 1000 concurrent connections, each requiring 2 sockets.
@@ -53,6 +60,9 @@ const DEVELOPMENT_MODE_RNG_SEED: u64 = 1234567890u64;
 /// The development mode number of genesis committee members.
 const DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS: u16 = 4;
 
+/// The environment variable containing the token of the mining pool.
+const POOL_TOKEN_ENV: &str = "SNARKOS_POOL_TOKEN";
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 struct BondedBalances(IndexMap<String, (String, u64)>);
 
@@ -67,6 +77,11 @@ impl FromStr for BondedBalances {
 /// Starts the snarkOS node.
 #[derive(Clone, Debug, Parser)]
 pub struct Start {
+    /// Specify the path to a TOML configuration file, whose options are overridden by the environment and the command line
+    /// (see `snarkos config init`)
+    #[clap(long = "config")]
+    pub config: Option<PathBuf>,
+
     /// Specify the network ID of this node
     #[clap(default_value = "0", long = "network")]
     pub network: u16,
@@ -84,9 +99,13 @@ pub struct Start {
     /// Specify the account private key of the node
     #[clap(long = "private-key")]
     pub private_key: Option<String>,
-    /// Specify the path to a file containing the account private key of the node
+    /// Specify the path to a file containing the account private key of the node, or its encrypted keystore
     #[clap(long = "private-key-file")]
     pub private_key_file: Option<PathBuf>,
+    /// Specify the path to a file containing the password of the keystore in '--private-key-file'
+    /// (if omitted, the password is read from the `SNARKOS_KEYSTORE_PASSWORD` environment variable, or a prompt)
+    #[clap(long = "keystore-password-file", requires = "private_key_file")]
+    pub keystore_password_file: Option<PathBuf>,
 
     /// Specify the IP address and port for the node server
     #[clap(default_value = "0.0.0.0:4130", long = "node")]
@@ -100,6 +119,57 @@ pub struct Start {
     /// Specify the IP address and port of the validator(s) to connect to
     #[clap(default_value = "", long = "validators")]
     pub validators: String,
+    /// If the node is a validator, specify the number of workers of the memory pool, from 1 to 8
+    /// (each worker buffers up to a full batch of transmissions, so the memory pool uses up to 8x the memory with 8 workers)
+    #[clap(default_value = "1", long = "workers", requires = "validator")]
+    pub workers: u8,
+    /// If the node is a validator, on shutdown, stop accepting transmissions and wait up to the given number of seconds
+    /// for the pending batch proposal to be certified or to expire (default: shut down immediately)
+    #[clap(long = "graceful-shutdown", requires = "validator")]
+    pub graceful_shutdown: Option<u64>,
+
+    /// Specify the minimum number of peers to maintain connections with (default: 3)
+    #[clap(long = "min-peers")]
+    pub min_peers: Option<usize>,
+    /// Specify the maximum number of peers to maintain connections with (default: 200 for a validator, 21 otherwise)
+    #[clap(long = "max-peers")]
+    pub max_peers: Option<usize>,
+    /// Specify the maximum number of provers to maintain connections with (default: a quarter of the maximum number of peers)
+    #[clap(long = "max-provers")]
+    pub max_provers: Option<usize>,
+    /// Specify the interval in seconds between the heartbeats of the router (default: 25)
+    #[clap(long = "heartbeat-interval")]
+    pub heartbeat_interval: Option<u64>,
+    /// Specify the duration in seconds after which a silent peer is disconnected, and a restricted peer is allowed again (default: 150)
+    #[clap(long = "radio-silence")]
+    pub radio_silence: Option<u64>,
+    /// Specify the maximum number of connection attempts permitted from an inbound peer within the radio silence (default: 5)
+    #[clap(long = "max-connection-failures")]
+    pub max_connection_failures: Option<usize>,
+    /// Specify the maximum number of messages accepted from a peer within the message limit time frame (default: 500)
+    #[clap(long = "message-limit")]
+    pub message_limit: Option<usize>,
+    /// Specify the time frame in seconds to enforce the message limit (default: 5)
+    #[clap(long = "message-limit-time-frame")]
+    pub message_limit_time_frame: Option<u64>,
+
+    /// If the node is a prover, specify the IP address and port to serve a mining pool for remote pool workers
+    #[clap(long = "pool-server", requires = "prover", conflicts_with = "pool")]
+    pub pool_server: Option<SocketAddr>,
+    /// If the node is a prover, specify the IP address and port of the mining pool to connect to, instead of the network
+    #[clap(long = "pool", requires = "prover")]
+    pub pool: Option<SocketAddr>,
+    /// Specify the path to a file containing the token shared by the mining pool and its pool workers
+    /// (if omitted, the token is read from the `SNARKOS_POOL_TOKEN` environment variable)
+    #[clap(long = "pool-token-file")]
+    pub pool_token_file: Option<PathBuf>,
+    /// If the node is a prover, specify the number of coinbase puzzle instances (default: the number of puzzle cores, or derived from the number of cores)
+    #[clap(long = "puzzle-instances", requires = "prover")]
+    pub puzzle_instances: Option<u8>,
+    /// If the node is a prover, specify the CPU core(s) to pin the coinbase puzzle instances to (e.g. "0,1,2,3")
+    /// (each instance runs on its own thread pool, with one thread pinned to each of its cores)
+    #[clap(long = "puzzle-cores", requires = "prover")]
+    pub puzzle_cores: Option<String>,
 
     /// Specify the IP address and port for the REST server
     #[clap(default_value = "0.0.0.0:3030", long = "rest")]
@@ -110,6 +180,20 @@ pub struct Start {
     /// If the flag is set, the node will not initialize the REST server
     #[clap(long)]
     pub norest: bool,
+    /// If the flag is set, the REST server will maintain an index of the transactions by address and the transitions by program
+    /// (over the most recent 100,000 blocks)
+    #[clap(long = "rest-index")]
+    pub rest_index: bool,
+    /// If the flag is set, the REST server will record the history of the mapping values, to query them at past heights
+    /// (over the most recent 100,000 blocks)
+    #[clap(long = "rest-mapping-history")]
+    pub rest_mapping_history: bool,
+    /// Specify the path to a file containing the JWT secret for the REST server (default: the `SNARKOS_JWT_SECRET` environment variable)
+    #[clap(long = "jwt-secret-file")]
+    pub jwt_secret_file: Option<PathBuf>,
+    /// Specify the path to a file containing the IDs of the revoked JWT tokens, one per line (tokens revoked at runtime are appended to it)
+    #[clap(long = "jwt-revocation-file")]
+    pub jwt_revocation_file: Option<PathBuf>,
 
     /// If the flag is set, the node will not render the display
     #[clap(long)]
@@ -120,11 +204,11 @@ pub struct Start {
     /// Specify the path to the file where logs will be stored
     #[clap(default_value_os_t = std::env::temp_dir().join("snarkos.log"), long = "logfile")]
     pub logfile: PathBuf,
-    /// Enables the metrics exporter
+    /// Enables the metrics, served on the REST server at `/metrics` (or by an exporter if the REST server is disabled)
     #[clap(default_value = "false", long = "metrics")]
     pub metrics: bool,
 
-    /// Enables the node to prefetch initial blocks from a CDN
+    /// Enables the node to prefetch initial blocks from a CDN, or from a local directory with `file://<DIRECTORY>`
     #[clap(default_value = "https://s3.us-west-1.amazonaws.com/testnet3.blocks/phase3", long = "cdn")]
     pub cdn: String,
     /// If the flag is set, the node will not prefetch from a CDN
@@ -153,8 +237,9 @@ impl Start {
         let log_receiver = crate::helpers::initialize_logger(self.verbosity, self.nodisplay, self.logfile.clone());
         // Initialize the runtime.
         Self::runtime().block_on(async move {
-            // Clone the configurations.
-            let mut cli = self.clone();
+            // Take the configurations.
+            // Note: The configurations are not cloned, so that the private key is not left in memory.
+            let mut cli = self;
             // Parse the network.
             match cli.network {
                 0 => {
@@ -178,6 +263,45 @@ impl Start {
 }
 
 impl Start {
+    /// Ensures the configurations are valid, without starting the node.
+    pub(crate) fn check(&self) -> Result<()> {
+        // Ensure at most one node type is specified.
+        ensure!(
+            [self.validator, self.prover, self.client].into_iter().filter(|is_set| *is_set).count() <= 1,
+            "Specify at most one of '--validator', '--prover', or '--client'"
+        );
+        // Ensure the IPs of the peers and validators are well-formed.
+        for ip in self.peers.split(',').chain(self.validators.split(',')).filter(|ip| !ip.is_empty()) {
+            if let Err(error) = ip.parse::<SocketAddr>() {
+                bail!("The IP '{ip}' is malformed: {error}")
+            }
+        }
+        // Ensure the network is supported, and the private key is well-formed for it, if one is given.
+        match (self.network, &self.private_key) {
+            (0, Some(private_key)) => {
+                PrivateKey::<MainnetV0>::from_str(private_key.trim())?;
+            }
+            (0, None) => (),
+            (network, _) => bail!("Invalid network ID specified ({network})"),
+        }
+        // Ensure the files exist, if they are given.
+        let paths =
+            [&self.private_key_file, &self.keystore_password_file, &self.pool_token_file, &self.jwt_secret_file];
+        for path in paths.into_iter().flatten() {
+            ensure!(path.exists(), "The file '{}' does not exist", path.display());
+        }
+        // Ensure the number of workers is valid.
+        ensure!(
+            (1..=MAX_WORKERS).contains(&self.workers),
+            "The number of workers must be between 1 and {MAX_WORKERS} (found {})",
+            self.workers
+        );
+        // Ensure the router and prover configurations are valid.
+        self.parse_router_config()?;
+        self.parse_puzzle_cores()?;
+        Ok(())
+    }
+
     /// Returns the initial peer(s) to connect to, from the given configurations.
     fn parse_trusted_peers(&self) -> Result<Vec<SocketAddr>> {
         match self.peers.is_empty() {
@@ -219,12 +343,15 @@ impl Start {
         // Determine if the node type is not declared.
         let is_no_node_type = !(self.validator || self.prover || self.client);
 
+        // Determine if the node is in development mode, and the CDN is not a local directory.
+        let is_dev_with_remote_cdn = self.dev.is_some() && !self.cdn.starts_with("file://");
+
         // Disable CDN if:
-        //  1. The node is in development mode.
+        //  1. The node is in development mode, and the CDN is not a local directory.
         //  2. The user has explicitly disabled CDN.
         //  3. The node is a prover (no need to sync).
         //  4. The node type is not declared (defaults to client) (no need to sync).
-        if self.dev.is_some() || self.cdn.is_empty() || self.nocdn || self.prover || is_no_node_type {
+        if is_dev_with_remote_cdn || self.cdn.is_empty() || self.nocdn || self.prover || is_no_node_type {
             None
         }
         // Enable the CDN otherwise.
@@ -243,7 +370,27 @@ impl Start {
                 // Parse the private key from a file.
                 (None, Some(path)) => {
                     check_permissions(path)?;
-                    Account::from_str(std::fs::read_to_string(path)?.trim())
+                    let contents = Zeroizing::new(std::fs::read_to_string(path)?);
+                    match Keystore::from_str(&contents) {
+                        // Unlock the encrypted keystore with the password.
+                        Ok(keystore) => {
+                            if let Some(password_file) = &self.keystore_password_file {
+                                check_permissions(password_file)?;
+                            }
+                            let password = read_password(self.keystore_password_file.as_deref(), false)?;
+                            Account::try_from(keystore.decrypt::<N>(&password)?)
+                        }
+                        // If the file is a malformed keystore, report the error, instead of treating it as a private key.
+                        Err(error) if contents.trim_start().starts_with('{') => {
+                            bail!("Failed to parse the keystore {path:?} - {error}")
+                        }
+                        // Parse the plaintext private key.
+                        Err(_) => {
+                            let warning = "⚠️  The private key file is not encrypted, consider using 'snarkos account import' to create a keystore.";
+                            println!("{}\n", warning.yellow().bold());
+                            Account::from_str(contents.trim())
+                        }
+                    }
                 }
                 // Ensure the private key is provided to the CLI, except for clients or nodes in development mode.
                 (None, None) => match self.client {
@@ -272,6 +419,24 @@ impl Start {
         }
     }
 
+    /// Initializes the JWT secret and the revoked JWT tokens of the REST server, from the given configurations.
+    /// If no JWT secret is configured, the REST server samples a random secret, which is only valid for this process.
+    fn parse_jwt(&self) -> Result<()> {
+        // Ensure the JWT secret file is not accessible by other users.
+        if let Some(path) = &self.jwt_secret_file {
+            check_permissions(path)?;
+        }
+        // Set the JWT secret, if one is configured.
+        if let Some(secret) = snarkos_node_rest::load_jwt_secret(self.jwt_secret_file.as_deref())? {
+            snarkos_node_rest::set_jwt_secret(secret)?;
+        }
+        // Load the revoked JWT tokens.
+        if let Some(path) = &self.jwt_revocation_file {
+            snarkos_node_rest::load_revoked_tokens(path)?;
+        }
+        Ok(())
+    }
+
     /// Updates the configurations if the node is in development mode.
     fn parse_development(
         &mut self,
@@ -441,6 +606,75 @@ impl Start {
         }
     }
 
+    /// Returns the pool mode of the prover, from the given configurations.
+    fn parse_pool_mode(&self) -> Result<Option<PoolMode>> {
+        // If the prover is not in a mining pool, return early.
+        if self.pool_server.is_none() && self.pool.is_none() {
+            return Ok(None);
+        }
+        // Read the token from the file, ignoring the trailing newline, or from the environment variable.
+        let token = match &self.pool_token_file {
+            Some(path) => std::fs::read_to_string(path)?.trim_end_matches(['\r', '\n']).to_string(),
+            None => std::env::var(POOL_TOKEN_ENV)
+                .map_err(|_| anyhow!("The mining pool requires '--pool-token-file' or '{POOL_TOKEN_ENV}'"))?,
+        };
+        ensure!(!token.is_empty(), "The token of the mining pool must not be empty");
+        match (self.pool_server, self.pool) {
+            (Some(listener_ip), _) => Ok(Some(PoolMode::Server { listener_ip, token })),
+            (None, Some(server_ip)) => Ok(Some(PoolMode::Worker { server_ip, token })),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Returns the CPU core(s) to pin the coinbase puzzle instances to, from the given configurations.
+    fn parse_puzzle_cores(&self) -> Result<Vec<usize>> {
+        match &self.puzzle_cores {
+            None => Ok(vec![]),
+            Some(puzzle_cores) => puzzle_cores
+                .split(',')
+                .map(|core| {
+                    core.trim()
+                        .parse::<usize>()
+                        .map_err(|e| anyhow!("The core supplied to --puzzle-cores ('{core}') is malformed: {e}"))
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the configuration of the peer limits and the heartbeat policy of the router, from the given configurations.
+    fn parse_router_config(&self) -> Result<RouterConfig> {
+        // Start from the given maximum number of peers, or from the defaults of the node type.
+        let mut config = match self.max_peers {
+            Some(max_peers) => RouterConfig::new(max_peers),
+            None => RouterConfig::for_node_type(self.parse_node_type()),
+        };
+        // Override the defaults with the given configurations.
+        if let Some(min_peers) = self.min_peers {
+            config.minimum_number_of_peers = min_peers;
+        }
+        if let Some(max_provers) = self.max_provers {
+            config.maximum_number_of_provers = max_provers;
+        }
+        if let Some(heartbeat_interval) = self.heartbeat_interval {
+            config.heartbeat_in_secs = heartbeat_interval;
+        }
+        if let Some(radio_silence) = self.radio_silence {
+            config.radio_silence_in_secs = radio_silence;
+        }
+        if let Some(max_connection_failures) = self.max_connection_failures {
+            config.maximum_connection_failures = max_connection_failures;
+        }
+        if let Some(message_limit) = self.message_limit {
+            config.message_limit = message_limit;
+        }
+        if let Some(message_limit_time_frame) = self.message_limit_time_frame {
+            config.message_limit_time_frame_in_secs = message_limit_time_frame;
+        }
+        // Ensure the configuration is consistent.
+        config.check()?;
+        Ok(config)
+    }
+
     /// Returns the node type, from the given configurations.
     const fn parse_node_type(&self) -> NodeType {
         if self.validator {
@@ -465,6 +699,9 @@ impl Start {
         // Parse the development configurations.
         self.parse_development(&mut trusted_peers, &mut trusted_validators)?;
 
+        // Parse the router configuration.
+        let router_config = self.parse_router_config()?;
+
         // Parse the CDN.
         let cdn = self.parse_cdn();
 
@@ -472,6 +709,8 @@ impl Start {
         let genesis = self.parse_genesis::<N>()?;
         // Parse the private key of the node.
         let account = self.parse_private_key::<N>()?;
+        // Zeroize the private key in the configurations, now that the account is constructed.
+        self.private_key.zeroize();
         // Parse the node type.
         let node_type = self.parse_node_type();
 
@@ -480,6 +719,10 @@ impl Start {
             true => None,
             false => Some(self.rest),
         };
+        // Parse the JWT configurations of the REST server.
+        if rest_ip.is_some() {
+            self.parse_jwt()?;
+        }
 
         // If the display is not enabled, render the welcome message.
         if self.nodisplay {
@@ -498,7 +741,11 @@ impl Start {
                 if let Some(rest_ip) = rest_ip {
                     println!("🌐 Starting the REST server at {}.\n", rest_ip.to_string().bold());
 
-                    if let Ok(jwt_token) = snarkos_node_rest::Claims::new(account.address()).to_jwt_string() {
+                    // Note: The one-time JWT token is only granted read access, for a limited time.
+                    let scopes = snarkos_node_rest::STARTUP_SCOPES.to_vec();
+                    let claims =
+                        snarkos_node_rest::Claims::new(account.address(), scopes, snarkos_node_rest::STARTUP_EXPIRATION);
+                    if let Ok(jwt_token) = claims.to_jwt_string() {
                         println!("🔑 Your one-time JWT token is {}\n", jwt_token.dimmed());
                     }
                 }
@@ -514,8 +761,9 @@ impl Start {
         crate::helpers::check_validator_machine(node_type);
 
         // Initialize the metrics.
+        // Note: If the REST server is enabled, the metrics are served on its `/metrics` route instead of an exporter.
         if self.metrics {
-            metrics::initialize_metrics();
+            metrics::initialize_metrics(rest_ip.is_none());
         }
 
         // Initialize the storage mode.
@@ -526,10 +774,11 @@ impl Start {
 
         // Initialize the node.
         let bft_ip = if self.dev.is_some() { self.bft } else { None };
+        let graceful_shutdown = self.graceful_shutdown.map(Duration::from_secs);
         match node_type {
-            NodeType::Validator => Node::new_validator(self.node, bft_ip, rest_ip, self.rest_rps, account, &trusted_peers, &trusted_validators, genesis, cdn, storage_mode).await,
-            NodeType::Prover => Node::new_prover(self.node, account, &trusted_peers, genesis, storage_mode).await,
-            NodeType::Client => Node::new_client(self.node, rest_ip, self.rest_rps, account, &trusted_peers, genesis, cdn, storage_mode).await,
+            NodeType::Validator => Node::new_validator(self.node, bft_ip, rest_ip, self.rest_rps, self.rest_index, self.rest_mapping_history, account, &trusted_peers, &trusted_validators, self.workers, router_config, genesis, cdn, storage_mode, graceful_shutdown).await,
+            NodeType::Prover => Node::new_prover(self.node, account, &trusted_peers, router_config, genesis, storage_mode, self.parse_pool_mode()?, self.puzzle_instances, self.parse_puzzle_cores()?).await,
+            NodeType::Client => Node::new_client(self.node, rest_ip, self.rest_rps, self.rest_index, self.rest_mapping_history, account, &trusted_peers, router_config, genesis, cdn, storage_mode).await,
         }
     }
 
@@ -719,6 +968,11 @@ mod tests {
         )
         .unwrap();
         assert!(config.parse_cdn().is_none());
+        let config = Start::try_parse_from(
+            ["snarkos", "--dev", "0", "--validator", "--private-key", "aleo1xx", "--cdn", "file:///tmp/blocks"].iter(),
+        )
+        .unwrap();
+        assert!(config.parse_cdn().is_some());
 
         // Prover (Prod)
         let config = Start::try_parse_from(["snarkos", "--prover", "--private-key", "aleo1xx"].iter()).unwrap();
@@ -787,6 +1041,177 @@ mod tests {
         assert!(config.parse_cdn().is_none());
     }
 
+    #[test]
+    fn test_parse_private_key_from_keystore() {
+        // Prepare a directory that is readable only by the owner.
+        let directory = tempfile::tempdir().unwrap();
+        let password_file = directory.path().join("password");
+        std::fs::write(&password_file, "password\n").unwrap();
+        #[cfg(target_family = "unix")]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(directory.path(), std::fs::Permissions::from_mode(0o700)).unwrap();
+            std::fs::set_permissions(&password_file, std::fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        // Encrypt a private key into a keystore.
+        let private_key = PrivateKey::<CurrentNetwork>::new(&mut rand::thread_rng()).unwrap();
+        let keystore = directory.path().join("keystore.json");
+        Keystore::encrypt(&private_key, "password", &mut rand::thread_rng()).unwrap().save(&keystore).unwrap();
+
+        // Unlock the keystore with the password file.
+        let config = Start::try_parse_from(
+            [
+                "snarkos",
+                "--private-key-file",
+                keystore.to_str().unwrap(),
+                "--keystore-password-file",
+                password_file.to_str().unwrap(),
+            ]
+            .iter(),
+        )
+        .unwrap();
+        assert_eq!(*config.parse_private_key::<CurrentNetwork>().unwrap().private_key(), private_key);
+
+        // Ensure an incorrect password is rejected.
+        std::fs::write(&password_file, "wrong password").unwrap();
+        assert!(config.parse_private_key::<CurrentNetwork>().is_err());
+
+        // Ensure the password file requires the private key file.
+        let config = Start::try_parse_from(
+            ["snarkos", "--private-key", &private_key.to_string(), "--keystore-password-file", "password"].iter(),
+        );
+        assert!(config.is_err());
+
+        // Ensure a malformed keystore is reported, instead of being parsed as a plaintext private key.
+        let malformed = directory.path().join("malformed.json");
+        std::fs::write(&malformed, r#"{ "version": 1 }"#).unwrap();
+        #[cfg(target_family = "unix")]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&malformed, std::fs::Permissions::from_mode(0o600)).unwrap();
+        }
+        let config =
+            Start::try_parse_from(["snarkos", "--private-key-file", malformed.to_str().unwrap()].iter()).unwrap();
+        let error = config.parse_private_key::<CurrentNetwork>().unwrap_err();
+        assert!(error.to_string().starts_with("Failed to parse the keystore"), "{error}");
+    }
+
+    #[test]
+    fn test_parse_pool_mode() {
+        let directory = tempfile::tempdir().unwrap();
+        let token_file = directory.path().join("pool.token");
+        std::fs::write(&token_file, "secret\n").unwrap();
+        let token_file = token_file.to_str().unwrap();
+
+        // Ensure the pool is disabled by default.
+        let config = Start::try_parse_from(["snarkos", "--prover"].iter()).unwrap();
+        assert_eq!(config.parse_pool_mode().unwrap(), None);
+
+        // Ensure the prover may serve a pool.
+        let config = Start::try_parse_from(
+            ["snarkos", "--prover", "--pool-server", "0.0.0.0:4140", "--pool-token-file", token_file].iter(),
+        )
+        .unwrap();
+        let expected = PoolMode::Server { listener_ip: "0.0.0.0:4140".parse().unwrap(), token: "secret".to_string() };
+        assert_eq!(config.parse_pool_mode().unwrap(), Some(expected));
+
+        // Ensure the prover may be a pool worker.
+        let config = Start::try_parse_from(
+            ["snarkos", "--prover", "--pool", "127.0.0.1:4140", "--pool-token-file", token_file].iter(),
+        )
+        .unwrap();
+        let expected = PoolMode::Worker { server_ip: "127.0.0.1:4140".parse().unwrap(), token: "secret".to_string() };
+        assert_eq!(config.parse_pool_mode().unwrap(), Some(expected));
+
+        // Ensure the token must not be empty.
+        std::fs::write(token_file, "\n").unwrap();
+        assert!(config.parse_pool_mode().is_err());
+
+        // Ensure the pool requires a prover, and a prover cannot both serve and join a pool.
+        assert!(Start::try_parse_from(["snarkos", "--pool", "127.0.0.1:4140"].iter()).is_err());
+        assert!(Start::try_parse_from(
+            ["snarkos", "--prover", "--pool-server", "0.0.0.0:4140", "--pool", "127.0.0.1:4140"].iter()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_puzzle_cores() {
+        // Ensure the puzzle cores are empty by default.
+        let config = Start::try_parse_from(["snarkos", "--prover"].iter()).unwrap();
+        assert!(config.parse_puzzle_cores().unwrap().is_empty());
+        assert_eq!(config.puzzle_instances, None);
+
+        // Ensure the puzzle instances and cores are parsed.
+        let config = Start::try_parse_from(
+            ["snarkos", "--prover", "--puzzle-instances", "2", "--puzzle-cores", "0, 2,3"].iter(),
+        )
+        .unwrap();
+        assert_eq!(config.puzzle_instances, Some(2));
+        assert_eq!(config.parse_puzzle_cores().unwrap(), vec![0, 2, 3]);
+
+        // Ensure a malformed core is rejected.
+        let config = Start::try_parse_from(["snarkos", "--prover", "--puzzle-cores", "0,a"].iter()).unwrap();
+        assert!(config.parse_puzzle_cores().is_err());
+
+        // Ensure the puzzle options require a prover.
+        assert!(Start::try_parse_from(["snarkos", "--puzzle-instances", "2"].iter()).is_err());
+        assert!(Start::try_parse_from(["snarkos", "--puzzle-cores", "0"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_workers() {
+        // Ensure the number of workers defaults to 1, and may be given to a validator.
+        assert_eq!(Start::try_parse_from(["snarkos"].iter()).unwrap().workers, 1);
+        let config = Start::try_parse_from(["snarkos", "--validator", "--workers", "4"].iter()).unwrap();
+        assert_eq!(config.workers, 4);
+        config.check().unwrap();
+
+        // Ensure the workers require a validator, and are bounded.
+        assert!(Start::try_parse_from(["snarkos", "--workers", "4"].iter()).is_err());
+        let config = Start::try_parse_from(["snarkos", "--validator", "--workers", "9"].iter()).unwrap();
+        assert!(config.check().is_err());
+    }
+
+    #[test]
+    fn test_parse_router_config() {
+        // Ensure the defaults depend on the node type.
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_router_config().unwrap(), RouterConfig::default());
+        let config = Start::try_parse_from(["snarkos", "--validator"].iter()).unwrap();
+        assert_eq!(config.parse_router_config().unwrap().maximum_number_of_peers, 200);
+
+        // Ensure the given configurations override the defaults.
+        let config = Start::try_parse_from(
+            [
+                "snarkos",
+                "--max-peers",
+                "100",
+                "--min-peers",
+                "10",
+                "--heartbeat-interval",
+                "5",
+                "--message-limit",
+                "1000",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let router_config = config.parse_router_config().unwrap();
+        assert_eq!(router_config.maximum_number_of_peers, 100);
+        assert_eq!(router_config.maximum_number_of_provers, 25);
+        assert_eq!(router_config.minimum_number_of_peers, 10);
+        assert_eq!(router_config.heartbeat_in_secs, 5);
+        assert_eq!(router_config.message_limit, 1000);
+
+        // Ensure an inconsistent configuration is rejected at startup.
+        let config = Start::try_parse_from(["snarkos", "--max-peers", "5", "--min-peers", "10"].iter()).unwrap();
+        assert!(config.parse_router_config().is_err());
+        let config = Start::try_parse_from(["snarkos", "--max-peers", "5", "--max-provers", "6"].iter()).unwrap();
+        assert!(config.parse_router_config().is_err());
+    }
+
     #[test]
     fn test_parse_development_and_genesis() {
         let prod_genesis = Block::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
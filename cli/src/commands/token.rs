@@ -0,0 +1,148 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_rest::{load_jwt_secret, set_jwt_secret, Claims, Scope, EXPIRATION, JWT_SECRET_ENV};
+use snarkvm::console::account::Address;
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use colored::Colorize;
+use core::str::FromStr;
+use std::{io::Write, path::PathBuf};
+
+type Network = snarkvm::prelude::MainnetV0;
+
+/// Commands to manage the JWT tokens of the REST server.
+#[derive(Debug, Parser)]
+pub enum Token {
+    /// Mints a new JWT token, signed with the JWT secret of the REST server
+    Mint {
+        /// Specify the Aleo address the token is issued to
+        #[clap(long)]
+        address: String,
+        /// Specify the comma-separated scopes granted to the token [options: admin, node:read, peers:read, peers:write]
+        #[clap(default_value = "node:read", long)]
+        scopes: String,
+        /// Specify the number of seconds the token is valid for
+        #[clap(default_value_t = EXPIRATION, long)]
+        expiry: i64,
+        /// Specify the path to a file containing the JWT secret (default: the `SNARKOS_JWT_SECRET` environment variable)
+        #[clap(long = "jwt-secret-file")]
+        jwt_secret_file: Option<PathBuf>,
+    },
+    /// Revokes a JWT token, by appending its ID to the revocation file of the REST server
+    Revoke {
+        /// Specify the ID of the token to revoke
+        #[clap(long)]
+        id: String,
+        /// Specify the path to the file containing the IDs of the revoked JWT tokens
+        #[clap(long = "jwt-revocation-file")]
+        jwt_revocation_file: PathBuf,
+    },
+}
+
+impl Token {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Mint { address, scopes, expiry, jwt_secret_file } => {
+                Self::mint(&address, &scopes, expiry, jwt_secret_file)
+            }
+            Self::Revoke { id, jwt_revocation_file } => Self::revoke(&id, jwt_revocation_file),
+        }
+    }
+
+    /// Mints a new JWT token for the given address, scopes, and expiry.
+    fn mint(address: &str, scopes: &str, expiry: i64, jwt_secret_file: Option<PathBuf>) -> Result<String> {
+        // Ensure the expiry is positive.
+        if expiry <= 0 {
+            bail!("The '--expiry' must be a positive number of seconds");
+        }
+        // Parse the address.
+        let address = Address::<Network>::from_str(address.trim())?;
+        // Parse the scopes.
+        let scopes = scopes.split(',').map(|scope| Scope::from_str(scope.trim())).collect::<Result<Vec<_>>>()?;
+        if scopes.is_empty() {
+            bail!("At least one scope must be granted to the token");
+        }
+
+        // Load the JWT secret. Note: A random secret would not be accepted by the REST server.
+        let Some(secret) = load_jwt_secret(jwt_secret_file.as_deref())? else {
+            bail!("Missing the '--jwt-secret-file' argument or the '{JWT_SECRET_ENV}' environment variable")
+        };
+        set_jwt_secret(secret)?;
+
+        // Mint the token.
+        let claims = Claims::new(address, scopes, expiry);
+        let jwt_token = claims.to_jwt_string()?;
+
+        Ok(format!(" {:>12}  {}\n {:>12}  {}", "Token ID".cyan().bold(), claims.id(), "Token".cyan().bold(), jwt_token))
+    }
+
+    /// Appends the given token ID to the revocation file.
+    fn revoke(id: &str, jwt_revocation_file: PathBuf) -> Result<String> {
+        // Ensure the token ID is not empty.
+        let id = id.trim();
+        if id.is_empty() {
+            bail!("The token ID must not be empty");
+        }
+        // Append the token ID to the revocation file.
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&jwt_revocation_file)?;
+        writeln!(file, "{id}")?;
+
+        Ok(format!(
+            "✅ Revoked the JWT token '{id}' (takes effect on restart, or use 'POST /mainnet/node/tokens/revoke')"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mint() {
+        // Write the JWT secret to a file.
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path().to_path_buf();
+        std::fs::write(&path, "a-sufficiently-long-jwt-secret\n").unwrap();
+
+        let address = "aleo1uxl69laseuv3876ksh8k0nd7tvpgjt6ccrgccedpjk9qwyfensxst9ftg5";
+        let command = Token::Mint {
+            address: address.to_string(),
+            scopes: "node:read,peers:write".to_string(),
+            expiry: 60,
+            jwt_secret_file: Some(path),
+        };
+        let output = command.parse().unwrap();
+
+        // Ensure the minted token is verified with the same secret, and carries the scopes.
+        let jwt_token = output.split_whitespace().last().unwrap();
+        let claims = Claims::from_jwt_string(jwt_token).unwrap();
+        assert_eq!(claims.scopes(), &[Scope::NodeRead, Scope::PeersWrite]);
+        assert!(claims.has_scope(Scope::PeersWrite));
+        assert!(!claims.has_scope(Scope::PeersRead));
+        assert!(!claims.is_expired());
+    }
+
+    #[test]
+    fn test_mint_invalid_scope() {
+        let command = Token::Mint {
+            address: "aleo1uxl69laseuv3876ksh8k0nd7tvpgjt6ccrgccedpjk9qwyfensxst9ftg5".to_string(),
+            scopes: "peers:delete".to_string(),
+            expiry: 60,
+            jwt_secret_file: None,
+        };
+        assert!(command.parse().is_err());
+    }
+}
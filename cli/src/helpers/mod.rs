@@ -18,6 +18,9 @@ pub use bech32m::*;
 mod log_writer;
 use log_writer::*;
 
+mod password;
+pub use password::*;
+
 pub mod logger;
 pub use logger::*;
 
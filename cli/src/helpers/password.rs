@@ -0,0 +1,49 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{ensure, Result};
+use std::path::Path;
+use zeroize::Zeroizing;
+
+/// The environment variable containing the keystore password.
+pub const KEYSTORE_PASSWORD_ENV: &str = "SNARKOS_KEYSTORE_PASSWORD";
+
+/// Returns the keystore password, read from the given file if one is given, or otherwise from
+/// the `SNARKOS_KEYSTORE_PASSWORD` environment variable if it is set, or otherwise from a prompt.
+///
+/// If `confirm` is set (i.e. to encrypt a new keystore), the environment variable is ignored,
+/// and the prompted password must be entered twice.
+pub fn read_password(password_file: Option<&Path>, confirm: bool) -> Result<Zeroizing<String>> {
+    // Read the password from the file, ignoring the trailing newline.
+    if let Some(path) = password_file {
+        let password = Zeroizing::new(std::fs::read_to_string(path)?);
+        let password = Zeroizing::new(password.trim_end_matches(['\r', '\n']).to_string());
+        ensure!(!password.is_empty(), "The password file {path:?} is empty");
+        return Ok(password);
+    }
+    // Read the password from the environment variable, if the keystore is being unlocked.
+    if let (false, Ok(password)) = (confirm, std::env::var(KEYSTORE_PASSWORD_ENV)) {
+        let password = Zeroizing::new(password);
+        ensure!(!password.is_empty(), "The '{KEYSTORE_PASSWORD_ENV}' environment variable is empty");
+        return Ok(password);
+    }
+    // Prompt for the password.
+    let password = Zeroizing::new(rpassword::prompt_password("Enter the keystore password: ")?);
+    ensure!(!password.is_empty(), "The password must not be empty");
+    if confirm {
+        let confirmation = Zeroizing::new(rpassword::prompt_password("Confirm the keystore password: ")?);
+        ensure!(*password == *confirmation, "The passwords do not match");
+    }
+    Ok(password)
+}
@@ -172,6 +172,7 @@ impl<N: Network> Display<N> {
         match self.tabs.index {
             0 => Overview.draw(f, chunks[1], &self.node),
             1 => self.logs.draw(f, chunks[1]),
+            2 => Prover.draw(f, chunks[1], &self.node),
             _ => unreachable!(),
         };
     }
@@ -18,4 +18,7 @@ pub(crate) use logs::Logs;
 mod overview;
 pub(crate) use overview::Overview;
 
-pub(crate) const PAGES: [&str; 2] = [" Overview ", " Logs "];
+mod prover;
+pub(crate) use prover::Prover;
+
+pub(crate) const PAGES: [&str; 3] = [" Overview ", " Logs ", " Prover "];
@@ -33,8 +33,20 @@ impl Overview {
             .constraints([Constraint::Percentage(10), Constraint::Percentage(70), Constraint::Max(2)].as_ref())
             .split(area);
 
-        let canvas = Canvas::default().block(Block::default().borders(Borders::ALL).title("Block")).paint(|_ctx| {
+        // Retrieve the progress of the CDN sync.
+        let cdn_progress = snarkos_node::cdn::sync_progress();
+
+        let canvas = Canvas::default().block(Block::default().borders(Borders::ALL).title("Block")).paint(|ctx| {
             // ctx.draw(&ball);
+            if cdn_progress.end_height > 0 {
+                let status = format!(
+                    "CDN sync: block {} of {} ({:.2}% complete)",
+                    cdn_progress.current_height,
+                    cdn_progress.end_height.saturating_sub(1),
+                    cdn_progress.percentage()
+                );
+                ctx.print(0f64, 0f64, Span::styled(status, Style::default().fg(Color::White)));
+            }
         });
         f.render_widget(canvas, chunks[0]);
 
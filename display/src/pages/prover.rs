@@ -0,0 +1,77 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node::{Node, HASHRATE_WINDOWS_IN_SECS};
+use snarkvm::prelude::Network;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Row, Table},
+    Frame,
+};
+
+pub(crate) struct Prover;
+
+impl Prover {
+    pub(crate) fn draw<N: Network>(&self, f: &mut Frame, area: Rect, node: &Node<N>) {
+        // Initialize the layout of the page.
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(6), Constraint::Min(0)].as_ref())
+            .split(area);
+
+        // Retrieve the statistics of the prover.
+        let Some(stats) = node.prover_stats() else {
+            let paragraph = Paragraph::new("The prover statistics are only available on a prover node.")
+                .block(Block::default().borders(Borders::ALL).title("Prover"));
+            f.render_widget(paragraph, area);
+            return;
+        };
+
+        // Render the hashrate and totals of the prover.
+        let [window_1m, window_15m, window_1h] = HASHRATE_WINDOWS_IN_SECS;
+        let summary = vec![
+            Line::from(format!(
+                "Hashrate: {:.2} proofs/s (1m), {:.2} proofs/s (15m), {:.2} proofs/s (1h)",
+                stats.hashrate(window_1m),
+                stats.hashrate(window_15m),
+                stats.hashrate(window_1h)
+            )),
+            Line::from(format!("Proofs attempted: {}", stats.proofs_attempted())),
+            Line::from(format!("Solutions found: {}", stats.solutions_found())),
+        ];
+        let summary = Paragraph::new(summary).block(Block::default().borders(Borders::ALL).title("Prover"));
+        f.render_widget(summary, chunks[0]);
+
+        // Render the statistics of each puzzle instance.
+        let rows = stats.instances().into_iter().enumerate().map(|(instance, stats)| {
+            Row::new(vec![
+                instance.to_string(),
+                stats.proofs_attempted.to_string(),
+                stats.solutions_found.to_string(),
+                stats.epoch_number.to_string(),
+                stats.best_target.to_string(),
+            ])
+        });
+        let header = Row::new(vec!["Instance", "Proofs", "Solutions", "Epoch", "Best Target"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let widths = [Constraint::Percentage(20); 5];
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title("Puzzle Instances"));
+        f.render_widget(table, chunks[1]);
+    }
+}
@@ -28,6 +28,8 @@ pub enum DisconnectReason {
     ProtocolViolation,
     /// The peer's client is outdated, judging by its version.
     OutdatedClientVersion,
+    /// The node is shutting down.
+    ShuttingDown,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -63,6 +65,7 @@ impl FromBytes for Disconnect {
             Ok(1) => DisconnectReason::NoReasonGiven,
             Ok(2) => DisconnectReason::ProtocolViolation,
             Ok(3) => DisconnectReason::OutdatedClientVersion,
+            Ok(4) => DisconnectReason::ShuttingDown,
             _ => return Err(io::Error::new(io::ErrorKind::Other, "Invalid 'Disconnect' event")),
         };
 
@@ -85,6 +88,7 @@ mod tests {
             DisconnectReason::NoReasonGiven,
             DisconnectReason::InvalidChallengeResponse,
             DisconnectReason::OutdatedClientVersion,
+            DisconnectReason::ShuttingDown,
         ];
 
         for reason in all_reasons.iter() {
@@ -21,12 +21,9 @@ use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
 use tracing::*;
 
 /// The maximum size of an event that can be transmitted during the handshake.
-const MAX_HANDSHAKE_SIZE: usize = 1024 * 1024; // 1 MiB
+pub const MAX_HANDSHAKE_SIZE: usize = 1024 * 1024; // 1 MiB
 /// The maximum size of an event that can be transmitted in the network.
-const MAX_EVENT_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
-
-/// The type of noise handshake to use for network encryption.
-pub const NOISE_HANDSHAKE_TYPE: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+pub const MAX_EVENT_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
 
 /// The codec used to decode and encode network `Event`s.
 pub struct EventCodec<N: Network> {
@@ -55,6 +52,10 @@ impl<N: Network> Encoder<Event<N>> for EventCodec<N> {
     type Error = std::io::Error;
 
     fn encode(&mut self, event: Event<N>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        // Retrieve the event type, to label the metrics.
+        #[cfg(feature = "metrics")]
+        let event_type = event.type_name();
+
         // Serialize the payload directly into dst.
         event
             .write_le(&mut dst.writer())
@@ -63,6 +64,17 @@ impl<N: Network> Encoder<Event<N>> for EventCodec<N> {
 
         let serialized_event = dst.split_to(dst.len()).freeze();
 
+        #[cfg(feature = "metrics")]
+        {
+            let labels = [("type", event_type.to_string())];
+            metrics::increment_counter_with_labels(metrics::bft::EVENTS_OUTBOUND, 1, &labels);
+            metrics::increment_counter_with_labels(
+                metrics::bft::EVENTS_OUTBOUND_BYTES,
+                serialized_event.len() as u64,
+                &labels,
+            );
+        }
+
         self.codec.encode(serialized_event, dst)
     }
 }
@@ -78,10 +90,26 @@ impl<N: Network> Decoder for EventCodec<N> {
             None => return Ok(None),
         };
 
+        // Retrieve the number of bytes, to record the metrics.
+        #[cfg(feature = "metrics")]
+        let num_bytes = bytes.len();
+
         // Convert the bytes to an event, or fail if it is not valid.
         let reader = bytes.reader();
         match Event::read_le(reader) {
-            Ok(event) => Ok(Some(event)),
+            Ok(event) => {
+                #[cfg(feature = "metrics")]
+                {
+                    let labels = [("type", event.type_name().to_string())];
+                    metrics::increment_counter_with_labels(metrics::bft::EVENTS_INBOUND, 1, &labels);
+                    metrics::increment_counter_with_labels(
+                        metrics::bft::EVENTS_INBOUND_BYTES,
+                        num_bytes as u64,
+                        &labels,
+                    );
+                }
+                Ok(Some(event))
+            }
             Err(error) => {
                 error!("Failed to deserialize an event: {}", error);
                 Err(std::io::ErrorKind::InvalidData.into())
@@ -50,6 +50,9 @@ pub use helpers::*;
 mod primary_ping;
 pub use primary_ping::PrimaryPing;
 
+mod primary_pong;
+pub use primary_pong::PrimaryPong;
+
 mod transmission_request;
 pub use transmission_request::TransmissionRequest;
 
@@ -102,6 +105,7 @@ pub enum Event<N: Network> {
     ChallengeResponse(ChallengeResponse<N>),
     Disconnect(Disconnect),
     PrimaryPing(PrimaryPing<N>),
+    PrimaryPong(PrimaryPong),
     TransmissionRequest(TransmissionRequest<N>),
     TransmissionResponse(TransmissionResponse<N>),
     ValidatorsRequest(ValidatorsRequest),
@@ -117,7 +121,7 @@ impl<N: Network> From<DisconnectReason> for Event<N> {
 
 impl<N: Network> Event<N> {
     /// The version of the event protocol; it can be incremented in order to force users to update.
-    pub const VERSION: u32 = 6;
+    pub const VERSION: u32 = 9;
 
     /// Returns the event name.
     #[inline]
@@ -134,6 +138,7 @@ impl<N: Network> Event<N> {
             Self::ChallengeResponse(event) => event.name(),
             Self::Disconnect(event) => event.name(),
             Self::PrimaryPing(event) => event.name(),
+            Self::PrimaryPong(event) => event.name(),
             Self::TransmissionRequest(event) => event.name(),
             Self::TransmissionResponse(event) => event.name(),
             Self::ValidatorsRequest(event) => event.name(),
@@ -142,6 +147,30 @@ impl<N: Network> Event<N> {
         }
     }
 
+    /// Returns the event type name, without any event-specific details.
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::BatchPropose(..) => "BatchPropose",
+            Self::BatchSignature(..) => "BatchSignature",
+            Self::BatchCertified(..) => "BatchCertified",
+            Self::BlockRequest(..) => "BlockRequest",
+            Self::BlockResponse(..) => "BlockResponse",
+            Self::CertificateRequest(..) => "CertificateRequest",
+            Self::CertificateResponse(..) => "CertificateResponse",
+            Self::ChallengeRequest(..) => "ChallengeRequest",
+            Self::ChallengeResponse(..) => "ChallengeResponse",
+            Self::Disconnect(..) => "Disconnect",
+            Self::PrimaryPing(..) => "PrimaryPing",
+            Self::PrimaryPong(..) => "PrimaryPong",
+            Self::TransmissionRequest(..) => "TransmissionRequest",
+            Self::TransmissionResponse(..) => "TransmissionResponse",
+            Self::ValidatorsRequest(..) => "ValidatorsRequest",
+            Self::ValidatorsResponse(..) => "ValidatorsResponse",
+            Self::WorkerPing(..) => "WorkerPing",
+        }
+    }
+
     /// Returns the event ID.
     #[inline]
     pub fn id(&self) -> u16 {
@@ -162,6 +191,7 @@ impl<N: Network> Event<N> {
             Self::ValidatorsRequest(..) => 13,
             Self::ValidatorsResponse(..) => 14,
             Self::WorkerPing(..) => 15,
+            Self::PrimaryPong(..) => 16,
         }
     }
 }
@@ -182,6 +212,7 @@ impl<N: Network> ToBytes for Event<N> {
             Self::ChallengeResponse(event) => event.write_le(writer),
             Self::Disconnect(event) => event.write_le(writer),
             Self::PrimaryPing(event) => event.write_le(writer),
+            Self::PrimaryPong(event) => event.write_le(writer),
             Self::TransmissionRequest(event) => event.write_le(writer),
             Self::TransmissionResponse(event) => event.write_le(writer),
             Self::ValidatorsRequest(event) => event.write_le(writer),
@@ -214,7 +245,8 @@ impl<N: Network> FromBytes for Event<N> {
             13 => Self::ValidatorsRequest(ValidatorsRequest::read_le(&mut reader)?),
             14 => Self::ValidatorsResponse(ValidatorsResponse::read_le(&mut reader)?),
             15 => Self::WorkerPing(WorkerPing::read_le(&mut reader)?),
-            16.. => return Err(error("Unknown event ID {id}")),
+            16 => Self::PrimaryPong(PrimaryPong::read_le(&mut reader)?),
+            17.. => return Err(error("Unknown event ID {id}")),
         };
 
         // Ensure that there are no "dangling" bytes.
@@ -261,6 +293,7 @@ pub mod prop_tests {
         Disconnect,
         DisconnectReason,
         Event,
+        PrimaryPong,
     };
     use snarkvm::{
         console::{network::Network, types::Field},
@@ -315,10 +348,12 @@ pub mod prop_tests {
                     DisconnectReason::NoReasonGiven,
                     DisconnectReason::InvalidChallengeResponse,
                     DisconnectReason::OutdatedClientVersion,
+                    DisconnectReason::ShuttingDown,
                 ]),
                 any::<Selector>()
             )
                 .prop_map(|(reasons, selector)| Event::Disconnect(Disconnect::from(selector.select(reasons)))),
+            Just(Event::PrimaryPong(PrimaryPong)),
             any_transmission_request().prop_map(Event::TransmissionRequest),
             any_transmission_response().prop_map(Event::TransmissionResponse),
             any_worker_ping().prop_map(Event::WorkerPing)
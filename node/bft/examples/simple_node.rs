@@ -23,7 +23,7 @@ use snarkos_node_bft::{
     MEMORY_POOL_PORT,
 };
 use snarkos_node_bft_ledger_service::MockLedgerService;
-use snarkos_node_bft_storage_service::BFTMemoryService;
+use snarkos_node_bft_storage_service::{BFTMemoryCertificateService, BFTMemoryService};
 use snarkvm::{
     ledger::{
         block::Transaction,
@@ -102,6 +102,7 @@ pub fn initialize_logger(verbosity: u8) {
 pub async fn start_bft(
     node_id: u16,
     num_nodes: u16,
+    num_workers: u8,
     peers: HashMap<u16, SocketAddr>,
 ) -> Result<(BFT<CurrentNetwork>, PrimarySender<CurrentNetwork>)> {
     // Initialize the primary channels.
@@ -114,6 +115,7 @@ pub async fn start_bft(
     let storage = Storage::new(
         ledger.clone(),
         Arc::new(BFTMemoryService::new()),
+        Arc::new(BFTMemoryCertificateService::new()),
         BatchHeader::<CurrentNetwork>::MAX_GC_ROUNDS as u64,
     );
     // Initialize the gateway IP and dev mode.
@@ -128,7 +130,7 @@ pub async fn start_bft(
     // Initialize the consensus receiver handler.
     consensus_handler(consensus_receiver);
     // Initialize the BFT instance.
-    let mut bft = BFT::<CurrentNetwork>::new(account, storage, ledger, ip, &trusted_validators, dev)?;
+    let mut bft = BFT::<CurrentNetwork>::new(account, storage, ledger, ip, &trusted_validators, num_workers, dev)?;
     // Run the BFT instance.
     bft.run(Some(consensus_sender), sender.clone(), receiver).await?;
     // Retrieve the BFT's primary.
@@ -143,6 +145,7 @@ pub async fn start_bft(
 pub async fn start_primary(
     node_id: u16,
     num_nodes: u16,
+    num_workers: u8,
     peers: HashMap<u16, SocketAddr>,
 ) -> Result<(Primary<CurrentNetwork>, PrimarySender<CurrentNetwork>)> {
     // Initialize the primary channels.
@@ -155,6 +158,7 @@ pub async fn start_primary(
     let storage = Storage::new(
         ledger.clone(),
         Arc::new(BFTMemoryService::new()),
+        Arc::new(BFTMemoryCertificateService::new()),
         BatchHeader::<CurrentNetwork>::MAX_GC_ROUNDS as u64,
     );
     // Initialize the gateway IP and dev mode.
@@ -165,7 +169,8 @@ pub async fn start_primary(
     // Initialize the trusted validators.
     let trusted_validators = trusted_validators(node_id, num_nodes, peers);
     // Initialize the primary instance.
-    let mut primary = Primary::<CurrentNetwork>::new(account, storage, ledger, ip, &trusted_validators, dev)?;
+    let mut primary =
+        Primary::<CurrentNetwork>::new(account, storage, ledger, ip, &trusted_validators, num_workers, dev)?;
     // Run the primary instance.
     primary.run(None, sender.clone(), receiver).await?;
     // Handle OS signals.
@@ -432,6 +437,9 @@ struct Args {
     /// The number of nodes in the network.
     #[arg(long, value_name = "N")]
     num_nodes: u16,
+    /// The number of workers of the node.
+    #[arg(long, value_name = "N", default_value = "1")]
+    workers: u8,
     /// If set, the path to the file containing the committee peers.
     #[arg(long, value_name = "PATH")]
     peers: Option<PathBuf>,
@@ -483,13 +491,13 @@ async fn main() -> Result<()> {
     let (primary, sender) = match args.mode {
         Mode::Bft => {
             // Start the BFT.
-            let (bft, sender) = start_bft(args.id, args.num_nodes, peers).await?;
+            let (bft, sender) = start_bft(args.id, args.num_nodes, args.workers, peers).await?;
             // Set the BFT holder.
             bft_holder = Some(bft.clone());
             // Return the primary and sender.
             (bft.primary().clone(), sender)
         }
-        Mode::Narwhal => start_primary(args.id, args.num_nodes, peers).await?,
+        Mode::Narwhal => start_primary(args.id, args.num_nodes, args.workers, peers).await?,
     };
 
     // The default interval to fire transmissions at.
@@ -517,7 +525,7 @@ async fn main() -> Result<()> {
     #[cfg(feature = "metrics")]
     if args.metrics {
         info!("Initializing metrics...");
-        metrics::initialize_metrics();
+        metrics::initialize_metrics(true);
     }
 
     // Start the monitoring server.
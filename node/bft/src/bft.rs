@@ -83,10 +83,11 @@ impl<N: Network> BFT<N> {
         ledger: Arc<dyn LedgerService<N>>,
         ip: Option<SocketAddr>,
         trusted_validators: &[SocketAddr],
+        num_workers: u8,
         dev: Option<u16>,
     ) -> Result<Self> {
         Ok(Self {
-            primary: Primary::new(account, storage, ledger, ip, trusted_validators, dev)?,
+            primary: Primary::new(account, storage, ledger, ip, trusted_validators, num_workers, dev)?,
             dag: Default::default(),
             leader_certificate: Default::default(),
             leader_certificate_timer: Default::default(),
@@ -810,6 +811,9 @@ impl<N: Network> BFT<N> {
     /// This method commits all the certificates into the DAG.
     /// Note that there is no need to insert the certificates into the DAG, because these certificates
     /// already exist in the ledger and therefore do not need to be re-ordered into future committed subdags.
+    ///
+    /// Afterwards, the uncommitted certificates that were reloaded into storage from a previous run
+    /// are inserted into the DAG, so that they can be ordered into future committed subdags.
     async fn sync_bft_dag_at_bootup(&self, certificates: Vec<BatchCertificate<N>>) {
         // Acquire the BFT write lock.
         let mut dag = self.dag.write();
@@ -818,6 +822,18 @@ impl<N: Network> BFT<N> {
         for certificate in certificates {
             dag.commit(&certificate, self.storage().max_gc_rounds());
         }
+
+        // Retrieve the last committed round.
+        let last_committed_round = dag.last_committed_round();
+        // Insert the uncommitted certificates from storage, starting after the last committed round.
+        for round in last_committed_round.saturating_add(1)..=self.storage().current_round() {
+            for certificate in self.storage().get_certificates_for_round(round) {
+                // Skip the certificate if it was already committed.
+                if !dag.is_recently_committed(round, certificate.id()) {
+                    dag.insert(certificate);
+                }
+            }
+        }
     }
 
     /// Spawns a task with the given future; it should only be used for long-running tasks.
@@ -845,7 +861,7 @@ mod tests {
     };
     use snarkos_account::Account;
     use snarkos_node_bft_ledger_service::MockLedgerService;
-    use snarkos_node_bft_storage_service::BFTMemoryService;
+    use snarkos_node_bft_storage_service::{BFTMemoryCertificateService, BFTMemoryService};
     use snarkvm::{
         console::account::{Address, PrivateKey},
         ledger::{
@@ -879,7 +895,8 @@ mod tests {
         let account = Account::new(rng).unwrap();
         let ledger = Arc::new(MockLedgerService::new(committee.clone()));
         let transmissions = Arc::new(BFTMemoryService::new());
-        let storage = Storage::new(ledger.clone(), transmissions, max_gc_rounds);
+        let storage =
+            Storage::new(ledger.clone(), transmissions, Arc::new(BFTMemoryCertificateService::new()), max_gc_rounds);
 
         (committee, account, ledger, storage)
     }
@@ -894,7 +911,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], 1, None)?;
         assert!(bft.is_timer_expired()); // 0 + 5 < now()
 
         // Ensure this call succeeds on an odd round.
@@ -932,7 +949,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], 1, None)?;
         assert!(bft.is_timer_expired()); // 0 + 5 < now()
 
         // Store is at round 1, and we are checking for round 2.
@@ -954,7 +971,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], 1, None)?;
         assert!(bft.is_timer_expired()); // 0 + 5 < now()
 
         // Ensure this call fails on an even round.
@@ -975,7 +992,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], 1, None)?;
 
         let result = bft.is_even_round_ready_for_next_round(IndexSet::new(), committee.clone(), 2);
         assert!(!result);
@@ -1000,7 +1017,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], 1, None)?;
 
         // Ensure this call fails on an odd round.
         let result = bft.update_leader_certificate_to_even_round(1);
@@ -1018,7 +1035,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], 1, None)?;
 
         // Ensure this call succeeds on an even round.
         let result = bft.update_leader_certificate_to_even_round(6);
@@ -1057,7 +1074,7 @@ mod tests {
 
         // Initialize the storage.
         let transmissions = Arc::new(BFTMemoryService::new());
-        let storage = Storage::new(ledger.clone(), transmissions, 10);
+        let storage = Storage::new(ledger.clone(), transmissions, Arc::new(BFTMemoryCertificateService::new()), 10);
         storage.testing_only_insert_certificate_testing_only(certificates[0].clone());
         storage.testing_only_insert_certificate_testing_only(certificates[1].clone());
         storage.testing_only_insert_certificate_testing_only(certificates[2].clone());
@@ -1070,7 +1087,7 @@ mod tests {
 
         // Initialize the BFT.
         let account = Account::new(rng)?;
-        let bft = BFT::new(account, storage.clone(), ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage.clone(), ledger, None, &[], 1, None)?;
 
         // Set the leader certificate.
         *bft.leader_certificate.write() = Some(leader_certificate);
@@ -1106,9 +1123,14 @@ mod tests {
         // Ensure the function succeeds in returning only certificates above GC.
         {
             // Initialize the storage.
-            let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+            let storage = Storage::new(
+                ledger.clone(),
+                Arc::new(BFTMemoryService::new()),
+                Arc::new(BFTMemoryCertificateService::new()),
+                1,
+            );
             // Initialize the BFT.
-            let bft = BFT::new(account.clone(), storage, ledger.clone(), None, &[], None)?;
+            let bft = BFT::new(account.clone(), storage, ledger.clone(), None, &[], 1, None)?;
 
             // Insert a mock DAG in the BFT.
             *bft.dag.write() = crate::helpers::dag::test_helpers::mock_dag_with_modified_last_committed_round(3);
@@ -1136,9 +1158,14 @@ mod tests {
         // Ensure the function succeeds in returning all given certificates.
         {
             // Initialize the storage.
-            let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+            let storage = Storage::new(
+                ledger.clone(),
+                Arc::new(BFTMemoryService::new()),
+                Arc::new(BFTMemoryCertificateService::new()),
+                1,
+            );
             // Initialize the BFT.
-            let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+            let bft = BFT::new(account, storage, ledger, None, &[], 1, None)?;
 
             // Insert a mock DAG in the BFT.
             *bft.dag.write() = crate::helpers::dag::test_helpers::mock_dag_with_modified_last_committed_round(2);
@@ -1196,7 +1223,7 @@ mod tests {
         /* Test missing previous certificate. */
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], 1, None)?;
 
         // The expected error message.
         let error_msg = format!(
@@ -1245,7 +1272,8 @@ mod tests {
 
         // Initialize the storage.
         let transmissions = Arc::new(BFTMemoryService::new());
-        let storage = Storage::new(ledger.clone(), transmissions, max_gc_rounds);
+        let storage =
+            Storage::new(ledger.clone(), transmissions, Arc::new(BFTMemoryCertificateService::new()), max_gc_rounds);
         // Insert the certificates into the storage.
         for certificate in certificates.iter() {
             storage.testing_only_insert_certificate_testing_only(certificate.clone());
@@ -1257,7 +1285,7 @@ mod tests {
 
         // Initialize the BFT.
         let account = Account::new(rng)?;
-        let bft = BFT::new(account, storage.clone(), ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage.clone(), ledger, None, &[], 1, None)?;
         // Insert a mock DAG in the BFT.
         *bft.dag.write() = crate::helpers::dag::test_helpers::mock_dag_with_modified_last_committed_round(commit_round);
 
@@ -1311,7 +1339,12 @@ mod tests {
         let ledger = Arc::new(MockLedgerService::new(committee.clone()));
 
         // Initialize the storage.
-        let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), max_gc_rounds);
+        let storage = Storage::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            max_gc_rounds,
+        );
         // Insert the certificates into the storage.
         for certificate in certificates.iter() {
             storage.testing_only_insert_certificate_testing_only(certificate.clone());
@@ -1323,7 +1356,7 @@ mod tests {
 
         // Initialize the BFT.
         let account = Account::new(rng)?;
-        let bft = BFT::new(account.clone(), storage, ledger.clone(), None, &[], None)?;
+        let bft = BFT::new(account.clone(), storage, ledger.clone(), None, &[], 1, None)?;
 
         // Insert a mock DAG in the BFT.
         *bft.dag.write() = crate::helpers::dag::test_helpers::mock_dag_with_modified_last_committed_round(commit_round);
@@ -1339,9 +1372,14 @@ mod tests {
         // Simulate a bootup of the BFT.
 
         // Initialize a new instance of storage.
-        let storage_2 = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), max_gc_rounds);
+        let storage_2 = Storage::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            max_gc_rounds,
+        );
         // Initialize a new instance of BFT.
-        let bootup_bft = BFT::new(account, storage_2, ledger, None, &[], None)?;
+        let bootup_bft = BFT::new(account, storage_2, ledger, None, &[], 1, None)?;
 
         // Sync the BFT DAG at bootup.
         bootup_bft.sync_bft_dag_at_bootup(certificates.clone()).await;
@@ -1367,6 +1405,73 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_sync_bft_dag_at_bootup_with_persisted_certificates() -> Result<()> {
+        let rng = &mut TestRng::default();
+
+        // Initialize the round parameters.
+        let max_gc_rounds = 10;
+        let committee_round = 0;
+        let current_round = 3;
+
+        // Sample the current certificate and previous certificates.
+        let (_, certificates) = snarkvm::ledger::narwhal::batch_certificate::test_helpers::sample_batch_certificate_with_previous_certificates(
+            current_round,
+            rng,
+        );
+
+        // Initialize the committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee_for_round_and_members(
+            committee_round,
+            vec![
+                certificates[0].author(),
+                certificates[1].author(),
+                certificates[2].author(),
+                certificates[3].author(),
+            ],
+            rng,
+        );
+
+        // Initialize the ledger.
+        let ledger = Arc::new(MockLedgerService::new(committee));
+
+        // Initialize the storage services, which persist across the restart.
+        let transmissions = Arc::new(BFTMemoryService::new());
+        let certificate_store = Arc::new(BFTMemoryCertificateService::new());
+        // Initialize the storage.
+        let storage = Storage::new(ledger.clone(), transmissions.clone(), certificate_store.clone(), max_gc_rounds);
+        // Insert the certificates into the storage.
+        for certificate in certificates.iter() {
+            storage.testing_only_insert_certificate_testing_only(certificate.clone());
+        }
+
+        // Simulate a bootup of the BFT.
+
+        // Initialize a new instance of storage, with the same storage services.
+        let bootup_storage = Storage::new(ledger.clone(), transmissions, certificate_store, max_gc_rounds);
+        // Ensure the certificates were reloaded into storage.
+        for certificate in certificates.iter() {
+            assert!(bootup_storage.contains_certificate(certificate.id()));
+        }
+        // Initialize a new instance of BFT.
+        let account = Account::new(rng)?;
+        let bootup_bft = BFT::new(account, bootup_storage, ledger, None, &[], 1, None)?;
+
+        // Sync the BFT DAG at bootup, without any committed certificates.
+        bootup_bft.sync_bft_dag_at_bootup(vec![]).await;
+
+        // Check that the reloaded certificates were inserted into the DAG, and were not committed.
+        for certificate in certificates {
+            let certificate_round = certificate.round();
+            let certificate_id = certificate.id();
+            assert!(bootup_bft.dag.read().contains_certificate_in_round(certificate_round, certificate_id));
+            assert!(!bootup_bft.dag.read().is_recently_committed(certificate_round, certificate_id));
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     #[tracing_test::traced_test]
     async fn test_sync_bft_dag_at_bootup_shutdown() -> Result<()> {
@@ -1458,7 +1563,12 @@ mod tests {
         // Initialize the ledger.
         let ledger = Arc::new(MockLedgerService::new(committee.clone()));
         // Initialize the storage.
-        let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), max_gc_rounds);
+        let storage = Storage::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            max_gc_rounds,
+        );
         // Get the leaders for the next 2 commit rounds.
         let leader = committee.get_leader(commit_round).unwrap();
         let next_leader = committee.get_leader(next_round).unwrap();
@@ -1495,7 +1605,7 @@ mod tests {
 
         // Initialize the BFT without bootup.
         let account = Account::new(rng)?;
-        let bft = BFT::new(account.clone(), storage, ledger.clone(), None, &[], None)?;
+        let bft = BFT::new(account.clone(), storage, ledger.clone(), None, &[], 1, None)?;
 
         // Insert a mock DAG in the BFT without bootup.
         *bft.dag.write() = crate::helpers::dag::test_helpers::mock_dag_with_modified_last_committed_round(0);
@@ -1517,10 +1627,15 @@ mod tests {
         // Simulate a bootup of the BFT.
 
         // Initialize a new instance of storage.
-        let bootup_storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), max_gc_rounds);
+        let bootup_storage = Storage::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            max_gc_rounds,
+        );
 
         // Initialize a new instance of BFT with bootup.
-        let bootup_bft = BFT::new(account, bootup_storage.clone(), ledger.clone(), None, &[], None)?;
+        let bootup_bft = BFT::new(account, bootup_storage.clone(), ledger.clone(), None, &[], 1, None)?;
 
         // Sync the BFT DAG at bootup.
         bootup_bft.sync_bft_dag_at_bootup(pre_shutdown_certificates.clone()).await;
@@ -1675,7 +1790,12 @@ mod tests {
         // Initialize the ledger.
         let ledger = Arc::new(MockLedgerService::new(committee.clone()));
         // Initialize the storage.
-        let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), max_gc_rounds);
+        let storage = Storage::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            max_gc_rounds,
+        );
         // Get the leaders for the next 2 commit rounds.
         let leader = committee.get_leader(commit_round).unwrap();
         let next_leader = committee.get_leader(next_round).unwrap();
@@ -1698,7 +1818,7 @@ mod tests {
         }
         // Initialize the bootup BFT.
         let account = Account::new(rng)?;
-        let bootup_bft = BFT::new(account.clone(), storage.clone(), ledger.clone(), None, &[], None)?;
+        let bootup_bft = BFT::new(account.clone(), storage.clone(), ledger.clone(), None, &[], 1, None)?;
         // Insert a mock DAG in the BFT without bootup.
         *bootup_bft.dag.write() = crate::helpers::dag::test_helpers::mock_dag_with_modified_last_committed_round(0);
         // Sync the BFT DAG at bootup.
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use crate::{
-    events::{EventCodec, PrimaryPing},
+    events::{EventCodec, PrimaryPing, PrimaryPong},
     helpers::{assign_to_worker, Cache, PrimarySender, Resolver, SyncSender, WorkerSender},
     spawn_blocking,
     Worker,
@@ -37,16 +37,23 @@ use snarkos_node_bft_events::{
     TransmissionResponse,
     ValidatorsRequest,
     ValidatorsResponse,
+    MAX_EVENT_SIZE,
+    MAX_HANDSHAKE_SIZE,
 };
 use snarkos_node_bft_ledger_service::LedgerService;
 use snarkos_node_sync::communication_service::CommunicationService;
 use snarkos_node_tcp::{
     is_bogon_ip,
     is_unspecified_or_broadcast_ip,
+    noise_handshake,
     protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
+    BanList,
     Config,
     Connection,
     ConnectionSide,
+    NoiseCodec,
+    NoiseState,
+    PeerBook,
     Tcp,
     P2P,
 };
@@ -64,7 +71,15 @@ use futures::SinkExt;
 use indexmap::{IndexMap, IndexSet};
 use parking_lot::{Mutex, RwLock};
 use rand::seq::{IteratorRandom, SliceRandom};
-use std::{collections::HashSet, future::Future, io, net::SocketAddr, sync::Arc, time::Duration};
+use std::{
+    collections::HashSet,
+    future::Future,
+    io,
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 use tokio::{
     net::TcpStream,
     sync::{oneshot, OnceCell},
@@ -87,6 +102,8 @@ const RESTRICTED_INTERVAL: i64 = (MAX_CONNECTION_ATTEMPTS as u64 * MAX_BATCH_DEL
 const MIN_CONNECTED_VALIDATORS: usize = 175;
 /// The maximum number of validators to send in a validators response event.
 const MAX_VALIDATORS_TO_SEND: usize = 200;
+/// The maximum duration in milliseconds to wait for the connected validators to be notified of a shutdown.
+const SHUTDOWN_NOTICE_TIMEOUT_IN_MS: u64 = MAX_BATCH_DELAY_IN_MS;
 
 /// Part of the Gateway API that deals with networking.
 /// This is a separate trait to allow for easier testing/mocking.
@@ -117,6 +134,10 @@ pub struct Gateway<N: Network> {
     /// prevent simultaneous "two-way" connections between two peers (i.e. both nodes simultaneously
     /// attempt to connect to each other). This set is used to prevent this from happening.
     connecting_peers: Arc<Mutex<IndexSet<SocketAddr>>>,
+    /// The book of the validators seen by the node, used to score the validators.
+    peer_book: Arc<PeerBook>,
+    /// The banned IPs, which are shared with the router.
+    ban_list: Arc<OnceCell<Arc<BanList>>>,
     /// The primary sender.
     primary_sender: Arc<OnceCell<PrimarySender<N>>>,
     /// The worker senders.
@@ -156,6 +177,8 @@ impl<N: Network> Gateway<N> {
             trusted_validators: trusted_validators.iter().copied().collect(),
             connected_peers: Default::default(),
             connecting_peers: Default::default(),
+            peer_book: Default::default(),
+            ban_list: Default::default(),
             primary_sender: Default::default(),
             worker_senders: Default::default(),
             sync_sender: Default::default(),
@@ -364,6 +387,33 @@ impl<N: Network> Gateway<N> {
         &self.connected_peers
     }
 
+    /// Returns the book of the validators seen by the node.
+    pub fn peer_book(&self) -> &PeerBook {
+        &self.peer_book
+    }
+
+    /// Loads the peer book from the given path, and persists the peer book to this path
+    /// whenever `save_peer_book` is called.
+    pub fn load_peer_book(&self, path: PathBuf) {
+        self.peer_book.load(path)
+    }
+
+    /// Persists the peer book, if persistence is enabled.
+    /// Note: This function writes to the file system, and should not be called on the async runtime.
+    pub fn save_peer_book(&self) -> Result<()> {
+        Ok(self.peer_book.save()?)
+    }
+
+    /// Sets the banned IPs, which are shared with the router.
+    pub fn set_ban_list(&self, ban_list: Arc<BanList>) -> Result<()> {
+        self.ban_list.set(ban_list).map_err(|_| anyhow!("The ban list is already set in the gateway"))
+    }
+
+    /// Returns `true` if the given IP is banned.
+    pub fn is_banned(&self, ip: &IpAddr) -> bool {
+        self.ban_list.get().map_or(false, |ban_list| ban_list.is_banned(ip))
+    }
+
     /// Attempts to connect to the given peer IP.
     pub fn connect(&self, peer_ip: SocketAddr) -> Option<JoinHandle<()>> {
         // Return early if the attempt is against the protocol rules.
@@ -371,14 +421,20 @@ impl<N: Network> Gateway<N> {
             warn!("{forbidden_error}");
             return None;
         }
+        // Record the connection attempt in the peer book.
+        self.peer_book.record_connection_attempt(peer_ip);
 
         let self_ = self.clone();
         Some(tokio::spawn(async move {
             debug!("Connecting to validator {peer_ip}...");
             // Attempt to connect to the peer.
-            if let Err(error) = self_.tcp.connect(peer_ip).await {
-                self_.connecting_peers.lock().shift_remove(&peer_ip);
-                warn!("Unable to connect to '{peer_ip}' - {error}");
+            match self_.tcp.connect(peer_ip).await {
+                // Record the successful connection in the peer book.
+                Ok(()) => self_.peer_book.record_connection_success(peer_ip),
+                Err(error) => {
+                    self_.connecting_peers.lock().shift_remove(&peer_ip);
+                    warn!("Unable to connect to '{peer_ip}' - {error}");
+                }
             }
         }))
     }
@@ -389,6 +445,10 @@ impl<N: Network> Gateway<N> {
         if self.is_local_ip(peer_ip) {
             bail!("{CONTEXT} Dropping connection attempt to '{peer_ip}' (attempted to self-connect)")
         }
+        // Ensure the peer IP is not banned.
+        if self.is_banned(&peer_ip.ip()) {
+            bail!("{CONTEXT} Dropping connection attempt to '{peer_ip}' (banned)")
+        }
         // Ensure the node does not surpass the maximum number of peer connections.
         if self.number_of_connected_peers() >= self.max_connected_peers() {
             bail!("{CONTEXT} Dropping connection attempt to '{peer_ip}' (maximum peers reached)")
@@ -410,6 +470,10 @@ impl<N: Network> Gateway<N> {
         if self.is_local_ip(peer_ip) {
             bail!("{CONTEXT} Dropping connection request from '{peer_ip}' (attempted to self-connect)")
         }
+        // Ensure the peer IP is not banned.
+        if self.is_banned(&peer_ip.ip()) {
+            bail!("{CONTEXT} Dropping connection request from '{peer_ip}' (banned)")
+        }
         // Ensure the node is not already connecting to this peer.
         if !self.connecting_peers.lock().insert(peer_ip) {
             bail!("{CONTEXT} Dropping connection request from '{peer_ip}' (already shaking hands as the initiator)")
@@ -472,8 +536,13 @@ impl<N: Network> Gateway<N> {
         self.resolver.remove_peer(peer_ip);
         // Remove this peer from the connected peers, if it exists.
         self.connected_peers.write().shift_remove(&peer_ip);
+        // Forget any ping still awaiting a pong from this peer.
+        self.cache.remove_outbound_ping(peer_ip);
         #[cfg(feature = "metrics")]
-        self.update_metrics();
+        {
+            metrics::remove_peer_latency(peer_ip);
+            self.update_metrics();
+        }
     }
 
     /// Sends the given event to specified peer.
@@ -489,6 +558,10 @@ impl<N: Network> Gateway<N> {
         };
         // Retrieve the event name.
         let name = event.name();
+        // If the event is a ping, record when it was sent, in order to measure the latency on the pong.
+        if matches!(event, Event::PrimaryPing(_)) {
+            self.cache.insert_outbound_ping(peer_ip);
+        }
         // Send the event to the peer.
         trace!("{CONTEXT} Sending '{name}' to '{peer_ip}'");
         let result = self.unicast(peer_addr, event);
@@ -514,6 +587,7 @@ impl<N: Network> Gateway<N> {
         // Drop the peer, if they have exceeded the rate limit (i.e. they are requesting too much from us).
         let num_events = self.cache.insert_inbound_event(peer_ip, CACHE_EVENTS_INTERVAL);
         if num_events >= self.max_cache_events() {
+            self.peer_book.record_misbehaviour(peer_ip);
             bail!("Dropping '{peer_ip}' for spamming events (num_events = {num_events})")
         }
         // Rate limit for duplicate requests.
@@ -607,7 +681,10 @@ impl<N: Network> Gateway<N> {
                     // Ensure the block response is well-formed.
                     blocks.ensure_response_is_well_formed(peer_ip, request.start_height, request.end_height)?;
                     // Send the blocks to the sync module.
-                    if let Err(e) = sync_sender.advance_with_sync_blocks(peer_ip, blocks.0).await {
+                    let result = sync_sender.advance_with_sync_blocks(peer_ip, blocks.0).await;
+                    // Record the outcome of the block response in the peer book.
+                    self.peer_book.record_block_response(peer_ip, result.is_ok());
+                    if let Err(e) = result {
                         warn!("Unable to process block response from '{peer_ip}' - {e}");
                     }
                 }
@@ -634,6 +711,12 @@ impl<N: Network> Gateway<N> {
                 bail!("{CONTEXT} Peer '{peer_ip}' is not following the protocol")
             }
             Event::Disconnect(disconnect) => {
+                // If the peer is shutting down, disconnect without considering it a protocol violation.
+                if disconnect.reason == DisconnectReason::ShuttingDown {
+                    info!("{CONTEXT} Validator '{peer_ip}' is shutting down");
+                    self.disconnect(peer_ip);
+                    return Ok(());
+                }
                 bail!("{CONTEXT} {:?}", disconnect.reason)
             }
             Event::PrimaryPing(ping) => {
@@ -652,10 +735,28 @@ impl<N: Network> Gateway<N> {
                     }
                 }
 
+                // Reply with a pong, so that the peer may measure its latency to this node.
+                let self_ = self.clone();
+                tokio::spawn(async move {
+                    Transport::send(&self_, peer_ip, Event::PrimaryPong(PrimaryPong)).await;
+                });
+
                 // Send the batch certificates to the primary.
                 let _ = self.primary_sender().tx_primary_ping.send((peer_ip, primary_certificate)).await;
                 Ok(())
             }
+            Event::PrimaryPong(_) => {
+                // Record the round-trip latency of the last ping sent to this peer.
+                if let Some(timestamp) = self.cache.remove_outbound_ping(peer_ip) {
+                    let latency = time::OffsetDateTime::now_utc() - timestamp;
+                    if let Ok(latency) = Duration::try_from(latency) {
+                        self.peer_book.record_latency(peer_ip, latency);
+                    }
+                    #[cfg(feature = "metrics")]
+                    metrics::record_peer_latency(metrics::bft::PEER_LATENCY, peer_ip, latency.as_seconds_f64());
+                }
+                Ok(())
+            }
             Event::TransmissionRequest(request) => {
                 // TODO (howardwu): Add rate limiting checks on this event, on a per-peer basis.
                 // Determine the worker ID.
@@ -792,6 +893,16 @@ impl<N: Network> Gateway<N> {
         }
     }
 
+    /// Sends a disconnect event with the given reason to the peer, and disconnects from it.
+    pub async fn disconnect_with_reason(&self, peer_ip: SocketAddr, reason: DisconnectReason) {
+        // Send the disconnect reason, and wait for it to be written before disconnecting.
+        if let Some(sent) = Transport::send(self, peer_ip, reason.into()).await {
+            let _ = sent.await;
+        }
+        // Disconnect from the peer.
+        let _ = self.disconnect(peer_ip).await;
+    }
+
     /// Disconnects from the given peer IP, if the peer is connected.
     pub fn disconnect(&self, peer_ip: SocketAddr) -> JoinHandle<()> {
         let gateway = self.clone();
@@ -829,6 +940,20 @@ impl<N: Network> Gateway<N> {
     /// Shuts down the gateway.
     pub async fn shut_down(&self) {
         info!("Shutting down the gateway...");
+        // Persist the peer book.
+        if let Err(error) = self.save_peer_book() {
+            warn!("{CONTEXT} Unable to save the peer book - {error}");
+        }
+        // Notify the connected validators that the node is shutting down, without waiting on unresponsive validators.
+        let connected_peers = self.connected_peers.read().clone();
+        let notices = futures::future::join_all(connected_peers.into_iter().map(|peer_ip| async move {
+            if let Some(receiver) = Transport::send(self, peer_ip, DisconnectReason::ShuttingDown.into()).await {
+                let _ = receiver.await;
+            }
+        }));
+        if tokio::time::timeout(Duration::from_millis(SHUTDOWN_NOTICE_TIMEOUT_IN_MS), notices).await.is_err() {
+            warn!("{CONTEXT} Timed out notifying the connected validators of the shutdown");
+        }
         // Abort the tasks.
         self.handles.lock().iter().for_each(|handle| handle.abort());
         // Close the listener.
@@ -846,6 +971,8 @@ impl<N: Network> Gateway<N> {
         self.handle_unauthorized_validators();
         // If the number of connected validators is less than the minimum, send a `ValidatorsRequest`.
         self.handle_min_connected_validators();
+        // Persist the peer book.
+        self.handle_peer_book();
     }
 
     /// Logs the connected validators.
@@ -903,11 +1030,23 @@ impl<N: Network> Gateway<N> {
         });
     }
 
-    /// This function sends a `ValidatorsRequest` to a random validator,
-    /// if the number of connected validators is less than the minimum.
+    /// This function connects to the highest-scoring known validators and sends a `ValidatorsRequest`
+    /// to a random validator, if the number of connected validators is less than the minimum.
     fn handle_min_connected_validators(&self) {
         // If the number of connected validators is less than the minimum, send a `ValidatorsRequest`.
         if self.number_of_connected_peers() < MIN_CONNECTED_VALIDATORS {
+            // Retrieve the known validators that are not connected or connecting.
+            // Note: The handshake ensures the validators are in the current committee.
+            let known_validators = self.peer_book.known_good_peers().into_iter().filter(|validator_ip| {
+                !self.is_local_ip(*validator_ip)
+                    && !self.is_connecting_ip(*validator_ip)
+                    && !self.is_connected_ip(*validator_ip)
+            });
+            // Attempt to connect to the highest-scoring known validators.
+            let num_deficient = MIN_CONNECTED_VALIDATORS.saturating_sub(self.number_of_connected_peers());
+            for validator_ip in known_validators.take(num_deficient) {
+                self.connect(validator_ip);
+            }
             // Retrieve the connected validators.
             let validators = self.connected_peers().read().clone();
             // If there are no validator IPs to connect to, return early.
@@ -926,6 +1065,17 @@ impl<N: Network> Gateway<N> {
             }
         }
     }
+
+    /// This function persists the peer book, if persistence is enabled.
+    fn handle_peer_book(&self) {
+        // Save the peer book without blocking the runtime.
+        let self_ = self.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(error) = self_.save_peer_book() {
+                warn!("{CONTEXT} Unable to save the peer book - {error}");
+            }
+        });
+    }
 }
 
 #[async_trait]
@@ -999,7 +1149,7 @@ impl<N: Network> P2P for Gateway<N> {
 
 #[async_trait]
 impl<N: Network> Reading for Gateway<N> {
-    type Codec = EventCodec<N>;
+    type Codec = NoiseCodec<EventCodec<N>>;
     type Message = Event<N>;
 
     /// The maximum queue depth of incoming messages for a single peer.
@@ -1010,8 +1160,8 @@ impl<N: Network> Reading for Gateway<N> {
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        NoiseCodec::new(EventCodec::default(), self.tcp.noise_states().get(peer_addr), MAX_EVENT_SIZE)
     }
 
     /// Processes a message received from the network.
@@ -1034,7 +1184,7 @@ impl<N: Network> Reading for Gateway<N> {
 
 #[async_trait]
 impl<N: Network> Writing for Gateway<N> {
-    type Codec = EventCodec<N>;
+    type Codec = NoiseCodec<EventCodec<N>>;
     type Message = Event<N>;
 
     /// The maximum queue depth of outgoing messages for a single peer.
@@ -1045,8 +1195,8 @@ impl<N: Network> Writing for Gateway<N> {
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        NoiseCodec::new(EventCodec::default(), self.tcp.noise_states().get(peer_addr), MAX_EVENT_SIZE)
     }
 }
 
@@ -1097,7 +1247,11 @@ impl<N: Network> Handshake for Gateway<N> {
         if let Some(ip) = peer_ip {
             self.connecting_peers.lock().shift_remove(&ip);
         }
-        let (ref peer_ip, _) = handshake_result?;
+        let (ref peer_ip, ref framed) = handshake_result?;
+        // Store the state of the encrypted connection for its codecs.
+        if let Some(noise_state) = framed.codec().state() {
+            self.tcp.noise_states().insert(peer_addr, noise_state.clone());
+        }
         info!("{CONTEXT} Gateway is connected to '{peer_ip}'");
 
         Ok(connection)
@@ -1138,9 +1292,14 @@ macro_rules! expect_event {
     };
 }
 
+/// Returns the data signed in a challenge response, from the given nonces and the handshake hash of the encrypted connection.
+fn challenge_data(request_nonce: u64, response_nonce: u64, handshake_hash: &[u8]) -> Vec<u8> {
+    [&request_nonce.to_le_bytes()[..], &response_nonce.to_le_bytes(), handshake_hash].concat()
+}
+
 /// Send the given message to the peer.
 async fn send_event<N: Network>(
-    framed: &mut Framed<&mut TcpStream, EventCodec<N>>,
+    framed: &mut Framed<&mut TcpStream, NoiseCodec<EventCodec<N>>>,
     peer_addr: SocketAddr,
     event: Event<N>,
 ) -> io::Result<()> {
@@ -1149,18 +1308,25 @@ async fn send_event<N: Network>(
 }
 
 impl<N: Network> Gateway<N> {
+    /// Returns the codec of the encrypted connection during the handshake.
+    fn handshake_codec(noise_state: NoiseState) -> NoiseCodec<EventCodec<N>> {
+        NoiseCodec::new(EventCodec::handshake(), Some(noise_state), MAX_HANDSHAKE_SIZE)
+    }
+
     /// The connection initiator side of the handshake.
     async fn handshake_inner_initiator<'a>(
         &'a self,
         peer_addr: SocketAddr,
         peer_ip: Option<SocketAddr>,
         stream: &'a mut TcpStream,
-    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, EventCodec<N>>)> {
+    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, NoiseCodec<EventCodec<N>>>)> {
         // This value is immediately guaranteed to be present, so it can be unwrapped.
         let peer_ip = peer_ip.unwrap();
 
+        // Perform the Noise handshake, to encrypt the connection.
+        let (noise_state, handshake_hash) = noise_handshake(stream, ConnectionSide::Initiator).await?;
         // Construct the stream.
-        let mut framed = Framed::new(stream, EventCodec::<N>::handshake());
+        let mut framed = Framed::new(stream, Self::handshake_codec(noise_state));
 
         // Initialize an RNG.
         let rng = &mut rand::rngs::OsRng;
@@ -1181,8 +1347,9 @@ impl<N: Network> Gateway<N> {
         let peer_request = expect_event!(Event::ChallengeRequest, framed, peer_addr);
 
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
-        if let Some(reason) =
-            self.verify_challenge_response(peer_addr, peer_request.address, peer_response, our_nonce).await
+        if let Some(reason) = self
+            .verify_challenge_response(peer_addr, peer_request.address, peer_response, our_nonce, &handshake_hash)
+            .await
         {
             send_event(&mut framed, peer_addr, reason.into()).await?;
             return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
@@ -1197,7 +1364,7 @@ impl<N: Network> Gateway<N> {
 
         // Sign the counterparty nonce.
         let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+        let data = challenge_data(peer_request.nonce, response_nonce, &handshake_hash);
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
@@ -1217,9 +1384,11 @@ impl<N: Network> Gateway<N> {
         peer_addr: SocketAddr,
         peer_ip: &mut Option<SocketAddr>,
         stream: &'a mut TcpStream,
-    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, EventCodec<N>>)> {
+    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, NoiseCodec<EventCodec<N>>>)> {
+        // Perform the Noise handshake, to encrypt the connection.
+        let (noise_state, handshake_hash) = noise_handshake(stream, ConnectionSide::Responder).await?;
         // Construct the stream.
-        let mut framed = Framed::new(stream, EventCodec::<N>::handshake());
+        let mut framed = Framed::new(stream, Self::handshake_codec(noise_state));
 
         /* Step 1: Receive the challenge request. */
 
@@ -1252,7 +1421,7 @@ impl<N: Network> Gateway<N> {
 
         // Sign the counterparty nonce.
         let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+        let data = challenge_data(peer_request.nonce, response_nonce, &handshake_hash);
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
@@ -1271,8 +1440,9 @@ impl<N: Network> Gateway<N> {
         // Listen for the challenge response message.
         let peer_response = expect_event!(Event::ChallengeResponse, framed, peer_addr);
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
-        if let Some(reason) =
-            self.verify_challenge_response(peer_addr, peer_request.address, peer_response, our_nonce).await
+        if let Some(reason) = self
+            .verify_challenge_response(peer_addr, peer_request.address, peer_response, our_nonce, &handshake_hash)
+            .await
         {
             send_event(&mut framed, peer_addr, reason.into()).await?;
             return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
@@ -1312,6 +1482,7 @@ impl<N: Network> Gateway<N> {
         peer_address: Address<N>,
         response: ChallengeResponse<N>,
         expected_nonce: u64,
+        handshake_hash: &[u8],
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge response.
         let ChallengeResponse { signature, nonce } = response;
@@ -1321,7 +1492,7 @@ impl<N: Network> Gateway<N> {
             return Some(DisconnectReason::InvalidChallengeResponse);
         };
         // Verify the signature.
-        if !signature.verify_bytes(&peer_address, &[expected_nonce.to_le_bytes(), nonce.to_le_bytes()].concat()) {
+        if !signature.verify_bytes(&peer_address, &challenge_data(expected_nonce, nonce, handshake_hash)) {
             warn!("{CONTEXT} Gateway handshake with '{peer_addr}' failed (invalid signature)");
             return Some(DisconnectReason::InvalidChallengeResponse);
         }
@@ -1341,7 +1512,7 @@ mod prop_tests {
     };
     use snarkos_account::Account;
     use snarkos_node_bft_ledger_service::MockLedgerService;
-    use snarkos_node_tcp::P2P;
+    use snarkos_node_tcp::{BanList, P2P};
     use snarkvm::{
         ledger::committee::{
             prop_tests::{CommitteeContext, ValidatorSet},
@@ -1480,6 +1651,30 @@ mod prop_tests {
         assert_eq!(gateway.account().address(), account.address());
     }
 
+    #[proptest]
+    fn gateway_ban_list(#[strategy(any_valid_dev_gateway())] input: GatewayInput) {
+        let (storage, _, private_key, dev) = input;
+        let account = Account::try_from(private_key).unwrap();
+        let gateway = Gateway::new(account, storage.ledger().clone(), dev.ip(), &[], dev.port()).unwrap();
+        let peer_ip = SocketAddr::from(([1, 2, 3, 4], MEMORY_POOL_PORT));
+
+        // Share a ban list with the gateway, which can only be set once.
+        let ban_list = Arc::new(BanList::default());
+        gateway.set_ban_list(ban_list.clone()).unwrap();
+        assert!(gateway.set_ban_list(Default::default()).is_err());
+
+        // Ensure the gateway refuses to connect to a banned IP, on any port.
+        ban_list.ban(peer_ip.ip(), None);
+        assert!(gateway.is_banned(&peer_ip.ip()));
+        assert!(gateway.check_connection_attempt(peer_ip).is_err());
+        assert!(gateway.check_connection_attempt(SocketAddr::from(([1, 2, 3, 4], 4130))).is_err());
+
+        // Ensure the gateway connects to the IP once it is unbanned.
+        ban_list.unban(&peer_ip.ip());
+        assert!(!gateway.is_banned(&peer_ip.ip()));
+        assert!(gateway.check_connection_attempt(peer_ip).is_ok());
+    }
+
     #[proptest(async = "tokio")]
     async fn gateway_start(
         #[strategy(any_valid_dev_gateway())] input: GatewayInput,
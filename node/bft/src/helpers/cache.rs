@@ -40,6 +40,8 @@ pub struct Cache<N: Network> {
     seen_outbound_transmissions: RwLock<BTreeMap<i64, HashMap<SocketAddr, u32>>>,
     /// The map of IPs to the number of validators requests.
     seen_outbound_validators_requests: RwLock<HashMap<SocketAddr, u32>>,
+    /// The map of peer IPs to the timestamp of the last primary ping sent to them.
+    seen_outbound_pings: RwLock<HashMap<SocketAddr, OffsetDateTime>>,
 }
 
 impl<N: Network> Default for Cache<N> {
@@ -61,6 +63,7 @@ impl<N: Network> Cache<N> {
             seen_outbound_certificates: Default::default(),
             seen_outbound_transmissions: Default::default(),
             seen_outbound_validators_requests: Default::default(),
+            seen_outbound_pings: Default::default(),
         }
     }
 }
@@ -119,6 +122,16 @@ impl<N: Network> Cache<N> {
     pub fn decrement_outbound_validators_requests(&self, peer_ip: SocketAddr) -> u32 {
         Self::decrement_counter(&self.seen_outbound_validators_requests, peer_ip)
     }
+
+    /// Inserts the current timestamp as the last ping sent to the given peer IP.
+    pub fn insert_outbound_ping(&self, peer_ip: SocketAddr) {
+        self.seen_outbound_pings.write().insert(peer_ip, OffsetDateTime::now_utc());
+    }
+
+    /// Removes the last ping sent to the given peer IP, returning its timestamp if it existed.
+    pub fn remove_outbound_ping(&self, peer_ip: SocketAddr) -> Option<OffsetDateTime> {
+        self.seen_outbound_pings.write().remove(&peer_ip)
+    }
 }
 
 impl<N: Network> Cache<N> {
@@ -293,4 +306,25 @@ mod tests {
        outbound_certificate,
        outbound_transmission
     }
+
+    #[test]
+    fn test_outbound_ping() {
+        let cache = Cache::<CurrentNetwork>::default();
+        let peer_ip = SocketAddr::input();
+
+        // Check the cache is empty.
+        assert!(cache.seen_outbound_pings.read().is_empty());
+        assert!(cache.remove_outbound_ping(peer_ip).is_none());
+
+        // Insert a ping.
+        cache.insert_outbound_ping(peer_ip);
+        assert_eq!(cache.seen_outbound_pings.read().len(), 1);
+
+        // Remove the ping.
+        let timestamp = cache.remove_outbound_ping(peer_ip).unwrap();
+        assert!(timestamp <= OffsetDateTime::now_utc());
+
+        // Check the cache is empty.
+        assert!(cache.remove_outbound_ping(peer_ip).is_none());
+    }
 }
@@ -27,6 +27,9 @@ pub use partition::*;
 pub mod pending;
 pub use pending::*;
 
+pub mod priority;
+pub use priority::*;
+
 pub mod proposal;
 pub use proposal::*;
 
@@ -36,6 +39,9 @@ pub use ready::*;
 pub mod resolver;
 pub use resolver::*;
 
+pub mod round_state;
+pub use round_state::*;
+
 pub mod storage;
 pub use storage::*;
 
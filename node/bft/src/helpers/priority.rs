@@ -0,0 +1,65 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::prelude::*,
+    ledger::{
+        block::Transaction,
+        narwhal::{Data, Transmission},
+    },
+};
+
+/// Returns the priority of the given transaction, as its priority fee (in microcredits) per credit of its base fee.
+/// Note: As the base fee covers the storage and compute cost of the transaction,
+/// this is the priority fee paid for each unit of the size and compute of the transaction.
+pub fn transaction_priority<N: Network>(transaction: &Transaction<N>) -> Result<u64> {
+    Ok(priority_fee_per_credit(*transaction.priority_fee_amount()?, *transaction.base_fee_amount()?))
+}
+
+/// Returns the priority of the given transmission in the ready queue.
+/// Solutions and ratifications take precedence, while transactions are ordered by their priority fee.
+/// If the fee of a transaction cannot be determined, the transaction has the lowest priority.
+pub fn transmission_priority<N: Network>(transmission: &Transmission<N>) -> u64 {
+    match transmission {
+        Transmission::Transaction(Data::Object(transaction)) => transaction_priority(transaction).unwrap_or(0),
+        Transmission::Transaction(Data::Buffer(bytes)) => Transaction::<N>::read_le(&bytes[..])
+            .map_err(Into::into)
+            .and_then(|transaction| transaction_priority(&transaction))
+            .unwrap_or(0),
+        Transmission::Ratification | Transmission::Solution(..) => u64::MAX,
+    }
+}
+
+/// Returns the given priority fee (in microcredits) per credit of the given base fee (in microcredits).
+fn priority_fee_per_credit(priority_fee: u64, base_fee: u64) -> u64 {
+    priority_fee.saturating_mul(1_000_000) / base_fee.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_fee_per_credit() {
+        assert_eq!(priority_fee_per_credit(0, 1_000_000), 0);
+        assert_eq!(priority_fee_per_credit(1, 1_000_000), 1);
+        assert_eq!(priority_fee_per_credit(5000, 2_500_000), 2000);
+        assert_eq!(priority_fee_per_credit(5000, 0), 5_000_000_000);
+        assert_eq!(priority_fee_per_credit(u64::MAX, 1_000_000), u64::MAX / 1_000_000);
+        // A transaction with a higher priority fee for the same base fee has a higher priority.
+        assert!(priority_fee_per_credit(2000, 1_000_000) > priority_fee_per_credit(1000, 1_000_000));
+        // A transaction with the same priority fee for a lower base fee has a higher priority.
+        assert!(priority_fee_per_credit(1000, 500_000) > priority_fee_per_credit(1000, 1_000_000));
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::helpers::transmission_priority;
 use snarkvm::{
     console::prelude::*,
     ledger::{
@@ -23,12 +24,26 @@ use snarkvm::{
 
 use indexmap::{IndexMap, IndexSet};
 use parking_lot::RwLock;
-use std::sync::Arc;
+use std::{cmp::Reverse, collections::BTreeMap, sync::Arc};
+
+/// The key of a transmission in the priority index, as `(priority, reversed sequence number)`.
+/// Note: Reversing the sequence number ensures that, for an equal priority, the oldest transmission comes first.
+type PriorityKey = (u64, Reverse<u64>);
 
 #[derive(Clone, Debug)]
 pub struct Ready<N: Network> {
-    /// The current map of `(transmission ID, transmission)` entries.
-    transmissions: Arc<RwLock<IndexMap<TransmissionID<N>, Transmission<N>>>>,
+    /// The transmissions, along with their priority index.
+    queue: Arc<RwLock<ReadyQueue<N>>>,
+}
+
+#[derive(Clone, Debug)]
+struct ReadyQueue<N: Network> {
+    /// The current map of `(transmission ID, (transmission, priority key))` entries.
+    transmissions: IndexMap<TransmissionID<N>, (Transmission<N>, PriorityKey)>,
+    /// The priority index, as a map of `priority key` to `transmission ID`.
+    index: BTreeMap<PriorityKey, TransmissionID<N>>,
+    /// The sequence number of the next transmission.
+    sequence: u64,
 }
 
 impl<N: Network> Default for Ready<N> {
@@ -41,47 +56,48 @@ impl<N: Network> Default for Ready<N> {
 impl<N: Network> Ready<N> {
     /// Initializes a new instance of the ready queue.
     pub fn new() -> Self {
-        Self { transmissions: Default::default() }
+        let queue = ReadyQueue { transmissions: Default::default(), index: Default::default(), sequence: 0 };
+        Self { queue: Arc::new(RwLock::new(queue)) }
     }
 
     /// Returns `true` if the ready queue is empty.
     pub fn is_empty(&self) -> bool {
-        self.transmissions.read().is_empty()
+        self.queue.read().transmissions.is_empty()
     }
 
     /// Returns the number of transmissions in the ready queue.
     pub fn num_transmissions(&self) -> usize {
-        self.transmissions.read().len()
+        self.queue.read().transmissions.len()
     }
 
     /// Returns the number of ratifications in the ready queue.
     pub fn num_ratifications(&self) -> usize {
-        self.transmissions.read().keys().filter(|id| matches!(id, TransmissionID::Ratification)).count()
+        self.queue.read().transmissions.keys().filter(|id| matches!(id, TransmissionID::Ratification)).count()
     }
 
     /// Returns the number of solutions in the ready queue.
     pub fn num_solutions(&self) -> usize {
-        self.transmissions.read().keys().filter(|id| matches!(id, TransmissionID::Solution(..))).count()
+        self.queue.read().transmissions.keys().filter(|id| matches!(id, TransmissionID::Solution(..))).count()
     }
 
     /// Returns the number of transactions in the ready queue.
     pub fn num_transactions(&self) -> usize {
-        self.transmissions.read().keys().filter(|id| matches!(id, TransmissionID::Transaction(..))).count()
+        self.queue.read().transmissions.keys().filter(|id| matches!(id, TransmissionID::Transaction(..))).count()
     }
 
     /// Returns the transmission IDs in the ready queue.
     pub fn transmission_ids(&self) -> IndexSet<TransmissionID<N>> {
-        self.transmissions.read().keys().copied().collect()
+        self.queue.read().transmissions.keys().copied().collect()
     }
 
     /// Returns the transmissions in the ready queue.
     pub fn transmissions(&self) -> IndexMap<TransmissionID<N>, Transmission<N>> {
-        self.transmissions.read().clone()
+        self.queue.read().transmissions.iter().map(|(id, (transmission, _))| (*id, transmission.clone())).collect()
     }
 
     /// Returns the solutions in the ready queue.
     pub fn solutions(&self) -> impl '_ + Iterator<Item = (PuzzleCommitment<N>, Data<ProverSolution<N>>)> {
-        self.transmissions.read().clone().into_iter().filter_map(|(id, transmission)| match (id, transmission) {
+        self.transmissions().into_iter().filter_map(|(id, transmission)| match (id, transmission) {
             (TransmissionID::Solution(id), Transmission::Solution(solution)) => Some((id, solution)),
             _ => None,
         })
@@ -89,7 +105,7 @@ impl<N: Network> Ready<N> {
 
     /// Returns the transactions in the ready queue.
     pub fn transactions(&self) -> impl '_ + Iterator<Item = (N::TransactionID, Data<Transaction<N>>)> {
-        self.transmissions.read().clone().into_iter().filter_map(|(id, transmission)| match (id, transmission) {
+        self.transmissions().into_iter().filter_map(|(id, transmission)| match (id, transmission) {
             (TransmissionID::Transaction(id), Transmission::Transaction(tx)) => Some((id, tx)),
             _ => None,
         })
@@ -99,32 +115,55 @@ impl<N: Network> Ready<N> {
 impl<N: Network> Ready<N> {
     /// Returns `true` if the ready queue contains the specified `transmission ID`.
     pub fn contains(&self, transmission_id: impl Into<TransmissionID<N>>) -> bool {
-        self.transmissions.read().contains_key(&transmission_id.into())
+        self.queue.read().transmissions.contains_key(&transmission_id.into())
     }
 
     /// Returns the transmission, given the specified `transmission ID`.
     pub fn get(&self, transmission_id: impl Into<TransmissionID<N>>) -> Option<Transmission<N>> {
-        self.transmissions.read().get(&transmission_id.into()).cloned()
+        self.queue.read().transmissions.get(&transmission_id.into()).map(|(transmission, _)| transmission.clone())
     }
 
     /// Inserts the specified (`transmission ID`, `transmission`) to the ready queue.
     /// Returns `true` if the transmission is new, and was added to the ready queue.
     pub fn insert(&self, transmission_id: impl Into<TransmissionID<N>>, transmission: Transmission<N>) -> bool {
         let transmission_id = transmission_id.into();
-        // Insert the transmission ID.
-        let is_new = self.transmissions.write().insert(transmission_id, transmission).is_none();
-        // Return whether the transmission is new.
-        is_new
+        // If the transmission already exists, return early.
+        if self.contains(transmission_id) {
+            return false;
+        }
+        // Determine the priority of the transmission, before acquiring the lock, as it may deserialize the transmission.
+        let priority = transmission_priority(&transmission);
+        // Acquire the write lock.
+        let mut queue = self.queue.write();
+        // If the transmission was added concurrently, return early.
+        if queue.transmissions.contains_key(&transmission_id) {
+            return false;
+        }
+        // Determine the priority key of the transmission.
+        let key = (priority, Reverse(queue.sequence));
+        queue.sequence += 1;
+        // Insert the transmission.
+        queue.index.insert(key, transmission_id);
+        queue.transmissions.insert(transmission_id, (transmission, key));
+        true
     }
 
-    /// Removes up to the specified number of transmissions and returns them.
+    /// Removes up to the specified number of transmissions with the highest priority and returns them.
+    /// Transmissions with the same priority are removed in the order they were inserted.
     pub fn drain(&self, num_transmissions: usize) -> IndexMap<TransmissionID<N>, Transmission<N>> {
         // Acquire the write lock.
-        let mut transmissions = self.transmissions.write();
-        // Determine the number of transmissions to drain.
-        let range = 0..transmissions.len().min(num_transmissions);
-        // Drain the transmission IDs.
-        transmissions.drain(range).collect::<IndexMap<_, _>>()
+        let mut queue = self.queue.write();
+        // Remove the transmissions from the highest to the lowest priority.
+        let mut drained = IndexMap::with_capacity(num_transmissions.min(queue.transmissions.len()));
+        while drained.len() < num_transmissions {
+            let Some((_, transmission_id)) = queue.index.pop_last() else {
+                break;
+            };
+            if let Some((transmission, _)) = queue.transmissions.swap_remove(&transmission_id) {
+                drained.insert(transmission_id, transmission);
+            }
+        }
+        drained
     }
 }
 
@@ -223,4 +262,40 @@ mod tests {
         // Check the number of transmissions.
         assert_eq!(ready.num_transmissions(), 1);
     }
+
+    #[test]
+    fn test_ready_drain_by_priority() {
+        let rng = &mut TestRng::default();
+
+        // Sample random fake bytes.
+        let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
+
+        // Initialize the ready queue.
+        let ready = Ready::<CurrentNetwork>::new();
+
+        // Insert a transaction, which has the lowest priority as its fee cannot be determined.
+        let transaction_id = TransmissionID::Transaction(Default::default());
+        let transaction = Transmission::Transaction(data(rng));
+        assert!(ready.insert(transaction_id, transaction.clone()));
+
+        // Insert the solutions.
+        let commitment_1 = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
+        let commitment_2 = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
+        let solution_1 = Transmission::Solution(data(rng));
+        let solution_2 = Transmission::Solution(data(rng));
+        assert!(ready.insert(commitment_1, solution_1.clone()));
+        assert!(ready.insert(commitment_2, solution_2.clone()));
+
+        // Drain the transmissions with the highest priority, in the order they were inserted.
+        let transmissions = ready.drain(2);
+        assert_eq!(
+            transmissions,
+            vec![(commitment_1, solution_1), (commitment_2, solution_2)].into_iter().collect::<IndexMap<_, _>>()
+        );
+
+        // Drain the remaining transaction.
+        assert_eq!(ready.num_transmissions(), 1);
+        assert_eq!(ready.drain(2), vec![(transaction_id, transaction)].into_iter().collect::<IndexMap<_, _>>());
+        assert!(ready.is_empty());
+    }
 }
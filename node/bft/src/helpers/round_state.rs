@@ -0,0 +1,102 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::{account::Signature, types::Field},
+    prelude::{Address, Network, Result},
+};
+
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// The file name of the persisted round state of the primary, in the ledger directory.
+pub const ROUND_STATE_FILE_NAME: &str = "bft-round-state.json";
+
+/// The round state of the primary, persisted on shutdown so that the node resumes from the same round on restart.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RoundState<N: Network> {
+    /// The current round of the primary.
+    pub round: u64,
+    /// The last round for which the primary proposed a batch.
+    pub proposed_round: u64,
+    /// The recently-signed batch proposals, as `(author, round, batch ID, signature)` entries.
+    pub signed_proposals: Vec<(Address<N>, u64, Field<N>, Signature<N>)>,
+}
+
+impl<N: Network> RoundState<N> {
+    /// Loads the round state from the given path. If the round state does not exist, or is corrupt, `None` is returned.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        match serde_json::from_slice(&fs::read(path)?) {
+            Ok(round_state) => Ok(Some(round_state)),
+            Err(error) => {
+                warn!("Discarding the corrupt round state at '{}' - {error}", path.display());
+                Ok(None)
+            }
+        }
+    }
+
+    /// Saves the round state to the given path, replacing the previous round state atomically.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, serde_json::to_vec(self)?)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        console::account::PrivateKey,
+        prelude::{TestRng, Uniform},
+    };
+
+    use rand::RngCore;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    #[test]
+    fn test_save_and_load() {
+        let rng = &mut TestRng::default();
+        let path = std::env::temp_dir().join(format!("round-state-{}.json", rng.next_u64()));
+
+        // Ensure a missing round state is not an error.
+        assert_eq!(RoundState::<CurrentNetwork>::load(&path).unwrap(), None);
+
+        // Sign a batch ID.
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let batch_id = Field::rand(rng);
+        let signature = private_key.sign(&[batch_id], rng).unwrap();
+        let author = Address::try_from(private_key).unwrap();
+
+        // Ensure the round state is reloaded as it was saved.
+        let round_state =
+            RoundState { round: 7, proposed_round: 6, signed_proposals: vec![(author, 6, batch_id, signature)] };
+        round_state.save(&path).unwrap();
+        assert_eq!(RoundState::load(&path).unwrap(), Some(round_state));
+
+        // Ensure a corrupt round state is discarded.
+        fs::write(&path, b"{ corrupt").unwrap();
+        assert_eq!(RoundState::<CurrentNetwork>::load(&path).unwrap(), None);
+        fs::remove_file(path).unwrap();
+    }
+}
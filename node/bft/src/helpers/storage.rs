@@ -14,7 +14,7 @@
 
 use crate::helpers::{check_timestamp_for_liveness, fmt_id};
 use snarkos_node_bft_ledger_service::LedgerService;
-use snarkos_node_bft_storage_service::StorageService;
+use snarkos_node_bft_storage_service::{CertificateStorageService, StorageService};
 use snarkvm::{
     ledger::{
         block::{Block, Transaction},
@@ -54,6 +54,9 @@ impl<N: Network> std::ops::Deref for Storage<N> {
 /// - `batch ID` to `round` entries.
 /// - `transmission ID` to `(transmission, certificate IDs)` entries.
 ///
+/// The `certificate ID` to `certificate` entries and the `current_round` are also written through
+/// to the certificate storage service, so that they can be reloaded when the node restarts.
+///
 /// The chain of events is as follows:
 /// 1. A `transmission` is received.
 /// 2. After a `batch` is ready to be stored:
@@ -86,6 +89,8 @@ pub struct StorageInner<N: Network> {
     batch_ids: RwLock<IndexMap<Field<N>, u64>>,
     /// The map of `transmission ID` to `(transmission, certificate IDs)` entries.
     transmissions: Arc<dyn StorageService<N>>,
+    /// The persisted `certificate ID` to `certificate` entries, and the persisted current round.
+    certificate_store: Arc<dyn CertificateStorageService<N>>,
 }
 
 impl<N: Network> Storage<N> {
@@ -93,12 +98,14 @@ impl<N: Network> Storage<N> {
     pub fn new(
         ledger: Arc<dyn LedgerService<N>>,
         transmissions: Arc<dyn StorageService<N>>,
+        certificate_store: Arc<dyn CertificateStorageService<N>>,
         max_gc_rounds: u64,
     ) -> Self {
         // Retrieve the current committee.
         let committee = ledger.current_committee().expect("Ledger is missing a committee.");
-        // Retrieve the current round.
-        let current_round = committee.starting_round().max(1);
+        // Retrieve the current round, resuming from the persisted round if it is ahead.
+        let persisted_round = certificate_store.current_round().unwrap_or(0);
+        let current_round = committee.starting_round().max(persisted_round).max(1);
 
         // Return the storage.
         let storage = Self(Arc::new(StorageInner {
@@ -111,7 +118,12 @@ impl<N: Network> Storage<N> {
             certificates: Default::default(),
             batch_ids: Default::default(),
             transmissions,
+            certificate_store,
         }));
+        // Reload the persisted certificates into storage.
+        storage.load_certificates_from_store();
+        // Ensure the current round is at least the highest round of the reloaded certificates.
+        let current_round = current_round.max(storage.highest_certificate_round());
         // Update the storage to the current round.
         storage.update_current_round(current_round);
         // Perform GC on the current round.
@@ -205,6 +217,59 @@ impl<N: Network> Storage<N> {
     fn update_current_round(&self, next_round: u64) {
         // Update the current round.
         self.current_round.store(next_round, Ordering::SeqCst);
+        // Persist the current round.
+        self.certificate_store.update_current_round(next_round);
+    }
+
+    /// Returns the highest round of the certificates in storage, or `0` if storage is empty.
+    fn highest_certificate_round(&self) -> u64 {
+        self.rounds.read().keys().max().copied().unwrap_or(0)
+    }
+
+    /// Reloads the certificates from the certificate store into the in-memory maps.
+    ///
+    /// Note: A certificate whose transmissions are missing from the transmissions storage (e.g. if the node stopped
+    /// before they were persisted) is discarded, as its transmissions could not be served to the peers.
+    fn load_certificates_from_store(&self) {
+        // Retrieve the persisted certificates, ordered by round.
+        let mut certificates = self.certificate_store.get_certificates();
+        certificates.sort_by_key(|certificate| certificate.round());
+        // Return early if there is nothing to reload.
+        if certificates.is_empty() {
+            return;
+        }
+        debug!("Reloading {} certificates from storage...", certificates.len());
+
+        // Acquire the write locks.
+        let mut rounds = self.rounds.write();
+        let mut certificates_map = self.certificates.write();
+        let mut batch_ids = self.batch_ids.write();
+        // Insert the certificates into the in-memory maps.
+        for certificate in certificates {
+            // Retrieve the certificate ID.
+            let certificate_id = certificate.id();
+            // Ensure the transmissions of the certificate are in storage.
+            let transmission_ids = certificate.transmission_ids();
+            if !transmission_ids
+                .iter()
+                .all(|transmission_id| self.transmissions.contains_transmission(*transmission_id))
+            {
+                warn!("Discarding the stored certificate {} (its transmissions are missing)", fmt_id(certificate_id));
+                self.transmissions.remove_transmissions(&certificate_id, transmission_ids);
+                self.certificate_store.remove_certificate(&certificate_id);
+                continue;
+            }
+            // Retrieve the round.
+            let round = certificate.round();
+            // Retrieve the batch ID.
+            let batch_id = certificate.batch_id();
+            // Insert the round to certificate ID entry.
+            rounds.entry(round).or_default().insert((certificate_id, batch_id, certificate.author()));
+            // Insert the certificate.
+            certificates_map.insert(certificate_id, certificate);
+            // Insert the batch ID.
+            batch_ids.insert(batch_id, round);
+        }
     }
 
     /// Update the storage by performing garbage collection based on the next round.
@@ -537,11 +602,13 @@ impl<N: Network> Storage<N> {
         // Obtain the certificate's transmission ids.
         let transmission_ids = certificate.transmission_ids().clone();
         // Insert the certificate.
-        self.certificates.write().insert(certificate_id, certificate);
+        self.certificates.write().insert(certificate_id, certificate.clone());
         // Insert the batch ID.
         self.batch_ids.write().insert(batch_id, round);
         // Insert the certificate ID for each of the transmissions into storage.
         self.transmissions.insert_transmissions(certificate_id, transmission_ids, missing_transmissions);
+        // Persist the certificate, now that its transmissions are in storage.
+        self.certificate_store.insert_certificate(certificate);
     }
 
     /// Removes the given `certificate ID` from storage.
@@ -585,6 +652,8 @@ impl<N: Network> Storage<N> {
         self.batch_ids.write().swap_remove(&batch_id);
         // Remove the transmission entries in the certificate from storage.
         self.transmissions.remove_transmissions(&certificate_id, certificate.transmission_ids());
+        // Remove the persisted certificate.
+        self.certificate_store.remove_certificate(&certificate_id);
         // Return successfully.
         true
     }
@@ -750,6 +819,10 @@ impl<N: Network> Storage<N> {
             .collect::<HashMap<_, _>>();
         // Insert the certificate ID for each of the transmissions into storage.
         self.transmissions.insert_transmissions(certificate_id, transmission_ids, missing_transmissions);
+        // Persist the certificate.
+        if let Some(certificate) = self.get_certificate(certificate_id) {
+            self.certificate_store.insert_certificate(certificate);
+        }
     }
 }
 
@@ -757,7 +830,7 @@ impl<N: Network> Storage<N> {
 mod tests {
     use super::*;
     use snarkos_node_bft_ledger_service::MockLedgerService;
-    use snarkos_node_bft_storage_service::BFTMemoryService;
+    use snarkos_node_bft_storage_service::{BFTMemoryCertificateService, BFTMemoryService};
     use snarkvm::{
         ledger::narwhal::Data,
         prelude::{Rng, TestRng},
@@ -838,7 +911,12 @@ mod tests {
         // Initialize the ledger.
         let ledger = Arc::new(MockLedgerService::new(committee));
         // Initialize the storage.
-        let storage = Storage::<CurrentNetwork>::new(ledger, Arc::new(BFTMemoryService::new()), 1);
+        let storage = Storage::<CurrentNetwork>::new(
+            ledger,
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            1,
+        );
 
         // Ensure the storage is empty.
         assert_storage(&storage, &[], &[], &[], &Default::default());
@@ -904,7 +982,12 @@ mod tests {
         // Initialize the ledger.
         let ledger = Arc::new(MockLedgerService::new(committee));
         // Initialize the storage.
-        let storage = Storage::<CurrentNetwork>::new(ledger, Arc::new(BFTMemoryService::new()), 1);
+        let storage = Storage::<CurrentNetwork>::new(
+            ledger,
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            1,
+        );
 
         // Ensure the storage is empty.
         assert_storage(&storage, &[], &[], &[], &Default::default());
@@ -950,6 +1033,71 @@ mod tests {
         // Check that the underlying storage representation remains unchanged.
         assert_storage(&storage, &rounds, &certificates, &batch_ids, &transmissions);
     }
+
+    #[test]
+    fn test_certificate_reload() {
+        let rng = &mut TestRng::default();
+
+        // Sample a committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        // Initialize the ledger.
+        let ledger = Arc::new(MockLedgerService::new(committee));
+        // Initialize the storage services, which persist across the restart.
+        let transmissions = Arc::new(BFTMemoryService::new());
+        let certificate_store = Arc::new(BFTMemoryCertificateService::new());
+        // Initialize the storage.
+        let storage =
+            Storage::<CurrentNetwork>::new(ledger.clone(), transmissions.clone(), certificate_store.clone(), 1);
+
+        // Create a new certificate.
+        let certificate = snarkvm::ledger::narwhal::batch_certificate::test_helpers::sample_batch_certificate(rng);
+        // Retrieve the certificate ID.
+        let certificate_id = certificate.id();
+        // Retrieve the round.
+        let round = certificate.round();
+        // Retrieve the batch ID.
+        let batch_id = certificate.batch_id();
+        // Retrieve the author of the batch.
+        let author = certificate.author();
+
+        // Construct the expected layout for 'rounds'.
+        let rounds = [(round, indexset! { (certificate_id, batch_id, author) })];
+        // Construct the expected layout for 'certificates'.
+        let certificates = [(certificate_id, certificate.clone())];
+        // Construct the expected layout for 'batch_ids'.
+        let batch_ids = [(batch_id, round)];
+        // Construct the sample 'transmissions'.
+        let (missing_transmissions, expected_transmissions) = sample_transmissions(&certificate, rng);
+
+        // Insert the certificate.
+        storage.insert_certificate_atomic(certificate.clone(), missing_transmissions);
+        // Ensure the certificate was persisted.
+        assert_eq!(certificate_store.get_certificate(certificate_id), Some(certificate));
+
+        // Simulate a restart, by initializing a new storage with the same storage services.
+        let storage =
+            Storage::<CurrentNetwork>::new(ledger.clone(), transmissions.clone(), certificate_store.clone(), 1);
+        // Ensure the certificate was reloaded.
+        assert!(storage.contains_certificate(certificate_id));
+        // Ensure the storage resumes from the round of the reloaded certificate.
+        assert!(storage.current_round() >= round);
+        // Check that the underlying storage representation is restored.
+        assert_storage(&storage, &rounds, &certificates, &batch_ids, &expected_transmissions);
+
+        // Remove the certificate.
+        assert!(storage.remove_certificate(certificate_id));
+        // Ensure the certificate was removed from the certificate store.
+        assert!(!certificate_store.contains_certificate(certificate_id));
+
+        // Persist a certificate without its transmissions.
+        let certificate = snarkvm::ledger::narwhal::batch_certificate::test_helpers::sample_batch_certificate(rng);
+        let certificate_id = certificate.id();
+        certificate_store.insert_certificate(certificate);
+        // Ensure the certificate is discarded on restart, as its transmissions are missing.
+        let storage = Storage::<CurrentNetwork>::new(ledger, transmissions, certificate_store.clone(), 1);
+        assert!(!storage.contains_certificate(certificate_id));
+        assert!(!certificate_store.contains_certificate(certificate_id));
+    }
 }
 
 #[cfg(test)]
@@ -957,7 +1105,7 @@ pub mod prop_tests {
     use super::*;
     use crate::helpers::{now, storage::tests::assert_storage};
     use snarkos_node_bft_ledger_service::MockLedgerService;
-    use snarkos_node_bft_storage_service::BFTMemoryService;
+    use snarkos_node_bft_storage_service::{BFTMemoryCertificateService, BFTMemoryService};
     use snarkvm::{
         ledger::{
             coinbase::PuzzleCommitment,
@@ -990,7 +1138,12 @@ pub mod prop_tests {
             (any::<CommitteeContext>(), 0..BatchHeader::<CurrentNetwork>::MAX_GC_ROUNDS as u64)
                 .prop_map(|(CommitteeContext(committee, _), gc_rounds)| {
                     let ledger = Arc::new(MockLedgerService::new(committee));
-                    Storage::<CurrentNetwork>::new(ledger, Arc::new(BFTMemoryService::new()), gc_rounds)
+                    Storage::<CurrentNetwork>::new(
+                        ledger,
+                        Arc::new(BFTMemoryService::new()),
+                        Arc::new(BFTMemoryCertificateService::new()),
+                        gc_rounds,
+                    )
                 })
                 .boxed()
         }
@@ -999,7 +1152,12 @@ pub mod prop_tests {
             (Just(context), 0..BatchHeader::<CurrentNetwork>::MAX_GC_ROUNDS as u64)
                 .prop_map(|(CommitteeContext(committee, _), gc_rounds)| {
                     let ledger = Arc::new(MockLedgerService::new(committee));
-                    Storage::<CurrentNetwork>::new(ledger, Arc::new(BFTMemoryService::new()), gc_rounds)
+                    Storage::<CurrentNetwork>::new(
+                        ledger,
+                        Arc::new(BFTMemoryService::new()),
+                        Arc::new(BFTMemoryCertificateService::new()),
+                        gc_rounds,
+                    )
                 })
                 .boxed()
         }
@@ -1116,7 +1274,12 @@ pub mod prop_tests {
 
         // Initialize the storage.
         let ledger = Arc::new(MockLedgerService::new(committee));
-        let storage = Storage::<CurrentNetwork>::new(ledger, Arc::new(BFTMemoryService::new()), 1);
+        let storage = Storage::<CurrentNetwork>::new(
+            ledger,
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            1,
+        );
 
         // Ensure the storage is empty.
         assert_storage(&storage, &[], &[], &[], &Default::default());
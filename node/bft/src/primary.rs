@@ -25,6 +25,7 @@ use crate::{
         PrimaryReceiver,
         PrimarySender,
         Proposal,
+        RoundState,
         Storage,
     },
     spawn_blocking,
@@ -63,8 +64,12 @@ use std::{
     collections::{HashMap, HashSet},
     future::Future,
     net::SocketAddr,
-    sync::Arc,
-    time::Duration,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 use tokio::{
     sync::{Mutex as TMutex, OnceCell},
@@ -84,6 +89,8 @@ pub struct Primary<N: Network> {
     storage: Storage<N>,
     /// The ledger service.
     ledger: Arc<dyn LedgerService<N>>,
+    /// The number of workers to spawn.
+    num_workers: u8,
     /// The workers.
     workers: Arc<[Worker<N>]>,
     /// The BFT sender.
@@ -96,6 +103,10 @@ pub struct Primary<N: Network> {
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The lock for propose_batch.
     propose_lock: Arc<TMutex<u64>>,
+    /// The path of the persisted round state, if persistence is enabled.
+    round_state_path: Arc<RwLock<Option<PathBuf>>>,
+    /// The flag indicating the primary is shutting down, and no longer accepts transmissions or proposes batches.
+    is_shutting_down: Arc<AtomicBool>,
 }
 
 impl<N: Network> Primary<N> {
@@ -106,8 +117,14 @@ impl<N: Network> Primary<N> {
         ledger: Arc<dyn LedgerService<N>>,
         ip: Option<SocketAddr>,
         trusted_validators: &[SocketAddr],
+        num_workers: u8,
         dev: Option<u16>,
     ) -> Result<Self> {
+        // Ensure the number of workers is valid.
+        ensure!(
+            (1..=MAX_WORKERS).contains(&num_workers),
+            "The number of workers must be between 1 and {MAX_WORKERS} (found {num_workers})"
+        );
         // Initialize the gateway.
         let gateway = Gateway::new(account, ledger.clone(), ip, trusted_validators, dev)?;
         // Initialize the sync module.
@@ -118,12 +135,15 @@ impl<N: Network> Primary<N> {
             gateway,
             storage,
             ledger,
+            num_workers,
             workers: Arc::from(vec![]),
             bft_sender: Default::default(),
             proposed_batch: Default::default(),
             signed_proposals: Default::default(),
             handles: Default::default(),
             propose_lock: Default::default(),
+            round_state_path: Default::default(),
+            is_shutting_down: Default::default(),
         })
     }
 
@@ -147,7 +167,7 @@ impl<N: Network> Primary<N> {
         // Construct a map for the workers.
         let mut workers = Vec::new();
         // Initialize the workers.
-        for id in 0..MAX_WORKERS {
+        for id in 0..self.num_workers {
             // Construct the worker channels.
             let (tx_worker, rx_worker) = init_worker_channels();
             // Construct the worker instance.
@@ -191,6 +211,11 @@ impl<N: Network> Primary<N> {
         self.sync.is_synced()
     }
 
+    /// Returns `true` if the primary is shutting down.
+    pub fn is_shutting_down(&self) -> bool {
+        self.is_shutting_down.load(Ordering::SeqCst)
+    }
+
     /// Returns the gateway.
     pub const fn gateway(&self) -> &Gateway<N> {
         &self.gateway
@@ -206,6 +231,11 @@ impl<N: Network> Primary<N> {
         &self.ledger
     }
 
+    /// Returns the sync module.
+    pub const fn sync(&self) -> &Sync<N> {
+        &self.sync
+    }
+
     /// Returns the number of workers.
     pub fn num_workers(&self) -> u8 {
         u8::try_from(self.workers.len()).expect("Too many workers")
@@ -312,6 +342,12 @@ impl<N: Network> Primary<N> {
             return Ok(());
         }
 
+        // If the primary is shutting down, then do not propose a new batch.
+        if self.is_shutting_down() {
+            debug!("Primary is safely skipping a batch proposal {}", "(node is shutting down)".dimmed());
+            return Ok(());
+        }
+
         // Retrieve the current round.
         let round = self.current_round();
 
@@ -393,39 +429,50 @@ impl<N: Network> Primary<N> {
         // Initialize a tracker for the number of transactions.
         let mut num_transactions = 0;
         // Take the transmissions from the workers.
-        for worker in self.workers.iter() {
-            for (id, transmission) in worker.drain(num_transmissions_per_worker) {
-                // Check if the ledger already contains the transmission.
-                if self.ledger.contains_transmission(&id).unwrap_or(true) {
-                    trace!("Proposing - Skipping transmission '{}' - Already in ledger", fmt_id(id));
-                    continue;
-                }
-                // Check the transmission is still valid.
-                match (id, transmission.clone()) {
-                    (TransmissionID::Solution(solution_id), Transmission::Solution(solution)) => {
-                        // Check if the solution is still valid.
-                        if let Err(e) = self.ledger.check_solution_basic(solution_id, solution).await {
-                            trace!("Proposing - Skipping solution '{}' - {e}", fmt_id(solution_id));
-                            continue;
-                        }
+        // Note: Each worker first contributes up to its share of the batch, and then the remaining capacity
+        // of the batch is filled from the workers in order, so that an uneven partition does not shrink the batch.
+        for is_first_pass in [true, false] {
+            for worker in self.workers.iter() {
+                // Determine the number of transmissions to take from the worker.
+                let remaining_capacity =
+                    BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH.saturating_sub(transmissions.len());
+                let num_transmissions = match is_first_pass {
+                    true => num_transmissions_per_worker.min(remaining_capacity),
+                    false => remaining_capacity,
+                };
+                for (id, transmission) in worker.drain(num_transmissions) {
+                    // Check if the ledger already contains the transmission.
+                    if self.ledger.contains_transmission(&id).unwrap_or(true) {
+                        trace!("Proposing - Skipping transmission '{}' - Already in ledger", fmt_id(id));
+                        continue;
                     }
-                    (TransmissionID::Transaction(transaction_id), Transmission::Transaction(transaction)) => {
-                        // Check if the transaction is still valid.
-                        if let Err(e) = self.ledger.check_transaction_basic(transaction_id, transaction).await {
-                            trace!("Proposing - Skipping transaction '{}' - {e}", fmt_id(transaction_id));
-                            continue;
+                    // Check the transmission is still valid.
+                    match (id, transmission.clone()) {
+                        (TransmissionID::Solution(solution_id), Transmission::Solution(solution)) => {
+                            // Check if the solution is still valid.
+                            if let Err(e) = self.ledger.check_solution_basic(solution_id, solution).await {
+                                trace!("Proposing - Skipping solution '{}' - {e}", fmt_id(solution_id));
+                                continue;
+                            }
+                        }
+                        (TransmissionID::Transaction(transaction_id), Transmission::Transaction(transaction)) => {
+                            // Check if the transaction is still valid.
+                            if let Err(e) = self.ledger.check_transaction_basic(transaction_id, transaction).await {
+                                trace!("Proposing - Skipping transaction '{}' - {e}", fmt_id(transaction_id));
+                                continue;
+                            }
+                            // Increment the number of transactions.
+                            num_transactions += 1;
                         }
-                        // Increment the number of transactions.
-                        num_transactions += 1;
+                        // Note: We explicitly forbid including ratifications,
+                        // as the protocol currently does not support ratifications.
+                        (TransmissionID::Ratification, Transmission::Ratification) => continue,
+                        // All other combinations are clearly invalid.
+                        _ => continue,
                     }
-                    // Note: We explicitly forbid including ratifications,
-                    // as the protocol currently does not support ratifications.
-                    (TransmissionID::Ratification, Transmission::Ratification) => continue,
-                    // All other combinations are clearly invalid.
-                    _ => continue,
+                    // Insert the transmission into the map.
+                    transmissions.insert(id, transmission);
                 }
-                // Insert the transmission into the map.
-                transmissions.insert(id, transmission);
             }
         }
         // If there are no unconfirmed transmissions to propose, return early.
@@ -1035,6 +1082,11 @@ impl<N: Network> Primary<N> {
                     error!("Unable to determine the worker ID for the unconfirmed solution");
                     continue;
                 };
+                // If the primary is shutting down, then reject the unconfirmed solution.
+                if self_.is_shutting_down() {
+                    callback.send(Err(anyhow!("The primary is shutting down"))).ok();
+                    continue;
+                }
                 let self_ = self_.clone();
                 tokio::spawn(async move {
                     // Retrieve the worker.
@@ -1057,6 +1109,11 @@ impl<N: Network> Primary<N> {
                     error!("Unable to determine the worker ID for the unconfirmed transaction");
                     continue;
                 };
+                // If the primary is shutting down, then reject the unconfirmed transaction.
+                if self_.is_shutting_down() {
+                    callback.send(Err(anyhow!("The primary is shutting down"))).ok();
+                    continue;
+                }
                 let self_ = self_.clone();
                 tokio::spawn(async move {
                     // Retrieve the worker.
@@ -1451,6 +1508,85 @@ impl<N: Network> Primary<N> {
         self.handles.lock().push(tokio::spawn(future));
     }
 
+    /// Stops accepting transmissions and proposing batches, and waits up to the given timeout
+    /// for the pending batch proposal to be certified or to expire.
+    ///
+    /// If the batch proposal is still pending after the timeout, it is dropped, and its transmissions
+    /// are reinserted into the workers. The primary then no longer proposes a batch for its round,
+    /// as the restored round state prevents proposing a conflicting batch after the restart.
+    pub async fn finish_round(&self, timeout: Duration) {
+        info!("Finishing the current round of the primary...");
+        // Stop accepting transmissions and proposing batches.
+        self.is_shutting_down.store(true, Ordering::SeqCst);
+
+        let start = Instant::now();
+        loop {
+            // Clear the proposed batch, if it has expired.
+            if let Err(e) = self.check_proposed_batch_for_expiration().await {
+                warn!("Failed to check the proposed batch for expiration - {e}");
+            }
+            // If there is no pending batch proposal, the round is finished.
+            let Some(round) = self.proposed_batch.read().as_ref().map(Proposal::round) else {
+                info!("Finished the current round {} of the primary", self.current_round());
+                return;
+            };
+            // If the timeout has elapsed, drop the pending batch proposal.
+            if start.elapsed() >= timeout {
+                warn!("Dropping the pending batch proposal for round {round} {}", "(shutdown timed out)".dimmed());
+                let proposal = self.proposed_batch.write().take();
+                if let Some(proposal) = proposal {
+                    if let Err(e) = self.reinsert_transmissions_into_workers(proposal) {
+                        warn!("Failed to reinsert the transmissions of the batch proposal - {e}");
+                    }
+                }
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    /// Loads the persisted round state from the given path, and enables persistence to it.
+    ///
+    /// This restores the recently-signed batch proposals and the last proposed round, so that the primary
+    /// neither signs nor proposes a conflicting batch for a round it already participated in before the restart.
+    pub async fn load_round_state(&self, path: PathBuf) -> Result<()> {
+        if let Some(round_state) = RoundState::<N>::load(&path)? {
+            let RoundState { round, proposed_round, signed_proposals } = round_state;
+            // Restore the last proposed round.
+            {
+                let mut lock_guard = self.propose_lock.lock().await;
+                *lock_guard = (*lock_guard).max(proposed_round);
+            }
+            // Restore the recently-signed batch proposals.
+            self.signed_proposals.write().extend(
+                signed_proposals
+                    .into_iter()
+                    .map(|(author, round, batch_id, signature)| (author, (round, batch_id, signature))),
+            );
+            info!("Resuming the primary from round {} (round {round} at shutdown)", self.current_round());
+        }
+        *self.round_state_path.write() = Some(path);
+        Ok(())
+    }
+
+    /// Persists the round state, if persistence is enabled.
+    pub fn save_round_state(&self) -> Result<()> {
+        let Some(path) = self.round_state_path.read().clone() else {
+            return Ok(());
+        };
+        // Retrieve the last proposed round.
+        // Note: If a batch is being proposed, conservatively consider the current round as proposed.
+        let proposed_round = self.propose_lock.try_lock().map_or(self.current_round(), |round| *round);
+        // Retrieve the recently-signed batch proposals.
+        let signed_proposals = self
+            .signed_proposals
+            .read()
+            .iter()
+            .map(|(author, (round, batch_id, signature))| (*author, *round, *batch_id, *signature))
+            .collect();
+        RoundState { round: self.current_round(), proposed_round, signed_proposals }.save(&path)
+    }
+
     /// Shuts down the primary.
     pub async fn shut_down(&self) {
         info!("Shutting down the primary...");
@@ -1458,6 +1594,10 @@ impl<N: Network> Primary<N> {
         self.workers.iter().for_each(|worker| worker.shut_down());
         // Abort the tasks.
         self.handles.lock().iter().for_each(|handle| handle.abort());
+        // Persist the round state.
+        if let Err(e) = self.save_round_state() {
+            warn!("Failed to persist the round state of the primary - {e}");
+        }
         // Close the gateway.
         self.gateway.shut_down().await;
     }
@@ -1467,7 +1607,7 @@ impl<N: Network> Primary<N> {
 mod tests {
     use super::*;
     use snarkos_node_bft_ledger_service::MockLedgerService;
-    use snarkos_node_bft_storage_service::BFTMemoryService;
+    use snarkos_node_bft_storage_service::{BFTMemoryCertificateService, BFTMemoryService};
     use snarkvm::{
         ledger::committee::{Committee, MIN_VALIDATOR_STAKE},
         prelude::{Address, Signature},
@@ -1501,10 +1641,15 @@ mod tests {
 
         let account = accounts.first().unwrap().1.clone();
         let ledger = Arc::new(MockLedgerService::new(committee));
-        let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 10);
+        let storage = Storage::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            10,
+        );
 
         // Initialize the primary.
-        let mut primary = Primary::new(account, storage, ledger, None, &[], None).unwrap();
+        let mut primary = Primary::new(account, storage, ledger, None, &[], 1, None).unwrap();
 
         // Construct a worker instance.
         primary.workers = Arc::from([Worker::new(
@@ -1734,6 +1879,41 @@ mod tests {
         assert!(primary.proposed_batch.read().is_some());
     }
 
+    #[tokio::test]
+    async fn test_finish_round() {
+        let mut rng = TestRng::default();
+        let (primary, _) = primary_without_handlers(&mut rng).await;
+
+        // Propose a batch.
+        let (solution_commitment, solution) = sample_unconfirmed_solution(&mut rng);
+        let (transaction_id, transaction) = sample_unconfirmed_transaction(&mut rng);
+        primary.workers[0].process_unconfirmed_solution(solution_commitment, solution).await.unwrap();
+        primary.workers[0].process_unconfirmed_transaction(transaction_id, transaction).await.unwrap();
+        assert!(primary.propose_batch().await.is_ok());
+        let round = primary.proposed_batch.read().as_ref().unwrap().round();
+
+        // Enable persistence of the round state.
+        let path = std::env::temp_dir().join(format!("primary-round-state-{}.json", rng.next_u64()));
+        primary.load_round_state(path.clone()).await.unwrap();
+
+        // Finish the round without waiting, which drops the batch proposal and reinserts its transmissions.
+        primary.finish_round(Duration::ZERO).await;
+        assert!(primary.is_shutting_down());
+        assert!(primary.proposed_batch.read().is_none());
+        assert_eq!(primary.num_unconfirmed_transmissions(), 2);
+
+        // Ensure the primary no longer proposes a batch.
+        assert!(primary.propose_batch().await.is_ok());
+        assert!(primary.proposed_batch.read().is_none());
+
+        // Ensure a restarted primary does not propose a batch for the same round again.
+        primary.save_round_state().unwrap();
+        let (restarted_primary, _) = primary_without_handlers(&mut rng).await;
+        restarted_primary.load_round_state(path.clone()).await.unwrap();
+        assert_eq!(*restarted_primary.propose_lock.lock().await, round);
+        std::fs::remove_file(path).unwrap();
+    }
+
     #[tokio::test]
     async fn test_propose_batch_in_round() {
         let round = 3;
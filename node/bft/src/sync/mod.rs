@@ -33,10 +33,13 @@ use parking_lot::Mutex;
 use rayon::prelude::*;
 use std::{collections::HashMap, future::Future, net::SocketAddr, sync::Arc};
 use tokio::{
-    sync::{oneshot, Mutex as TMutex, OnceCell},
+    sync::{broadcast, oneshot, Mutex as TMutex, OnceCell},
     task::JoinHandle,
 };
 
+/// The maximum number of block notifications buffered for each subscriber.
+const MAX_BLOCK_NOTIFICATIONS: usize = 64;
+
 #[derive(Clone)]
 pub struct Sync<N: Network> {
     /// The gateway.
@@ -57,6 +60,8 @@ pub struct Sync<N: Network> {
     response_lock: Arc<TMutex<()>>,
     /// The sync lock.
     sync_lock: Arc<TMutex<()>>,
+    /// The sender for the blocks that the ledger was synced to without the BFT.
+    block_notifications: broadcast::Sender<Block<N>>,
 }
 
 impl<N: Network> Sync<N> {
@@ -75,9 +80,16 @@ impl<N: Network> Sync<N> {
             handles: Default::default(),
             response_lock: Default::default(),
             sync_lock: Default::default(),
+            block_notifications: broadcast::channel(MAX_BLOCK_NOTIFICATIONS).0,
         }
     }
 
+    /// Returns a new receiver for the blocks that the ledger is synced to without the BFT.
+    /// Note: The blocks that are committed by the BFT are not included, as those are notified by consensus.
+    pub fn subscribe_blocks(&self) -> broadcast::Receiver<Block<N>> {
+        self.block_notifications.subscribe()
+    }
+
     /// Starts the sync module.
     pub async fn run(&self, bft_sender: Option<BFTSender<N>>, sync_receiver: SyncReceiver<N>) -> Result<()> {
         // If a BFT sender was provided, set it.
@@ -318,6 +330,8 @@ impl<N: Network> Sync<N> {
         self.storage.sync_height_with_block(block.height());
         // Sync the round with the block.
         self.storage.sync_round_with_block(block.round());
+        // Notify the subscribers, if there are any.
+        let _ = self.block_notifications.send(block);
 
         Ok(())
     }
@@ -99,8 +99,8 @@ impl<N: Network> Worker<N> {
 
 impl<N: Network> Worker<N> {
     /// The maximum number of transmissions allowed in a worker.
-    pub const MAX_TRANSMISSIONS_PER_WORKER: usize =
-        BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH / MAX_WORKERS as usize;
+    /// Note: Each worker may hold a full batch, so that a batch can be filled from any of the workers.
+    pub const MAX_TRANSMISSIONS_PER_WORKER: usize = BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH;
     /// The maximum number of transmissions allowed in a worker ping.
     pub const MAX_TRANSMISSIONS_PER_WORKER_PING: usize = BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH / 10;
 
@@ -200,7 +200,7 @@ impl<N: Network> Worker<N> {
         Ok((transmission_id, transmission))
     }
 
-    /// Removes up to the specified number of transmissions from the ready queue, and returns them.
+    /// Removes up to the specified number of transmissions with the highest priority from the ready queue, and returns them.
     pub(crate) fn drain(&self, num_transmissions: usize) -> impl Iterator<Item = (TransmissionID<N>, Transmission<N>)> {
         self.ready.drain(num_transmissions).into_iter()
     }
@@ -469,7 +469,7 @@ impl<N: Network> Worker<N> {
 mod tests {
     use super::*;
     use snarkos_node_bft_ledger_service::LedgerService;
-    use snarkos_node_bft_storage_service::BFTMemoryService;
+    use snarkos_node_bft_storage_service::{BFTMemoryCertificateService, BFTMemoryService};
     use snarkvm::{
         console::{network::Network, types::Field},
         ledger::{
@@ -579,7 +579,12 @@ mod tests {
         mock_ledger.expect_check_solution_basic().returning(|_, _| Ok(()));
         let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(mock_ledger);
         // Initialize the storage.
-        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+        let storage = Storage::<CurrentNetwork>::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            1,
+        );
 
         // Create the Worker.
         let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
@@ -617,7 +622,12 @@ mod tests {
         mock_ledger.expect_ensure_transmission_is_well_formed().returning(|_, _| Ok(()));
         let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(mock_ledger);
         // Initialize the storage.
-        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+        let storage = Storage::<CurrentNetwork>::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            1,
+        );
 
         // Create the Worker.
         let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
@@ -655,7 +665,12 @@ mod tests {
         mock_ledger.expect_check_solution_basic().returning(|_, _| Ok(()));
         let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(mock_ledger);
         // Initialize the storage.
-        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+        let storage = Storage::<CurrentNetwork>::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            1,
+        );
 
         // Create the Worker.
         let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
@@ -695,7 +710,12 @@ mod tests {
         mock_ledger.expect_check_solution_basic().returning(|_, _| Err(anyhow!("")));
         let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(mock_ledger);
         // Initialize the storage.
-        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+        let storage = Storage::<CurrentNetwork>::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            1,
+        );
 
         // Create the Worker.
         let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
@@ -735,7 +755,12 @@ mod tests {
         mock_ledger.expect_check_transaction_basic().returning(|_, _| Ok(()));
         let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(mock_ledger);
         // Initialize the storage.
-        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+        let storage = Storage::<CurrentNetwork>::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            1,
+        );
 
         // Create the Worker.
         let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
@@ -775,7 +800,12 @@ mod tests {
         mock_ledger.expect_check_transaction_basic().returning(|_, _| Err(anyhow!("")));
         let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(mock_ledger);
         // Initialize the storage.
-        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+        let storage = Storage::<CurrentNetwork>::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            1,
+        );
 
         // Create the Worker.
         let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
@@ -815,7 +845,12 @@ mod tests {
         mock_ledger.expect_check_transaction_basic().returning(|_, _| Ok(()));
         let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(mock_ledger);
         // Initialize the storage.
-        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+        let storage = Storage::<CurrentNetwork>::new(
+            ledger.clone(),
+            Arc::new(BFTMemoryService::new()),
+            Arc::new(BFTMemoryCertificateService::new()),
+            1,
+        );
 
         // Create the Worker.
         let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
@@ -894,8 +929,12 @@ mod tests {
 
             let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(mock_ledger);
             // Initialize the storage.
-            let storage =
-                Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), max_gc_rounds);
+            let storage = Storage::<CurrentNetwork>::new(
+                ledger.clone(),
+                Arc::new(BFTMemoryService::new()),
+                Arc::new(BFTMemoryCertificateService::new()),
+                max_gc_rounds,
+            );
 
             // Ensure that the storage GC round is correct.
             assert_eq!(storage.gc_round(), expected_gc_round);
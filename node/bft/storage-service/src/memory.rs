@@ -12,15 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::StorageService;
+use crate::{CertificateStorageService, StorageService};
 use snarkvm::{
-    ledger::narwhal::{BatchHeader, Transmission, TransmissionID},
+    ledger::narwhal::{BatchCertificate, BatchHeader, Transmission, TransmissionID},
     prelude::{bail, Field, Network, Result},
 };
 
 use indexmap::{indexset, map::Entry, IndexMap, IndexSet};
 use parking_lot::RwLock;
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
 use tracing::error;
 
 /// A BFT in-memory storage service.
@@ -149,3 +152,69 @@ impl<N: Network> StorageService<N> for BFTMemoryService<N> {
         self.transmissions.read().clone().into_iter().collect()
     }
 }
+
+/// A BFT in-memory certificate storage service.
+#[derive(Debug)]
+pub struct BFTMemoryCertificateService<N: Network> {
+    /// The map of `certificate ID` to `certificate` entries.
+    certificates: RwLock<IndexMap<Field<N>, BatchCertificate<N>>>,
+    /// The last round that was stored.
+    current_round: AtomicU64,
+}
+
+impl<N: Network> Default for BFTMemoryCertificateService<N> {
+    /// Initializes a new BFT in-memory certificate storage service.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> BFTMemoryCertificateService<N> {
+    /// Initializes a new BFT in-memory certificate storage service.
+    pub fn new() -> Self {
+        Self { certificates: Default::default(), current_round: Default::default() }
+    }
+}
+
+impl<N: Network> CertificateStorageService<N> for BFTMemoryCertificateService<N> {
+    /// Returns `true` if the storage contains the specified `certificate ID`.
+    fn contains_certificate(&self, certificate_id: Field<N>) -> bool {
+        // Check if the certificate ID exists in storage.
+        self.certificates.read().contains_key(&certificate_id)
+    }
+
+    /// Returns the certificate for the given `certificate ID`.
+    /// If the certificate ID does not exist in storage, `None` is returned.
+    fn get_certificate(&self, certificate_id: Field<N>) -> Option<BatchCertificate<N>> {
+        // Get the certificate.
+        self.certificates.read().get(&certificate_id).cloned()
+    }
+
+    /// Returns all of the certificates in storage.
+    fn get_certificates(&self) -> Vec<BatchCertificate<N>> {
+        self.certificates.read().values().cloned().collect()
+    }
+
+    /// Inserts the given certificate into storage.
+    fn insert_certificate(&self, certificate: BatchCertificate<N>) {
+        self.certificates.write().insert(certificate.id(), certificate);
+    }
+
+    /// Removes the certificate for the given `certificate ID` from storage.
+    fn remove_certificate(&self, certificate_id: &Field<N>) {
+        self.certificates.write().shift_remove(certificate_id);
+    }
+
+    /// Returns the last round that was stored, if one exists.
+    fn current_round(&self) -> Option<u64> {
+        match self.current_round.load(Ordering::SeqCst) {
+            0 => None,
+            round => Some(round),
+        }
+    }
+
+    /// Updates the last round that was stored.
+    fn update_current_round(&self, round: u64) {
+        self.current_round.store(round, Ordering::SeqCst);
+    }
+}
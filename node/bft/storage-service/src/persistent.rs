@@ -12,12 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::StorageService;
+use crate::{CertificateStorageService, StorageService};
 use snarkvm::{
     ledger::{
-        narwhal::{BatchHeader, Transmission, TransmissionID},
+        narwhal::{BatchCertificate, BatchHeader, Transmission, TransmissionID},
         store::{
             cow_to_cloned,
+            cow_to_copied,
             helpers::{
                 rocksdb::{
                     internal::{self, BFTMap, Database, MapID},
@@ -197,7 +198,122 @@ impl<N: Network> StorageService<N> for BFTPersistentStorage<N> {
     /// Returns a HashMap over the `(transmission ID, (transmission, certificate IDs))` entries.
     #[cfg(any(test, feature = "test"))]
     fn as_hashmap(&self) -> HashMap<TransmissionID<N>, (Transmission<N>, IndexSet<Field<N>>)> {
-        use snarkvm::ledger::store::cow_to_copied;
         self.transmissions.iter_confirmed().map(|(k, v)| (cow_to_copied!(k), cow_to_cloned!(v))).collect()
     }
 }
+
+/// The map IDs for the BFT certificate storage.
+///
+/// Note: These IDs are allocated above the range used by the ledger maps in snarkVM,
+/// so that the certificate maps never share a prefix with an existing map in the database.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u16)]
+pub enum CertificateMapID {
+    Certificates = 0xB000,
+    CurrentRound = 0xB001,
+}
+
+impl From<CertificateMapID> for u16 {
+    fn from(id: CertificateMapID) -> u16 {
+        id as u16
+    }
+}
+
+/// The key under which the current round is stored.
+const CURRENT_ROUND_KEY: u8 = 0;
+
+/// A BFT persistent certificate storage service.
+#[derive(Debug)]
+pub struct BFTPersistentCertificateStorage<N: Network> {
+    /// The map of `certificate ID` to `certificate` entries.
+    certificates: DataMap<Field<N>, BatchCertificate<N>>,
+    /// The map containing the last round that was stored.
+    current_round: DataMap<u8, u64>,
+}
+
+impl<N: Network> BFTPersistentCertificateStorage<N> {
+    /// Initializes a new BFT persistent certificate storage service.
+    pub fn open(storage_mode: StorageMode) -> Result<Self> {
+        Ok(Self {
+            certificates: internal::RocksDB::open_map(N::ID, storage_mode.clone(), CertificateMapID::Certificates)?,
+            current_round: internal::RocksDB::open_map(N::ID, storage_mode, CertificateMapID::CurrentRound)?,
+        })
+    }
+
+    /// Initializes a new BFT persistent certificate storage service.
+    #[cfg(any(test, feature = "test"))]
+    pub fn open_testing(temp_dir: std::path::PathBuf, dev: Option<u16>) -> Result<Self> {
+        Ok(Self {
+            certificates: internal::RocksDB::open_map_testing(temp_dir.clone(), dev, CertificateMapID::Certificates)?,
+            current_round: internal::RocksDB::open_map_testing(temp_dir, dev, CertificateMapID::CurrentRound)?,
+        })
+    }
+}
+
+impl<N: Network> CertificateStorageService<N> for BFTPersistentCertificateStorage<N> {
+    /// Returns `true` if the storage contains the specified `certificate ID`.
+    fn contains_certificate(&self, certificate_id: Field<N>) -> bool {
+        // Check if the certificate ID exists in storage.
+        let result = self.certificates.contains_key_confirmed(&certificate_id);
+        // If the result is an error, log the error.
+        if let Err(error) = &result {
+            error!("Failed to check if certificate ID exists in storage - {error}");
+        }
+        // Return the result.
+        result.unwrap_or(false)
+    }
+
+    /// Returns the certificate for the given `certificate ID`.
+    /// If the certificate ID does not exist in storage, `None` is returned.
+    fn get_certificate(&self, certificate_id: Field<N>) -> Option<BatchCertificate<N>> {
+        // Get the certificate.
+        match self.certificates.get_confirmed(&certificate_id) {
+            Ok(Some(certificate)) => Some(cow_to_cloned!(certificate)),
+            Ok(None) => None,
+            Err(error) => {
+                error!("Failed to get certificate from storage - {error}");
+                None
+            }
+        }
+    }
+
+    /// Returns all of the certificates in storage.
+    fn get_certificates(&self) -> Vec<BatchCertificate<N>> {
+        self.certificates.values_confirmed().map(|certificate| cow_to_cloned!(certificate)).collect()
+    }
+
+    /// Inserts the given certificate into storage.
+    fn insert_certificate(&self, certificate: BatchCertificate<N>) {
+        // Retrieve the certificate ID.
+        let certificate_id = certificate.id();
+        // Insert the certificate.
+        if let Err(e) = self.certificates.insert(certificate_id, certificate) {
+            error!("Failed to insert certificate {certificate_id} into storage - {e}");
+        }
+    }
+
+    /// Removes the certificate for the given `certificate ID` from storage.
+    fn remove_certificate(&self, certificate_id: &Field<N>) {
+        if let Err(e) = self.certificates.remove(certificate_id) {
+            error!("Failed to remove certificate {certificate_id} from storage - {e}");
+        }
+    }
+
+    /// Returns the last round that was stored, if one exists.
+    fn current_round(&self) -> Option<u64> {
+        match self.current_round.get_confirmed(&CURRENT_ROUND_KEY) {
+            Ok(round) => round.map(|round| cow_to_copied!(round)),
+            Err(error) => {
+                error!("Failed to get the current round from storage - {error}");
+                None
+            }
+        }
+    }
+
+    /// Updates the last round that was stored.
+    fn update_current_round(&self, round: u64) {
+        if let Err(e) = self.current_round.insert(CURRENT_ROUND_KEY, round) {
+            error!("Failed to update the current round {round} in storage - {e}");
+        }
+    }
+}
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use snarkvm::{
-    ledger::narwhal::{BatchHeader, Transmission, TransmissionID},
+    ledger::narwhal::{BatchCertificate, BatchHeader, Transmission, TransmissionID},
     prelude::{Field, Network, Result},
 };
 
@@ -52,3 +52,31 @@ pub trait StorageService<N: Network>: Debug + Send + Sync {
     #[cfg(any(test, feature = "test"))]
     fn as_hashmap(&self) -> HashMap<TransmissionID<N>, (Transmission<N>, IndexSet<Field<N>>)>;
 }
+
+/// A storage service for the batch certificates, and the current round, which are reloaded when the node restarts.
+///
+/// Note: A certificate is only inserted once its transmissions are in the `StorageService`,
+/// and the certificates are checked against the stored transmissions when they are reloaded.
+pub trait CertificateStorageService<N: Network>: Debug + Send + Sync {
+    /// Returns `true` if the storage contains the specified `certificate ID`.
+    fn contains_certificate(&self, certificate_id: Field<N>) -> bool;
+
+    /// Returns the certificate for the given `certificate ID`.
+    /// If the certificate ID does not exist in storage, `None` is returned.
+    fn get_certificate(&self, certificate_id: Field<N>) -> Option<BatchCertificate<N>>;
+
+    /// Returns all of the certificates in storage.
+    fn get_certificates(&self) -> Vec<BatchCertificate<N>>;
+
+    /// Inserts the given certificate into storage.
+    fn insert_certificate(&self, certificate: BatchCertificate<N>);
+
+    /// Removes the certificate for the given `certificate ID` from storage.
+    fn remove_certificate(&self, certificate_id: &Field<N>);
+
+    /// Returns the last round that was stored, if one exists.
+    fn current_round(&self) -> Option<u64>;
+
+    /// Updates the last round that was stored.
+    fn update_current_round(&self, round: u64);
+}
@@ -32,6 +32,7 @@ async fn test_state_coherence() {
 
     let mut network = TestNetwork::new(TestNetworkConfig {
         num_nodes: N,
+        num_workers: 1,
         bft: true,
         connect_all: true,
         fire_transmissions: Some(TRANSMISSION_INTERVAL_MS),
@@ -53,6 +54,7 @@ async fn test_resync() {
     const TRANSMISSION_INTERVAL_MS: u64 = 10;
     let mut network = TestNetwork::new(TestNetworkConfig {
         num_nodes: N,
+        num_workers: 1,
         bft: true,
         connect_all: true,
         fire_transmissions: Some(TRANSMISSION_INTERVAL_MS),
@@ -71,6 +73,7 @@ async fn test_resync() {
 
     let mut spare_network = TestNetwork::new(TestNetworkConfig {
         num_nodes: N,
+        num_workers: 1,
         bft: true,
         connect_all: false,
         fire_transmissions: None,
@@ -99,6 +102,7 @@ async fn test_quorum_threshold() {
 
     let mut network = TestNetwork::new(TestNetworkConfig {
         num_nodes: N,
+        num_workers: 1,
         bft: true,
         connect_all: false,
         fire_transmissions: None,
@@ -151,6 +155,7 @@ async fn test_quorum_break() {
     const TRANSMISSION_INTERVAL_MS: u64 = 10;
     let mut network = TestNetwork::new(TestNetworkConfig {
         num_nodes: N,
+        num_workers: 1,
         bft: true,
         connect_all: true,
         fire_transmissions: Some(TRANSMISSION_INTERVAL_MS),
@@ -187,6 +192,7 @@ async fn test_leader_election_consistency() {
     const CANNON_INTERVAL_MS: u64 = 10;
     let mut network = TestNetwork::new(TestNetworkConfig {
         num_nodes: N,
+        num_workers: 1,
         bft: true,
         connect_all: true,
         fire_transmissions: Some(CANNON_INTERVAL_MS),
@@ -239,6 +245,7 @@ async fn test_transient_break() {
     const TRANSMISSION_INTERVAL_MS: u64 = 10;
     let mut network = TestNetwork::new(TestNetworkConfig {
         num_nodes: N,
+        num_workers: 1,
         bft: true,
         connect_all: true,
         fire_transmissions: Some(TRANSMISSION_INTERVAL_MS),
@@ -274,3 +281,45 @@ async fn test_transient_break() {
     let network_clone = network.clone();
     deadline!(Duration::from_secs(60), move || { network_clone.is_round_reached(RECOVERY_ROUND) });
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_bft_with_two_workers() {
+    assert_commits_with_workers(2).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_bft_with_three_workers() {
+    assert_commits_with_workers(3).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_bft_with_four_workers() {
+    assert_commits_with_workers(4).await;
+}
+
+/// Starts N nodes with the given number of workers, connects them and starts the cannons for each,
+/// then checks the committed certificates are coherent and were assembled across all of the workers.
+async fn assert_commits_with_workers(num_workers: u8) {
+    const N: u16 = 4;
+    const CANNON_INTERVAL_MS: u64 = 10;
+    let mut network = TestNetwork::new(TestNetworkConfig {
+        num_nodes: N,
+        num_workers,
+        bft: true,
+        connect_all: true,
+        fire_transmissions: Some(CANNON_INTERVAL_MS),
+        // Set this to Some(0..=4) to see the logs.
+        log_level: None,
+        log_connections: false,
+    });
+    network.start().await;
+
+    // Check the nodes reach a target round.
+    const TARGET_ROUND: u64 = 8;
+    let cloned_network = network.clone();
+    deadline!(Duration::from_secs(40), move || { cloned_network.is_round_reached(TARGET_ROUND) });
+
+    // Check the round certificates are coherent, and contain transmissions from every worker.
+    assert!(network.is_certificate_round_coherent(1..TARGET_ROUND - 1));
+    assert!(network.is_worker_coverage_complete(1..TARGET_ROUND - 1));
+}
@@ -19,12 +19,12 @@ use crate::common::{
 };
 use snarkos_account::Account;
 use snarkos_node_bft::{
-    helpers::{init_primary_channels, PrimarySender, Storage},
+    helpers::{assign_to_worker, init_primary_channels, PrimarySender, Storage},
     Primary,
     BFT,
     MAX_BATCH_DELAY_IN_MS,
 };
-use snarkos_node_bft_storage_service::BFTMemoryService;
+use snarkos_node_bft_storage_service::{BFTMemoryCertificateService, BFTMemoryService};
 use snarkvm::{
     console::{
         account::{Address, PrivateKey},
@@ -46,7 +46,7 @@ use indexmap::IndexMap;
 use itertools::Itertools;
 use parking_lot::Mutex;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     ops::RangeBounds,
     sync::{Arc, OnceLock},
     time::Duration,
@@ -59,6 +59,8 @@ use tracing::*;
 pub struct TestNetworkConfig {
     /// The number of nodes to spin up.
     pub num_nodes: u16,
+    /// The number of workers of each node.
+    pub num_workers: u8,
     /// If this is set to `true`, the BFT protocol is started on top of Narwhal.
     pub bft: bool,
     /// If this is set to `true`, all nodes are connected to each other (when they're first
@@ -150,15 +152,33 @@ impl TestNetwork {
             let storage = Storage::new(
                 ledger.clone(),
                 Arc::new(BFTMemoryService::new()),
+                Arc::new(BFTMemoryCertificateService::new()),
                 BatchHeader::<CurrentNetwork>::MAX_GC_ROUNDS as u64,
             );
 
             let (primary, bft) = if config.bft {
-                let bft = BFT::<CurrentNetwork>::new(account, storage, ledger, None, &[], Some(id as u16)).unwrap();
+                let bft = BFT::<CurrentNetwork>::new(
+                    account,
+                    storage,
+                    ledger,
+                    None,
+                    &[],
+                    config.num_workers,
+                    Some(id as u16),
+                )
+                .unwrap();
                 (bft.primary().clone(), Some(bft))
             } else {
-                let primary =
-                    Primary::<CurrentNetwork>::new(account, storage, ledger, None, &[], Some(id as u16)).unwrap();
+                let primary = Primary::<CurrentNetwork>::new(
+                    account,
+                    storage,
+                    ledger,
+                    None,
+                    &[],
+                    config.num_workers,
+                    Some(id as u16),
+                )
+                .unwrap();
                 (primary, None)
             };
 
@@ -321,6 +341,25 @@ impl TestNetwork {
             self.validators.values().map(|v| v.primary.storage().get_certificates_for_round(round)).dedup().count() == 1
         })
     }
+
+    // Checks if the certificates in storage for all nodes over a range of rounds contain transmissions
+    // from every worker, i.e. if the batches were assembled across all of the workers.
+    pub fn is_worker_coverage_complete<T>(&self, rounds_range: T) -> bool
+    where
+        T: RangeBounds<u64> + IntoIterator<Item = u64> + Clone,
+    {
+        self.validators.values().all(|v| {
+            let num_workers = v.primary.num_workers();
+            let worker_ids = rounds_range
+                .clone()
+                .into_iter()
+                .flat_map(|round| v.primary.storage().get_certificates_for_round(round))
+                .flat_map(|certificate| certificate.transmission_ids().iter().copied().collect::<Vec<_>>())
+                .filter_map(|transmission_id| assign_to_worker(transmission_id, num_workers).ok())
+                .collect::<HashSet<_>>();
+            worker_ids.len() == num_workers as usize
+        })
+    }
 }
 
 // Initializes a new test committee.
@@ -19,7 +19,7 @@ use crate::common::{primary, CurrentNetwork, TranslucentLedgerService};
 use snarkos_account::Account;
 use snarkos_node_bft::{helpers::Storage, Gateway, Worker};
 use snarkos_node_bft_ledger_service::LedgerService;
-use snarkos_node_bft_storage_service::BFTMemoryService;
+use snarkos_node_bft_storage_service::{BFTMemoryCertificateService, BFTMemoryService};
 use snarkvm::{
     console::{account::Address, network::Network},
     ledger::{narwhal::BatchHeader, store::helpers::memory::ConsensusMemory},
@@ -55,7 +55,12 @@ pub fn sample_ledger(
 
 /// Samples a new storage with the given ledger.
 pub fn sample_storage<N: Network>(ledger: Arc<TranslucentLedgerService<N, ConsensusMemory<N>>>) -> Storage<N> {
-    Storage::new(ledger, Arc::new(BFTMemoryService::new()), BatchHeader::<N>::MAX_GC_ROUNDS as u64)
+    Storage::new(
+        ledger,
+        Arc::new(BFTMemoryService::new()),
+        Arc::new(BFTMemoryCertificateService::new()),
+        BatchHeader::<N>::MAX_GC_ROUNDS as u64,
+    )
 }
 
 /// Samples a new gateway with the given ledger.
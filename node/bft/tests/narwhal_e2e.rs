@@ -31,6 +31,7 @@ async fn test_state_coherence() {
 
     let mut network = TestNetwork::new(TestNetworkConfig {
         num_nodes: N,
+        num_workers: 1,
         bft: false,
         connect_all: true,
         fire_transmissions: Some(TRANSMISSION_INTERVAL_MS),
@@ -55,6 +56,7 @@ async fn test_quorum_threshold() {
 
     let mut network = TestNetwork::new(TestNetworkConfig {
         num_nodes: N,
+        num_workers: 1,
         bft: false,
         connect_all: false,
         fire_transmissions: None,
@@ -107,6 +109,7 @@ async fn test_quorum_break() {
     const TRANSMISSION_INTERVAL_MS: u64 = 10;
     let mut network = TestNetwork::new(TestNetworkConfig {
         num_nodes: N,
+        num_workers: 1,
         bft: false,
         connect_all: true,
         fire_transmissions: Some(TRANSMISSION_INTERVAL_MS),
@@ -137,6 +140,7 @@ async fn test_storage_coherence() {
     const TRANSMISSION_INTERVAL_MS: u64 = 10;
     let mut network = TestNetwork::new(TestNetworkConfig {
         num_nodes: N,
+        num_workers: 1,
         bft: false,
         connect_all: true,
         fire_transmissions: Some(TRANSMISSION_INTERVAL_MS),
@@ -162,3 +166,51 @@ async fn test_storage_coherence() {
     // the nodes have completed the round.
     assert!(network.is_certificate_round_coherent(1..TARGET_ROUND - 1));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_two_workers() {
+    assert_progress_with_workers(2).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_three_workers() {
+    assert_progress_with_workers(3).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_four_workers() {
+    assert_progress_with_workers(4).await;
+}
+
+/// Starts N nodes with the given number of workers, connects them and starts the cannons for each,
+/// then checks the nodes advance through the rounds with batches assembled across all of the workers.
+async fn assert_progress_with_workers(num_workers: u8) {
+    const N: u16 = 4;
+    const TRANSMISSION_INTERVAL_MS: u64 = 10;
+    let mut network = TestNetwork::new(TestNetworkConfig {
+        num_nodes: N,
+        num_workers,
+        bft: false,
+        connect_all: true,
+        fire_transmissions: Some(TRANSMISSION_INTERVAL_MS),
+        // Set this to Some(0..=4) to see the logs.
+        log_level: None,
+        log_connections: false,
+    });
+    network.start().await;
+
+    // Check each node spawned the workers.
+    for validator in network.validators.values() {
+        assert_eq!(validator.primary.num_workers(), num_workers);
+    }
+
+    // Check the nodes have started advancing through the rounds.
+    const TARGET_ROUND: u64 = 8;
+    // Note: cloning the network is fine because the primaries it wraps are `Arc`ed.
+    let network_clone = network.clone();
+    deadline!(Duration::from_secs(40), move || { network_clone.is_round_reached(TARGET_ROUND) });
+
+    // Check the round certificates are coherent across the network, and contain transmissions from every worker.
+    assert!(network.is_certificate_round_coherent(1..TARGET_ROUND - 1));
+    assert!(network.is_worker_coverage_complete(1..TARGET_ROUND - 1));
+}
@@ -16,6 +16,10 @@
 // https://github.com/rust-lang/rust-clippy/issues/6446
 #![allow(clippy::await_holding_lock)]
 
+use crate::{
+    bundles::{bundle_name, checksum_name, verify_checksum, BundleCache},
+    progress::{update_progress, SyncProgress},
+};
 use snarkvm::prelude::{
     block::Block,
     store::{cow_to_copied, ConsensusStorage},
@@ -29,9 +33,10 @@ use snarkvm::prelude::{
 use anyhow::{anyhow, bail, Result};
 use colored::Colorize;
 use parking_lot::Mutex;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use std::{
     cmp,
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, AtomicU32, Ordering},
         Arc,
@@ -40,30 +45,62 @@ use std::{
 };
 
 /// The number of blocks per file.
-const BLOCKS_PER_FILE: u32 = 50;
-/// The desired number of concurrent requests to the CDN.
+pub(crate) const BLOCKS_PER_FILE: u32 = 50;
+/// The initial number of concurrent requests to the CDN.
 const CONCURRENT_REQUESTS: u32 = 16;
+/// The minimum number of concurrent requests to the CDN.
+const MINIMUM_CONCURRENT_REQUESTS: u32 = 1;
+/// The maximum number of concurrent requests to the CDN.
+const MAXIMUM_CONCURRENT_REQUESTS: u32 = 32;
+/// The interval at which the number of concurrent requests is adjusted, based on the throughput.
+const CONCURRENCY_ADJUSTMENT_INTERVAL: Duration = Duration::from_secs(10);
 /// Maximum number of pending sync blocks.
 const MAXIMUM_PENDING_BLOCKS: u32 = BLOCKS_PER_FILE * CONCURRENT_REQUESTS * 2;
 /// Maximum number of attempts for a request to the CDN.
 const MAXIMUM_REQUEST_ATTEMPTS: u8 = 10;
+/// The delay before the first retry of a request to the CDN, which doubles on every attempt.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// The maximum delay between retries of a request to the CDN.
+const MAXIMUM_RETRY_DELAY: Duration = Duration::from_secs(60);
 /// The supported network.
 const NETWORK_ID: u16 = 3;
+/// The URL scheme of a CDN in a local directory.
+const FILE_SCHEME: &str = "file://";
+
+/// Whether a bundle without a checksum manifest was already reported, which is only warned about once.
+static MISSING_CHECKSUM_REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// A representation of the 'latest.json' file object.
+#[derive(Deserialize, Serialize, Debug)]
+pub(crate) struct LatestState {
+    pub(crate) exclusive_height: u32,
+    pub(crate) inclusive_height: u32,
+    pub(crate) hash: String,
+}
 
 /// Loads blocks from a CDN into the ledger.
 ///
+/// If a cache directory is given, the bundles that were downloaded but not yet processed are stored in it,
+/// so that an interrupted sync resumes from the cached bundles, instead of downloading them again.
+///
 /// On success, this function returns the completed block height.
 /// On failure, this function returns the last successful block height (if any), along with the error.
 pub async fn sync_ledger_with_cdn<N: Network, C: ConsensusStorage<N>>(
     base_url: &str,
     ledger: Ledger<N, C>,
+    cache_dir: Option<PathBuf>,
     shutdown: Arc<AtomicBool>,
 ) -> Result<u32, (u32, anyhow::Error)> {
     // Fetch the node height.
     let start_height = ledger.latest_height() + 1;
+    // Open the bundle cache.
+    let cache = match cache_dir.map(|directory| BundleCache::open(directory, start_height)).transpose() {
+        Ok(cache) => cache,
+        Err(error) => return Err((start_height, anyhow!("Failed to open the CDN cache - {error}"))),
+    };
     // Load the blocks from the CDN into the ledger.
     let ledger_clone = ledger.clone();
-    let result = load_blocks(base_url, start_height, None, shutdown, move |block: Block<N>| {
+    let result = load_blocks_inner(base_url, start_height, None, cache, shutdown, move |block: Block<N>| {
         ledger_clone.advance_to_next_block(&block)
     })
     .await;
@@ -106,6 +143,18 @@ pub async fn load_blocks<N: Network>(
     end_height: Option<u32>,
     shutdown: Arc<AtomicBool>,
     process: impl FnMut(Block<N>) -> Result<()> + Clone + Send + Sync + 'static,
+) -> Result<u32, (u32, anyhow::Error)> {
+    load_blocks_inner(base_url, start_height, end_height, None, shutdown, process).await
+}
+
+/// Loads blocks from a CDN, or from the given bundle cache, and process them with the given function.
+async fn load_blocks_inner<N: Network>(
+    base_url: &str,
+    start_height: u32,
+    end_height: Option<u32>,
+    cache: Option<BundleCache>,
+    shutdown: Arc<AtomicBool>,
+    process: impl FnMut(Block<N>) -> Result<()> + Clone + Send + Sync + 'static,
 ) -> Result<u32, (u32, anyhow::Error)> {
     // If the network is not supported, return.
     if N::ID != NETWORK_ID {
@@ -159,12 +208,25 @@ pub async fn load_blocks<N: Network>(
     // Start a timer.
     let timer = Instant::now();
 
+    // Reset the sync progress.
+    update_progress(|progress| {
+        *progress = SyncProgress {
+            start_height,
+            current_height: start_height.saturating_sub(1),
+            end_height,
+            concurrency: CONCURRENT_REQUESTS,
+            ..Default::default()
+        }
+    });
+
     // Spawn a background task responsible for concurrent downloads.
     let pending_blocks_clone = pending_blocks.clone();
     let base_url = base_url.to_owned();
+    let cache_clone = cache.clone();
     let shutdown_clone = shutdown.clone();
     tokio::spawn(async move {
-        download_block_bundles(client, base_url, cdn_start, cdn_end, pending_blocks_clone, shutdown_clone).await;
+        download_block_bundles(client, base_url, cdn_start, cdn_end, pending_blocks_clone, cache_clone, shutdown_clone)
+            .await;
     });
 
     // A loop for inserting the pending blocks into the ledger.
@@ -201,6 +263,9 @@ pub async fn load_blocks<N: Network>(
         let next_blocks = std::mem::replace(&mut *candidate_blocks, retained_blocks);
         drop(candidate_blocks);
 
+        // Note: The pending blocks start at a multiple of BLOCKS_PER_FILE, so these blocks form a single bundle.
+        let bundle_start = next_height;
+
         // Attempt to advance the ledger using the CDN block bundle.
         let mut process_clone = process.clone();
         let shutdown_clone = shutdown.clone();
@@ -231,6 +296,13 @@ pub async fn load_blocks<N: Network>(
         .await
         .map_err(|e| (current_height, e.into()))?
         .map_err(|e| (current_height, e))?;
+
+        // Remove the processed bundle from the cache.
+        if let Some(cache) = &cache {
+            cache.remove(bundle_start, bundle_start + BLOCKS_PER_FILE);
+        }
+        // Update the sync progress.
+        update_progress(|progress| progress.current_height = current_height);
     }
 
     Ok(current_height)
@@ -242,10 +314,13 @@ async fn download_block_bundles<N: Network>(
     cdn_start: u32,
     cdn_end: u32,
     pending_blocks: Arc<Mutex<Vec<Block<N>>>>,
+    cache: Option<BundleCache>,
     shutdown: Arc<AtomicBool>,
 ) {
     // Keep track of the number of concurrent requests.
     let active_requests: Arc<AtomicU32> = Default::default();
+    // Keep track of the throughput, to adjust the number of concurrent requests.
+    let concurrency = Arc::new(Mutex::new(AdaptiveConcurrency::new(CONCURRENT_REQUESTS)));
 
     let mut start = cdn_start;
     while start < cdn_end - 1 {
@@ -258,16 +333,23 @@ async fn download_block_bundles<N: Network>(
         let num_pending_blocks = pending_blocks.lock().len();
         if num_pending_blocks >= MAXIMUM_PENDING_BLOCKS as usize {
             debug!("Maximum number of pending blocks reached ({num_pending_blocks}), waiting...");
+            // The ledger is the bottleneck, so the throughput of the CDN is not measured meanwhile.
+            concurrency.lock().reset();
             tokio::time::sleep(Duration::from_secs(5)).await;
             continue;
         }
 
-        // The number of concurrent requests is maintained at CONCURRENT_REQUESTS, unless the maximum
+        // The number of concurrent requests is adjusted based on the throughput, unless the maximum
         // number of pending blocks may be breached.
+        let concurrency_limit = concurrency.lock().adjust();
+        update_progress(|progress| progress.concurrency = concurrency_limit);
         let active_request_count = active_requests.load(Ordering::Relaxed);
-        let num_requests =
-            cmp::min(CONCURRENT_REQUESTS, (MAXIMUM_PENDING_BLOCKS - num_pending_blocks as u32) / BLOCKS_PER_FILE)
-                .saturating_sub(active_request_count);
+        let pending_capacity = (MAXIMUM_PENDING_BLOCKS - num_pending_blocks as u32) / BLOCKS_PER_FILE;
+        if pending_capacity < concurrency_limit {
+            // The ledger is the bottleneck, so the throughput of the CDN is not measured meanwhile.
+            concurrency.lock().reset();
+        }
+        let num_requests = cmp::min(concurrency_limit, pending_capacity).saturating_sub(active_request_count);
 
         // Spawn concurrent requests for bundles of blocks.
         for i in 0..num_requests {
@@ -283,26 +365,37 @@ async fn download_block_bundles<N: Network>(
             let client_clone = client.clone();
             let base_url_clone = base_url.clone();
             let pending_blocks_clone = pending_blocks.clone();
+            let cache_clone = cache.clone();
             let active_requests_clone = active_requests.clone();
+            let concurrency_clone = concurrency.clone();
             let shutdown_clone = shutdown.clone();
             tokio::spawn(async move {
                 // Increment the number of active requests.
                 active_requests_clone.fetch_add(1, Ordering::Relaxed);
+                concurrency_clone.lock().start_request();
+                let mut num_downloaded_bytes = 0;
 
                 let ctx = format!("blocks {start} to {end}");
                 debug!("Requesting {ctx} (of {cdn_end})");
 
                 // Prepare the URL.
-                let blocks_url = format!("{base_url_clone}/{start}.{end}.blocks");
-                let ctx = format!("blocks {start} to {end}");
+                let blocks_url = format!("{base_url_clone}/{}", bundle_name(start, end));
                 // Download blocks, retrying on failure.
                 let mut attempts = 0;
                 let request_time = Instant::now();
 
                 loop {
                     // Fetch the blocks.
-                    match cdn_get(client_clone.clone(), &blocks_url, &ctx).await {
-                        Ok::<Vec<Block<N>>, _>(blocks) => {
+                    match fetch_bundle(&client_clone, &base_url_clone, start, end, cache_clone.as_ref()).await {
+                        Ok::<(Vec<Block<N>>, _), _>((blocks, source)) => {
+                            // Record the source of the bundle.
+                            match source {
+                                BundleSource::Cache => update_progress(|progress| progress.cached_bundles += 1),
+                                BundleSource::Cdn(num_bytes) => {
+                                    num_downloaded_bytes = num_bytes;
+                                    update_progress(|progress| progress.downloaded_bytes += num_bytes);
+                                }
+                            }
                             // Keep the collection of pending blocks sorted by the height.
                             let mut pending_blocks = pending_blocks_clone.lock();
                             for block in blocks {
@@ -315,7 +408,7 @@ async fn download_block_bundles<N: Network>(
                             break;
                         }
                         Err(error) => {
-                            // Increment the attempt counter, and wait with a linear backoff, or abort in
+                            // Increment the attempt counter, and wait with an exponential backoff, or abort in
                             // case the maximum number of attempts has been breached.
                             attempts += 1;
                             if attempts > MAXIMUM_REQUEST_ATTEMPTS {
@@ -323,13 +416,14 @@ async fn download_block_bundles<N: Network>(
                                 shutdown_clone.store(true, Ordering::Relaxed);
                                 break;
                             }
-                            tokio::time::sleep(Duration::from_secs(attempts as u64 * 10)).await;
+                            tokio::time::sleep(retry_delay(attempts)).await;
                             warn!("{error} - retrying ({attempts} attempt(s) so far)");
                         }
                     }
                 }
 
                 // Decrement the number of active requests.
+                concurrency_clone.lock().finish_request(num_downloaded_bytes);
                 active_requests_clone.fetch_sub(1, Ordering::Relaxed);
             });
         }
@@ -348,31 +442,11 @@ async fn download_block_bundles<N: Network>(
 ///
 /// Note: This function decrements the tip by a few blocks, to ensure the
 /// tip is not on a block that is not yet available on the CDN.
-async fn cdn_height<const BLOCKS_PER_FILE: u32>(client: &Client, base_url: &str) -> Result<u32> {
-    // A representation of the 'latest.json' file object.
-    #[derive(Deserialize, Serialize, Debug)]
-    struct LatestState {
-        exclusive_height: u32,
-        inclusive_height: u32,
-        hash: String,
-    }
+pub(crate) async fn cdn_height<const BLOCKS_PER_FILE: u32>(client: &Client, base_url: &str) -> Result<u32> {
     // Prepare the URL.
     let latest_json_url = format!("{base_url}/latest.json");
-    // Send the request.
-    let response = match client.get(latest_json_url).send().await {
-        Ok(response) => response,
-        Err(error) => bail!("Failed to fetch the CDN height - {error}"),
-    };
-    // Parse the response.
-    let bytes = match response.bytes().await {
-        Ok(bytes) => bytes,
-        Err(error) => bail!("Failed to parse the CDN height response - {error}"),
-    };
-    // Parse the bytes for the string.
-    let latest_state_string = match bincode::deserialize::<String>(&bytes) {
-        Ok(string) => string,
-        Err(error) => bail!("Failed to deserialize the CDN height response - {error}"),
-    };
+    // Fetch the string.
+    let latest_state_string = cdn_get::<String>(client.clone(), &latest_json_url, "the CDN height").await?;
     // Parse the string for the tip.
     let tip = match serde_json::from_str::<LatestState>(&latest_state_string) {
         Ok(latest) => latest.exclusive_height,
@@ -387,16 +461,16 @@ async fn cdn_height<const BLOCKS_PER_FILE: u32>(client: &Client, base_url: &str)
 /// Retrieves the objects from the CDN with the given URL.
 async fn cdn_get<T: 'static + DeserializeOwned + Send>(client: Client, url: &str, ctx: &str) -> Result<T> {
     // Fetch the bytes from the given URL.
-    let response = match client.get(url).send().await {
-        Ok(response) => response,
-        Err(error) => bail!("Failed to fetch {ctx} - {error}"),
-    };
-    // Parse the response.
-    let bytes = match response.bytes().await {
+    let bytes = match cdn_fetch(&client, url).await {
         Ok(bytes) => bytes,
-        Err(error) => bail!("Failed to parse {ctx} - {error}"),
+        Err(error) => bail!("Failed to fetch {ctx} - {error}"),
     };
     // Parse the objects.
+    cdn_deserialize(bytes, ctx).await
+}
+
+/// Deserializes the objects from the given bytes.
+async fn cdn_deserialize<T: 'static + DeserializeOwned + Send>(bytes: Vec<u8>, ctx: &str) -> Result<T> {
     match tokio::task::spawn_blocking(move || bincode::deserialize::<T>(&bytes)).await {
         Ok(Ok(objects)) => Ok(objects),
         Ok(Err(error)) => bail!("Failed to deserialize {ctx} - {error}"),
@@ -404,6 +478,205 @@ async fn cdn_get<T: 'static + DeserializeOwned + Send>(client: Client, url: &str
     }
 }
 
+/// The source of a bundle of blocks.
+pub(crate) enum BundleSource {
+    /// The bundle was loaded from the local cache.
+    Cache,
+    /// The bundle was downloaded from the CDN, with the given number of bytes.
+    Cdn(u64),
+}
+
+/// Retrieves the bundle of blocks in the given range from the cache if possible, or from the CDN otherwise.
+///
+/// If the CDN provides a checksum manifest for the bundle, the bundle is verified against it,
+/// otherwise a warning is logged, as the bundle is only checked once deserialized.
+/// Once verified and deserialized, a downloaded bundle is inserted into the cache.
+pub(crate) async fn fetch_bundle<N: Network>(
+    client: &Client,
+    base_url: &str,
+    start: u32,
+    end: u32,
+    cache: Option<&BundleCache>,
+) -> Result<(Vec<Block<N>>, BundleSource)> {
+    let name = bundle_name(start, end);
+    let ctx = format!("blocks {start} to {end}");
+
+    // Attempt to load the bundle from the cache.
+    if let Some(cache) = cache {
+        let cache = cache.clone();
+        if let Ok(Some(bytes)) = tokio::task::spawn_blocking(move || cache.get(start, end)).await {
+            match cdn_deserialize(bytes, &ctx).await {
+                Ok(blocks) => return Ok((blocks, BundleSource::Cache)),
+                Err(error) => warn!("Failed to load the cached {ctx} - {error}"),
+            }
+        }
+    }
+
+    // Fetch the bundle from the CDN.
+    let bytes = match cdn_fetch(client, &format!("{base_url}/{name}")).await {
+        Ok(bytes) => bytes,
+        Err(error) => bail!("Failed to fetch {ctx} - {error}"),
+    };
+    // Verify the bundle against its checksum manifest, if the CDN provides one.
+    match cdn_fetch_optional(client, &format!("{base_url}/{}", checksum_name(&name))).await {
+        Ok(Some(manifest)) => verify_checksum(&name, &bytes, &String::from_utf8_lossy(&manifest))?,
+        Ok(None) => match MISSING_CHECKSUM_REPORTED.swap(true, Ordering::Relaxed) {
+            true => debug!("Skipping the checksum verification of {ctx} (the CDN has no checksum manifest)"),
+            false => warn!("Skipping the checksum verification of {ctx}, as the CDN has no checksum manifest for it"),
+        },
+        Err(error) => bail!("Failed to fetch the checksum manifest of {ctx} - {error}"),
+    }
+    let num_bytes = bytes.len() as u64;
+
+    // Insert the bundle into the cache.
+    if let Some(cache) = cache {
+        let cache = cache.clone();
+        let bytes = bytes.clone();
+        match tokio::task::spawn_blocking(move || cache.insert(start, end, &bytes)).await {
+            Ok(Ok(())) => (),
+            Ok(Err(error)) => warn!("Failed to cache {ctx} - {error}"),
+            Err(error) => warn!("Failed to join task for caching {ctx} - {error}"),
+        }
+    }
+
+    // Parse the blocks.
+    Ok((cdn_deserialize(bytes, &ctx).await?, BundleSource::Cdn(num_bytes)))
+}
+
+/// Retrieves the bytes from the given URL, which is either a remote URL or a `file://` path to a local directory.
+async fn cdn_fetch(client: &Client, url: &str) -> Result<Vec<u8>> {
+    match cdn_fetch_optional(client, url).await? {
+        Some(bytes) => Ok(bytes),
+        None => bail!("'{url}' was not found"),
+    }
+}
+
+/// Retrieves the bytes from the given URL, which is either a remote URL or a `file://` path to a local directory.
+///
+/// This function returns `None` if the object does not exist (i.e. a missing file, or an HTTP 404),
+/// and an error on any other failure.
+async fn cdn_fetch_optional(client: &Client, url: &str) -> Result<Option<Vec<u8>>> {
+    match url.strip_prefix(FILE_SCHEME) {
+        // Read the bytes from the local file.
+        Some(path) => match tokio::fs::read(path).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => bail!("Failed to read '{path}' - {error}"),
+        },
+        // Send the request, and parse the response.
+        None => {
+            let response = client.get(url).send().await?;
+            if response.status() == StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+            match response.error_for_status()?.bytes().await {
+                Ok(bytes) => Ok(Some(bytes.into())),
+                Err(error) => bail!("Failed to parse the response - {error}"),
+            }
+        }
+    }
+}
+
+/// Returns the delay before retrying a request to the CDN, after the given number of failed attempts.
+fn retry_delay(attempts: u8) -> Duration {
+    let factor = 2u32.saturating_pow(attempts.saturating_sub(1) as u32);
+    cmp::min(INITIAL_RETRY_DELAY.saturating_mul(factor), MAXIMUM_RETRY_DELAY)
+}
+
+/// Adjusts the number of concurrent requests to the CDN, based on the throughput.
+///
+/// The limit is increased while the throughput increases, and halved when the throughput drops,
+/// which converges to the concurrency that the connection to the CDN can sustain.
+///
+/// The throughput is only measured while requests are in flight, and the measurement is discarded
+/// whenever the downloads are throttled by the pending blocks (i.e. the ledger is the bottleneck).
+struct AdaptiveConcurrency {
+    /// The current limit on the number of concurrent requests.
+    limit: u32,
+    /// The throughput (in bytes per second) measured over the previous interval.
+    previous_throughput: f64,
+    /// The number of bytes downloaded during the current interval.
+    num_bytes: u64,
+    /// The time spent with requests in flight during the current interval.
+    busy_time: Duration,
+    /// The number of requests in flight.
+    num_in_flight: u32,
+    /// The last time the number of requests in flight changed.
+    last_update: Instant,
+}
+
+impl AdaptiveConcurrency {
+    /// Initializes the concurrency with the given limit.
+    fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            previous_throughput: 0.0,
+            num_bytes: 0,
+            busy_time: Duration::ZERO,
+            num_in_flight: 0,
+            last_update: Instant::now(),
+        }
+    }
+
+    /// Records the start of a request.
+    fn start_request(&mut self) {
+        self.update_busy_time();
+        self.num_in_flight += 1;
+    }
+
+    /// Records the end of a request, with the given number of downloaded bytes.
+    fn finish_request(&mut self, num_bytes: u64) {
+        self.update_busy_time();
+        self.num_in_flight = self.num_in_flight.saturating_sub(1);
+        self.num_bytes += num_bytes;
+    }
+
+    /// Discards the measurement of the current interval.
+    fn reset(&mut self) {
+        self.update_busy_time();
+        self.busy_time = Duration::ZERO;
+        self.num_bytes = 0;
+    }
+
+    /// Adds the time elapsed since the last update to the busy time, if any requests were in flight.
+    fn update_busy_time(&mut self) {
+        let now = Instant::now();
+        if self.num_in_flight > 0 {
+            self.busy_time += now.saturating_duration_since(self.last_update);
+        }
+        self.last_update = now;
+    }
+
+    /// Returns the limit on the number of concurrent requests, which is adjusted once per interval.
+    fn adjust(&mut self) -> u32 {
+        self.update_busy_time();
+        if self.busy_time >= CONCURRENCY_ADJUSTMENT_INTERVAL {
+            let throughput = self.num_bytes as f64 / self.busy_time.as_secs_f64();
+            self.limit = Self::next_limit(self.limit, self.previous_throughput, throughput);
+            self.previous_throughput = throughput;
+            self.busy_time = Duration::ZERO;
+            self.num_bytes = 0;
+        }
+        self.limit
+    }
+
+    /// Returns the next limit, given the throughput of the previous and current intervals.
+    fn next_limit(limit: u32, previous_throughput: f64, throughput: f64) -> u32 {
+        // If the throughput dropped significantly, halve the limit.
+        if throughput < previous_throughput * 0.8 {
+            cmp::max(limit / 2, MINIMUM_CONCURRENT_REQUESTS)
+        }
+        // If the throughput increased, probe a higher limit.
+        else if throughput > previous_throughput {
+            cmp::min(limit + 1, MAXIMUM_CONCURRENT_REQUESTS)
+        }
+        // Otherwise, keep the current limit.
+        else {
+            limit
+        }
+    }
+}
+
 /// Logs the progress of the sync.
 fn log_progress<const OBJECTS_PER_FILE: u32>(
     timer: Instant,
@@ -435,13 +708,28 @@ fn log_progress<const OBJECTS_PER_FILE: u32>(
 #[cfg(test)]
 mod tests {
     use crate::{
-        blocks::{cdn_get, cdn_height, log_progress, BLOCKS_PER_FILE},
+        blocks::{
+            cdn_get,
+            cdn_height,
+            fetch_bundle,
+            log_progress,
+            retry_delay,
+            AdaptiveConcurrency,
+            BLOCKS_PER_FILE,
+            MAXIMUM_CONCURRENT_REQUESTS,
+            MAXIMUM_RETRY_DELAY,
+            MINIMUM_CONCURRENT_REQUESTS,
+        },
+        bundles::{bundle_name, checksum_manifest, checksum_name},
         load_blocks,
     };
-    use snarkvm::prelude::{block::Block, MainnetV0};
+    use snarkvm::prelude::{block::Block, FromBytes, MainnetV0, Network};
 
     use parking_lot::RwLock;
-    use std::{sync::Arc, time::Instant};
+    use std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    };
 
     type CurrentNetwork = MainnetV0;
 
@@ -518,6 +806,38 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_fetch_bundle_checksum_manifest() {
+        let directory = tempfile::tempdir().unwrap();
+        let base_url = format!("file://{}", directory.path().display());
+        let name = bundle_name(0, 1);
+        let manifest_path = directory.path().join(checksum_name(&name));
+
+        // Write a bundle with the genesis block.
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let bytes = bincode::serialize(&vec![genesis.clone()]).unwrap();
+        std::fs::write(directory.path().join(&name), &bytes).unwrap();
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let client = reqwest::Client::new();
+            let fetch = || fetch_bundle::<CurrentNetwork>(&client, &base_url, 0, 1, None);
+
+            // A bundle without a checksum manifest is not verified.
+            assert_eq!(fetch().await.unwrap().0, vec![genesis.clone()]);
+            // A bundle with a matching checksum manifest is verified.
+            std::fs::write(&manifest_path, checksum_manifest(&name, &bytes)).unwrap();
+            assert_eq!(fetch().await.unwrap().0, vec![genesis.clone()]);
+            // A bundle with a mismatching checksum manifest is rejected.
+            std::fs::write(&manifest_path, checksum_manifest(&name, b"invalid")).unwrap();
+            assert!(fetch().await.is_err());
+            // A checksum manifest that fails to load is not skipped, so the bundle is retried.
+            std::fs::remove_file(&manifest_path).unwrap();
+            std::fs::create_dir(&manifest_path).unwrap();
+            assert!(fetch().await.is_err());
+        });
+    }
+
     #[test]
     fn test_log_progress() {
         // This test sanity checks that basic arithmetic is correct (i.e. no divide by zero, etc.).
@@ -537,4 +857,57 @@ mod tests {
         log_progress::<10>(timer, 90, cdn_start, cdn_end, object_name);
         log_progress::<10>(timer, 100, cdn_start, cdn_end, object_name);
     }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), Duration::from_secs(1));
+        assert_eq!(retry_delay(2), Duration::from_secs(2));
+        assert_eq!(retry_delay(3), Duration::from_secs(4));
+        assert_eq!(retry_delay(6), Duration::from_secs(32));
+        assert_eq!(retry_delay(7), MAXIMUM_RETRY_DELAY);
+        assert_eq!(retry_delay(u8::MAX), MAXIMUM_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_adaptive_concurrency() {
+        // The limit increases while the throughput increases.
+        assert_eq!(AdaptiveConcurrency::next_limit(16, 100.0, 120.0), 17);
+        assert_eq!(
+            AdaptiveConcurrency::next_limit(MAXIMUM_CONCURRENT_REQUESTS, 100.0, 120.0),
+            MAXIMUM_CONCURRENT_REQUESTS
+        );
+        // The limit is kept while the throughput is stable.
+        assert_eq!(AdaptiveConcurrency::next_limit(16, 100.0, 100.0), 16);
+        assert_eq!(AdaptiveConcurrency::next_limit(16, 100.0, 90.0), 16);
+        // The limit is halved when the throughput drops.
+        assert_eq!(AdaptiveConcurrency::next_limit(16, 100.0, 50.0), 8);
+        assert_eq!(AdaptiveConcurrency::next_limit(1, 100.0, 50.0), MINIMUM_CONCURRENT_REQUESTS);
+
+        // The limit is only adjusted once per interval.
+        let mut concurrency = AdaptiveConcurrency::new(16);
+        concurrency.start_request();
+        concurrency.finish_request(1024);
+        assert_eq!(concurrency.adjust(), 16);
+        assert_eq!(concurrency.num_bytes, 1024);
+
+        // The throughput is only measured while requests are in flight.
+        let mut concurrency = AdaptiveConcurrency::new(16);
+        std::thread::sleep(Duration::from_millis(20));
+        concurrency.adjust();
+        assert_eq!(concurrency.busy_time, Duration::ZERO);
+        concurrency.start_request();
+        std::thread::sleep(Duration::from_millis(20));
+        concurrency.finish_request(1024);
+        let busy_time = concurrency.busy_time;
+        assert!(busy_time >= Duration::from_millis(20));
+        std::thread::sleep(Duration::from_millis(20));
+        concurrency.adjust();
+        assert_eq!(concurrency.busy_time, busy_time);
+
+        // The measurement is discarded on a reset.
+        concurrency.reset();
+        assert_eq!(concurrency.busy_time, Duration::ZERO);
+        assert_eq!(concurrency.num_bytes, 0);
+        assert_eq!(concurrency.adjust(), 16);
+    }
 }
@@ -0,0 +1,161 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{ensure, Result};
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Returns the file name of the bundle of blocks in the given range.
+pub(crate) fn bundle_name(start: u32, end: u32) -> String {
+    format!("{start}.{end}.blocks")
+}
+
+/// Returns the file name of the checksum manifest of the given bundle.
+pub(crate) fn checksum_name(bundle_name: &str) -> String {
+    format!("{bundle_name}.sha256")
+}
+
+/// Returns the checksum manifest of the given bundle, in the `sha256sum` format.
+pub(crate) fn checksum_manifest(bundle_name: &str, bytes: &[u8]) -> String {
+    format!("{:x}  {bundle_name}\n", Sha256::digest(bytes))
+}
+
+/// Ensures the given bundle matches the checksum in the given manifest.
+pub(crate) fn verify_checksum(bundle_name: &str, bytes: &[u8], manifest: &str) -> Result<()> {
+    // Retrieve the expected checksum, which is the first entry of the manifest.
+    let expected = manifest.split_whitespace().next().unwrap_or_default();
+    // Compute the actual checksum.
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    ensure!(
+        expected.eq_ignore_ascii_case(&actual),
+        "Invalid checksum for '{bundle_name}' (expected {expected}, found {actual})"
+    );
+    Ok(())
+}
+
+/// Writes the given bytes to a temporary file, which is then renamed to the given path.
+pub(crate) fn write_atomically(path: &Path, bytes: &[u8]) -> Result<()> {
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, bytes)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// A local cache of the bundles that were downloaded from the CDN, but not yet processed.
+///
+/// Each bundle is stored along with its checksum manifest, so that an interrupted sync
+/// may resume from the cached bundles, instead of downloading them again.
+#[derive(Clone, Debug)]
+pub(crate) struct BundleCache {
+    /// The directory of the cache.
+    directory: PathBuf,
+}
+
+impl BundleCache {
+    /// Opens the cache in the given directory, and removes the bundles that end at or before the given height.
+    pub(crate) fn open(directory: PathBuf, start_height: u32) -> Result<Self> {
+        fs::create_dir_all(&directory)?;
+        // Remove the bundles that were already processed.
+        for entry in fs::read_dir(&directory)?.flatten() {
+            let file_name = entry.file_name();
+            let Some(end) = file_name.to_str().and_then(|name| name.split('.').nth(1)?.parse::<u32>().ok()) else {
+                continue;
+            };
+            if end <= start_height {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+        Ok(Self { directory })
+    }
+
+    /// Returns the cached bundle of blocks in the given range, if it exists and matches its checksum.
+    pub(crate) fn get(&self, start: u32, end: u32) -> Option<Vec<u8>> {
+        let name = bundle_name(start, end);
+        let bytes = fs::read(self.directory.join(&name)).ok()?;
+        let manifest = fs::read_to_string(self.directory.join(checksum_name(&name))).ok()?;
+        match verify_checksum(&name, &bytes, &manifest) {
+            Ok(()) => Some(bytes),
+            Err(error) => {
+                warn!("Discarding the cached bundle - {error}");
+                self.remove(start, end);
+                None
+            }
+        }
+    }
+
+    /// Inserts the given bundle of blocks in the given range into the cache.
+    pub(crate) fn insert(&self, start: u32, end: u32, bytes: &[u8]) -> Result<()> {
+        let name = bundle_name(start, end);
+        write_atomically(&self.directory.join(&name), bytes)?;
+        write_atomically(&self.directory.join(checksum_name(&name)), checksum_manifest(&name, bytes).as_bytes())
+    }
+
+    /// Removes the bundle of blocks in the given range from the cache.
+    pub(crate) fn remove(&self, start: u32, end: u32) {
+        let name = bundle_name(start, end);
+        let _ = fs::remove_file(self.directory.join(checksum_name(&name)));
+        let _ = fs::remove_file(self.directory.join(name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum() {
+        let name = bundle_name(0, 50);
+        assert_eq!(name, "0.50.blocks");
+        assert_eq!(checksum_name(&name), "0.50.blocks.sha256");
+
+        let manifest = checksum_manifest(&name, b"bundle");
+        assert!(manifest.ends_with("  0.50.blocks\n"));
+        assert!(verify_checksum(&name, b"bundle", &manifest).is_ok());
+        assert!(verify_checksum(&name, b"bundle", &manifest.to_uppercase()).is_ok());
+        assert!(verify_checksum(&name, b"corrupted", &manifest).is_err());
+        assert!(verify_checksum(&name, b"bundle", "").is_err());
+    }
+
+    #[test]
+    fn test_bundle_cache() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let directory = tempdir.path().to_path_buf();
+
+        // Insert two bundles.
+        let cache = BundleCache::open(directory.clone(), 0).unwrap();
+        cache.insert(0, 50, b"first").unwrap();
+        cache.insert(50, 100, b"second").unwrap();
+        assert_eq!(cache.get(0, 50).unwrap(), b"first");
+        assert_eq!(cache.get(50, 100).unwrap(), b"second");
+        assert!(cache.get(100, 150).is_none());
+
+        // Corrupt the second bundle, which must be discarded.
+        fs::write(directory.join(bundle_name(50, 100)), b"corrupted").unwrap();
+        assert!(cache.get(50, 100).is_none());
+        assert!(!directory.join(bundle_name(50, 100)).exists());
+
+        // Reopen the cache from a later height, which removes the processed bundle.
+        cache.insert(50, 100, b"second").unwrap();
+        let cache = BundleCache::open(directory.clone(), 50).unwrap();
+        assert!(cache.get(0, 50).is_none());
+        assert_eq!(cache.get(50, 100).unwrap(), b"second");
+
+        // Remove the remaining bundle.
+        cache.remove(50, 100);
+        assert!(cache.get(50, 100).is_none());
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    blocks::{LatestState, BLOCKS_PER_FILE},
+    bundles::{bundle_name, checksum_manifest, checksum_name, write_atomically},
+};
+use snarkvm::prelude::{block::Block, Network};
+
+use anyhow::{bail, ensure, Result};
+use std::{fs, path::Path};
+
+/// Exports the blocks in the given range into the given directory, in the CDN bundle format.
+///
+/// The blocks are written in bundles of `BLOCKS_PER_FILE` blocks, named `{start}.{end}.blocks`, each with a
+/// `{start}.{end}.blocks.sha256` checksum manifest, along with a `latest.json` file containing the exported height.
+/// As such, the directory may be served as a CDN, or used directly with a `file://` base URL.
+///
+/// The start height is rounded down, and the end height (exclusive) is rounded down, to a multiple of
+/// `BLOCKS_PER_FILE`. Bundles that already exist in the directory are skipped, so an export may be resumed.
+///
+/// On success, this function returns the exclusive height of the exported blocks.
+pub fn export_blocks<N: Network>(
+    directory: &Path,
+    start_height: u32,
+    end_height: u32,
+    get_block: impl Fn(u32) -> Result<Block<N>>,
+) -> Result<u32> {
+    export_bundles::<N, BLOCKS_PER_FILE>(directory, start_height, end_height, get_block)
+}
+
+/// Exports the blocks in the given range into the given directory, in bundles of `BLOCKS_PER_FILE` blocks.
+fn export_bundles<N: Network, const BLOCKS_PER_FILE: u32>(
+    directory: &Path,
+    start_height: u32,
+    end_height: u32,
+    get_block: impl Fn(u32) -> Result<Block<N>>,
+) -> Result<u32> {
+    // Compute the bundle range, rounded down to the nearest multiple.
+    let cdn_start = start_height - (start_height % BLOCKS_PER_FILE);
+    let cdn_end = end_height - (end_height % BLOCKS_PER_FILE);
+    // Ensure there is at least one full bundle to export.
+    ensure!(
+        cdn_start < cdn_end,
+        "There are not enough blocks to export a bundle of {BLOCKS_PER_FILE} blocks (from {start_height} to {end_height})"
+    );
+
+    // Create the directory, if it does not exist.
+    fs::create_dir_all(directory)?;
+
+    for start in (cdn_start..cdn_end).step_by(BLOCKS_PER_FILE as usize) {
+        let end = start + BLOCKS_PER_FILE;
+        // Prepare the bundle paths.
+        let name = bundle_name(start, end);
+        let path = directory.join(&name);
+        let checksum_path = directory.join(checksum_name(&name));
+        // If the bundle was previously exported, skip it.
+        if path.exists() && checksum_path.exists() {
+            debug!("Skipping blocks {start} to {end} (already exported)");
+            continue;
+        }
+        // Retrieve the blocks.
+        let blocks = (start..end).map(&get_block).collect::<Result<Vec<_>>>()?;
+        // Write the bundle, followed by its checksum manifest.
+        let bytes = bincode::serialize(&blocks)?;
+        write_atomically(&path, &bytes)?;
+        write_atomically(&checksum_path, checksum_manifest(&name, &bytes).as_bytes())?;
+        info!("Exported blocks {start} to {end} (of {cdn_end})");
+    }
+
+    // Retrieve the last exported block.
+    let block = get_block(cdn_end - 1)?;
+    if block.height() != cdn_end - 1 {
+        bail!("Expected block {} but found block {}", cdn_end - 1, block.height());
+    }
+    // Write the 'latest.json' file, encoded in the same way as the CDN.
+    let latest =
+        LatestState { exclusive_height: cdn_end, inclusive_height: cdn_end - 1, hash: block.hash().to_string() };
+    let latest_json = bincode::serialize(&serde_json::to_string(&latest)?)?;
+    write_atomically(&directory.join("latest.json"), &latest_json)?;
+
+    Ok(cdn_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{cdn_height, fetch_bundle};
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    use reqwest::Client;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_export_blocks_requires_a_full_bundle() {
+        let directory = tempfile::tempdir().unwrap();
+        let get_block = |_height: u32| -> Result<Block<CurrentNetwork>> { bail!("No blocks") };
+        assert!(export_blocks(directory.path(), 0, BLOCKS_PER_FILE - 1, get_block).is_err());
+        assert!(export_blocks(directory.path(), 10, BLOCKS_PER_FILE + 10, get_block).is_err());
+    }
+
+    #[test]
+    fn test_export_and_load_blocks() {
+        let directory = tempfile::tempdir().unwrap();
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+
+        // Export the genesis block, in bundles of a single block.
+        let get_block = |height: u32| {
+            ensure!(height == 0, "There is no block at height {height}");
+            Ok(genesis.clone())
+        };
+        let exported_height = export_bundles::<CurrentNetwork, 1>(directory.path(), 0, 1, get_block).unwrap();
+        assert_eq!(exported_height, 1);
+        assert!(directory.path().join("0.1.blocks").exists());
+        assert!(directory.path().join("0.1.blocks.sha256").exists());
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let client = Client::new();
+            let base_url = format!("file://{}", directory.path().display());
+
+            // Load the height from the exported 'latest.json' file.
+            assert_eq!(cdn_height::<1>(&client, &base_url).await.unwrap(), 1);
+            // Load the exported bundle, which is verified against its checksum manifest.
+            let (blocks, _) = fetch_bundle::<CurrentNetwork>(&client, &base_url, 0, 1, None).await.unwrap();
+            assert_eq!(blocks, vec![genesis.clone()]);
+
+            // Corrupt the exported bundle, and ensure it is rejected.
+            let path = directory.path().join("0.1.blocks");
+            let mut bytes = fs::read(&path).unwrap();
+            let last = bytes.len() - 1;
+            bytes[last] ^= 1;
+            fs::write(&path, bytes).unwrap();
+            assert!(fetch_bundle::<CurrentNetwork>(&client, &base_url, 0, 1, None).await.is_err());
+        });
+    }
+}
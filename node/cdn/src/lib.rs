@@ -19,3 +19,11 @@ extern crate tracing;
 
 mod blocks;
 pub use blocks::{load_blocks, sync_ledger_with_cdn};
+
+mod bundles;
+
+mod export;
+pub use export::export_blocks;
+
+mod progress;
+pub use progress::{subscribe_progress, sync_progress, SyncProgress};
@@ -0,0 +1,89 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::OnceLock;
+use tokio::sync::watch;
+
+/// The progress of the CDN sync.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncProgress {
+    /// The height of the first block to sync.
+    pub start_height: u32,
+    /// The height of the last processed block.
+    pub current_height: u32,
+    /// The height at which the sync ends (exclusive).
+    pub end_height: u32,
+    /// The number of bytes downloaded from the CDN.
+    pub downloaded_bytes: u64,
+    /// The number of bundles loaded from the local cache, instead of the CDN.
+    pub cached_bundles: u32,
+    /// The current number of concurrent requests to the CDN.
+    pub concurrency: u32,
+}
+
+impl SyncProgress {
+    /// Returns the percentage of the sync that is complete.
+    pub fn percentage(&self) -> f64 {
+        let total = self.end_height.saturating_sub(self.start_height);
+        match total {
+            0 => 100.0,
+            _ => self.current_height.saturating_sub(self.start_height) as f64 * 100.0 / total as f64,
+        }
+    }
+
+    /// Returns `true` if the sync has not started, or has processed every block.
+    pub fn is_complete(&self) -> bool {
+        self.current_height + 1 >= self.end_height
+    }
+}
+
+/// Returns the sender of the CDN sync progress.
+fn sender() -> &'static watch::Sender<SyncProgress> {
+    static PROGRESS: OnceLock<watch::Sender<SyncProgress>> = OnceLock::new();
+    PROGRESS.get_or_init(|| watch::channel(SyncProgress::default()).0)
+}
+
+/// Returns a receiver for the progress of the CDN sync, which is notified on every update.
+pub fn subscribe_progress() -> watch::Receiver<SyncProgress> {
+    sender().subscribe()
+}
+
+/// Returns the latest progress of the CDN sync.
+pub fn sync_progress() -> SyncProgress {
+    sender().borrow().clone()
+}
+
+/// Updates the progress of the CDN sync with the given function.
+pub(crate) fn update_progress(update: impl FnOnce(&mut SyncProgress)) {
+    sender().send_modify(update);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentage() {
+        let progress = SyncProgress { start_height: 100, current_height: 150, end_height: 200, ..Default::default() };
+        assert_eq!(progress.percentage(), 50.0);
+        assert!(!progress.is_complete());
+
+        let progress = SyncProgress { start_height: 100, current_height: 199, end_height: 200, ..Default::default() };
+        assert!(progress.is_complete());
+
+        let progress = SyncProgress::default();
+        assert_eq!(progress.percentage(), 100.0);
+        assert!(progress.is_complete());
+    }
+}
@@ -17,6 +17,17 @@
 #[macro_use]
 extern crate tracing;
 
+pub use snarkos_node_bft as bft;
+
+mod mempool;
+use mempool::*;
+
+mod snapshot;
+use snapshot::*;
+
+mod transaction_status;
+pub use transaction_status::*;
+
 use snarkos_account::Account;
 use snarkos_node_bft::{
     helpers::{
@@ -31,10 +42,10 @@ use snarkos_node_bft::{
     BFT,
 };
 use snarkos_node_bft_ledger_service::LedgerService;
-use snarkos_node_bft_storage_service::BFTPersistentStorage;
+use snarkos_node_bft_storage_service::{BFTPersistentCertificateStorage, BFTPersistentStorage};
 use snarkvm::{
     ledger::{
-        block::Transaction,
+        block::{Block, Transaction},
         coinbase::{ProverSolution, PuzzleCommitment},
         narwhal::{BatchHeader, Data, Subdag, Transmission, TransmissionID},
     },
@@ -47,36 +58,30 @@ use colored::Colorize;
 use indexmap::IndexMap;
 use lru::LruCache;
 use parking_lot::Mutex;
-use std::{future::Future, net::SocketAddr, num::NonZeroUsize, sync::Arc};
+use std::{future::Future, net::SocketAddr, num::NonZeroUsize, path::PathBuf, sync::Arc, time::Duration};
 use tokio::{
-    sync::{oneshot, OnceCell},
+    sync::{broadcast, oneshot, OnceCell},
     task::JoinHandle,
 };
 
-/// Percentage of mempool transactions capacity reserved for deployments.
-const CAPACITY_FOR_DEPLOYMENTS: usize = 20;
-/// Percentage of mempool transactions capacity reserved for executions.
-const CAPACITY_FOR_EXECUTIONS: usize = 80;
+/// The maximum number of transactions from a single sender in the memory pool.
+const MAX_TRANSACTIONS_PER_SENDER: usize = 16;
 
-/// Helper struct to track incoming transactions.
-struct TransactionsQueue<N: Network> {
-    pub deployments: LruCache<N::TransactionID, Transaction<N>>,
-    pub executions: LruCache<N::TransactionID, Transaction<N>>,
-}
+/// The interval between snapshots of the memory pool.
+const MEMPOOL_SNAPSHOT_INTERVAL_IN_SECS: u64 = 60;
 
-impl<N: Network> Default for TransactionsQueue<N> {
-    fn default() -> Self {
-        Self {
-            deployments: LruCache::new(
-                NonZeroUsize::new(BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH * CAPACITY_FOR_DEPLOYMENTS / 100)
-                    .unwrap(),
-            ),
-            executions: LruCache::new(
-                NonZeroUsize::new(BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH * CAPACITY_FOR_EXECUTIONS / 100)
-                    .unwrap(),
-            ),
-        }
-    }
+/// The maximum number of consensus events buffered for each subscriber.
+const MAX_CONSENSUS_EVENTS: usize = 1024;
+
+/// A notification emitted by consensus, as the memory pool and the ledger advance.
+#[derive(Clone, Debug)]
+pub enum ConsensusEvent<N: Network> {
+    /// The unconfirmed solution was added to the memory pool.
+    UnconfirmedSolution(ProverSolution<N>),
+    /// The unconfirmed transaction was added to the memory pool.
+    UnconfirmedTransaction(Transaction<N>),
+    /// The ledger advanced to the block.
+    Block(Block<N>),
 }
 
 #[derive(Clone)]
@@ -89,12 +94,18 @@ pub struct Consensus<N: Network> {
     primary_sender: Arc<OnceCell<PrimarySender<N>>>,
     /// The unconfirmed solutions queue.
     solutions_queue: Arc<Mutex<LruCache<PuzzleCommitment<N>, ProverSolution<N>>>>,
-    /// The unconfirmed transactions queue.
+    /// The unconfirmed transactions queue, ordered by fee priority.
     transactions_queue: Arc<Mutex<TransactionsQueue<N>>>,
     /// The recently-seen unconfirmed solutions.
     seen_solutions: Arc<Mutex<LruCache<PuzzleCommitment<N>, ()>>>,
     /// The recently-seen unconfirmed transactions.
     seen_transactions: Arc<Mutex<LruCache<N::TransactionID, ()>>>,
+    /// The statuses of the recently-seen unconfirmed transactions.
+    transaction_statuses: Arc<TransactionStatuses<N>>,
+    /// The sender for the consensus events.
+    events: broadcast::Sender<ConsensusEvent<N>>,
+    /// The path of the memory pool snapshot.
+    mempool_path: PathBuf,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
 }
@@ -106,6 +117,7 @@ impl<N: Network> Consensus<N> {
         ledger: Arc<dyn LedgerService<N>>,
         ip: Option<SocketAddr>,
         trusted_validators: &[SocketAddr],
+        num_workers: u8,
         storage_mode: StorageMode,
     ) -> Result<Self> {
         // Recover the development ID, if it is present.
@@ -113,12 +125,17 @@ impl<N: Network> Consensus<N> {
             StorageMode::Development(id) => Some(id),
             StorageMode::Production | StorageMode::Custom(..) => None,
         };
+        // Determine the path of the memory pool snapshot.
+        let mempool_path = aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone()).join(MEMPOOL_SNAPSHOT_FILE_NAME);
         // Initialize the Narwhal transmissions.
-        let transmissions = Arc::new(BFTPersistentStorage::open(storage_mode)?);
+        let transmissions = Arc::new(BFTPersistentStorage::open(storage_mode.clone())?);
+        // Initialize the Narwhal certificates.
+        let certificates = Arc::new(BFTPersistentCertificateStorage::open(storage_mode)?);
         // Initialize the Narwhal storage.
-        let storage = NarwhalStorage::new(ledger.clone(), transmissions, BatchHeader::<N>::MAX_GC_ROUNDS as u64);
+        let storage =
+            NarwhalStorage::new(ledger.clone(), transmissions, certificates, BatchHeader::<N>::MAX_GC_ROUNDS as u64);
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger.clone(), ip, trusted_validators, dev)?;
+        let bft = BFT::new(account, storage, ledger.clone(), ip, trusted_validators, num_workers, dev)?;
         // Return the consensus.
         Ok(Self {
             ledger,
@@ -127,9 +144,15 @@ impl<N: Network> Consensus<N> {
             solutions_queue: Arc::new(Mutex::new(LruCache::new(
                 NonZeroUsize::new(BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH).unwrap(),
             ))),
-            transactions_queue: Default::default(),
+            transactions_queue: Arc::new(Mutex::new(TransactionsQueue::new(
+                BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH,
+                MAX_TRANSACTIONS_PER_SENDER,
+            ))),
             seen_solutions: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
             seen_transactions: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
+            transaction_statuses: Arc::new(TransactionStatuses::new()),
+            events: broadcast::channel(MAX_CONSENSUS_EVENTS).0,
+            mempool_path,
             handles: Default::default(),
         })
     }
@@ -144,8 +167,11 @@ impl<N: Network> Consensus<N> {
         let (consensus_sender, consensus_receiver) = init_consensus_channels();
         // Then, start the consensus handlers.
         self.start_handlers(consensus_receiver);
-        // Lastly, the consensus.
+        // Next, the consensus.
         self.bft.run(Some(consensus_sender), primary_sender, primary_receiver).await?;
+        // Lastly, restore the memory pool from its snapshot.
+        let self_ = self.clone();
+        self.spawn(async move { self_.restore_mempool().await });
         Ok(())
     }
 
@@ -163,6 +189,11 @@ impl<N: Network> Consensus<N> {
     pub fn primary_sender(&self) -> &PrimarySender<N> {
         self.primary_sender.get().expect("Primary sender not set")
     }
+
+    /// Returns a new receiver for the consensus events.
+    pub fn subscribe(&self) -> broadcast::Receiver<ConsensusEvent<N>> {
+        self.events.subscribe()
+    }
 }
 
 impl<N: Network> Consensus<N> {
@@ -209,9 +240,31 @@ impl<N: Network> Consensus<N> {
     }
 }
 
+impl<N: Network> Consensus<N> {
+    /// Returns the status of the given transaction, if it was recently seen by consensus.
+    pub fn transaction_status(&self, transaction_id: &N::TransactionID) -> Option<TransactionStatus> {
+        let status = self.transaction_statuses.get(transaction_id)?;
+        // If the transaction left the worker, determine whether it is in a proposed or certified batch.
+        if status == TransactionStatus::InWorker {
+            let transmission_id = TransmissionID::from(transaction_id);
+            if self.bft.storage().contains_transmission(transmission_id) {
+                return Some(TransactionStatus::InCertifiedBatch);
+            }
+            let proposed_batch = self.bft.primary().proposed_batch().read();
+            if proposed_batch.as_ref().map_or(false, |proposal| proposal.transmissions().contains_key(&transmission_id))
+            {
+                return Some(TransactionStatus::InProposedBatch);
+            }
+        }
+        Some(status)
+    }
+}
+
 impl<N: Network> Consensus<N> {
     /// Adds the given unconfirmed solution to the memory pool.
     pub async fn add_unconfirmed_solution(&self, solution: ProverSolution<N>) -> Result<()> {
+        // Ensure the node is not shutting down.
+        ensure!(!self.bft.primary().is_shutting_down(), "Unable to accept the solution (the node is shutting down)");
         #[cfg(feature = "metrics")]
         {
             metrics::increment_gauge(metrics::consensus::UNCONFIRMED_SOLUTIONS, 1f64);
@@ -235,6 +288,8 @@ impl<N: Network> Consensus<N> {
             if self.solutions_queue.lock().put(solution_id, solution).is_some() {
                 bail!("Solution '{}' exists in the memory pool", fmt_id(solution_id));
             }
+            // Notify the subscribers, if there are any.
+            let _ = self.events.send(ConsensusEvent::UnconfirmedSolution(solution));
         }
 
         // If the memory pool of this node is full, return early.
@@ -270,6 +325,8 @@ impl<N: Network> Consensus<N> {
 
     /// Adds the given unconfirmed transaction to the memory pool.
     pub async fn add_unconfirmed_transaction(&self, transaction: Transaction<N>) -> Result<()> {
+        // Ensure the node is not shutting down.
+        ensure!(!self.bft.primary().is_shutting_down(), "Unable to accept the transaction (the node is shutting down)");
         #[cfg(feature = "metrics")]
         {
             metrics::increment_gauge(metrics::consensus::UNCONFIRMED_TRANSACTIONS, 1f64);
@@ -292,15 +349,32 @@ impl<N: Network> Consensus<N> {
             if self.ledger.contains_transmission(&TransmissionID::from(&transaction_id))? {
                 bail!("Transaction '{}' exists in the ledger {}", fmt_id(transaction_id), "(skipping)".dimmed());
             }
+            // Check if the transaction already reached a final status (e.g. it was aborted in a block).
+            if let Some(status) = self.transaction_statuses.get_final(&transaction_id) {
+                bail!("Transaction '{}' is already {status:?} {}", fmt_id(transaction_id), "(skipping)".dimmed());
+            }
             // Add the transaction to the memory pool.
             trace!("Received unconfirmed transaction '{}' in the queue", fmt_id(transaction_id));
-            if transaction.is_deploy() {
-                if self.transactions_queue.lock().deployments.put(transaction_id, transaction).is_some() {
-                    bail!("Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
+            let result = self.transactions_queue.lock().insert(transaction.clone());
+            match result {
+                // If a transaction with a lower fee was evicted, forget it, so that it may be resubmitted with a higher fee.
+                Ok(Some(evicted_id)) => {
+                    trace!("Evicted unconfirmed transaction '{}' from the queue", fmt_id(evicted_id));
+                    self.seen_transactions.lock().pop(&evicted_id);
+                    self.transaction_statuses.remove(&evicted_id);
+                }
+                Ok(None) => (),
+                Err(e) => {
+                    // Forget the transaction, so that it may be resubmitted (e.g. once the memory pool has capacity).
+                    self.seen_transactions.lock().pop(&transaction_id);
+                    self.transaction_statuses.remove(&transaction_id);
+                    return Err(e);
                 }
-            } else if self.transactions_queue.lock().executions.put(transaction_id, transaction).is_some() {
-                bail!("Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
             }
+            // Record that the transaction is queued.
+            self.transaction_statuses.update(transaction_id, TransactionStatus::Queued);
+            // Notify the subscribers, if there are any.
+            let _ = self.events.send(ConsensusEvent::UnconfirmedTransaction(transaction));
         }
 
         // If the memory pool of this node is full, return early.
@@ -314,38 +388,26 @@ impl<N: Network> Consensus<N> {
             let capacity = BatchHeader::<N>::MAX_TRANSMISSIONS_PER_BATCH.saturating_sub(num_unconfirmed);
             // Acquire the lock on the transactions queue.
             let mut tx_queue = self.transactions_queue.lock();
-            // Determine the number of deployments to send.
-            let num_deployments = tx_queue.deployments.len().min(capacity * CAPACITY_FOR_DEPLOYMENTS / 100);
-            // Determine the number of executions to send.
-            let num_executions = tx_queue.executions.len().min(capacity.saturating_sub(num_deployments));
-            // Create an iterator which will select interleaved deployments and executions within the capacity.
-            // Note: interleaving ensures we will never have consecutive invalid deployments blocking the queue.
-            let selector_iter = (0..num_deployments).map(|_| true).interleave((0..num_executions).map(|_| false));
-            // Drain the transactions from the queue, interleaving deployments and executions.
-            selector_iter
-                .filter_map(|select_deployment| {
-                    if select_deployment {
-                        tx_queue.deployments.pop_lru().map(|(_, tx)| tx)
-                    } else {
-                        tx_queue.executions.pop_lru().map(|(_, tx)| tx)
-                    }
-                })
-                .collect_vec()
+            // Drain the transactions from the queue, from the highest to the lowest fee priority.
+            tx_queue.drain(capacity)
         };
         // Iterate over the transactions.
         for transaction in transactions.into_iter() {
             let transaction_id = transaction.id();
             trace!("Adding unconfirmed transaction '{}' to the memory pool...", fmt_id(transaction_id));
             // Send the unconfirmed transaction to the primary.
-            if let Err(e) =
-                self.primary_sender().send_unconfirmed_transaction(transaction_id, Data::Object(transaction)).await
-            {
-                // If the BFT is synced, then log the warning.
-                if self.bft.is_synced() {
-                    warn!(
-                        "Failed to add unconfirmed transaction '{}' to the memory pool - {e}",
-                        fmt_id(transaction_id)
-                    );
+            match self.primary_sender().send_unconfirmed_transaction(transaction_id, Data::Object(transaction)).await {
+                Ok(()) => self.transaction_statuses.update(transaction_id, TransactionStatus::InWorker),
+                Err(e) => {
+                    // If the BFT is synced, then log the warning.
+                    if self.bft.is_synced() {
+                        warn!(
+                            "Failed to add unconfirmed transaction '{}' to the memory pool - {e}",
+                            fmt_id(transaction_id)
+                        );
+                    }
+                    self.transaction_statuses
+                        .update(transaction_id, TransactionStatus::Rejected { reason: e.to_string() });
                 }
             }
         }
@@ -365,6 +427,92 @@ impl<N: Network> Consensus<N> {
                 self_.process_bft_subdag(committed_subdag, transmissions, callback).await;
             }
         });
+
+        // Periodically snapshot the memory pool.
+        let self_ = self.clone();
+        self.spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(MEMPOOL_SNAPSHOT_INTERVAL_IN_SECS)).await;
+                let self__ = self_.clone();
+                if let Err(e) = spawn_blocking!(self__.save_mempool()) {
+                    warn!("Failed to snapshot the memory pool - {e}");
+                }
+            }
+        });
+    }
+
+    /// Saves a snapshot of the unconfirmed solutions and transactions in the memory pool,
+    /// including the queues of consensus and the ready queues of the workers.
+    fn save_mempool(&self) -> Result<()> {
+        // Collect the unconfirmed solutions.
+        let mut solutions =
+            self.solutions_queue.lock().iter().map(|(_, solution)| Data::Object(*solution)).collect_vec();
+        solutions.extend(self.bft.unconfirmed_solutions().map(|(_, solution)| solution));
+        // Collect the unconfirmed transactions.
+        let mut transactions = self
+            .transactions_queue
+            .lock()
+            .transactions()
+            .map(|transaction| Data::Object(transaction.clone()))
+            .collect_vec();
+        transactions.extend(self.bft.unconfirmed_transactions().map(|(_, transaction)| transaction));
+        // Save the snapshot.
+        let (num_solutions, num_transactions) = (solutions.len(), transactions.len());
+        MempoolSnapshot { solutions, transactions }.save(&self.mempool_path)?;
+        debug!("Saved a snapshot of the memory pool ({num_solutions} solutions, {num_transactions} transactions)");
+        Ok(())
+    }
+
+    /// Restores the unconfirmed solutions and transactions from the snapshot of the memory pool, if it exists.
+    /// Each solution and transaction is checked again before it is queued, so that a stale entry does not take
+    /// the capacity of the memory pool, and is dropped if it is invalid or already in the ledger.
+    async fn restore_mempool(&self) {
+        // Load the snapshot.
+        let path = self.mempool_path.clone();
+        let snapshot = match spawn_blocking!(MempoolSnapshot::<N>::load(&path)) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                warn!("Failed to load the memory pool snapshot - {e}");
+                return;
+            }
+        };
+        let (mut num_solutions, mut num_transactions) = (0, 0);
+        // Restore the solutions.
+        for solution in snapshot.solutions {
+            let Data::Object(solution) = solution else { continue };
+            let commitment = solution.commitment();
+            // Drop the solution if it is already in the ledger, or is no longer valid.
+            if self.ledger.contains_transmission(&TransmissionID::from(commitment)).unwrap_or(true) {
+                continue;
+            }
+            if let Err(e) = self.ledger.check_solution_basic(commitment, Data::Object(solution)).await {
+                trace!("Dropping the restored solution '{}' - {e}", fmt_id(commitment));
+                continue;
+            }
+            if self.add_unconfirmed_solution(solution).await.is_ok() {
+                num_solutions += 1;
+            }
+        }
+        // Restore the transactions.
+        for transaction in snapshot.transactions {
+            let Data::Object(transaction) = transaction else { continue };
+            let transaction_id = transaction.id();
+            // Drop the transaction if it is already in the ledger, or is no longer valid.
+            if self.ledger.contains_transmission(&TransmissionID::from(&transaction_id)).unwrap_or(true) {
+                continue;
+            }
+            if let Err(e) = self.ledger.check_transaction_basic(transaction_id, Data::Object(transaction.clone())).await
+            {
+                trace!("Dropping the restored transaction '{}' - {e}", fmt_id(transaction_id));
+                continue;
+            }
+            if self.add_unconfirmed_transaction(transaction).await.is_ok() {
+                num_transactions += 1;
+            }
+        }
+        if num_solutions + num_transactions > 0 {
+            info!("Restored {num_solutions} solutions and {num_transactions} transactions into the memory pool");
+        }
     }
 
     /// Processes the committed subdag and transmissions from the BFT.
@@ -410,6 +558,15 @@ impl<N: Network> Consensus<N> {
         // Advance to the next block.
         self.ledger.advance_to_next_block(&next_block)?;
 
+        // Update the statuses of the transactions in the block.
+        self.transaction_statuses.update_for_block(
+            next_block.height(),
+            next_block.transactions().iter().filter_map(|transaction| {
+                Some((transaction.to_unconfirmed_transaction_id().ok()?, transaction.is_accepted()))
+            }),
+            next_block.aborted_transaction_ids().iter().copied(),
+        );
+
         #[cfg(feature = "metrics")]
         {
             let elapsed = std::time::Duration::from_secs((snarkos_node_bft::helpers::now() - start) as u64);
@@ -428,6 +585,8 @@ impl<N: Network> Consensus<N> {
             metrics::histogram(metrics::consensus::CERTIFICATE_COMMIT_LATENCY, elapsed.as_secs_f64());
             metrics::histogram(metrics::consensus::BLOCK_LATENCY, block_latency as f64);
         }
+        // Notify the subscribers, if there are any.
+        let _ = self.events.send(ConsensusEvent::Block(next_block));
         Ok(())
     }
 
@@ -475,6 +634,11 @@ impl<N: Network> Consensus<N> {
     /// Shuts down the BFT.
     pub async fn shut_down(&self) {
         info!("Shutting down consensus...");
+        // Snapshot the memory pool, before its queues are dropped.
+        let self_ = self.clone();
+        if let Err(e) = spawn_blocking!(self_.save_mempool()) {
+            warn!("Failed to snapshot the memory pool - {e}");
+        }
         // Shut down the BFT.
         self.bft.shut_down().await;
         // Abort the tasks.
@@ -0,0 +1,340 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bft::helpers::{fmt_id, transaction_priority};
+use snarkvm::prelude::{block::Transaction, *};
+
+use anyhow::Result;
+use itertools::Itertools;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+};
+
+/// Percentage of the memory pool capacity reserved for deployments.
+const CAPACITY_FOR_DEPLOYMENTS: usize = 20;
+/// Percentage of the memory pool capacity reserved for executions.
+const CAPACITY_FOR_EXECUTIONS: usize = 80;
+
+/// The key of a transaction in the priority index, as `(priority, reversed sequence number)`.
+/// Note: Reversing the sequence number ensures that, for an equal priority, the oldest transaction comes first.
+type PriorityKey = (u64, Reverse<u64>);
+
+/// Returns the sender of the given transaction (i.e. the payer of a public fee), if there is one.
+fn sender<N: Network>(transaction: &Transaction<N>) -> Option<Address<N>> {
+    transaction.fee_transition().and_then(|fee| fee.payer())
+}
+
+/// A queue of unconfirmed transactions, with separate memory pools for deployments and executions.
+///
+/// Each memory pool is ordered by the priority fee of its transactions, and a share of the capacity is
+/// reserved for deployments. Each sender (i.e. the payer of a public fee) may have up to a maximum number
+/// of transactions in the queue.
+pub(crate) struct TransactionsQueue<N: Network> {
+    /// The memory pool of deployments.
+    deployments: Mempool<N>,
+    /// The memory pool of executions.
+    executions: Mempool<N>,
+    /// The maximum number of transactions per sender.
+    max_per_sender: usize,
+}
+
+impl<N: Network> TransactionsQueue<N> {
+    /// Initializes a new queue with the given capacity, and the given maximum number of transactions per sender.
+    pub(crate) fn new(capacity: usize, max_per_sender: usize) -> Self {
+        Self {
+            deployments: Mempool::new(capacity * CAPACITY_FOR_DEPLOYMENTS / 100),
+            executions: Mempool::new(capacity * CAPACITY_FOR_EXECUTIONS / 100),
+            max_per_sender,
+        }
+    }
+
+    /// Returns the number of transactions in the queue.
+    pub(crate) fn len(&self) -> usize {
+        self.deployments.len() + self.executions.len()
+    }
+
+    /// Returns `true` if the queue contains the given transaction ID.
+    pub(crate) fn contains(&self, transaction_id: &N::TransactionID) -> bool {
+        self.deployments.contains(transaction_id) || self.executions.contains(transaction_id)
+    }
+
+    /// Returns the transactions in the queue, with the deployments first, each from the highest to the lowest priority.
+    pub(crate) fn transactions(&self) -> impl '_ + Iterator<Item = &Transaction<N>> {
+        self.deployments.transactions().chain(self.executions.transactions())
+    }
+
+    /// Inserts the given transaction into the queue.
+    /// On success, this method returns the ID of the transaction that was evicted, if the memory pool was full.
+    pub(crate) fn insert(&mut self, transaction: Transaction<N>) -> Result<Option<N::TransactionID>> {
+        let transaction_id = transaction.id();
+        // Ensure the transaction is not already in the queue.
+        ensure!(!self.contains(&transaction_id), "Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
+        // Ensure the sender has not reached the maximum number of transactions.
+        if let Some(sender) = sender(&transaction) {
+            let num_transactions =
+                self.deployments.num_transactions_from(&sender) + self.executions.num_transactions_from(&sender);
+            ensure!(
+                num_transactions < self.max_per_sender,
+                "Transaction '{}' exceeds the limit of {} transactions per sender in the memory pool",
+                fmt_id(transaction_id),
+                self.max_per_sender
+            );
+        }
+        // Insert the transaction into its memory pool.
+        match transaction.is_deploy() {
+            true => self.deployments.insert(transaction),
+            false => self.executions.insert(transaction),
+        }
+    }
+
+    /// Removes and returns up to the given number of transactions, from the highest to the lowest priority.
+    ///
+    /// Up to `CAPACITY_FOR_DEPLOYMENTS` percent of the transactions are deployments, which are interleaved
+    /// with the executions. Note: Interleaving ensures consecutive invalid deployments never block the queue.
+    pub(crate) fn drain(&mut self, capacity: usize) -> Vec<Transaction<N>> {
+        // Determine the number of deployments to drain.
+        let num_deployments = self.deployments.len().min(capacity * CAPACITY_FOR_DEPLOYMENTS / 100);
+        // Determine the number of executions to drain.
+        let num_executions = self.executions.len().min(capacity.saturating_sub(num_deployments));
+        // Create an iterator which will select interleaved deployments and executions within the capacity.
+        let selector_iter = (0..num_deployments).map(|_| true).interleave((0..num_executions).map(|_| false));
+        // Drain the transactions, interleaving deployments and executions.
+        selector_iter
+            .filter_map(|select_deployment| match select_deployment {
+                true => self.deployments.pop(),
+                false => self.executions.pop(),
+            })
+            .collect()
+    }
+}
+
+/// An unconfirmed transaction in the memory pool.
+struct Entry<N: Network> {
+    /// The transaction.
+    transaction: Transaction<N>,
+    /// The key of the transaction in the priority index.
+    key: PriorityKey,
+    /// The sender of the transaction, if the fee is public.
+    sender: Option<Address<N>>,
+}
+
+/// A memory pool of unconfirmed transactions, ordered by their priority fee.
+///
+/// When the memory pool is full, the transaction with the lowest priority is evicted for a new transaction,
+/// if the new transaction has a higher priority.
+pub(crate) struct Mempool<N: Network> {
+    /// The maximum number of transactions.
+    capacity: usize,
+    /// The map of `transaction ID` to `entry`.
+    transactions: HashMap<N::TransactionID, Entry<N>>,
+    /// The priority index, as a map of `priority key` to `transaction ID`.
+    index: BTreeMap<PriorityKey, N::TransactionID>,
+    /// The number of transactions of each sender.
+    senders: HashMap<Address<N>, usize>,
+    /// The sequence number of the next transaction.
+    sequence: u64,
+}
+
+impl<N: Network> Mempool<N> {
+    /// Initializes a new memory pool with the given capacity.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            transactions: Default::default(),
+            index: Default::default(),
+            senders: Default::default(),
+            sequence: 0,
+        }
+    }
+
+    /// Returns the number of transactions in the memory pool.
+    pub(crate) fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Returns `true` if the memory pool contains the given transaction ID.
+    pub(crate) fn contains(&self, transaction_id: &N::TransactionID) -> bool {
+        self.transactions.contains_key(transaction_id)
+    }
+
+    /// Returns the number of transactions of the given sender in the memory pool.
+    pub(crate) fn num_transactions_from(&self, sender: &Address<N>) -> usize {
+        self.senders.get(sender).copied().unwrap_or_default()
+    }
+
+    /// Returns the transactions in the memory pool, from the highest to the lowest priority.
+    pub(crate) fn transactions(&self) -> impl '_ + Iterator<Item = &Transaction<N>> {
+        self.index
+            .values()
+            .rev()
+            .filter_map(|transaction_id| self.transactions.get(transaction_id).map(|entry| &entry.transaction))
+    }
+
+    /// Inserts the given transaction into the memory pool.
+    /// On success, this method returns the ID of the transaction that was evicted, if the memory pool was full.
+    pub(crate) fn insert(&mut self, transaction: Transaction<N>) -> Result<Option<N::TransactionID>> {
+        let priority = transaction_priority(&transaction)?;
+        self.insert_with_priority(transaction, priority)
+    }
+
+    /// Inserts the given transaction into the memory pool, with the given priority.
+    fn insert_with_priority(&mut self, transaction: Transaction<N>, priority: u64) -> Result<Option<N::TransactionID>> {
+        let transaction_id = transaction.id();
+        // Ensure the transaction is not already in the memory pool.
+        ensure!(!self.contains(&transaction_id), "Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
+        // If the memory pool is full, evict the transaction with the lowest priority.
+        let mut evicted = None;
+        if self.len() >= self.capacity {
+            match self.index.first_key_value() {
+                Some(((lowest_priority, _), lowest_id)) if *lowest_priority < priority => {
+                    let lowest_id = *lowest_id;
+                    self.remove(&lowest_id);
+                    evicted = Some(lowest_id);
+                }
+                _ => bail!(
+                    "Transaction '{}' has an insufficient priority fee ({priority} microcredits per credit of base fee) for the full memory pool",
+                    fmt_id(transaction_id)
+                ),
+            }
+        }
+        // Insert the transaction.
+        let key = (priority, Reverse(self.sequence));
+        self.sequence += 1;
+        self.index.insert(key, transaction_id);
+        let sender = sender(&transaction);
+        if let Some(sender) = sender {
+            *self.senders.entry(sender).or_default() += 1;
+        }
+        self.transactions.insert(transaction_id, Entry { transaction, key, sender });
+        Ok(evicted)
+    }
+
+    /// Removes and returns the transaction with the highest priority.
+    pub(crate) fn pop(&mut self) -> Option<Transaction<N>> {
+        let (_, transaction_id) = self.index.last_key_value()?;
+        let transaction_id = *transaction_id;
+        self.remove(&transaction_id)
+    }
+
+    /// Removes and returns the transaction with the given ID.
+    fn remove(&mut self, transaction_id: &N::TransactionID) -> Option<Transaction<N>> {
+        let entry = self.transactions.remove(transaction_id)?;
+        self.index.remove(&entry.key);
+        if let Some(sender) = entry.sender {
+            if let Some(num_transactions) = self.senders.get_mut(&sender) {
+                *num_transactions -= 1;
+                if *num_transactions == 0 {
+                    self.senders.remove(&sender);
+                }
+            }
+        }
+        Some(entry.transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::ledger::ledger_test_helpers::{sample_deployment_transaction, sample_execution_transaction_with_fee};
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns two distinct deployments and two distinct executions.
+    fn sample_transactions(rng: &mut TestRng) -> (Vec<Transaction<CurrentNetwork>>, Vec<Transaction<CurrentNetwork>>) {
+        let deployments = vec![sample_deployment_transaction(true, rng), sample_deployment_transaction(false, rng)];
+        let executions =
+            vec![sample_execution_transaction_with_fee(true, rng), sample_execution_transaction_with_fee(false, rng)];
+        (deployments, executions)
+    }
+
+    #[test]
+    fn test_mempool_ordering() {
+        let rng = &mut TestRng::default();
+        let (deployments, executions) = sample_transactions(rng);
+        let [a, b, c, d] = [&deployments[0], &deployments[1], &executions[0], &executions[1]].map(|tx| tx.clone());
+
+        let mut mempool = Mempool::<CurrentNetwork>::new(4);
+        assert_eq!(mempool.insert_with_priority(a.clone(), 10).unwrap(), None);
+        assert_eq!(mempool.insert_with_priority(b.clone(), 30).unwrap(), None);
+        assert_eq!(mempool.insert_with_priority(c.clone(), 20).unwrap(), None);
+        assert_eq!(mempool.insert_with_priority(d.clone(), 30).unwrap(), None);
+        assert_eq!(mempool.len(), 4);
+
+        // Ensure a duplicate transaction is rejected.
+        assert!(mempool.insert_with_priority(a.clone(), 40).is_err());
+
+        // Ensure the transactions are ordered by priority, with the oldest transaction first for an equal priority.
+        let expected = [b.id(), d.id(), c.id(), a.id()];
+        assert_eq!(mempool.transactions().map(|tx| tx.id()).collect_vec(), expected);
+        assert_eq!((0..4).filter_map(|_| mempool.pop()).map(|tx| tx.id()).collect_vec(), expected);
+        assert!(mempool.pop().is_none());
+        assert_eq!(mempool.len(), 0);
+    }
+
+    #[test]
+    fn test_mempool_eviction() {
+        let rng = &mut TestRng::default();
+        let (deployments, executions) = sample_transactions(rng);
+        let [a, b, c] = [&deployments[0], &deployments[1], &executions[0]].map(|tx| tx.clone());
+
+        let mut mempool = Mempool::<CurrentNetwork>::new(2);
+        assert_eq!(mempool.insert_with_priority(a.clone(), 10).unwrap(), None);
+        assert_eq!(mempool.insert_with_priority(b.clone(), 20).unwrap(), None);
+
+        // Ensure a transaction with a priority that is not higher than the lowest priority is rejected.
+        assert!(mempool.insert_with_priority(c.clone(), 10).is_err());
+        assert!(!mempool.contains(&c.id()));
+
+        // Ensure a transaction with a higher priority evicts the transaction with the lowest priority.
+        assert_eq!(mempool.insert_with_priority(c.clone(), 15).unwrap(), Some(a.id()));
+        assert!(!mempool.contains(&a.id()));
+        assert_eq!(mempool.transactions().map(|tx| tx.id()).collect_vec(), [b.id(), c.id()]);
+    }
+
+    #[test]
+    fn test_transactions_queue_deployments_and_executions() {
+        let rng = &mut TestRng::default();
+        let (deployments, executions) = sample_transactions(rng);
+
+        // Initialize a queue with a capacity of 1 deployment and 4 executions.
+        let mut queue = TransactionsQueue::<CurrentNetwork>::new(5, 16);
+        for transaction in executions.iter().chain(&deployments[..1]) {
+            queue.insert(transaction.clone()).unwrap();
+        }
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.deployments.len(), 1);
+        assert_eq!(queue.executions.len(), 2);
+        // Ensure a duplicate transaction is rejected.
+        assert!(queue.insert(executions[0].clone()).is_err());
+
+        // Ensure the deployments are limited to their share of the capacity, and interleaved with the executions.
+        let drained = queue.drain(10);
+        assert_eq!(drained.len(), 3);
+        assert!(drained[0].is_deploy());
+        assert!(drained[1..].iter().all(|transaction| transaction.is_execute()));
+        assert_eq!(queue.len(), 0);
+
+        // Ensure the deployments are limited to their share of the capacity when draining.
+        let mut queue = TransactionsQueue::<CurrentNetwork>::new(10, 16);
+        for transaction in deployments.iter().chain(&executions) {
+            queue.insert(transaction.clone()).unwrap();
+        }
+        assert_eq!(queue.deployments.len(), 2);
+        let drained = queue.drain(5);
+        assert_eq!(drained.iter().filter(|transaction| transaction.is_deploy()).count(), 1);
+        assert_eq!(drained.iter().filter(|transaction| transaction.is_execute()).count(), 2);
+        assert_eq!(queue.deployments.len(), 1);
+    }
+}
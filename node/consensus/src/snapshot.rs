@@ -0,0 +1,181 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::{block::Transaction, coinbase::ProverSolution, narwhal::Data},
+    prelude::{error, FromBytes, IoResult, Network, Read, ToBytes, Write},
+};
+
+use anyhow::Result;
+use std::{fs, path::Path};
+
+/// The file name of the memory pool snapshot, in the ledger directory.
+pub(crate) const MEMPOOL_SNAPSHOT_FILE_NAME: &str = "mempool.snapshot";
+
+/// The maximum number of bytes of a solution or transaction in the snapshot.
+const MAX_DATA_SIZE: u32 = 16 * 1024 * 1024; // 16 MiB
+
+/// A snapshot of the unconfirmed solutions and transactions in the memory pool.
+pub(crate) struct MempoolSnapshot<N: Network> {
+    /// The unconfirmed solutions.
+    pub(crate) solutions: Vec<Data<ProverSolution<N>>>,
+    /// The unconfirmed transactions.
+    pub(crate) transactions: Vec<Data<Transaction<N>>>,
+}
+
+impl<N: Network> MempoolSnapshot<N> {
+    /// The version of the snapshot format.
+    const VERSION: u8 = 1;
+
+    /// Loads the snapshot from the given path. If the snapshot does not exist, an empty snapshot is returned.
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        match path.exists() {
+            true => Ok(Self::from_bytes_le(&fs::read(path)?)?),
+            false => Ok(Self { solutions: Default::default(), transactions: Default::default() }),
+        }
+    }
+
+    /// Saves the snapshot to the given path, replacing the previous snapshot atomically.
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, self.to_bytes_le()?)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Writes the given data, prefixed with its length.
+fn write_data<T: FromBytes + ToBytes + Send + 'static, W: Write>(data: &Data<T>, mut writer: W) -> IoResult<()> {
+    let bytes = match data {
+        Data::Object(object) => object.to_bytes_le().map_err(error)?,
+        Data::Buffer(bytes) => bytes.to_vec(),
+    };
+    u32::try_from(bytes.len()).map_err(error)?.write_le(&mut writer)?;
+    writer.write_all(&bytes)
+}
+
+/// Reads the data, prefixed with its length.
+fn read_data<T: FromBytes + ToBytes + Send + 'static, R: Read>(mut reader: R) -> IoResult<Data<T>> {
+    let num_bytes = u32::read_le(&mut reader)?;
+    if num_bytes > MAX_DATA_SIZE {
+        return Err(error(format!("Memory pool snapshot entry is too large ({num_bytes} bytes)")));
+    }
+    // Note: The buffer only grows with the bytes that are actually read, as the file may be truncated.
+    let mut bytes = Vec::new();
+    if reader.take(num_bytes as u64).read_to_end(&mut bytes)? != num_bytes as usize {
+        return Err(error("Memory pool snapshot entry is truncated"));
+    }
+    Ok(Data::Object(T::from_bytes_le(&bytes).map_err(error)?))
+}
+
+impl<N: Network> ToBytes for MempoolSnapshot<N> {
+    /// Writes the snapshot to the buffer.
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        // Write the version.
+        Self::VERSION.write_le(&mut writer)?;
+        // Write the solutions.
+        u32::try_from(self.solutions.len()).map_err(error)?.write_le(&mut writer)?;
+        for solution in &self.solutions {
+            write_data(solution, &mut writer)?;
+        }
+        // Write the transactions.
+        u32::try_from(self.transactions.len()).map_err(error)?.write_le(&mut writer)?;
+        for transaction in &self.transactions {
+            write_data(transaction, &mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for MempoolSnapshot<N> {
+    /// Reads the snapshot from the buffer.
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        // Read the version.
+        let version = u8::read_le(&mut reader)?;
+        if version != Self::VERSION {
+            return Err(error(format!("Invalid memory pool snapshot version ({version})")));
+        }
+        // Read the solutions.
+        let num_solutions = u32::read_le(&mut reader)?;
+        let solutions = (0..num_solutions).map(|_| read_data(&mut reader)).collect::<IoResult<Vec<_>>>()?;
+        // Read the transactions.
+        let num_transactions = u32::read_le(&mut reader)?;
+        let transactions = (0..num_transactions).map(|_| read_data(&mut reader)).collect::<IoResult<Vec<_>>>()?;
+        Ok(Self { solutions, transactions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::ledger_test_helpers::{sample_deployment_transaction, sample_execution_transaction_with_fee},
+        prelude::{MainnetV0, TestRng},
+    };
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let rng = &mut TestRng::default();
+        let deployment = sample_deployment_transaction(true, rng);
+        let execution = sample_execution_transaction_with_fee(false, rng);
+
+        let snapshot = MempoolSnapshot::<CurrentNetwork> {
+            solutions: vec![],
+            transactions: vec![Data::Object(deployment.clone()), Data::Buffer(execution.to_bytes_le().unwrap().into())],
+        };
+
+        // Save and load the snapshot.
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(MEMPOOL_SNAPSHOT_FILE_NAME);
+        snapshot.save(&path).unwrap();
+        let candidate = MempoolSnapshot::<CurrentNetwork>::load(&path).unwrap();
+        assert!(candidate.solutions.is_empty());
+        assert_eq!(candidate.transactions.len(), 2);
+        for (transaction, expected) in candidate.transactions.into_iter().zip([deployment, execution]) {
+            let Data::Object(transaction) = transaction else { panic!("Expected a deserialized transaction") };
+            assert_eq!(transaction, expected);
+        }
+
+        // Loading a missing snapshot returns an empty snapshot.
+        let candidate = MempoolSnapshot::<CurrentNetwork>::load(&directory.path().join("missing")).unwrap();
+        assert!(candidate.solutions.is_empty() && candidate.transactions.is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_rejects_invalid_lengths() {
+        let rng = &mut TestRng::default();
+        let transaction = sample_execution_transaction_with_fee(true, rng);
+        let snapshot =
+            MempoolSnapshot::<CurrentNetwork> { solutions: vec![], transactions: vec![Data::Object(transaction)] };
+        let bytes = snapshot.to_bytes_le().unwrap();
+
+        // A truncated entry is rejected.
+        assert!(MempoolSnapshot::<CurrentNetwork>::from_bytes_le(&bytes[..bytes.len() - 1]).is_err());
+
+        // An entry that claims to exceed the maximum size is rejected.
+        let mut bytes = vec![MempoolSnapshot::<CurrentNetwork>::VERSION];
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&(MAX_DATA_SIZE + 1).to_le_bytes());
+        assert!(MempoolSnapshot::<CurrentNetwork>::from_bytes_le(&bytes).is_err());
+
+        // An unknown version is rejected.
+        assert!(MempoolSnapshot::<CurrentNetwork>::from_bytes_le(&[0, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::Network;
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
+
+/// The maximum number of transactions tracked in the status index.
+pub const MAX_TRACKED_TRANSACTIONS: usize = 1 << 16;
+
+/// The status of a transaction, as it moves from the memory pool to a block.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TransactionStatus {
+    /// The transaction is waiting in the transactions queue for capacity in the memory pool.
+    Queued,
+    /// The transaction was accepted into the memory pool of a worker.
+    InWorker,
+    /// The transaction is in the batch proposed by this node.
+    InProposedBatch,
+    /// The transaction is in a certified batch, which is waiting to be committed.
+    InCertifiedBatch,
+    /// The transaction was committed in the block at the given height.
+    Committed { height: u32 },
+    /// The transaction was rejected, for the given reason.
+    Rejected { reason: String },
+    /// The transaction is not in the ledger, and its status is not tracked by this node.
+    Unknown,
+}
+
+impl TransactionStatus {
+    /// Returns `true` if the transaction has reached a final status.
+    pub const fn is_final(&self) -> bool {
+        matches!(self, Self::Committed { .. } | Self::Rejected { .. })
+    }
+
+    /// Returns `true` if this status may be replaced by the given status.
+    /// A final status is never replaced by a status that is not final.
+    pub const fn can_update_to(&self, status: &TransactionStatus) -> bool {
+        !self.is_final() || status.is_final()
+    }
+}
+
+/// The statuses of the transactions recently seen by consensus.
+pub(crate) struct TransactionStatuses<N: Network> {
+    /// The statuses, bounded to the most recently updated transactions.
+    statuses: Mutex<LruCache<N::TransactionID, TransactionStatus>>,
+}
+
+impl<N: Network> TransactionStatuses<N> {
+    /// Initializes a new instance of the transaction statuses.
+    pub(crate) fn new() -> Self {
+        Self { statuses: Mutex::new(LruCache::new(NonZeroUsize::new(MAX_TRACKED_TRANSACTIONS).unwrap())) }
+    }
+
+    /// Returns the status of the given transaction, if it is tracked.
+    pub(crate) fn get(&self, transaction_id: &N::TransactionID) -> Option<TransactionStatus> {
+        self.statuses.lock().peek(transaction_id).cloned()
+    }
+
+    /// Returns the status of the given transaction, if it reached a final status.
+    pub(crate) fn get_final(&self, transaction_id: &N::TransactionID) -> Option<TransactionStatus> {
+        self.get(transaction_id).filter(|status| status.is_final())
+    }
+
+    /// Updates the status of the given transaction, unless the transaction already reached a final status.
+    pub(crate) fn update(&self, transaction_id: N::TransactionID, status: TransactionStatus) {
+        let mut statuses = self.statuses.lock();
+        if statuses.peek(&transaction_id).map_or(true, |current| current.can_update_to(&status)) {
+            statuses.put(transaction_id, status);
+        }
+    }
+
+    /// Stops tracking the given transaction (e.g. it was evicted from the memory pool, and may be resubmitted).
+    pub(crate) fn remove(&self, transaction_id: &N::TransactionID) {
+        self.statuses.lock().pop(transaction_id);
+    }
+
+    /// Updates the statuses of the transactions in the block at the given height,
+    /// from the confirmed transactions (and whether they were accepted), and the aborted transactions.
+    pub(crate) fn update_for_block(
+        &self,
+        height: u32,
+        confirmed: impl IntoIterator<Item = (N::TransactionID, bool)>,
+        aborted: impl IntoIterator<Item = N::TransactionID>,
+    ) {
+        for (transaction_id, is_accepted) in confirmed {
+            let status = match is_accepted {
+                true => TransactionStatus::Committed { height },
+                false => TransactionStatus::Rejected { reason: format!("Rejected in block {height}") },
+            };
+            self.update(transaction_id, status);
+        }
+        for transaction_id in aborted {
+            let reason = format!("Aborted in block {height}");
+            self.update(transaction_id, TransactionStatus::Rejected { reason });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Field, MainnetV0};
+
+    type CurrentNetwork = MainnetV0;
+
+    fn sample_statuses() -> Vec<TransactionStatus> {
+        vec![
+            TransactionStatus::Queued,
+            TransactionStatus::InWorker,
+            TransactionStatus::InProposedBatch,
+            TransactionStatus::InCertifiedBatch,
+            TransactionStatus::Committed { height: 7 },
+            TransactionStatus::Rejected { reason: "Aborted in block 7".to_string() },
+            TransactionStatus::Unknown,
+        ]
+    }
+
+    #[test]
+    fn test_is_final() {
+        let expected = [false, false, false, false, true, true, false];
+        for (status, expected) in sample_statuses().into_iter().zip(expected) {
+            assert_eq!(status.is_final(), expected, "{status:?}");
+        }
+    }
+
+    #[test]
+    fn test_can_update_to() {
+        let queued = TransactionStatus::Queued;
+        let committed = TransactionStatus::Committed { height: 7 };
+        let rejected = TransactionStatus::Rejected { reason: "Rejected in block 7".to_string() };
+
+        // A status that is not final may be replaced by any status.
+        for status in sample_statuses() {
+            assert!(queued.can_update_to(&status), "{status:?}");
+        }
+        // A final status may only be replaced by a final status (e.g. a rejected transaction is committed by another validator).
+        for status in sample_statuses() {
+            assert_eq!(committed.can_update_to(&status), status.is_final(), "{status:?}");
+            assert_eq!(rejected.can_update_to(&status), status.is_final(), "{status:?}");
+        }
+        // A re-broadcast transaction does not overwrite its final status.
+        assert!(!committed.can_update_to(&TransactionStatus::InWorker));
+        assert!(!rejected.can_update_to(&TransactionStatus::Queued));
+    }
+
+    #[test]
+    fn test_serde_json() {
+        let expected = [
+            r#"{"status":"queued"}"#,
+            r#"{"status":"in_worker"}"#,
+            r#"{"status":"in_proposed_batch"}"#,
+            r#"{"status":"in_certified_batch"}"#,
+            r#"{"status":"committed","height":7}"#,
+            r#"{"status":"rejected","reason":"Aborted in block 7"}"#,
+            r#"{"status":"unknown"}"#,
+        ];
+        for (status, expected) in sample_statuses().into_iter().zip(expected) {
+            let candidate = serde_json::to_string(&status).unwrap();
+            assert_eq!(candidate, expected);
+            assert_eq!(serde_json::from_str::<TransactionStatus>(&candidate).unwrap(), status);
+        }
+    }
+
+    #[test]
+    fn test_transaction_statuses() {
+        let statuses = TransactionStatuses::<CurrentNetwork>::new();
+        let [committed_id, aborted_id, evicted_id] =
+            [1, 2, 3].map(|i| <CurrentNetwork as Network>::TransactionID::from(Field::from_u64(i)));
+
+        // Drive a transaction from the queue to a block.
+        assert_eq!(statuses.get(&committed_id), None);
+        statuses.update(committed_id, TransactionStatus::Queued);
+        assert_eq!(statuses.get(&committed_id), Some(TransactionStatus::Queued));
+        statuses.update(committed_id, TransactionStatus::InWorker);
+        assert_eq!(statuses.get(&committed_id), Some(TransactionStatus::InWorker));
+        assert_eq!(statuses.get_final(&committed_id), None);
+
+        // Drive another transaction to the worker, and commit the block, in which it is aborted.
+        statuses.update(aborted_id, TransactionStatus::InWorker);
+        statuses.update_for_block(7, [(committed_id, true)], [aborted_id]);
+        assert_eq!(statuses.get_final(&committed_id), Some(TransactionStatus::Committed { height: 7 }));
+        let rejected = TransactionStatus::Rejected { reason: "Aborted in block 7".to_string() };
+        assert_eq!(statuses.get_final(&aborted_id), Some(rejected.clone()));
+
+        // Ensure a re-broadcast transaction does not overwrite its final status.
+        statuses.update(committed_id, TransactionStatus::Queued);
+        assert_eq!(statuses.get(&committed_id), Some(TransactionStatus::Committed { height: 7 }));
+        statuses.update(aborted_id, TransactionStatus::Queued);
+        assert_eq!(statuses.get(&aborted_id), Some(rejected));
+
+        // Ensure a final status is replaced by a final status (e.g. the rejected transaction is committed later).
+        statuses.update_for_block(8, [(aborted_id, true)], []);
+        assert_eq!(statuses.get(&aborted_id), Some(TransactionStatus::Committed { height: 8 }));
+
+        // Ensure an evicted transaction is forgotten.
+        statuses.update(evicted_id, TransactionStatus::Queued);
+        statuses.remove(&evicted_id);
+        assert_eq!(statuses.get(&evicted_id), None);
+    }
+}
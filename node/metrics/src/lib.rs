@@ -13,16 +13,36 @@
 // limitations under the License.
 
 mod names;
+mod peers;
 
 // Expose the names at the crate level for easy access.
 pub use names::*;
+pub use peers::{record_peer_latency, remove_peer_latency};
 // Re-export the snarkVM metrics.
 pub use snarkvm::metrics::*;
 
-/// Initializes the metrics and returns a handle to the task running the metrics exporter.
-pub fn initialize_metrics() {
-    // Build the Prometheus exporter.
-    metrics_exporter_prometheus::PrometheusBuilder::new().install().expect("can't build the prometheus exporter");
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use std::sync::OnceLock;
+
+/// The handle to the Prometheus recorder, used to render the metrics on demand.
+static PROMETHEUS_HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Initializes the metrics.
+///
+/// If `with_exporter` is `true`, the metrics are served by a standalone Prometheus exporter.
+/// Otherwise, the metrics are only recorded, and may be rendered with [`render_metrics`] (e.g. by the REST server).
+///
+/// Note that the per-peer latencies are only included in [`render_metrics`].
+pub fn initialize_metrics(with_exporter: bool) {
+    match with_exporter {
+        // Build and install the Prometheus exporter.
+        true => PrometheusBuilder::new().install().expect("can't build the prometheus exporter"),
+        // Install the Prometheus recorder, and store its handle.
+        false => {
+            let handle = PrometheusBuilder::new().install_recorder().expect("can't build the prometheus recorder");
+            let _ = PROMETHEUS_HANDLE.set(handle);
+        }
+    }
 
     // Register the snarkVM metrics.
     snarkvm::metrics::register_metrics();
@@ -38,3 +58,28 @@ pub fn initialize_metrics() {
         register_histogram(name);
     }
 }
+
+/// Returns the metrics in the Prometheus text format, if the metrics were initialized without an exporter.
+pub fn render_metrics() -> Option<String> {
+    PROMETHEUS_HANDLE.get().map(|handle| handle.render() + &peers::render_peer_latencies())
+}
+
+/// Increments the counter with the given name and labels by the given value.
+pub fn increment_counter_with_labels(name: &'static str, value: u64, labels: &[(&'static str, String)]) {
+    ::metrics::counter!(name, to_labels(labels)).increment(value);
+}
+
+/// Sets the gauge with the given name and labels to the given value.
+pub fn gauge_with_labels(name: &'static str, value: f64, labels: &[(&'static str, String)]) {
+    ::metrics::gauge!(name, to_labels(labels)).set(value);
+}
+
+/// Records the given value in the histogram with the given name and labels.
+pub fn histogram_with_labels(name: &'static str, value: f64, labels: &[(&'static str, String)]) {
+    ::metrics::histogram!(name, to_labels(labels)).record(value);
+}
+
+/// Converts the given key-value pairs into metric labels.
+fn to_labels(labels: &[(&'static str, String)]) -> Vec<::metrics::Label> {
+    labels.iter().map(|(key, value)| ::metrics::Label::new(*key, value.clone())).collect()
+}
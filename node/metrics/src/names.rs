@@ -14,7 +14,7 @@
 
 pub(super) const COUNTER_NAMES: [&str; 1] = [bft::LEADERS_ELECTED];
 
-pub(super) const GAUGE_NAMES: [&str; 18] = [
+pub(super) const GAUGE_NAMES: [&str; 29] = [
     bft::CONNECTED,
     bft::CONNECTING,
     bft::LAST_STORED_ROUND,
@@ -24,14 +24,25 @@ pub(super) const GAUGE_NAMES: [&str; 18] = [
     blocks::SOLUTIONS,
     blocks::TRANSACTIONS,
     blocks::TRANSMISSIONS,
+    cdn::HEIGHT,
+    cdn::TARGET_HEIGHT,
+    cdn::DOWNLOADED_BYTES,
+    cdn::CACHED_BUNDLES,
+    cdn::CONCURRENT_REQUESTS,
     consensus::COMMITTED_CERTIFICATES,
     consensus::LAST_COMMITTED_ROUND,
     consensus::UNCONFIRMED_SOLUTIONS,
     consensus::UNCONFIRMED_TRANSACTIONS,
     consensus::UNCONFIRMED_TRANSMISSIONS,
+    prover::HASHRATE_1M,
+    prover::HASHRATE_15M,
+    prover::HASHRATE_1H,
     router::CONNECTED,
     router::CANDIDATE,
     router::RESTRICTED,
+    storage::LEDGER_SIZE,
+    sync::BLOCKS_BEHIND,
+    sync::GREATEST_PEER_HEIGHT,
     tcp::TCP_TASKS,
 ];
 
@@ -53,6 +64,13 @@ pub mod bft {
     pub const LEADERS_ELECTED: &str = "snarkos_bft_leaders_elected_total";
     pub const PROPOSAL_ROUND: &str = "snarkos_bft_primary_proposal_round";
     pub const CERTIFIED_BATCHES: &str = "snarkos_bft_primary_certified_batches";
+    // The following metrics are labelled with the event type.
+    pub const EVENTS_INBOUND: &str = "snarkos_bft_events_inbound_total";
+    pub const EVENTS_INBOUND_BYTES: &str = "snarkos_bft_events_inbound_bytes_total";
+    pub const EVENTS_OUTBOUND: &str = "snarkos_bft_events_outbound_total";
+    pub const EVENTS_OUTBOUND_BYTES: &str = "snarkos_bft_events_outbound_bytes_total";
+    // The following metric is labelled with the peer IP, and is removed on disconnect.
+    pub const PEER_LATENCY: &str = "snarkos_bft_peer_latency_secs";
 }
 
 pub mod blocks {
@@ -62,6 +80,14 @@ pub mod blocks {
     pub const SOLUTIONS: &str = "snarkos_blocks_solutions_total";
 }
 
+pub mod cdn {
+    pub const HEIGHT: &str = "snarkos_cdn_height";
+    pub const TARGET_HEIGHT: &str = "snarkos_cdn_target_height";
+    pub const DOWNLOADED_BYTES: &str = "snarkos_cdn_downloaded_bytes_total";
+    pub const CACHED_BUNDLES: &str = "snarkos_cdn_cached_bundles_total";
+    pub const CONCURRENT_REQUESTS: &str = "snarkos_cdn_concurrent_requests";
+}
+
 pub mod consensus {
     pub const CERTIFICATE_COMMIT_LATENCY: &str = "snarkos_consensus_certificate_commit_latency_secs";
     pub const COMMITTED_CERTIFICATES: &str = "snarkos_consensus_committed_certificates_total";
@@ -72,10 +98,44 @@ pub mod consensus {
     pub const UNCONFIRMED_SOLUTIONS: &str = "snarkos_consensus_unconfirmed_solutions_total";
 }
 
+pub mod prover {
+    pub const HASHRATE_1M: &str = "snarkos_prover_hashrate_1m";
+    pub const HASHRATE_15M: &str = "snarkos_prover_hashrate_15m";
+    pub const HASHRATE_1H: &str = "snarkos_prover_hashrate_1h";
+    // The following metrics are labelled with the puzzle instance.
+    pub const PROOFS_ATTEMPTED: &str = "snarkos_prover_proofs_attempted_total";
+    pub const SOLUTIONS_FOUND: &str = "snarkos_prover_solutions_found_total";
+    pub const BEST_TARGET: &str = "snarkos_prover_best_target";
+}
+
 pub mod router {
     pub const CONNECTED: &str = "snarkos_router_connected_total";
     pub const CANDIDATE: &str = "snarkos_router_candidate_total";
     pub const RESTRICTED: &str = "snarkos_router_restricted_total";
+    // The following metrics are labelled with the message type.
+    pub const MESSAGES_INBOUND: &str = "snarkos_router_messages_inbound_total";
+    pub const MESSAGES_INBOUND_BYTES: &str = "snarkos_router_messages_inbound_bytes_total";
+    pub const MESSAGES_OUTBOUND: &str = "snarkos_router_messages_outbound_total";
+    pub const MESSAGES_OUTBOUND_BYTES: &str = "snarkos_router_messages_outbound_bytes_total";
+    // The following metric is labelled with the node type of the peer.
+    pub const PING_LATENCY: &str = "snarkos_router_ping_latency_secs";
+    // The following metric is labelled with the peer IP, and is removed on disconnect.
+    pub const PEER_LATENCY: &str = "snarkos_router_peer_latency_secs";
+}
+
+pub mod rest {
+    // The following metrics are labelled with the HTTP method and the route (and status, for the requests).
+    pub const REQUESTS: &str = "snarkos_rest_requests_total";
+    pub const REQUEST_LATENCY: &str = "snarkos_rest_request_latency_secs";
+}
+
+pub mod storage {
+    pub const LEDGER_SIZE: &str = "snarkos_storage_ledger_size_bytes";
+}
+
+pub mod sync {
+    pub const BLOCKS_BEHIND: &str = "snarkos_sync_blocks_behind";
+    pub const GREATEST_PEER_HEIGHT: &str = "snarkos_sync_greatest_peer_height";
 }
 
 pub mod tcp {
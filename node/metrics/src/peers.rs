@@ -0,0 +1,106 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeMap, fmt::Write, net::SocketAddr, sync::Mutex};
+
+/// The upper bounds of the latency buckets, in seconds.
+const LATENCY_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+/// The per-peer latency histograms, keyed by the metric name and the peer IP.
+///
+/// These are kept outside of the metrics recorder, which offers no way to remove a series;
+/// this way, the series of a peer can be dropped on disconnect, bounding the label cardinality.
+static PEER_LATENCIES: Mutex<BTreeMap<(&'static str, SocketAddr), LatencyHistogram>> = Mutex::new(BTreeMap::new());
+
+#[derive(Default)]
+struct LatencyHistogram {
+    /// The cumulative number of samples in each bucket.
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    /// The total number of samples.
+    count: u64,
+    /// The sum of all samples, in seconds.
+    sum: f64,
+}
+
+/// Records the given latency (in seconds) in the histogram with the given name, labelled with the peer IP.
+pub fn record_peer_latency(name: &'static str, peer_ip: SocketAddr, latency_in_secs: f64) {
+    let mut latencies = PEER_LATENCIES.lock().unwrap_or_else(|e| e.into_inner());
+    let histogram = latencies.entry((name, peer_ip)).or_default();
+    for (bucket, bound) in histogram.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+        if latency_in_secs <= bound {
+            *bucket += 1;
+        }
+    }
+    histogram.count += 1;
+    histogram.sum += latency_in_secs;
+}
+
+/// Removes the latency series of the given peer IP, from every histogram.
+pub fn remove_peer_latency(peer_ip: SocketAddr) {
+    PEER_LATENCIES.lock().unwrap_or_else(|e| e.into_inner()).retain(|(_, ip), _| *ip != peer_ip);
+}
+
+/// Returns the per-peer latency histograms in the Prometheus text format.
+pub(crate) fn render_peer_latencies() -> String {
+    let latencies = PEER_LATENCIES.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut output = String::new();
+    let mut previous_name = None;
+    for ((name, peer_ip), histogram) in latencies.iter() {
+        // Write the type once per histogram; the map is ordered by name.
+        if previous_name != Some(*name) {
+            let _ = writeln!(output, "# TYPE {name} histogram");
+            previous_name = Some(*name);
+        }
+        for (count, bound) in histogram.buckets.iter().zip(LATENCY_BUCKETS) {
+            let _ = writeln!(output, "{name}_bucket{{peer=\"{peer_ip}\",le=\"{bound}\"}} {count}");
+        }
+        let _ = writeln!(output, "{name}_bucket{{peer=\"{peer_ip}\",le=\"+Inf\"}} {}", histogram.count);
+        let _ = writeln!(output, "{name}_sum{{peer=\"{peer_ip}\"}} {}", histogram.sum);
+        let _ = writeln!(output, "{name}_count{{peer=\"{peer_ip}\"}} {}", histogram.count);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_latency() {
+        const NAME: &str = "test_peer_latency_secs";
+        let peer_a: SocketAddr = "127.0.0.1:4130".parse().unwrap();
+        let peer_b: SocketAddr = "127.0.0.1:4131".parse().unwrap();
+
+        // Record a latency for each peer.
+        record_peer_latency(NAME, peer_a, 0.02);
+        record_peer_latency(NAME, peer_a, 0.2);
+        record_peer_latency(NAME, peer_b, 10.0);
+
+        let output = render_peer_latencies();
+        assert_eq!(output.matches(&format!("# TYPE {NAME} histogram")).count(), 1);
+        assert!(output.contains(&format!("{NAME}_bucket{{peer=\"{peer_a}\",le=\"0.01\"}} 0")));
+        assert!(output.contains(&format!("{NAME}_bucket{{peer=\"{peer_a}\",le=\"0.025\"}} 1")));
+        assert!(output.contains(&format!("{NAME}_bucket{{peer=\"{peer_a}\",le=\"0.25\"}} 2")));
+        assert!(output.contains(&format!("{NAME}_count{{peer=\"{peer_a}\"}} 2")));
+        assert!(output.contains(&format!("{NAME}_bucket{{peer=\"{peer_b}\",le=\"5\"}} 0")));
+        assert!(output.contains(&format!("{NAME}_bucket{{peer=\"{peer_b}\",le=\"+Inf\"}} 1")));
+
+        // Remove the first peer, and ensure only its series is gone.
+        remove_peer_latency(peer_a);
+        let output = render_peer_latencies();
+        assert!(!output.contains(&format!("peer=\"{peer_a}\"")));
+        assert!(output.contains(&format!("{NAME}_count{{peer=\"{peer_b}\"}} 1")));
+    }
+}
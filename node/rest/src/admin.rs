@@ -0,0 +1,301 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use snarkos_node_consensus::bft::events::DisconnectReason as GatewayDisconnectReason;
+use snarkos_node_router::{messages::DisconnectReason, BannedIp, PeerRules};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::net::IpAddr;
+
+/// The `add_trusted_peer` request object.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct TrustedPeerRequest {
+    /// The peer IP to trust.
+    peer_ip: SocketAddr,
+    /// If `true`, the change is persisted to survive a restart.
+    #[serde(default)]
+    persist: bool,
+}
+
+/// The `ban_ip` request object.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct BanRequest {
+    /// The IP to ban.
+    ip: IpAddr,
+    /// The duration of the ban in seconds, or `None` to ban indefinitely.
+    duration_secs: Option<u64>,
+    /// If `true`, the change is persisted to survive a restart.
+    #[serde(default)]
+    persist: bool,
+}
+
+/// The `connect_peer` request object.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct ConnectRequest {
+    /// The peer IP to connect to.
+    peer_ip: SocketAddr,
+    /// If `true`, the peer is a validator to connect to through the BFT gateway, instead of the router.
+    #[serde(default)]
+    gateway: bool,
+}
+
+/// The `disconnect_peer` request object.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct DisconnectRequest {
+    /// The peer IP to disconnect from.
+    peer_ip: SocketAddr,
+    /// The disconnect reason sent to the peer (default: `NoReasonGiven`).
+    /// Note: The BFT gateway only supports the `InvalidChallengeResponse`, `NoReasonGiven`, `ProtocolViolation`,
+    /// and `OutdatedClientVersion` reasons.
+    reason: Option<String>,
+    /// If `true`, the peer is a validator to disconnect from through the BFT gateway, instead of the router.
+    #[serde(default)]
+    gateway: bool,
+}
+
+/// The `revoke_token` request object.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct RevokeTokenRequest {
+    /// The ID of the token to revoke.
+    id: String,
+}
+
+/// The query object for the administrative `DELETE` endpoints.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct PersistQuery {
+    /// If `true`, the change is persisted to survive a restart.
+    persist: Option<bool>,
+}
+
+/// Parses the given disconnect reason, e.g. `ProtocolViolation`.
+fn parse_disconnect_reason(reason: &str) -> Result<DisconnectReason, RestError> {
+    match reason {
+        "ExceededForkRange" => Ok(DisconnectReason::ExceededForkRange),
+        "InvalidChallengeResponse" => Ok(DisconnectReason::InvalidChallengeResponse),
+        "InvalidForkDepth" => Ok(DisconnectReason::InvalidForkDepth),
+        "INeedToSyncFirst" => Ok(DisconnectReason::INeedToSyncFirst),
+        "NoReasonGiven" => Ok(DisconnectReason::NoReasonGiven),
+        "ProtocolViolation" => Ok(DisconnectReason::ProtocolViolation),
+        "OutdatedClientVersion" => Ok(DisconnectReason::OutdatedClientVersion),
+        "PeerHasDisconnected" => Ok(DisconnectReason::PeerHasDisconnected),
+        "PeerRefresh" => Ok(DisconnectReason::PeerRefresh),
+        "ShuttingDown" => Ok(DisconnectReason::ShuttingDown),
+        "SyncComplete" => Ok(DisconnectReason::SyncComplete),
+        "TooManyFailures" => Ok(DisconnectReason::TooManyFailures),
+        "TooManyPeers" => Ok(DisconnectReason::TooManyPeers),
+        "YouNeedToSyncFirst" => Ok(DisconnectReason::YouNeedToSyncFirst),
+        _ => Err(RestError(format!("Invalid disconnect reason '{reason}'"))),
+    }
+}
+
+/// Returns the disconnect reason of the BFT gateway for the given disconnect reason.
+fn to_gateway_disconnect_reason(reason: DisconnectReason) -> Result<GatewayDisconnectReason, RestError> {
+    match reason {
+        DisconnectReason::InvalidChallengeResponse => Ok(GatewayDisconnectReason::InvalidChallengeResponse),
+        DisconnectReason::NoReasonGiven => Ok(GatewayDisconnectReason::NoReasonGiven),
+        DisconnectReason::ProtocolViolation => Ok(GatewayDisconnectReason::ProtocolViolation),
+        DisconnectReason::OutdatedClientVersion => Ok(GatewayDisconnectReason::OutdatedClientVersion),
+        _ => Err(RestError(format!("The disconnect reason '{reason:?}' is not supported by the BFT gateway"))),
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    /// Persists the given change to the peer rules of the router, if requested.
+    fn persist_peer_rules(&self, persist: bool, update: impl FnOnce(&mut PeerRules)) -> Result<(), RestError> {
+        if persist {
+            self.routing.router().persist_peer_rules(update)?;
+        }
+        Ok(())
+    }
+
+    /// Sends a disconnect message with the given reason to the peer, and disconnects from it.
+    async fn disconnect_with_reason(&self, peer_ip: SocketAddr, reason: DisconnectReason) {
+        // Send the disconnect reason, and wait for it to be written before disconnecting.
+        if let Some(sent) = self.routing.send(peer_ip, Message::Disconnect(reason.into())) {
+            let _ = sent.await;
+        }
+        // Disconnect from the peer.
+        let _ = self.routing.router().disconnect(peer_ip).await;
+    }
+
+    // POST /mainnet/node/tokens/revoke
+    pub(crate) async fn revoke_token(Json(request): Json<RevokeTokenRequest>) -> Result<ErasedJson, RestError> {
+        // Revoke the token, which takes effect immediately.
+        Ok(ErasedJson::pretty(revoke_token(&request.id)?))
+    }
+
+    // GET /mainnet/peers/trusted
+    pub(crate) async fn get_peers_trusted(State(rest): State<Self>) -> ErasedJson {
+        ErasedJson::pretty(rest.routing.router().trusted_peers())
+    }
+
+    // GET /mainnet/peers/restricted
+    pub(crate) async fn get_peers_restricted(State(rest): State<Self>) -> ErasedJson {
+        // Retrieve the restricted peers, with the seconds left on each restriction.
+        let restricted = rest
+            .routing
+            .router()
+            .restricted_peers_with_time_left()
+            .into_iter()
+            .map(|(peer_ip, time_left)| json!({ "peer_ip": peer_ip, "seconds_left": time_left.as_secs() }))
+            .collect::<Vec<_>>();
+        // Retrieve the banned IPs, with the seconds left on each ban (`null` if indefinite).
+        let banned = rest
+            .routing
+            .router()
+            .banned_ips()
+            .into_iter()
+            .map(|banned| {
+                let seconds_left = banned.time_left().map(|time_left| time_left.as_secs());
+                json!({ "ip": banned.ip, "seconds_left": seconds_left })
+            })
+            .collect::<Vec<_>>();
+
+        ErasedJson::pretty(json!({ "restricted": restricted, "banned": banned }))
+    }
+
+    // POST /mainnet/peers/trusted
+    pub(crate) async fn add_trusted_peer(
+        State(rest): State<Self>,
+        Json(request): Json<TrustedPeerRequest>,
+    ) -> Result<ErasedJson, RestError> {
+        // Ensure the peer IP is not this node.
+        if rest.routing.router().is_local_ip(&request.peer_ip) {
+            return Err(RestError(format!("Peer IP '{}' is this node", request.peer_ip)));
+        }
+        // Add the trusted peer.
+        let is_new = rest.routing.router().insert_trusted_peer(request.peer_ip);
+        rest.persist_peer_rules(request.persist, |rules| rules.insert_trusted_peer(request.peer_ip))?;
+        // Attempt to connect to the trusted peer, if it is not connected.
+        if !rest.routing.router().is_connected(&request.peer_ip) {
+            rest.routing.router().connect(request.peer_ip);
+        }
+        Ok(ErasedJson::pretty(is_new))
+    }
+
+    // DELETE /mainnet/peers/trusted/:peer_ip?persist={bool}
+    pub(crate) async fn remove_trusted_peer(
+        State(rest): State<Self>,
+        Path(peer_ip): Path<SocketAddr>,
+        Query(query): Query<PersistQuery>,
+    ) -> Result<ErasedJson, RestError> {
+        // Remove the trusted peer.
+        let was_trusted = rest.routing.router().remove_trusted_peer(peer_ip);
+        rest.persist_peer_rules(query.persist.unwrap_or(false), |rules| rules.remove_trusted_peer(peer_ip))?;
+        Ok(ErasedJson::pretty(was_trusted))
+    }
+
+    // POST /mainnet/peers/banned
+    pub(crate) async fn ban_ip(
+        State(rest): State<Self>,
+        Json(request): Json<BanRequest>,
+    ) -> Result<ErasedJson, RestError> {
+        // Ban the IP.
+        let duration = request.duration_secs.map(std::time::Duration::from_secs);
+        rest.routing.router().ban_ip(request.ip, duration);
+        rest.persist_peer_rules(request.persist, |rules| rules.insert_banned_ip(BannedIp::new(request.ip, duration)))?;
+
+        // Disconnect from the connected peers with the banned IP.
+        let peer_ips = rest.routing.router().connected_peers().into_iter().filter(|peer_ip| peer_ip.ip() == request.ip);
+        for peer_ip in peer_ips.collect::<Vec<_>>() {
+            rest.disconnect_with_reason(peer_ip, DisconnectReason::NoReasonGiven).await;
+        }
+        // Disconnect from the connected validators with the banned IP.
+        if let Some(consensus) = &rest.consensus {
+            let gateway = consensus.bft().primary().gateway();
+            let peer_ips = gateway
+                .connected_peers()
+                .read()
+                .iter()
+                .filter(|peer_ip| peer_ip.ip() == request.ip)
+                .copied()
+                .collect::<Vec<_>>();
+            for peer_ip in peer_ips {
+                gateway.disconnect_with_reason(peer_ip, GatewayDisconnectReason::NoReasonGiven).await;
+            }
+        }
+        Ok(ErasedJson::pretty(request.ip))
+    }
+
+    // DELETE /mainnet/peers/banned/:ip?persist={bool}
+    pub(crate) async fn unban_ip(
+        State(rest): State<Self>,
+        Path(ip): Path<IpAddr>,
+        Query(query): Query<PersistQuery>,
+    ) -> Result<ErasedJson, RestError> {
+        // Unban the IP.
+        let was_banned = rest.routing.router().unban_ip(ip);
+        rest.persist_peer_rules(query.persist.unwrap_or(false), |rules| rules.remove_banned_ip(ip))?;
+        Ok(ErasedJson::pretty(was_banned))
+    }
+
+    // POST /mainnet/peers/connect
+    pub(crate) async fn connect_peer(
+        State(rest): State<Self>,
+        Json(request): Json<ConnectRequest>,
+    ) -> Result<ErasedJson, RestError> {
+        let is_connected = match request.gateway {
+            // Connect to the validator through the BFT gateway.
+            true => match &rest.consensus {
+                Some(consensus) => match consensus.bft().primary().gateway().connect(request.peer_ip) {
+                    Some(handle) => handle.await.is_ok(),
+                    None => false,
+                },
+                None => return Err(RestError("The BFT gateway is only available on validators".to_string())),
+            },
+            // Connect to the peer through the router.
+            false => match rest.routing.router().connect(request.peer_ip) {
+                Some(handle) => handle.await.unwrap_or(false),
+                None => false,
+            },
+        };
+        Ok(ErasedJson::pretty(is_connected))
+    }
+
+    // POST /mainnet/peers/disconnect
+    pub(crate) async fn disconnect_peer(
+        State(rest): State<Self>,
+        Json(request): Json<DisconnectRequest>,
+    ) -> Result<ErasedJson, RestError> {
+        // Parse the disconnect reason.
+        let reason = match &request.reason {
+            Some(reason) => parse_disconnect_reason(reason)?,
+            None => DisconnectReason::NoReasonGiven,
+        };
+
+        match request.gateway {
+            // Disconnect from the validator through the BFT gateway.
+            true => match &rest.consensus {
+                Some(consensus) => {
+                    let gateway = consensus.bft().primary().gateway();
+                    if !gateway.connected_peers().read().contains(&request.peer_ip) {
+                        return Err(RestError(format!("Validator '{}' is not connected", request.peer_ip)));
+                    }
+                    gateway.disconnect_with_reason(request.peer_ip, to_gateway_disconnect_reason(reason)?).await;
+                }
+                None => return Err(RestError("The BFT gateway is only available on validators".to_string())),
+            },
+            // Disconnect from the peer through the router.
+            false => {
+                if !rest.routing.router().is_connected(&request.peer_ip) {
+                    return Err(RestError(format!("Peer '{}' is not connected", request.peer_ip)));
+                }
+                rest.disconnect_with_reason(request.peer_ip, reason).await;
+            }
+        }
+        Ok(ErasedJson::pretty(request.peer_ip))
+    }
+}
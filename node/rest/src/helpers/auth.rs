@@ -15,9 +15,10 @@
 use snarkvm::prelude::*;
 
 use ::time::OffsetDateTime;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, ensure, Result};
 use axum::{
     body::Body,
+    extract::State,
     http::{Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
@@ -28,21 +29,153 @@ use axum_extra::{
     TypedHeader,
 };
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
-/// The time a jwt token is valid for.
+/// The default time a jwt token is valid for.
 pub const EXPIRATION: i64 = 10 * 365 * 24 * 60 * 60; // 10 years.
+/// The time the one-time jwt token, printed when the node starts, is valid for.
+pub const STARTUP_EXPIRATION: i64 = 24 * 60 * 60; // 1 day.
+/// The scopes granted to the one-time jwt token, printed when the node starts.
+/// Note: The admin scope must be granted explicitly, with `snarkos token mint`.
+pub const STARTUP_SCOPES: [Scope; 2] = [Scope::NodeRead, Scope::PeersRead];
+/// The environment variable that may contain the JWT secret.
+pub const JWT_SECRET_ENV: &str = "SNARKOS_JWT_SECRET";
+/// The minimum length of a configured JWT secret, in bytes.
+pub const MIN_JWT_SECRET_LENGTH: usize = 16;
+
+/// The JWT secret for the node instance.
+static SECRET: OnceCell<Vec<u8>> = OnceCell::new();
+/// The IDs of the revoked tokens.
+static REVOKED_TOKENS: Lazy<RwLock<HashSet<String>>> = Lazy::new(Default::default);
+/// The file that the revoked token IDs are persisted to, if configured.
+static REVOCATION_FILE: OnceCell<PathBuf> = OnceCell::new();
 
 /// Returns the JWT secret for the node instance.
+/// If no secret was configured, a random secret is sampled, which is only valid for this process.
 fn jwt_secret() -> &'static Vec<u8> {
-    static SECRET: OnceCell<Vec<u8>> = OnceCell::new();
     SECRET.get_or_init(|| {
         let seed: [u8; 16] = ::rand::thread_rng().gen();
         seed.to_vec()
     })
 }
 
+/// Reads the JWT secret from the given file, or from the `SNARKOS_JWT_SECRET` environment variable
+/// if no file is given. Returns `None` if no file is given and the environment variable is not set.
+pub fn load_jwt_secret(path: Option<&Path>) -> Result<Option<Vec<u8>>> {
+    // Read the secret.
+    let secret = match path {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => match std::env::var(JWT_SECRET_ENV) {
+            Ok(secret) => secret,
+            Err(std::env::VarError::NotPresent) => return Ok(None),
+            Err(error) => bail!("Invalid '{JWT_SECRET_ENV}' environment variable - {error}"),
+        },
+    };
+    // Ensure the secret is sufficiently long.
+    let secret = secret.trim().as_bytes().to_vec();
+    ensure!(secret.len() >= MIN_JWT_SECRET_LENGTH, "The JWT secret must be at least {MIN_JWT_SECRET_LENGTH} bytes");
+    Ok(Some(secret))
+}
+
+/// Sets the JWT secret for the node instance.
+/// Note: This must be called before any token is minted or verified.
+pub fn set_jwt_secret(secret: Vec<u8>) -> Result<()> {
+    match SECRET.try_insert(secret) {
+        Ok(_) => Ok(()),
+        // Setting the same secret twice is a no-op.
+        Err((existing, secret)) if *existing == secret => Ok(()),
+        Err(_) => bail!("The JWT secret is already initialized"),
+    }
+}
+
+/// Adds the token IDs in the given file (one per line) to the revocation list,
+/// and persists the tokens that are revoked at runtime to the same file.
+pub fn load_revoked_tokens(path: &Path) -> Result<()> {
+    // Note: The file may not exist yet, in which case it is created on the first revocation.
+    if path.exists() {
+        let revoked = std::fs::read_to_string(path)?;
+        REVOKED_TOKENS.write().extend(revoked.lines().map(str::trim).filter(|id| !id.is_empty()).map(str::to_string));
+    }
+    if REVOCATION_FILE.set(path.to_path_buf()).is_err() {
+        bail!("The JWT revocation file is already initialized");
+    }
+    Ok(())
+}
+
+/// Adds the given token ID to the revocation list, and appends it to the revocation file, if configured.
+/// Returns `true` if the token was not already revoked.
+pub fn revoke_token(id: &str) -> Result<bool> {
+    // Ensure the token ID is not empty.
+    let id = id.trim();
+    ensure!(!id.is_empty(), "The token ID must not be empty");
+    // Revoke the token.
+    if !REVOKED_TOKENS.write().insert(id.to_string()) {
+        return Ok(false);
+    }
+    // Persist the revocation.
+    if let Some(path) = REVOCATION_FILE.get() {
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{id}")?;
+    }
+    Ok(true)
+}
+
+/// The scopes that may be granted to a JSON web token.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Scope {
+    /// Grants access to every protected route.
+    #[serde(rename = "admin")]
+    Admin,
+    /// Grants read access to the node information.
+    #[serde(rename = "node:read")]
+    NodeRead,
+    /// Grants read access to the peers.
+    #[serde(rename = "peers:read")]
+    PeersRead,
+    /// Grants write access to the peers.
+    #[serde(rename = "peers:write")]
+    PeersWrite,
+}
+
+impl Scope {
+    /// Returns the string representation of the scope.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::NodeRead => "node:read",
+            Self::PeersRead => "peers:read",
+            Self::PeersWrite => "peers:write",
+        }
+    }
+}
+
+impl FromStr for Scope {
+    type Err = Error;
+
+    fn from_str(scope: &str) -> Result<Self, Self::Err> {
+        match scope {
+            "admin" => Ok(Self::Admin),
+            "node:read" => Ok(Self::NodeRead),
+            "peers:read" => Ok(Self::PeersRead),
+            "peers:write" => Ok(Self::PeersWrite),
+            _ => bail!("Invalid JWT scope '{scope}'"),
+        }
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// The Json web token claims.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Claims {
@@ -52,14 +185,42 @@ pub struct Claims {
     iat: i64,
     /// Expiration time (as UTC timestamp).
     exp: i64,
+    /// The unique token ID.
+    jti: String,
+    /// The scopes granted to the token.
+    scopes: Vec<Scope>,
 }
 
 impl Claims {
-    pub fn new<N: Network>(address: Address<N>) -> Self {
+    /// Initializes new claims for the given address, granting the given scopes for `expiration` seconds.
+    pub fn new<N: Network>(address: Address<N>, scopes: Vec<Scope>, expiration: i64) -> Self {
         let issued_at = OffsetDateTime::now_utc().unix_timestamp();
-        let expiration = issued_at.saturating_add(EXPIRATION);
+        let expiration = issued_at.saturating_add(expiration);
+        let id = format!("{:032x}", ::rand::thread_rng().gen::<u128>());
+
+        Self { sub: address.to_string(), iat: issued_at, exp: expiration, jti: id, scopes }
+    }
+
+    /// Decodes and verifies the claims of the given json web token string.
+    pub fn from_jwt_string(token: &str) -> Result<Self> {
+        decode::<Claims>(token, &DecodingKey::from_secret(jwt_secret()), &Validation::new(Algorithm::HS256))
+            .map(|decoded| decoded.claims)
+            .map_err(|e| anyhow!(e))
+    }
+
+    /// Returns the unique token ID.
+    pub fn id(&self) -> &str {
+        &self.jti
+    }
+
+    /// Returns the scopes granted to the token.
+    pub fn scopes(&self) -> &[Scope] {
+        &self.scopes
+    }
 
-        Self { sub: address.to_string(), iat: issued_at, exp: expiration }
+    /// Returns true if the token grants the given scope.
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.iter().any(|granted| *granted == Scope::Admin || *granted == scope)
     }
 
     /// Returns true if the token is expired.
@@ -67,29 +228,37 @@ impl Claims {
         OffsetDateTime::now_utc().unix_timestamp() >= self.exp
     }
 
+    /// Returns true if the token is revoked.
+    pub fn is_revoked(&self) -> bool {
+        REVOKED_TOKENS.read().contains(&self.jti)
+    }
+
     /// Returns the json web token string.
     pub fn to_jwt_string(&self) -> Result<String> {
         encode(&Header::default(), &self, &EncodingKey::from_secret(jwt_secret())).map_err(|e| anyhow!(e))
     }
 }
 
-pub async fn auth_middleware(request: Request<Body>, next: Next) -> Result<Response, Response> {
+/// Ensures the request carries a valid json web token that grants the given scope.
+pub async fn auth_middleware(
+    State(scope): State<Scope>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, Response> {
     // Deconstruct the request to extract the auth token.
     let (mut parts, body) = request.into_parts();
     let auth: TypedHeader<Authorization<Bearer>> =
         parts.extract().await.map_err(|_| StatusCode::UNAUTHORIZED.into_response())?;
 
-    match decode::<Claims>(auth.token(), &DecodingKey::from_secret(jwt_secret()), &Validation::new(Algorithm::HS256)) {
-        Ok(decoded) => {
-            let claims = decoded.claims;
-            if claims.is_expired() {
-                return Err((StatusCode::UNAUTHORIZED, "Expired JSON Web Token".to_owned()).into_response());
-            }
-        }
-
-        Err(_) => {
-            return Err(StatusCode::UNAUTHORIZED.into_response());
-        }
+    let claims = Claims::from_jwt_string(auth.token()).map_err(|_| StatusCode::UNAUTHORIZED.into_response())?;
+    if claims.is_expired() {
+        return Err((StatusCode::UNAUTHORIZED, "Expired JSON Web Token".to_owned()).into_response());
+    }
+    if claims.is_revoked() {
+        return Err((StatusCode::UNAUTHORIZED, "Revoked JSON Web Token".to_owned()).into_response());
+    }
+    if !claims.has_scope(scope) {
+        return Err((StatusCode::FORBIDDEN, format!("JSON Web Token is missing the '{scope}' scope")).into_response());
     }
 
     // Reconstruct the request.
@@ -97,3 +266,82 @@ pub async fn auth_middleware(request: Request<Body>, next: Next) -> Result<Respo
 
     Ok(next.run(request).await)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns a router with a route that requires the `node:read` scope, and a route that requires the `peers:write` scope.
+    fn sample_router() -> Router {
+        Router::new()
+            .route(
+                "/node",
+                get(|| async {}).route_layer(middleware::from_fn_with_state(Scope::NodeRead, auth_middleware)),
+            )
+            .route(
+                "/peers",
+                get(|| async {}).route_layer(middleware::from_fn_with_state(Scope::PeersWrite, auth_middleware)),
+            )
+    }
+
+    /// Returns a token with the given scopes, that is valid for the given number of seconds.
+    fn sample_claims(scopes: Vec<Scope>, expiration: i64) -> Claims {
+        let address =
+            Address::<CurrentNetwork>::from_str("aleo1uxl69laseuv3876ksh8k0nd7tvpgjt6ccrgccedpjk9qwyfensxst9ftg5");
+        Claims::new(address.unwrap(), scopes, expiration)
+    }
+
+    /// Returns the status code of a request to the given route, with the given token, if any.
+    async fn request_status(route: &str, token: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().uri(route);
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        sample_router().oneshot(request.body(Body::empty()).unwrap()).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_auth_middleware_scopes() {
+        // Ensure a token is only accepted for its scopes.
+        let token = sample_claims(vec![Scope::NodeRead], EXPIRATION).to_jwt_string().unwrap();
+        assert_eq!(request_status("/node", Some(&token)).await, StatusCode::OK);
+        assert_eq!(request_status("/peers", Some(&token)).await, StatusCode::FORBIDDEN);
+
+        // Ensure the admin scope grants every scope.
+        let token = sample_claims(vec![Scope::Admin], EXPIRATION).to_jwt_string().unwrap();
+        assert_eq!(request_status("/node", Some(&token)).await, StatusCode::OK);
+        assert_eq!(request_status("/peers", Some(&token)).await, StatusCode::OK);
+
+        // Ensure a missing or malformed token is rejected.
+        assert_eq!(request_status("/node", None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(request_status("/node", Some("not-a-token")).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_auth_middleware_rejects_expired_token() {
+        let claims = sample_claims(vec![Scope::Admin], -120);
+        assert!(claims.is_expired());
+        let token = claims.to_jwt_string().unwrap();
+        assert_eq!(request_status("/node", Some(&token)).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_auth_middleware_rejects_revoked_token() {
+        let claims = sample_claims(vec![Scope::Admin], EXPIRATION);
+        let token = claims.to_jwt_string().unwrap();
+        assert_eq!(request_status("/node", Some(&token)).await, StatusCode::OK);
+
+        // Revoke the token, and ensure it is rejected from then on.
+        assert!(revoke_token(claims.id()).unwrap());
+        assert!(!revoke_token(claims.id()).unwrap());
+        assert!(claims.is_revoked());
+        assert_eq!(request_status("/node", Some(&token)).await, StatusCode::UNAUTHORIZED);
+
+        // Ensure an empty token ID is rejected.
+        assert!(revoke_token(" ").is_err());
+    }
+}
@@ -0,0 +1,720 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use snarkvm::{
+    console::program::{Argument, Future},
+    prelude::{
+        block::{Input, Output, Transaction},
+        Field,
+        FinalizeOperation,
+        Identifier,
+        Literal,
+        Plaintext,
+        ToBits,
+        Value,
+    },
+};
+
+use anyhow::{anyhow, bail, ensure};
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use rayon::prelude::*;
+use std::{collections::HashMap, str::FromStr};
+
+/// The number of most recent blocks covered by the mapping history.
+const HISTORY_RETENTION_IN_BLOCKS: u32 = 100_000;
+/// The number of blocks between each pruning of the mapping history.
+const HISTORY_PRUNE_INTERVAL_IN_BLOCKS: u32 = 1_000;
+
+/// A mapping, as `(program ID, mapping name)`.
+type MappingName<N> = (ProgramID<N>, Identifier<N>);
+
+/// A change to the value of a mapping key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Change<N: Network> {
+    /// The key was set to the value.
+    Value(Value<N>),
+    /// The key was removed.
+    Removed,
+    /// The key changed to values that are unknown, as several blocks were recorded at once.
+    Unknown,
+}
+
+/// The changes to the value of a mapping key, as `(block height, change)` in ascending order of height.
+type KeyHistory<N> = Vec<(u32, Change<N>)>;
+
+/// The mappings and keys touched by a range of blocks, with the first and last height at which each was touched.
+struct Touched<N: Network> {
+    /// The mappings to read in full.
+    mappings: IndexMap<MappingName<N>, (u32, u32)>,
+    /// The keys to read, for the mappings that are not read in full.
+    keys: IndexMap<(MappingName<N>, Plaintext<N>), (u32, u32)>,
+}
+
+impl<N: Network> Touched<N> {
+    /// Records that the given mapping was touched at the given height.
+    fn mapping(&mut self, mapping: MappingName<N>, height: u32) {
+        self.mappings.entry(mapping).and_modify(|(_, last)| *last = height).or_insert((height, height));
+    }
+
+    /// Records that the given key was touched at the given height.
+    fn key(&mut self, mapping: MappingName<N>, key: Plaintext<N>, height: u32) {
+        self.keys.entry((mapping, key)).and_modify(|(_, last)| *last = height).or_insert((height, height));
+    }
+}
+
+/// An index of the finalize state, recording the value of each mapping key after every block.
+///
+/// The history starts at the height of the ledger when the node starts, and is held in memory.
+/// It covers the most recent blocks, and the keys that changed before are only kept with their last value.
+///
+/// Note: The finalize operations of each block identify the mappings and keys it touches, which are read again
+/// from the finalize store. A key that is not yet known is matched against the plaintexts of its transaction,
+/// and its whole mapping is only read if the key cannot be derived (e.g. it is computed in the finalize scope).
+/// As the staking and puzzle rewards have no finalize operations in the block, the mappings of `credits.aleo`
+/// (except `account`) and the accounts of the provers are also read for each block.
+/// As the finalize store only holds the latest state, if several blocks are recorded at once, the keys that changed
+/// in between are marked as unknown, from the first to the last block that touched them.
+pub struct MappingHistory<N: Network> {
+    /// The number of most recent blocks covered by the history.
+    retention: u32,
+    /// The height from which the history is complete, once the initial state is recorded.
+    start_height: RwLock<Option<u32>>,
+    /// The height of the next block to index.
+    next_height: RwLock<u32>,
+    /// The history of each key, for each mapping.
+    mappings: RwLock<HashMap<MappingName<N>, IndexMap<Plaintext<N>, KeyHistory<N>>>>,
+    /// The mapping of each mapping ID, as used in the finalize operations.
+    mapping_ids: RwLock<HashMap<Field<N>, MappingName<N>>>,
+    /// The key of each key ID, as used in the finalize operations.
+    key_ids: RwLock<HashMap<Field<N>, Plaintext<N>>>,
+}
+
+impl<N: Network> Default for MappingHistory<N> {
+    fn default() -> Self {
+        Self::new(HISTORY_RETENTION_IN_BLOCKS)
+    }
+}
+
+impl<N: Network> MappingHistory<N> {
+    /// Initializes a new, empty history, covering the given number of most recent blocks.
+    pub fn new(retention: u32) -> Self {
+        Self {
+            retention: retention.max(1),
+            start_height: Default::default(),
+            next_height: Default::default(),
+            mappings: Default::default(),
+            mapping_ids: Default::default(),
+            key_ids: Default::default(),
+        }
+    }
+
+    /// Returns the latest height covered by the history.
+    pub fn latest_height(&self) -> Result<u32> {
+        ensure!(self.start_height.read().is_some(), "The mapping history is not yet initialized");
+        Ok(self.next_height.read().saturating_sub(1))
+    }
+
+    /// Ensures the history covers the given height.
+    fn ensure_height(&self, height: u32) -> Result<()> {
+        let Some(start_height) = *self.start_height.read() else {
+            bail!("The mapping history is not yet initialized");
+        };
+        let next_height = *self.next_height.read();
+        ensure!(
+            (start_height..next_height).contains(&height),
+            "The mapping history covers the heights {start_height} to {}",
+            next_height.saturating_sub(1)
+        );
+        Ok(())
+    }
+
+    /// Returns the value of the given key in the given mapping, as of the given height.
+    pub fn get_value(
+        &self,
+        program_id: &ProgramID<N>,
+        mapping_name: &Identifier<N>,
+        key: &Plaintext<N>,
+        height: u32,
+    ) -> Result<Option<Value<N>>> {
+        self.ensure_height(height)?;
+        let mappings = self.mappings.read();
+        match mappings.get(&(*program_id, *mapping_name)).and_then(|keys| keys.get(key)) {
+            Some(history) => Self::value_at(history, height).ok_or_else(|| Self::unknown_error(key, height)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the values of the given keys in the given mapping, in the order of the keys, as of the given height.
+    pub fn get_values(
+        &self,
+        program_id: &ProgramID<N>,
+        mapping_name: &Identifier<N>,
+        keys: &[Plaintext<N>],
+        height: u32,
+    ) -> Result<Vec<Option<Value<N>>>> {
+        keys.iter().map(|key| self.get_value(program_id, mapping_name, key, height)).collect()
+    }
+
+    /// Returns up to `limit` entries of the given mapping as of the given height, starting after the given key,
+    /// and the key to continue from, if there may be more entries.
+    pub fn get_entries(
+        &self,
+        program_id: &ProgramID<N>,
+        mapping_name: &Identifier<N>,
+        height: u32,
+        cursor: Option<&Plaintext<N>>,
+        limit: usize,
+    ) -> Result<(Vec<(Plaintext<N>, Value<N>)>, Option<Plaintext<N>>)> {
+        self.ensure_height(height)?;
+        let mappings = self.mappings.read();
+        let Some(keys) = mappings.get(&(*program_id, *mapping_name)) else {
+            return match cursor {
+                Some(_) => Err(anyhow!("The cursor is not a key of the mapping")),
+                None => Ok((vec![], None)),
+            };
+        };
+        // Determine the position of the first key.
+        let start = match cursor {
+            Some(cursor) => {
+                keys.get_index_of(cursor).ok_or_else(|| anyhow!("The cursor is not a key of the mapping"))? + 1
+            }
+            None => 0,
+        };
+        // Collect the entries that hold a value as of the given height.
+        let mut entries = Vec::with_capacity(limit.min(keys.len()));
+        for (index, (key, history)) in keys.iter().enumerate().skip(start) {
+            if entries.len() == limit {
+                // Note: The cursor is the last returned key, so that the next page starts after it.
+                let cursor = keys.get_index(index - 1).map(|(key, _)| key.clone());
+                return Ok((entries, cursor));
+            }
+            if let Some(value) = Self::value_at(history, height).ok_or_else(|| Self::unknown_error(key, height))? {
+                entries.push((key.clone(), value));
+            }
+        }
+        Ok((entries, None))
+    }
+
+    /// Returns the value in the given key history as of the given height, or `None` if the value is unknown.
+    fn value_at(history: &KeyHistory<N>, height: u32) -> Option<Option<Value<N>>> {
+        // Find the last change at or before the given height.
+        let index = history.partition_point(|(change_height, _)| *change_height <= height);
+        match index.checked_sub(1).map(|index| &history[index].1) {
+            Some(Change::Value(value)) => Some(Some(value.clone())),
+            Some(Change::Removed) | None => Some(None),
+            Some(Change::Unknown) => None,
+        }
+    }
+
+    /// Returns the error for a key whose value is unknown at the given height.
+    fn unknown_error(key: &Plaintext<N>, height: u32) -> anyhow::Error {
+        anyhow!("The mapping history does not hold the value of '{key}' at height {height}")
+    }
+
+    /// Records the given value of the given key, which was touched from the `first` to the `last` height.
+    fn record_value(
+        &self,
+        mapping: MappingName<N>,
+        key: Plaintext<N>,
+        value: Option<Value<N>>,
+        (first, last): (u32, u32),
+    ) {
+        let change = value.map_or(Change::Removed, Change::Value);
+        let mut mappings = self.mappings.write();
+        let history = mappings.entry(mapping).or_default().entry(key).or_default();
+        // If the value is unchanged, return early.
+        let current = history.last().map(|(_, change)| change).unwrap_or(&Change::Removed);
+        if *current == change {
+            return;
+        }
+        // If the key was touched by several blocks, its values in between are unknown.
+        if first < last {
+            history.push((first, Change::Unknown));
+        }
+        history.push((last, change));
+    }
+
+    /// Records the given contents of the given mapping, which was touched from the `first` to the `last` height.
+    fn record_mapping(&self, mapping: MappingName<N>, entries: Vec<(Plaintext<N>, Value<N>)>, heights: (u32, u32)) {
+        // Index the new keys by their key ID.
+        self.index_keys(&mapping, entries.iter().map(|(key, _)| key));
+        // Record the keys that were removed.
+        let entries = entries.into_iter().collect::<IndexMap<_, _>>();
+        let removed = self
+            .mappings
+            .read()
+            .get(&mapping)
+            .map(|keys| keys.keys().filter(|key| !entries.contains_key(*key)).cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        for key in removed {
+            self.record_value(mapping, key, None, heights);
+        }
+        // Record the keys that were inserted or updated.
+        for (key, value) in entries {
+            self.record_value(mapping, key, Some(value), heights);
+        }
+    }
+
+    /// Indexes the given keys of the given mapping by their key ID, if they are not yet indexed.
+    fn index_keys<'a>(&self, mapping: &MappingName<N>, keys: impl Iterator<Item = &'a Plaintext<N>>) {
+        let new_keys = {
+            let mappings = self.mappings.read();
+            let known = mappings.get(mapping);
+            keys.filter(|key| known.map_or(true, |known| !known.contains_key(*key))).cloned().collect::<Vec<_>>()
+        };
+        let key_ids = new_keys
+            .into_par_iter()
+            .filter_map(|key| to_key_id(&mapping.0, &mapping.1, &key).ok().map(|key_id| (key_id, key)))
+            .collect::<Vec<_>>();
+        self.key_ids.write().extend(key_ids);
+    }
+
+    /// Indexes the mapping IDs of the programs in the ledger that are not yet indexed.
+    fn index_mappings<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Result<()> {
+        let finalize_store = ledger.vm().finalize_store();
+        let program_ids = ledger.vm().process().read().program_ids().copied().collect::<Vec<_>>();
+        for program_id in program_ids {
+            for mapping_name in finalize_store.get_mapping_names_confirmed(&program_id)?.unwrap_or_default() {
+                let mapping_id = to_mapping_id(&program_id, &mapping_name)?;
+                self.mapping_ids.write().entry(mapping_id).or_insert((program_id, mapping_name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the mappings and keys touched by the given block.
+    fn touched_by_block(&self, block: &Block<N>, touched: &mut Touched<N>) -> Result<()> {
+        let height = block.height();
+        let (mapping_ids, key_ids) = (self.mapping_ids.read(), self.key_ids.read());
+        // Add the mappings and keys touched by the finalize operations.
+        for confirmed in block.transactions().iter() {
+            // The plaintexts of the transaction, from which the new keys are derived, if needed.
+            let mut candidate_keys = None;
+            for operation in confirmed.finalize_operations().iter() {
+                match operation {
+                    // Note: An insert or an update identifies its key, which is derived from the transaction if it is new.
+                    FinalizeOperation::InsertKeyValue(mapping_id, key_id, _)
+                    | FinalizeOperation::UpdateKeyValue(mapping_id, .., key_id, _) => {
+                        let Some(mapping) = mapping_ids.get(mapping_id) else { continue };
+                        let key = key_ids.get(key_id).cloned().or_else(|| {
+                            candidate_keys
+                                .get_or_insert_with(|| Self::candidate_keys(confirmed.transaction()))
+                                .iter()
+                                .find(|key| to_key_id(&mapping.0, &mapping.1, key).map_or(false, |id| id == *key_id))
+                                .cloned()
+                        });
+                        match key {
+                            Some(key) => touched.key(*mapping, key, height),
+                            None => touched.mapping(*mapping, height),
+                        }
+                    }
+                    FinalizeOperation::InitializeMapping(mapping_id)
+                    | FinalizeOperation::RemoveKeyValue(mapping_id, ..)
+                    | FinalizeOperation::ReplaceMapping(mapping_id)
+                    | FinalizeOperation::RemoveMapping(mapping_id) => {
+                        if let Some(mapping) = mapping_ids.get(mapping_id) {
+                            touched.mapping(*mapping, height);
+                        }
+                    }
+                }
+            }
+        }
+        // Add the mappings and keys touched by the staking and puzzle rewards.
+        let credits = ProgramID::from_str("credits.aleo")?;
+        let account = Identifier::from_str("account")?;
+        for (program_id, mapping_name) in mapping_ids.values() {
+            if *program_id == credits && *mapping_name != account {
+                touched.mapping((*program_id, *mapping_name), height);
+            }
+        }
+        if let Some(solutions) = block.solutions().as_ref() {
+            for solution in solutions.values() {
+                touched.key((credits, account), Plaintext::from(Literal::Address(solution.address())), height);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the plaintexts of the given transaction that may be the keys of its finalize operations, i.e. its
+    /// fee payer, and the plaintexts (and their members) in the public inputs, outputs, and finalize arguments.
+    fn candidate_keys(transaction: &Transaction<N>) -> Vec<Plaintext<N>> {
+        let mut keys = Vec::new();
+        // Add the fee payer.
+        if let Some(payer) = transaction.fee_transition().and_then(|fee| fee.payer()) {
+            keys.push(Plaintext::from(Literal::Address(payer)));
+        }
+        // Add the plaintexts of each transition.
+        for transition in transaction.transitions() {
+            for input in transition.inputs() {
+                if let Input::Constant(_, Some(plaintext)) | Input::Public(_, Some(plaintext)) = input {
+                    Self::plaintext_keys(plaintext, &mut keys);
+                }
+            }
+            for output in transition.outputs() {
+                match output {
+                    Output::Constant(_, Some(plaintext)) | Output::Public(_, Some(plaintext)) => {
+                        Self::plaintext_keys(plaintext, &mut keys)
+                    }
+                    Output::Future(_, Some(future)) => Self::future_keys(future, &mut keys),
+                    _ => {}
+                }
+            }
+        }
+        keys
+    }
+
+    /// Adds the plaintexts in the arguments of the given future.
+    fn future_keys(future: &Future<N>, keys: &mut Vec<Plaintext<N>>) {
+        for argument in future.arguments() {
+            match argument {
+                Argument::Plaintext(plaintext) => Self::plaintext_keys(plaintext, keys),
+                Argument::Future(future) => Self::future_keys(future, keys),
+            }
+        }
+    }
+
+    /// Adds the given plaintext, and its members or elements.
+    fn plaintext_keys(plaintext: &Plaintext<N>, keys: &mut Vec<Plaintext<N>>) {
+        match plaintext {
+            Plaintext::Literal(..) => {}
+            Plaintext::Struct(members, _) => members.values().for_each(|member| Self::plaintext_keys(member, keys)),
+            Plaintext::Array(elements, _) => elements.iter().for_each(|element| Self::plaintext_keys(element, keys)),
+        }
+        keys.push(plaintext.clone());
+    }
+
+    /// Records the initial state of every mapping, as of the latest height of the ledger.
+    fn initialize<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Result<()> {
+        let finalize_store = ledger.vm().finalize_store();
+        loop {
+            let height = ledger.latest_height();
+            self.index_mappings(ledger)?;
+            let mappings = self.mapping_ids.read().values().copied().collect::<Vec<_>>();
+            for (program_id, mapping_name) in mappings {
+                let entries = finalize_store.get_mapping_confirmed(program_id, mapping_name)?;
+                self.record_mapping((program_id, mapping_name), entries, (height, height));
+            }
+            // If a block was added while recording the state, then record it again.
+            if ledger.latest_height() == height {
+                *self.next_height.write() = height.saturating_add(1);
+                *self.start_height.write() = Some(height);
+                return Ok(());
+            }
+            self.mappings.write().clear();
+        }
+    }
+
+    /// Prunes the changes that precede the retention of the history, keeping the value of each key at its start.
+    fn prune(&self) {
+        let next_height = *self.next_height.read();
+        let Some(start_height) = *self.start_height.read() else { return };
+        let cutoff = next_height.saturating_sub(self.retention);
+        if cutoff < start_height.saturating_add(HISTORY_PRUNE_INTERVAL_IN_BLOCKS) {
+            return;
+        }
+        let mut mappings = self.mappings.write();
+        for keys in mappings.values_mut() {
+            keys.retain(|_, history| {
+                // Remove the changes before the last change at or before the cutoff.
+                let index = history.partition_point(|(height, _)| *height <= cutoff);
+                history.drain(..index.saturating_sub(1));
+                // Remove the key if it was removed before the cutoff, and did not change since.
+                !matches!(history.as_slice(), [(_, Change::Removed)])
+            });
+        }
+        *self.start_height.write() = Some(cutoff);
+    }
+}
+
+impl<N: Network> BlockIndex<N> for MappingHistory<N> {
+    const NAME: &'static str = "mapping history";
+
+    /// Records the mappings and keys touched by the blocks of the ledger that are not yet indexed.
+    fn sync_with_ledger<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Result<()> {
+        // Record the initial state, if it is not yet recorded.
+        if self.start_height.read().is_none() {
+            return self.initialize(ledger);
+        }
+        let finalize_store = ledger.vm().finalize_store();
+        let next_height = *self.next_height.read();
+        let mut touched = Touched { mappings: Default::default(), keys: Default::default() };
+        let mut height = next_height;
+        loop {
+            // Determine the mappings and keys touched by the blocks that are not yet recorded.
+            let latest_height = ledger.latest_height();
+            if latest_height < height {
+                return Ok(());
+            }
+            self.index_mappings(ledger)?;
+            for height in height..=latest_height {
+                self.touched_by_block(&ledger.get_block(height)?, &mut touched)?;
+            }
+            // Read the touched mappings and keys.
+            let mappings = touched
+                .mappings
+                .iter()
+                .map(|(mapping, heights)| {
+                    Ok((*mapping, finalize_store.get_mapping_confirmed(mapping.0, mapping.1)?, *heights))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let keys = touched
+                .keys
+                .iter()
+                .filter(|((mapping, _), _)| !touched.mappings.contains_key(mapping))
+                .map(|((mapping, key), heights)| {
+                    Ok((
+                        *mapping,
+                        key.clone(),
+                        finalize_store.get_value_confirmed(mapping.0, mapping.1, key)?,
+                        *heights,
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            // If a block was added while reading the state, then read the state again, including the new blocks.
+            if ledger.latest_height() != latest_height {
+                height = latest_height.saturating_add(1);
+                continue;
+            }
+            // Record the state.
+            for (mapping, entries, heights) in mappings {
+                self.record_mapping(mapping, entries, heights);
+            }
+            for (mapping, key, value, heights) in keys {
+                self.index_keys(&mapping, std::iter::once(&key));
+                self.record_value(mapping, key, value, heights);
+            }
+            *self.next_height.write() = latest_height.saturating_add(1);
+            break;
+        }
+        // Prune the changes that precede the retention of the history.
+        self.prune();
+        Ok(())
+    }
+}
+
+/// Returns the ID of the given mapping, as used in the finalize operations.
+/// Note: This matches the derivation of the mapping IDs in the finalize store.
+fn to_mapping_id<N: Network>(program_id: &ProgramID<N>, mapping_name: &Identifier<N>) -> Result<Field<N>> {
+    let mut preimage = Vec::new();
+    program_id.write_bits_le(&mut preimage);
+    false.write_bits_le(&mut preimage);
+    mapping_name.write_bits_le(&mut preimage);
+    N::hash_bhp1024(&preimage)
+}
+
+/// Returns the ID of the given key of the given mapping, as used in the finalize operations.
+/// Note: This matches the derivation of the key IDs in the finalize store.
+fn to_key_id<N: Network>(
+    program_id: &ProgramID<N>,
+    mapping_name: &Identifier<N>,
+    key: &Plaintext<N>,
+) -> Result<Field<N>> {
+    let mut preimage = Vec::new();
+    program_id.write_bits_le(&mut preimage);
+    false.write_bits_le(&mut preimage);
+    mapping_name.write_bits_le(&mut preimage);
+    false.write_bits_le(&mut preimage);
+    key.write_bits_le(&mut preimage);
+    N::hash_bhp1024(&preimage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    use indexmap::IndexSet;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns the mapping, key, and values used in the tests.
+    fn sample() -> (MappingName<CurrentNetwork>, Plaintext<CurrentNetwork>, Value<CurrentNetwork>, Value<CurrentNetwork>)
+    {
+        let mapping = (ProgramID::from_str("credits.aleo").unwrap(), Identifier::from_str("account").unwrap());
+        (
+            mapping,
+            Plaintext::from_str("1u8").unwrap(),
+            Value::from_str("1u64").unwrap(),
+            Value::from_str("2u64").unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_record_mapping() {
+        let history = MappingHistory::<CurrentNetwork>::default();
+        let (mapping, key, one, two) = sample();
+        let (program_id, mapping_name) = mapping;
+
+        // Record the key at height 0, update it at height 2, and remove it at height 4.
+        history.record_mapping(mapping, vec![(key.clone(), one.clone())], (0, 0));
+        history.record_mapping(mapping, vec![(key.clone(), one.clone())], (1, 1));
+        history.record_mapping(mapping, vec![(key.clone(), two.clone())], (2, 2));
+        history.record_mapping(mapping, vec![], (4, 4));
+        *history.start_height.write() = Some(0);
+        *history.next_height.write() = 5;
+
+        // Ensure the value is returned as of each height.
+        let get = |height| history.get_value(&program_id, &mapping_name, &key, height).unwrap();
+        assert_eq!(get(0), Some(one.clone()));
+        assert_eq!(get(1), Some(one));
+        assert_eq!(get(3), Some(two.clone()));
+        assert_eq!(get(4), None);
+        assert_eq!(
+            history.get_entries(&program_id, &mapping_name, 2, None, 10).unwrap(),
+            (vec![(key.clone(), two)], None)
+        );
+
+        // Ensure a height outside of the history is rejected.
+        assert!(history.get_value(&program_id, &mapping_name, &Plaintext::from_str("2u8").unwrap(), 5).is_err());
+        // Ensure the key is indexed by its key ID.
+        let key_id = to_key_id(&program_id, &mapping_name, &key).unwrap();
+        assert_eq!(history.key_ids.read().get(&key_id), Some(&key));
+    }
+
+    #[test]
+    fn test_record_several_blocks() {
+        let history = MappingHistory::<CurrentNetwork>::default();
+        let (mapping, key, one, two) = sample();
+        let (program_id, mapping_name) = mapping;
+        let other_key = Plaintext::from_str("2u8").unwrap();
+
+        // Record the keys at height 0, and update one key in blocks 2 and 4, which are recorded at once.
+        history.record_mapping(mapping, vec![(key.clone(), one.clone()), (other_key.clone(), one.clone())], (0, 0));
+        history.record_value(mapping, key.clone(), Some(two.clone()), (2, 4));
+        *history.start_height.write() = Some(0);
+        *history.next_height.write() = 5;
+
+        // Ensure the value of the updated key is unknown in between, and the other key is unaffected.
+        let get = |key, height| history.get_value(&program_id, &mapping_name, key, height);
+        assert_eq!(get(&key, 1).unwrap(), Some(one.clone()));
+        assert!(get(&key, 2).is_err());
+        assert!(get(&key, 3).is_err());
+        assert_eq!(get(&key, 4).unwrap(), Some(two.clone()));
+        assert_eq!(get(&other_key, 3).unwrap(), Some(one.clone()));
+        // Ensure the entries are only returned where they are known.
+        assert!(history.get_entries(&program_id, &mapping_name, 3, None, 10).is_err());
+        assert_eq!(history.get_entries(&program_id, &mapping_name, 4, None, 10).unwrap().0.len(), 2);
+    }
+
+    #[test]
+    fn test_get_values_and_entries() {
+        let history = MappingHistory::<CurrentNetwork>::default();
+        let (mapping, _, one, _) = sample();
+        let (program_id, mapping_name) = mapping;
+        let keys = (0..5).map(|i| Plaintext::from_str(&format!("{i}u8")).unwrap()).collect::<Vec<_>>();
+
+        // Record five keys at height 0, and remove the second key at height 1.
+        let entries = keys.iter().map(|key| (key.clone(), one.clone())).collect::<Vec<_>>();
+        history.record_mapping(mapping, entries.clone(), (0, 0));
+        history.record_value(mapping, keys[1].clone(), None, (1, 1));
+        *history.start_height.write() = Some(0);
+        *history.next_height.write() = 2;
+
+        // Ensure the values are returned in the order of the keys.
+        let missing = Plaintext::from_str("9u8").unwrap();
+        let values = history.get_values(&program_id, &mapping_name, &[keys[1].clone(), missing, keys[0].clone()], 1);
+        assert_eq!(values.unwrap(), vec![None, None, Some(one.clone())]);
+
+        // Ensure the entries are paginated by key, skipping the removed key.
+        let (page, cursor) = history.get_entries(&program_id, &mapping_name, 1, None, 2).unwrap();
+        assert_eq!(page, vec![entries[0].clone(), entries[2].clone()]);
+        assert_eq!(cursor.as_ref(), Some(&keys[2]));
+        let (page, cursor) = history.get_entries(&program_id, &mapping_name, 1, cursor.as_ref(), 2).unwrap();
+        assert_eq!(page, vec![entries[3].clone(), entries[4].clone()]);
+        assert_eq!(cursor, None);
+        // Ensure the removed key is returned as of the height before its removal.
+        let (page, _) = history.get_entries(&program_id, &mapping_name, 0, Some(&keys[0]), 1).unwrap();
+        assert_eq!(page, vec![entries[1].clone()]);
+        // Ensure an unknown cursor is rejected.
+        assert!(history
+            .get_entries(&program_id, &mapping_name, 1, Some(&Plaintext::from_str("9u8").unwrap()), 2)
+            .is_err());
+    }
+
+    #[test]
+    fn test_prune() {
+        let history = MappingHistory::<CurrentNetwork>::new(10);
+        let (mapping, key, one, two) = sample();
+        let (program_id, mapping_name) = mapping;
+        let removed_key = Plaintext::from_str("2u8").unwrap();
+
+        // Record a key that is updated at height 5, and a key that is removed at height 6.
+        history.record_mapping(mapping, vec![(key.clone(), one.clone()), (removed_key.clone(), one)], (0, 0));
+        history.record_value(mapping, key.clone(), Some(two.clone()), (5, 5));
+        history.record_value(mapping, removed_key.clone(), None, (6, 6));
+        *history.start_height.write() = Some(0);
+
+        // Ensure the history is not pruned before the prune interval.
+        *history.next_height.write() = 20;
+        history.prune();
+        assert_eq!(*history.start_height.read(), Some(0));
+
+        // Ensure the history is pruned to its retention, keeping the value of each key at its start.
+        let next_height = 10 + HISTORY_PRUNE_INTERVAL_IN_BLOCKS;
+        *history.next_height.write() = next_height;
+        history.prune();
+        assert_eq!(*history.start_height.read(), Some(HISTORY_PRUNE_INTERVAL_IN_BLOCKS));
+        assert_eq!(history.get_value(&program_id, &mapping_name, &key, next_height - 1).unwrap(), Some(two));
+        assert!(history.get_value(&program_id, &mapping_name, &key, 5).is_err());
+        assert_eq!(history.mappings.read()[&mapping].len(), 1);
+    }
+
+    #[test]
+    fn test_plaintext_keys() {
+        // Ensure a plaintext is a candidate key, along with each of its members and elements.
+        let plaintext = Plaintext::<CurrentNetwork>::from_str("{ a: 1u64, b: [2u8, 3u8] }").unwrap();
+        let mut keys = Vec::new();
+        MappingHistory::plaintext_keys(&plaintext, &mut keys);
+        let expected = ["1u64", "2u8", "3u8", "[2u8, 3u8]"]
+            .into_iter()
+            .map(|key| Plaintext::from_str(key).unwrap())
+            .chain([plaintext.clone()])
+            .collect::<Vec<_>>();
+        assert_eq!(keys, expected);
+
+        // Ensure the key identifier of a candidate matches the one of the finalize operations.
+        let (program_id, mapping_name) =
+            (ProgramID::from_str("credits.aleo").unwrap(), Identifier::from_str("account").unwrap());
+        let key_id = to_key_id(&program_id, &mapping_name, &expected[0]).unwrap();
+        let found = keys.iter().find(|key| to_key_id(&program_id, &mapping_name, key).unwrap() == key_id);
+        assert_eq!(found, Some(&expected[0]));
+    }
+
+    #[test]
+    fn test_mapping_ids() {
+        // Ensure the finalize operations of the genesis block only touch the mappings of 'credits.aleo'.
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let credits = ProgramID::from_str("credits.aleo").unwrap();
+        let mapping_ids = ["committee", "bonded", "unbonding", "account", "metadata", "withdraw", "delegated"]
+            .into_iter()
+            .filter_map(|name| Identifier::from_str(name).ok())
+            .map(|name| to_mapping_id(&credits, &name).unwrap())
+            .collect::<IndexSet<_>>();
+        for confirmed in genesis.transactions().iter() {
+            for operation in confirmed.finalize_operations().iter() {
+                let mapping_id = match operation {
+                    FinalizeOperation::InitializeMapping(mapping_id)
+                    | FinalizeOperation::InsertKeyValue(mapping_id, ..)
+                    | FinalizeOperation::UpdateKeyValue(mapping_id, ..)
+                    | FinalizeOperation::RemoveKeyValue(mapping_id, ..)
+                    | FinalizeOperation::ReplaceMapping(mapping_id)
+                    | FinalizeOperation::RemoveMapping(mapping_id) => mapping_id,
+                };
+                assert!(mapping_ids.contains(mapping_id));
+            }
+        }
+    }
+}
@@ -0,0 +1,594 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use snarkvm::{
+    console::program::{Argument, Future, Literal, Plaintext},
+    prelude::{
+        block::{Input, Output, Transaction, Transition},
+        Address,
+        Identifier,
+    },
+};
+
+use anyhow::anyhow;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize, Serializer};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+    str::FromStr,
+};
+use tokio::{sync::broadcast::error::RecvError, time::MissedTickBehavior};
+
+/// The default number of items per page of the index routes.
+const DEFAULT_PAGE_SIZE: usize = 50;
+/// The maximum number of items per page of the index routes.
+const MAX_PAGE_SIZE: usize = 100;
+/// The interval at which the index catches up with the ledger, if it was not notified of new blocks.
+const INDEX_SYNC_INTERVAL_IN_SECS: u64 = 10;
+/// The number of most recent blocks covered by the ledger index.
+const INDEX_RETENTION_IN_BLOCKS: u32 = 100_000;
+
+/// The query object for the paginated index routes.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct PageQuery {
+    /// The cursor returned by the previous page (default: the most recent items).
+    cursor: Option<String>,
+    /// The number of items per page (default: 50).
+    page_size: Option<usize>,
+    /// The function name to filter the transitions by.
+    function: Option<String>,
+}
+
+/// The position from which a page of the index starts, i.e. the height of a block,
+/// and the number of its items already returned by the previous pages.
+/// Note: As the items of an indexed block never change, the cursor is stable as new blocks are indexed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IndexCursor {
+    /// The block height.
+    pub height: u32,
+    /// The number of items of the block to skip, from the most recent.
+    pub offset: usize,
+}
+
+impl FromStr for IndexCursor {
+    type Err = anyhow::Error;
+
+    /// Parses the cursor from `{height}.{offset}`, or `{height}` if no item of the block is skipped.
+    fn from_str(cursor: &str) -> Result<Self, Self::Err> {
+        let (height, offset) = cursor.split_once('.').unwrap_or((cursor, "0"));
+        match (height.parse(), offset.parse()) {
+            (Ok(height), Ok(offset)) => Ok(Self { height, offset }),
+            _ => Err(anyhow!("Invalid cursor '{cursor}'")),
+        }
+    }
+}
+
+impl fmt::Display for IndexCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.height, self.offset)
+    }
+}
+
+impl Serialize for IndexCursor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// An item of the index, which belongs to a block.
+pub trait IndexItem: Clone {
+    /// Returns the height of the block containing the item.
+    fn height(&self) -> u32;
+}
+
+/// A page of items from the index, ordered from the most recent to the oldest.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct IndexPage<T> {
+    /// The number of items per page.
+    pub page_size: usize,
+    /// The items in the page.
+    pub items: Vec<T>,
+    /// The cursor of the next page, if there are more items.
+    pub next_cursor: Option<IndexCursor>,
+}
+
+impl<T: IndexItem> IndexPage<T> {
+    /// Returns the page of the given items (in ledger order) that match the given filter, starting at the given cursor,
+    /// ordered from the most recent to the oldest.
+    fn new(items: &VecDeque<T>, filter: impl Fn(&T) -> bool, cursor: Option<IndexCursor>, page_size: usize) -> Self {
+        // Start from the last item of the block of the cursor, skipping the items returned by the previous pages.
+        // Note: The items of a block are contiguous, so that the skipped items are the first ones in reverse order.
+        let (end, offset) = match cursor {
+            Some(cursor) => (items.partition_point(|item| item.height() <= cursor.height), cursor.offset),
+            None => (items.len(), 0),
+        };
+        let mut remaining = items.range(..end).rev().filter(|item| filter(item)).skip(offset).peekable();
+        let page = remaining.by_ref().take(page_size).cloned().collect::<Vec<_>>();
+
+        // Determine the cursor of the next page, from the next item and the items of its block in the page.
+        let next_cursor = remaining.peek().map(|next| {
+            let height = next.height();
+            let returned = page.iter().rev().take_while(|item| item.height() == height).count();
+            let offset = match cursor {
+                Some(cursor) if cursor.height == height => cursor.offset + returned,
+                _ => returned,
+            };
+            IndexCursor { height, offset }
+        });
+        Self { page_size, items: page, next_cursor }
+    }
+}
+
+/// A transaction that touches a public address.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct AddressTransaction<N: Network> {
+    /// The height of the block containing the transaction.
+    pub height: u32,
+    /// The transaction ID.
+    pub transaction_id: N::TransactionID,
+}
+
+impl<N: Network> IndexItem for AddressTransaction<N> {
+    fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+/// A transition that calls a program function.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct ProgramTransition<N: Network> {
+    /// The height of the block containing the transition.
+    pub height: u32,
+    /// The ID of the transaction containing the transition.
+    pub transaction_id: N::TransactionID,
+    /// The transition ID.
+    pub transition_id: N::TransitionID,
+    /// The name of the called function.
+    pub function: Identifier<N>,
+}
+
+impl<N: Network> IndexItem for ProgramTransition<N> {
+    fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+/// A secondary index, that is maintained as blocks are added to the ledger.
+pub trait BlockIndex<N: Network>: 'static + Send + Sync {
+    /// The name of the index.
+    const NAME: &'static str;
+
+    /// Indexes the blocks of the ledger that are not yet indexed.
+    fn sync_with_ledger<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Result<()>;
+}
+
+/// A secondary index of the ledger, mapping public addresses to their transactions,
+/// and programs to the transitions that call them.
+///
+/// The index is held in memory, and only covers the given number of most recent blocks,
+/// which are indexed from the ledger when the node starts.
+pub struct LedgerIndex<N: Network> {
+    /// The number of most recent blocks covered by the index.
+    retention: u32,
+    /// The height of the next block to index.
+    next_height: RwLock<u32>,
+    /// The transactions touching each public address, in ledger order.
+    address_transactions: RwLock<HashMap<Address<N>, VecDeque<AddressTransaction<N>>>>,
+    /// The transitions calling each program, in ledger order.
+    program_transitions: RwLock<HashMap<ProgramID<N>, VecDeque<ProgramTransition<N>>>>,
+    /// The addresses and programs touched by each indexed block, in ledger order, to prune the oldest blocks.
+    blocks: RwLock<VecDeque<(u32, Vec<Address<N>>, Vec<ProgramID<N>>)>>,
+}
+
+impl<N: Network> Default for LedgerIndex<N> {
+    fn default() -> Self {
+        Self::new(INDEX_RETENTION_IN_BLOCKS)
+    }
+}
+
+impl<N: Network> LedgerIndex<N> {
+    /// Initializes a new, empty index, covering the given number of most recent blocks.
+    pub fn new(retention: u32) -> Self {
+        Self {
+            retention: retention.max(1),
+            next_height: Default::default(),
+            address_transactions: Default::default(),
+            program_transitions: Default::default(),
+            blocks: Default::default(),
+        }
+    }
+
+    /// Returns the height of the next block to index.
+    pub fn next_height(&self) -> u32 {
+        *self.next_height.read()
+    }
+
+    /// Returns the page of the transactions touching the given public address, starting at the given cursor.
+    pub fn address_transactions(
+        &self,
+        address: &Address<N>,
+        cursor: Option<IndexCursor>,
+        page_size: usize,
+    ) -> IndexPage<AddressTransaction<N>> {
+        let address_transactions = self.address_transactions.read();
+        match address_transactions.get(address) {
+            Some(transactions) => IndexPage::new(transactions, |_| true, cursor, page_size),
+            None => IndexPage::new(&VecDeque::new(), |_| true, cursor, page_size),
+        }
+    }
+
+    /// Returns the page of the transitions calling the given program, optionally for the given function,
+    /// starting at the given cursor.
+    pub fn program_transitions(
+        &self,
+        program_id: &ProgramID<N>,
+        function: Option<&Identifier<N>>,
+        cursor: Option<IndexCursor>,
+        page_size: usize,
+    ) -> IndexPage<ProgramTransition<N>> {
+        let program_transitions = self.program_transitions.read();
+        let filter = |transition: &ProgramTransition<N>| function.map_or(true, |f| transition.function == *f);
+        match program_transitions.get(program_id) {
+            Some(transitions) => IndexPage::new(transitions, filter, cursor, page_size),
+            None => IndexPage::new(&VecDeque::new(), filter, cursor, page_size),
+        }
+    }
+
+    /// Indexes the given block, unless it is already indexed.
+    pub fn insert_block(&self, block: &Block<N>) {
+        self.insert_transactions(block.height(), block.transactions().iter().map(|confirmed| confirmed.transaction()))
+    }
+
+    /// Indexes the given transactions at the given height, unless the height is already indexed,
+    /// and prunes the blocks that fall out of the retention of the index.
+    fn insert_transactions<'a>(&self, height: u32, transactions: impl Iterator<Item = &'a Transaction<N>>) {
+        let mut next_height = self.next_height.write();
+        if height < *next_height {
+            return;
+        }
+
+        let mut address_transactions = self.address_transactions.write();
+        let mut program_transitions = self.program_transitions.write();
+        let (mut addresses, mut program_ids) = (HashSet::new(), HashSet::new());
+        for transaction in transactions {
+            let transaction_id = transaction.id();
+            // Index the transaction under each public address it touches.
+            for address in Self::addresses(transaction) {
+                address_transactions
+                    .entry(address)
+                    .or_default()
+                    .push_back(AddressTransaction { height, transaction_id });
+                addresses.insert(address);
+            }
+            // Index each transition under its program.
+            for transition in transaction.transitions() {
+                program_transitions.entry(*transition.program_id()).or_default().push_back(ProgramTransition {
+                    height,
+                    transaction_id,
+                    transition_id: *transition.id(),
+                    function: *transition.function_name(),
+                });
+                program_ids.insert(*transition.program_id());
+            }
+        }
+        *next_height = height.saturating_add(1);
+
+        // Prune the blocks that fall out of the retention of the index.
+        let mut blocks = self.blocks.write();
+        blocks.push_back((height, addresses.into_iter().collect(), program_ids.into_iter().collect()));
+        let cutoff = next_height.saturating_sub(self.retention);
+        while blocks.front().map_or(false, |(height, ..)| *height < cutoff) {
+            let Some((_, addresses, program_ids)) = blocks.pop_front() else { break };
+            for address in addresses {
+                prune(&mut address_transactions, address, |transaction| transaction.height < cutoff);
+            }
+            for program_id in program_ids {
+                prune(&mut program_transitions, program_id, |transition| transition.height < cutoff);
+            }
+        }
+    }
+
+    /// Returns the public addresses touched by the given transaction, i.e. its fee payer,
+    /// and the addresses in the public inputs, outputs, and finalize arguments of its transitions.
+    fn addresses(transaction: &Transaction<N>) -> HashSet<Address<N>> {
+        let mut addresses = HashSet::new();
+        // Add the fee payer.
+        if let Some(payer) = transaction.fee_transition().and_then(|fee| fee.payer()) {
+            addresses.insert(payer);
+        }
+        // Add the addresses of each transition.
+        for transition in transaction.transitions() {
+            Self::transition_addresses(transition, &mut addresses);
+        }
+        addresses
+    }
+
+    /// Adds the public addresses in the given transition.
+    fn transition_addresses(transition: &Transition<N>, addresses: &mut HashSet<Address<N>>) {
+        for input in transition.inputs() {
+            if let Input::Constant(_, Some(plaintext)) | Input::Public(_, Some(plaintext)) = input {
+                Self::plaintext_addresses(plaintext, addresses);
+            }
+        }
+        for output in transition.outputs() {
+            match output {
+                Output::Constant(_, Some(plaintext)) | Output::Public(_, Some(plaintext)) => {
+                    Self::plaintext_addresses(plaintext, addresses)
+                }
+                Output::Future(_, Some(future)) => Self::future_addresses(future, addresses),
+                _ => {}
+            }
+        }
+    }
+
+    /// Adds the addresses in the arguments of the given future.
+    fn future_addresses(future: &Future<N>, addresses: &mut HashSet<Address<N>>) {
+        for argument in future.arguments() {
+            match argument {
+                Argument::Plaintext(plaintext) => Self::plaintext_addresses(plaintext, addresses),
+                Argument::Future(future) => Self::future_addresses(future, addresses),
+            }
+        }
+    }
+
+    /// Adds the addresses in the given plaintext.
+    fn plaintext_addresses(plaintext: &Plaintext<N>, addresses: &mut HashSet<Address<N>>) {
+        match plaintext {
+            Plaintext::Literal(Literal::Address(address), _) => {
+                addresses.insert(*address);
+            }
+            Plaintext::Literal(..) => {}
+            Plaintext::Struct(members, _) => {
+                members.values().for_each(|member| Self::plaintext_addresses(member, addresses))
+            }
+            Plaintext::Array(elements, _) => {
+                elements.iter().for_each(|element| Self::plaintext_addresses(element, addresses))
+            }
+        }
+    }
+}
+
+/// Removes the oldest items of the given key that match the given predicate, and the key once it has no items.
+fn prune<K: Eq + std::hash::Hash, T>(map: &mut HashMap<K, VecDeque<T>>, key: K, is_pruned: impl Fn(&T) -> bool) {
+    if let Some(items) = map.get_mut(&key) {
+        while items.front().map_or(false, &is_pruned) {
+            items.pop_front();
+        }
+        if items.is_empty() {
+            map.remove(&key);
+        }
+    }
+}
+
+impl<N: Network> BlockIndex<N> for LedgerIndex<N> {
+    const NAME: &'static str = "ledger index";
+
+    /// Indexes the blocks of the ledger that are not yet indexed, within the retention of the index.
+    fn sync_with_ledger<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Result<()> {
+        let latest_height = ledger.latest_height();
+        let start_height = self.next_height().max(latest_height.saturating_add(1).saturating_sub(self.retention));
+        for height in start_height..=latest_height {
+            self.insert_block(&ledger.get_block(height)?);
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    /// Spawns the task that maintains the given index, as blocks are added to the ledger.
+    pub(crate) fn spawn_index<I: BlockIndex<N>>(&self, index: Arc<I>) {
+        let ledger = self.ledger.clone();
+        let mut receiver = self.streams.subscribe();
+        self.handles.lock().push(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(INDEX_SYNC_INTERVAL_IN_SECS));
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                // Catch up with the ledger, without blocking the runtime.
+                let (index_, ledger_) = (index.clone(), ledger.clone());
+                match tokio::task::spawn_blocking(move || index_.sync_with_ledger(&ledger_)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(error)) => warn!("Failed to update the {} - {error}", I::NAME),
+                    Err(error) => warn!("Failed to update the {} - {error}", I::NAME),
+                }
+                // Wait for a new block, or for the next interval.
+                loop {
+                    tokio::select! {
+                        event = receiver.recv() => match event {
+                            Ok(StreamEvent::Block(..)) | Err(RecvError::Lagged(_)) => break,
+                            Ok(_) => continue,
+                            Err(RecvError::Closed) => return,
+                        },
+                        _ = interval.tick() => break,
+                    }
+                }
+            }
+        }));
+    }
+
+    /// Returns the index, or an error if the index is disabled.
+    fn index(&self) -> Result<&Arc<LedgerIndex<N>>, RestError> {
+        self.index.as_ref().ok_or_else(|| RestError("The index is disabled on this node".to_string()))
+    }
+
+    /// Returns the cursor and page size from the given query.
+    fn parse_page(query: &PageQuery) -> Result<(Option<IndexCursor>, usize), RestError> {
+        let page_size = query.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+        if page_size == 0 || page_size > MAX_PAGE_SIZE {
+            return Err(RestError(format!("The page size must be between 1 and {MAX_PAGE_SIZE}")));
+        }
+        let cursor = query.cursor.as_deref().map(IndexCursor::from_str).transpose()?;
+        Ok((cursor, page_size))
+    }
+
+    // GET /mainnet/address/{address}/transactions?cursor={cursor}&page_size={pageSize}
+    pub(crate) async fn get_address_transactions(
+        State(rest): State<Self>,
+        Path(address): Path<Address<N>>,
+        Query(query): Query<PageQuery>,
+    ) -> Result<ErasedJson, RestError> {
+        let (cursor, page_size) = Self::parse_page(&query)?;
+        Ok(ErasedJson::pretty(rest.index()?.address_transactions(&address, cursor, page_size)))
+    }
+
+    // GET /mainnet/program/{programID}/transitions?function={functionName}&cursor={cursor}&page_size={pageSize}
+    pub(crate) async fn get_program_transitions(
+        State(rest): State<Self>,
+        Path(program_id): Path<ProgramID<N>>,
+        Query(query): Query<PageQuery>,
+    ) -> Result<ErasedJson, RestError> {
+        let (cursor, page_size) = Self::parse_page(&query)?;
+        // Parse the function name.
+        let function = match &query.function {
+            Some(function) => Some(
+                Identifier::<N>::from_str(function)
+                    .map_err(|_| RestError(format!("Invalid function name '{function}'")))?,
+            ),
+            None => None,
+        };
+        Ok(ErasedJson::pretty(rest.index()?.program_transitions(&program_id, function.as_ref(), cursor, page_size)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    type CurrentNetwork = MainnetV0;
+
+    /// An item at the given height, with the given number.
+    impl IndexItem for (u32, u32) {
+        fn height(&self) -> u32 {
+            self.0
+        }
+    }
+
+    /// Returns every page of the given query, by following the cursors.
+    fn all_pages<T>(page: impl Fn(Option<IndexCursor>) -> IndexPage<T>) -> Vec<IndexPage<T>> {
+        let mut pages = vec![page(None)];
+        while let Some(cursor) = pages.last().and_then(|page| page.next_cursor) {
+            pages.push(page(Some(cursor)));
+        }
+        pages
+    }
+
+    #[test]
+    fn test_index_page() {
+        // Three items in each of the blocks 0 to 3, in ledger order.
+        let items = (0..4).flat_map(|height| (0..3).map(move |number| (height, number))).collect::<VecDeque<_>>();
+
+        // Ensure the first page holds the most recent items, and continues from the middle of a block.
+        let page = IndexPage::new(&items, |_| true, None, 4);
+        assert_eq!(page.items, vec![(3, 2), (3, 1), (3, 0), (2, 2)]);
+        assert_eq!(page.next_cursor, Some(IndexCursor { height: 2, offset: 1 }));
+
+        // Ensure the next pages continue from the cursor, up to a partial last page.
+        let page = IndexPage::new(&items, |_| true, page.next_cursor, 4);
+        assert_eq!(page.items, vec![(2, 1), (2, 0), (1, 2), (1, 1)]);
+        assert_eq!(page.next_cursor, Some(IndexCursor { height: 1, offset: 2 }));
+        let page = IndexPage::new(&items, |_| true, page.next_cursor, 2);
+        assert_eq!(page.items, vec![(1, 0), (0, 2)]);
+        let page = IndexPage::new(&items, |_| true, page.next_cursor, 4);
+        assert_eq!((page.items, page.next_cursor), (vec![(0, 1), (0, 0)], None));
+
+        // Ensure the cursor is stable as new blocks are added, and a cursor within a block accumulates its offset.
+        let mut items = items;
+        items.push_back((4, 0));
+        let cursor = Some(IndexCursor { height: 3, offset: 1 });
+        let page = IndexPage::new(&items, |_| true, cursor, 1);
+        assert_eq!((page.items, page.next_cursor), (vec![(3, 1)], Some(IndexCursor { height: 3, offset: 2 })));
+
+        // Ensure the filtered items are paged in full, and a cursor of a pruned block returns no items.
+        let pages = all_pages(|cursor| IndexPage::new(&items, |item| item.1 == 0, cursor, 2));
+        let filtered = pages.into_iter().flat_map(|page| page.items).collect::<Vec<_>>();
+        assert_eq!(filtered, vec![(4, 0), (3, 0), (2, 0), (1, 0), (0, 0)]);
+        items.retain(|item| item.0 > 1);
+        assert!(IndexPage::new(&items, |_| true, Some(IndexCursor { height: 1, offset: 0 }), 4).items.is_empty());
+
+        // Ensure the cursor is parsed from its string representation.
+        assert_eq!(IndexCursor::from_str("2.1").unwrap(), IndexCursor { height: 2, offset: 1 });
+        assert_eq!(IndexCursor::from_str("2").unwrap(), IndexCursor { height: 2, offset: 0 });
+        assert_eq!(IndexCursor { height: 2, offset: 1 }.to_string(), "2.1");
+        assert!(IndexCursor::from_str("2.x").is_err());
+    }
+
+    #[test]
+    fn test_insert_block() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let transactions = genesis.transactions().iter().map(|confirmed| confirmed.transaction()).collect::<Vec<_>>();
+        let credits = ProgramID::<CurrentNetwork>::from_str("credits.aleo").unwrap();
+
+        let index = LedgerIndex::<CurrentNetwork>::default();
+        index.insert_block(&genesis);
+        assert_eq!(index.next_height(), 1);
+
+        // Ensure each address touched by the genesis block is indexed.
+        let addresses =
+            transactions.iter().flat_map(|transaction| LedgerIndex::addresses(transaction)).collect::<Vec<_>>();
+        assert!(!addresses.is_empty());
+        for address in &addresses {
+            let page = index.address_transactions(address, None, MAX_PAGE_SIZE);
+            assert!(!page.items.is_empty());
+            assert!(page.items.iter().all(|item| item.height == 0));
+            assert!(page.items.iter().all(|item| transactions.iter().any(|tx| tx.id() == item.transaction_id)));
+        }
+        // Ensure the transitions of the genesis block are indexed under their program.
+        let num_transitions = transactions.iter().map(|transaction| transaction.transitions().count()).sum::<usize>();
+        let count = |function: Option<&Identifier<CurrentNetwork>>| {
+            let pages = all_pages(|cursor| index.program_transitions(&credits, function, cursor, MAX_PAGE_SIZE));
+            pages.iter().map(|page| page.items.len()).sum::<usize>()
+        };
+        assert_eq!(count(None), num_transitions);
+        let function = Identifier::from_str("missing_function").unwrap();
+        assert_eq!(count(Some(&function)), 0);
+
+        // Ensure an indexed block is not indexed again.
+        index.insert_block(&genesis);
+        assert_eq!(count(None), num_transitions);
+        // Ensure an unknown address has no transactions.
+        let page = index.address_transactions(&Address::zero(), None, MAX_PAGE_SIZE);
+        assert_eq!((page.items.len(), page.next_cursor), (0, None));
+    }
+
+    #[test]
+    fn test_index_retention() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let transactions = genesis.transactions().iter().map(|confirmed| confirmed.transaction()).collect::<Vec<_>>();
+        let credits = ProgramID::<CurrentNetwork>::from_str("credits.aleo").unwrap();
+        let num_transitions = transactions.iter().map(|transaction| transaction.transitions().count()).sum::<usize>();
+
+        // Index the same transactions at three heights, with a retention of two blocks.
+        let index = LedgerIndex::<CurrentNetwork>::new(2);
+        for height in 0..3 {
+            index.insert_transactions(height, transactions.iter().copied());
+        }
+        assert_eq!(index.next_height(), 3);
+
+        // Ensure the oldest block was pruned.
+        let pages = all_pages(|cursor| index.program_transitions(&credits, None, cursor, MAX_PAGE_SIZE));
+        let items = pages.into_iter().flat_map(|page| page.items).collect::<Vec<_>>();
+        assert_eq!(items.len(), 2 * num_transitions);
+        assert!(items.iter().all(|item| item.height >= 1));
+        assert_eq!(index.blocks.read().len(), 2);
+
+        // Ensure the keys are removed once all of their items are pruned.
+        index.insert_transactions(10, std::iter::empty());
+        assert!(index.address_transactions.read().is_empty());
+        assert!(index.program_transitions.read().is_empty());
+    }
+}
@@ -17,10 +17,25 @@
 #[macro_use]
 extern crate tracing;
 
+mod admin;
+
 mod helpers;
 pub use helpers::*;
 
+mod history;
+pub use history::*;
+
+mod index;
+pub use index::*;
+
 mod routes;
+use routes::MappingSnapshots;
+
+mod simulate;
+pub use simulate::*;
+
+mod streams;
+pub use streams::*;
 
 use snarkos_node_consensus::Consensus;
 use snarkos_node_router::{
@@ -30,7 +45,7 @@ use snarkos_node_router::{
 use snarkvm::{
     console::{program::ProgramID, types::Field},
     ledger::narwhal::Data,
-    prelude::{cfg_into_iter, store::ConsensusStorage, Ledger, Network},
+    prelude::{block::Block, cfg_into_iter, store::ConsensusStorage, Ledger, Network},
 };
 
 use anyhow::Result;
@@ -41,13 +56,17 @@ use axum::{
     middleware,
     middleware::Next,
     response::Response,
-    routing::{get, post},
+    routing::{delete, get, post},
     Json,
 };
 use axum_extra::response::ErasedJson;
 use parking_lot::Mutex;
 use std::{net::SocketAddr, sync::Arc};
-use tokio::{net::TcpListener, task::JoinHandle};
+use tokio::{
+    net::TcpListener,
+    sync::{broadcast, Semaphore},
+    task::JoinHandle,
+};
 use tower_governor::{governor::GovernorConfigBuilder, GovernorLayer};
 use tower_http::{
     cors::{Any, CorsLayer},
@@ -63,21 +82,58 @@ pub struct Rest<N: Network, C: ConsensusStorage<N>, R: Routing<N>> {
     ledger: Ledger<N, C>,
     /// The node (routing).
     routing: Arc<R>,
+    /// The secondary index of the ledger, if enabled.
+    index: Option<Arc<LedgerIndex<N>>>,
+    /// The history of the mapping values, if enabled.
+    mapping_history: Option<Arc<MappingHistory<N>>>,
+    /// The sender for the stream events.
+    streams: broadcast::Sender<StreamEvent<N>>,
+    /// The permits of the concurrent transaction simulations.
+    simulations: Arc<Semaphore>,
+    /// The latest snapshots of the mappings being paged, if the mapping history is disabled.
+    mapping_snapshots: Arc<Mutex<MappingSnapshots<N>>>,
     /// The server handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
 }
 
 impl<N: Network, C: 'static + ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     /// Initializes a new instance of the server.
+    #[allow(clippy::too_many_arguments)]
     pub async fn start(
         rest_ip: SocketAddr,
         rest_rps: u32,
         consensus: Option<Consensus<N>>,
         ledger: Ledger<N, C>,
         routing: Arc<R>,
+        blocks: Option<broadcast::Receiver<Block<N>>>,
+        with_index: bool,
+        with_mapping_history: bool,
     ) -> Result<Self> {
         // Initialize the server.
-        let mut server = Self { consensus, ledger, routing, handles: Default::default() };
+        let streams = broadcast::channel(MAX_STREAM_EVENTS).0;
+        let index = with_index.then(|| Arc::new(LedgerIndex::default()));
+        let mapping_history = with_mapping_history.then(|| Arc::new(MappingHistory::default()));
+        let simulations = Arc::new(Semaphore::new(MAX_CONCURRENT_SIMULATIONS));
+        let mut server = Self {
+            consensus,
+            ledger,
+            routing,
+            index,
+            mapping_history,
+            streams,
+            simulations,
+            mapping_snapshots: Default::default(),
+            handles: Default::default(),
+        };
+        // Spawn the stream forwarders.
+        server.spawn_stream_forwarders(blocks);
+        // Spawn the indexes, if enabled.
+        if let Some(index) = &server.index {
+            server.spawn_index(index.clone());
+        }
+        if let Some(mapping_history) = &server.mapping_history {
+            server.spawn_index(mapping_history.clone());
+        }
         // Spawn the server.
         server.spawn_server(rest_ip, rest_rps).await;
         // Return the server.
@@ -101,7 +157,7 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     async fn spawn_server(&mut self, rest_ip: SocketAddr, rest_rps: u32) {
         let cors = CorsLayer::new()
             .allow_origin(Any)
-            .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+            .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::OPTIONS])
             .allow_headers([CONTENT_TYPE]);
 
         // Log the REST rate limit per IP.
@@ -117,12 +173,26 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
                 .expect("Couldn't set up rate limiting for the REST server!"),
         );
 
+        // Prepare the JWT auth layers, which require the given scope.
+        let admin = middleware::from_fn_with_state(Scope::Admin, auth_middleware);
+        let node_read = middleware::from_fn_with_state(Scope::NodeRead, auth_middleware);
+        let peers_read = middleware::from_fn_with_state(Scope::PeersRead, auth_middleware);
+        let peers_write = middleware::from_fn_with_state(Scope::PeersWrite, auth_middleware);
+
         let router = {
             axum::Router::new()
 
-            // All the endpoints before the call to `route_layer` are protected with JWT auth.
-            .route("/mainnet/node/address", get(Self::get_node_address))
-            .route_layer(middleware::from_fn(auth_middleware))
+            // The following endpoints are protected with JWT auth.
+            .route("/mainnet/node/address", get(Self::get_node_address).route_layer(node_read))
+            .route("/mainnet/node/tokens/revoke", post(Self::revoke_token).route_layer(admin))
+            .route("/mainnet/peers/trusted", get(Self::get_peers_trusted).route_layer(peers_read.clone()))
+            .route("/mainnet/peers/trusted", post(Self::add_trusted_peer).route_layer(peers_write.clone()))
+            .route("/mainnet/peers/trusted/:peer_ip", delete(Self::remove_trusted_peer).route_layer(peers_write.clone()))
+            .route("/mainnet/peers/restricted", get(Self::get_peers_restricted).route_layer(peers_read))
+            .route("/mainnet/peers/banned", post(Self::ban_ip).route_layer(peers_write.clone()))
+            .route("/mainnet/peers/banned/:ip", delete(Self::unban_ip).route_layer(peers_write.clone()))
+            .route("/mainnet/peers/connect", post(Self::connect_peer).route_layer(peers_write.clone()))
+            .route("/mainnet/peers/disconnect", post(Self::disconnect_peer).route_layer(peers_write))
 
             // ----------------- DEPRECATED ROUTES -----------------
             // The following `GET ../latest/..` routes will be removed before mainnet.
@@ -152,7 +222,9 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             // GET and POST ../transaction/..
             .route("/mainnet/transaction/:id", get(Self::get_transaction))
             .route("/mainnet/transaction/confirmed/:id", get(Self::get_confirmed_transaction))
+            .route("/mainnet/transaction/:id/status", get(Self::get_transaction_status))
             .route("/mainnet/transaction/broadcast", post(Self::transaction_broadcast))
+            .route("/mainnet/transaction/simulate", post(Self::transaction_simulate))
 
             // POST ../solution/broadcast
             .route("/mainnet/solution/broadcast", post(Self::solution_broadcast))
@@ -163,6 +235,9 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             .route("/mainnet/find/transactionID/:transition_id", get(Self::find_transaction_id_from_transition_id))
             .route("/mainnet/find/transitionID/:input_or_output_id", get(Self::find_transition_id))
 
+            // GET ../address/..
+            .route("/mainnet/address/:address/transactions", get(Self::get_address_transactions))
+
             // GET ../peers/..
             .route("/mainnet/peers/count", get(Self::get_peers_count))
             .route("/mainnet/peers/all", get(Self::get_peers_all))
@@ -171,7 +246,10 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             // GET ../program/..
             .route("/mainnet/program/:id", get(Self::get_program))
             .route("/mainnet/program/:id/mappings", get(Self::get_mapping_names))
+            .route("/mainnet/program/:id/mapping/:name/batch", post(Self::get_mapping_values))
+            .route("/mainnet/program/:id/mapping/:name/entries", get(Self::get_mapping_entries))
             .route("/mainnet/program/:id/mapping/:name/:key", get(Self::get_mapping_value))
+            .route("/mainnet/program/:id/transitions", get(Self::get_program_transitions))
 
             // GET misc endpoints.
             .route("/mainnet/blocks", get(Self::get_blocks))
@@ -183,6 +261,18 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             .route("/mainnet/stateRoot/latest", get(Self::get_state_root_latest))
             .route("/mainnet/committee/latest", get(Self::get_committee_latest))
 
+            // GET ../stream/..
+            .route("/mainnet/stream/sse", get(Self::stream_sse))
+            .route("/mainnet/stream/ws", get(Self::stream_ws))
+        };
+
+        // If the metrics are enabled, serve them and record the request metrics of every route.
+        #[cfg(feature = "metrics")]
+        let router =
+            router.route("/metrics", get(Self::get_metrics)).route_layer(middleware::from_fn(metrics_middleware));
+
+        let router = {
+            router
             // Pass in `Rest` to make things convenient.
             .with_state(self.clone())
             // Enable tower-http tracing.
@@ -217,3 +307,25 @@ async fn log_middleware(
 
     Ok(next.run(request).await)
 }
+
+/// Records the number of requests and the request latency, labelled by route.
+#[cfg(feature = "metrics")]
+async fn metrics_middleware(request: Request<Body>, next: Next) -> Response {
+    // Retrieve the matched route, so that the labels do not contain the path parameters.
+    let route = match request.extensions().get::<axum::extract::MatchedPath>() {
+        Some(path) => path.as_str().to_string(),
+        None => request.uri().path().to_string(),
+    };
+    let method = request.method().to_string();
+
+    // Run the request, and measure its latency.
+    let timer = std::time::Instant::now();
+    let response = next.run(request).await;
+    let latency = timer.elapsed().as_secs_f64();
+
+    let labels = [("method", method), ("route", route), ("status", response.status().as_u16().to_string())];
+    metrics::increment_counter_with_labels(metrics::rest::REQUESTS, 1, &labels);
+    metrics::histogram_with_labels(metrics::rest::REQUEST_LATENCY, latency, &labels[..2]);
+
+    response
+}
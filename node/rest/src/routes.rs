@@ -13,30 +13,225 @@
 // limitations under the License.
 
 use super::*;
+use snarkos_node_consensus::TransactionStatus;
 use snarkos_node_router::messages::UnconfirmedSolution;
 use snarkvm::{
     ledger::coinbase::ProverSolution,
-    prelude::{block::Transaction, Identifier, Plaintext},
+    prelude::{
+        block::{Header, Transaction},
+        Identifier,
+        Plaintext,
+        ToBytes,
+        Value,
+    },
 };
 
+use anyhow::anyhow;
+use axum::{http::HeaderValue, response::IntoResponse};
 use indexmap::IndexMap;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::{
+    hash::Hash,
+    io::{Result as IoResult, Write},
+    ops::Range,
+    str::FromStr,
+};
+use tokio_stream::wrappers::ReceiverStream;
+
+/// Returns the `ToBytes` encoding of the given object, prefixed by its length, as a `u32` in little-endian.
+fn length_prefixed<T: ToBytes>(object: &T) -> Result<Vec<u8>> {
+    let bytes = object.to_bytes_le()?;
+    let mut output = Vec::with_capacity(4 + bytes.len());
+    output.extend_from_slice(&u32::try_from(bytes.len())?.to_le_bytes());
+    output.extend_from_slice(&bytes);
+    Ok(output)
+}
+
+/// Returns a streaming response, with the encoding of each block height in the given range, from the given ledger.
+/// Note: The blocks are read and encoded in a blocking task, which stops if the client disconnects.
+fn stream_blocks<N: Network, C: ConsensusStorage<N>>(
+    ledger: Ledger<N, C>,
+    range: Range<u32>,
+    encode: impl Fn(u32, &Ledger<N, C>) -> Result<Vec<u8>> + Send + 'static,
+) -> Response {
+    let (sender, receiver) = tokio::sync::mpsc::channel::<Result<Vec<u8>, std::io::Error>>(STREAMED_BLOCKS_BUFFER);
+    tokio::task::spawn_blocking(move || {
+        for height in range {
+            let chunk = encode(height, &ledger).map_err(|error| {
+                warn!("Failed to stream block {height} - {error}");
+                std::io::Error::new(std::io::ErrorKind::Other, error.to_string())
+            });
+            let is_error = chunk.is_err();
+            // If the client disconnected, or the block failed to encode, then stop streaming.
+            if sender.blocking_send(chunk).is_err() || is_error {
+                break;
+            }
+        }
+    });
+    Response::new(Body::from_stream(ReceiverStream::new(receiver)))
+}
+
+/// The maximum number of blocks returned per call, as a JSON array.
+const MAX_BLOCK_RANGE: u32 = 50;
+/// The maximum number of block headers returned per call, as a JSON array.
+const MAX_HEADER_RANGE: u32 = 1000;
+/// The maximum number of blocks (or headers) streamed per call, as NDJSON or bytes.
+/// Note: This matches the header range, so that a single call cannot hold the ledger for long.
+const MAX_STREAMED_BLOCK_RANGE: u32 = 1000;
+/// The number of encoded blocks buffered ahead of a streaming response.
+const STREAMED_BLOCKS_BUFFER: usize = 16;
+/// The response header that holds the starting height of the next page of blocks, if any.
+const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
 
 /// The `get_blocks` query object.
 #[derive(Deserialize, Serialize)]
 pub(crate) struct BlockRange {
-    /// The starting block height (inclusive).
+    /// The starting block height (inclusive), i.e. the cursor returned by the previous page.
+    #[serde(alias = "cursor")]
+    start: u32,
+    /// The ending block height (exclusive) (default: the largest range for the format, up to the latest block).
+    end: Option<u32>,
+    /// If `true`, only the block headers are returned (default: `false`).
+    #[serde(default)]
+    headers: bool,
+    /// The format of the response (default: `json`).
+    #[serde(default)]
+    format: BlocksFormat,
+}
+
+/// The response formats of `get_blocks`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum BlocksFormat {
+    /// A JSON array.
+    #[default]
+    Json,
+    /// A stream of newline-delimited JSON objects.
+    Ndjson,
+    /// A stream of length-prefixed (`u32` little-endian) objects, in the snarkVM `ToBytes` encoding.
+    Bytes,
+}
+
+impl BlocksFormat {
+    /// Returns the content type of the format.
+    const fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Ndjson => "application/x-ndjson",
+            Self::Bytes => "application/octet-stream",
+        }
+    }
+
+    /// Returns the maximum number of blocks (or headers) per call, for the format.
+    const fn max_range(&self, headers: bool) -> u32 {
+        match (self, headers) {
+            (Self::Json, false) => MAX_BLOCK_RANGE,
+            (Self::Json, true) => MAX_HEADER_RANGE,
+            (Self::Ndjson | Self::Bytes, _) => MAX_STREAMED_BLOCK_RANGE,
+        }
+    }
+}
+
+/// A compact block header, returned by `get_blocks` if only the headers are requested.
+#[derive(Serialize)]
+#[serde(bound = "")]
+pub(crate) struct BlockHeader<N: Network> {
+    /// The block hash.
+    hash: N::BlockHash,
+    /// The block header.
+    header: Header<N>,
+}
+
+impl<N: Network> ToBytes for BlockHeader<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.hash.write_le(&mut writer)?;
+        self.header.write_le(&mut writer)
+    }
+}
+
+/// Returns the range of block heights to return, given the requested range, the latest height, and the maximum range.
+/// Note: If the end height is not given, the range ends at the latest block (inclusive), or at the maximum range,
+/// and is empty if the start height is past the latest block. An explicit end height past the latest block is rejected,
+/// as the streamed formats cannot report the missing blocks once the response has started.
+fn resolve_block_range(
     start: u32,
-    /// The ending block height (exclusive).
-    end: u32,
+    end: Option<u32>,
+    latest_height: u32,
+    max_range: u32,
+) -> Result<Range<u32>, RestError> {
+    // Determine the end height.
+    let end = end.unwrap_or_else(|| latest_height.saturating_add(1).min(start.saturating_add(max_range)).max(start));
+    // Ensure the end height is greater than the start height.
+    if start > end {
+        return Err(RestError("Invalid block range".to_string()));
+    }
+    // Ensure the block range does not end past the latest block.
+    if end > latest_height.saturating_add(1) {
+        return Err(RestError(format!("Invalid block range (the latest block height is {latest_height})")));
+    }
+    // Ensure the block range is bounded.
+    if end - start > max_range {
+        return Err(RestError(format!(
+            "Cannot request more than {max_range} blocks per call (requested {})",
+            end - start
+        )));
+    }
+    Ok(start..end)
+}
+
+/// The maximum number of keys per call to `get_mapping_values`.
+const MAX_MAPPING_BATCH_SIZE: usize = 100;
+/// The default number of entries per call to `get_mapping_entries`.
+const DEFAULT_MAPPING_ENTRIES: usize = 100;
+/// The maximum number of entries per call to `get_mapping_entries`.
+const MAX_MAPPING_ENTRIES: usize = 1000;
+
+/// The maximum number of mapping snapshots kept for `get_mapping_entries`.
+const MAX_MAPPING_SNAPSHOTS: usize = 16;
+
+/// The snapshots of the mappings, with the height at which each one was read.
+pub(crate) type MappingSnapshots<N> =
+    IndexMap<(ProgramID<N>, Identifier<N>), (u32, Arc<IndexMap<Plaintext<N>, Value<N>>>)>;
+
+/// Returns up to `limit` of the given entries, starting after the given key,
+/// and the key to continue from, if there are more entries.
+fn page_entries<K: Hash + Eq + Clone, V: Clone>(
+    entries: &IndexMap<K, V>,
+    cursor: Option<&K>,
+    limit: usize,
+) -> Result<(Vec<(K, V)>, Option<K>)> {
+    let start = match cursor {
+        Some(cursor) => entries.get_index_of(cursor).ok_or_else(|| anyhow!("Invalid cursor"))? + 1,
+        None => 0,
+    };
+    let has_more = entries.len() > start.saturating_add(limit);
+    let entries =
+        entries.iter().skip(start).take(limit).map(|(key, value)| (key.clone(), value.clone())).collect::<Vec<_>>();
+    let next_cursor = has_more.then(|| entries.last().map(|(key, _)| key.clone())).flatten();
+    Ok((entries, next_cursor))
 }
 
-/// The `get_mapping_value` query object.
+/// The `get_mapping_value` and `get_mapping_values` query object.
 #[derive(Deserialize, Serialize)]
 pub(crate) struct Metadata {
+    /// If `true`, the value is returned with the height of its state (default: `false`).
+    #[serde(default)]
     metadata: bool,
+    /// The height at which to read the values (default: the latest height).
+    height: Option<u32>,
+}
+
+/// The `get_mapping_entries` query object.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct MappingEntriesQuery {
+    /// The key after which to start, i.e. the cursor returned by the previous page (default: the first key).
+    cursor: Option<String>,
+    /// The maximum number of entries to return (default: 100).
+    limit: Option<usize>,
+    /// The height at which to read the entries (default: the latest height).
+    height: Option<u32>,
 }
 
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
@@ -112,34 +307,60 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(block))
     }
 
-    // GET /mainnet/blocks?start={start_height}&end={end_height}
+    // GET /mainnet/blocks?start={start_height}&end={end_height}&headers={bool}&format={json|ndjson|bytes}
+    // GET /mainnet/blocks?cursor={next_cursor}&headers={bool}&format={json|ndjson|bytes}
     pub(crate) async fn get_blocks(
         State(rest): State<Self>,
         Query(block_range): Query<BlockRange>,
-    ) -> Result<ErasedJson, RestError> {
-        let start_height = block_range.start;
-        let end_height = block_range.end;
-
-        const MAX_BLOCK_RANGE: u32 = 50;
-
-        // Ensure the end height is greater than the start height.
-        if start_height > end_height {
-            return Err(RestError("Invalid block range".to_string()));
-        }
+    ) -> Result<Response, RestError> {
+        let BlockRange { start, end, headers, format } = block_range;
+
+        // Determine the block range.
+        let latest_height = rest.ledger.latest_height();
+        let range = resolve_block_range(start, end, latest_height, format.max_range(headers))?;
+
+        let mut response = match (format, headers) {
+            (BlocksFormat::Json, false) => {
+                let blocks = cfg_into_iter!(range.clone())
+                    .map(|height| rest.ledger.get_block(height))
+                    .collect::<Result<Vec<_>, _>>()?;
+                ErasedJson::pretty(blocks).into_response()
+            }
+            (BlocksFormat::Json, true) => {
+                let headers = cfg_into_iter!(range.clone())
+                    .map(|height| Self::get_block_header(&rest.ledger, height))
+                    .collect::<Result<Vec<_>, _>>()?;
+                ErasedJson::pretty(headers).into_response()
+            }
+            (BlocksFormat::Ndjson, false) => stream_blocks(rest.ledger.clone(), range.clone(), |height, ledger| {
+                let mut line = serde_json::to_vec(&ledger.get_block(height)?)?;
+                line.push(b'\n');
+                Ok(line)
+            }),
+            (BlocksFormat::Ndjson, true) => stream_blocks(rest.ledger.clone(), range.clone(), |height, ledger| {
+                let mut line = serde_json::to_vec(&Self::get_block_header(ledger, height)?)?;
+                line.push(b'\n');
+                Ok(line)
+            }),
+            (BlocksFormat::Bytes, false) => stream_blocks(rest.ledger.clone(), range.clone(), |height, ledger| {
+                length_prefixed(&ledger.get_block(height)?)
+            }),
+            (BlocksFormat::Bytes, true) => stream_blocks(rest.ledger.clone(), range.clone(), |height, ledger| {
+                length_prefixed(&Self::get_block_header(ledger, height)?)
+            }),
+        };
 
-        // Ensure the block range is bounded.
-        if end_height - start_height > MAX_BLOCK_RANGE {
-            return Err(RestError(format!(
-                "Cannot request more than {MAX_BLOCK_RANGE} blocks per call (requested {})",
-                end_height - start_height
-            )));
+        // Set the content type, and the cursor of the next page, if there are more blocks.
+        response.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static(format.content_type()));
+        if range.end <= latest_height {
+            response.headers_mut().insert(NEXT_CURSOR_HEADER, HeaderValue::from(range.end));
         }
+        Ok(response)
+    }
 
-        let blocks = cfg_into_iter!((start_height..end_height))
-            .map(|height| rest.ledger.get_block(height))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(ErasedJson::pretty(blocks))
+    /// Returns the compact header of the block at the given height, from the given ledger.
+    fn get_block_header(ledger: &Ledger<N, C>, height: u32) -> Result<BlockHeader<N>> {
+        Ok(BlockHeader { hash: ledger.get_hash(height)?, header: ledger.get_header(height)? })
     }
 
     // GET /mainnet/height/{blockHash}
@@ -174,6 +395,22 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(rest.ledger.get_confirmed_transaction(tx_id)?))
     }
 
+    // GET /mainnet/transaction/{transactionID}/status
+    pub(crate) async fn get_transaction_status(
+        State(rest): State<Self>,
+        Path(tx_id): Path<N::TransactionID>,
+    ) -> Result<ErasedJson, RestError> {
+        // If the transaction is in the ledger, it was committed.
+        if let Some(block_hash) = rest.ledger.find_block_hash(&tx_id)? {
+            let height = rest.ledger.get_height(&block_hash)?;
+            return Ok(ErasedJson::pretty(TransactionStatus::Committed { height }));
+        }
+        // Otherwise, retrieve the status from the status index of consensus.
+        // Note: Only validators track the statuses of unconfirmed transactions.
+        let status = rest.consensus.as_ref().and_then(|consensus| consensus.transaction_status(&tx_id));
+        Ok(ErasedJson::pretty(status.unwrap_or(TransactionStatus::Unknown)))
+    }
+
     // GET /mainnet/memoryPool/transmissions
     pub(crate) async fn get_memory_pool_transmissions(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
         match rest.consensus {
@@ -217,20 +454,27 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     }
 
     // GET /mainnet/program/{programID}/mapping/{mappingName}/{mappingKey}
-    // GET /mainnet/program/{programID}/mapping/{mappingName}/{mappingKey}?metadata={true}
+    // GET /mainnet/program/{programID}/mapping/{mappingName}/{mappingKey}?metadata={true}&height={height}
     pub(crate) async fn get_mapping_value(
         State(rest): State<Self>,
         Path((id, name, key)): Path<(ProgramID<N>, Identifier<N>, Plaintext<N>)>,
         metadata: Option<Query<Metadata>>,
     ) -> Result<ErasedJson, RestError> {
-        // Retrieve the mapping value.
-        let mapping_value = rest.ledger.vm().finalize_store().get_value_confirmed(id, name, &key)?;
+        let (with_metadata, height) = metadata.map(|Query(q)| (q.metadata, q.height)).unwrap_or_default();
+
+        // Retrieve the mapping value, at the given height, or at the latest height.
+        let (mapping_value, height) = match height {
+            Some(height) => (rest.mapping_history()?.get_value(&id, &name, &key, height)?, height),
+            None => {
+                (rest.ledger.vm().finalize_store().get_value_confirmed(id, name, &key)?, rest.ledger.latest_height())
+            }
+        };
 
         // Check if metadata is requested and return the value with metadata if so.
-        if metadata.map(|q| q.metadata).unwrap_or(false) {
+        if with_metadata {
             return Ok(ErasedJson::pretty(json!({
                 "data": mapping_value,
-                "height": rest.ledger.latest_height(),
+                "height": height,
             })));
         }
 
@@ -238,6 +482,125 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(mapping_value))
     }
 
+    // POST /mainnet/program/{programID}/mapping/{mappingName}/batch
+    // POST /mainnet/program/{programID}/mapping/{mappingName}/batch?metadata={true}&height={height}
+    pub(crate) async fn get_mapping_values(
+        State(rest): State<Self>,
+        Path((id, name)): Path<(ProgramID<N>, Identifier<N>)>,
+        metadata: Option<Query<Metadata>>,
+        Json(keys): Json<Vec<Plaintext<N>>>,
+    ) -> Result<ErasedJson, RestError> {
+        let (with_metadata, height) = metadata.map(|Query(q)| (q.metadata, q.height)).unwrap_or_default();
+
+        // Ensure the number of keys is bounded.
+        if keys.len() > MAX_MAPPING_BATCH_SIZE {
+            return Err(RestError(format!(
+                "Cannot request more than {MAX_MAPPING_BATCH_SIZE} keys per call (requested {})",
+                keys.len()
+            )));
+        }
+
+        // Retrieve the mapping values, in the order of the keys, at the given height, or at the latest height.
+        let (mapping_values, height) = match height {
+            Some(height) => (rest.mapping_history()?.get_values(&id, &name, &keys, height)?, height),
+            None => {
+                let finalize_store = rest.ledger.vm().finalize_store();
+                let values = keys
+                    .iter()
+                    .map(|key| finalize_store.get_value_confirmed(id, name, key))
+                    .collect::<Result<Vec<_>>>()?;
+                (values, rest.ledger.latest_height())
+            }
+        };
+
+        // Check if metadata is requested and return the values with metadata if so.
+        if with_metadata {
+            return Ok(ErasedJson::pretty(json!({
+                "data": mapping_values,
+                "height": height,
+            })));
+        }
+
+        // Return the values without metadata.
+        Ok(ErasedJson::pretty(mapping_values))
+    }
+
+    // GET /mainnet/program/{programID}/mapping/{mappingName}/entries?cursor={cursor}&limit={limit}&height={height}
+    pub(crate) async fn get_mapping_entries(
+        State(rest): State<Self>,
+        Path((id, name)): Path<(ProgramID<N>, Identifier<N>)>,
+        Query(query): Query<MappingEntriesQuery>,
+    ) -> Result<ErasedJson, RestError> {
+        let limit = query.limit.unwrap_or(DEFAULT_MAPPING_ENTRIES);
+
+        // Ensure the number of entries is bounded.
+        if limit == 0 || limit > MAX_MAPPING_ENTRIES {
+            return Err(RestError(format!("The limit must be between 1 and {MAX_MAPPING_ENTRIES}")));
+        }
+        // Parse the cursor, which is the last key of the previous page.
+        let cursor = match &query.cursor {
+            Some(cursor) => {
+                Some(Plaintext::<N>::from_str(cursor).map_err(|_| RestError(format!("Invalid cursor '{cursor}'")))?)
+            }
+            None => None,
+        };
+
+        // Retrieve the page of entries, at the given height, or at the latest height.
+        // Note: If the mapping history is enabled, it serves the latest entries without reading the whole mapping.
+        let ((entries, next_cursor), height) = match (query.height, &rest.mapping_history) {
+            (Some(height), _) => {
+                (rest.mapping_history()?.get_entries(&id, &name, height, cursor.as_ref(), limit)?, height)
+            }
+            (None, Some(mapping_history)) => {
+                let height = mapping_history.latest_height()?;
+                (mapping_history.get_entries(&id, &name, height, cursor.as_ref(), limit)?, height)
+            }
+            (None, None) => {
+                let (height, entries) = rest.mapping_snapshot(id, name)?;
+                (page_entries(&entries, cursor.as_ref(), limit)?, height)
+            }
+        };
+
+        Ok(ErasedJson::pretty(json!({
+            "entries": entries,
+            "next_cursor": next_cursor.map(|key| key.to_string()),
+            "height": height,
+        })))
+    }
+
+    /// Returns the snapshot of the given mapping at the latest height, which is only read once per block,
+    /// so that the following pages are served from the cursor key without reading the whole mapping again.
+    fn mapping_snapshot(
+        &self,
+        id: ProgramID<N>,
+        name: Identifier<N>,
+    ) -> Result<(u32, Arc<IndexMap<Plaintext<N>, Value<N>>>)> {
+        let height = self.ledger.latest_height();
+        // Return the snapshot, if it is up to date.
+        if let Some((snapshot_height, entries)) = self.mapping_snapshots.lock().get(&(id, name)) {
+            if *snapshot_height == height {
+                return Ok((height, entries.clone()));
+            }
+        }
+        // Read the mapping, and replace its snapshot.
+        let entries =
+            Arc::new(self.ledger.vm().finalize_store().get_mapping_confirmed(id, name)?.into_iter().collect());
+        let mut snapshots = self.mapping_snapshots.lock();
+        snapshots.shift_remove(&(id, name));
+        if snapshots.len() >= MAX_MAPPING_SNAPSHOTS {
+            snapshots.shift_remove_index(0);
+        }
+        snapshots.insert((id, name), (height, Arc::clone(&entries)));
+        Ok((height, entries))
+    }
+
+    /// Returns the mapping history, or an error if the mapping history is disabled.
+    fn mapping_history(&self) -> Result<&Arc<MappingHistory<N>>, RestError> {
+        self.mapping_history
+            .as_ref()
+            .ok_or_else(|| RestError("The mapping history is disabled on this node".to_string()))
+    }
+
     // GET /mainnet/statePath/{commitment}
     pub(crate) async fn get_state_path_for_commitment(
         State(rest): State<Self>,
@@ -355,4 +718,85 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
 
         Ok(ErasedJson::pretty(commitment))
     }
+
+    // GET /metrics
+    #[cfg(feature = "metrics")]
+    pub(crate) async fn get_metrics() -> Result<String, RestError> {
+        metrics::render_metrics().ok_or_else(|| RestError("Metrics are not enabled on this node".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{store::helpers::memory::ConsensusMemory, FromBytes, MainnetV0};
+
+    use aleo_std::StorageMode;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_resolve_block_range() {
+        // Ensure an explicit range is returned as is.
+        assert_eq!(resolve_block_range(10, Some(20), 100, MAX_BLOCK_RANGE).ok(), Some(10..20));
+        // Ensure a missing end height stops at the maximum range, or at the latest block.
+        assert_eq!(resolve_block_range(10, None, 100, MAX_BLOCK_RANGE).ok(), Some(10..60));
+        assert_eq!(resolve_block_range(90, None, 100, MAX_BLOCK_RANGE).ok(), Some(90..101));
+        assert_eq!(resolve_block_range(200, None, 100, MAX_BLOCK_RANGE).ok(), Some(200..200));
+        // Ensure an invalid or unbounded range is rejected.
+        assert!(resolve_block_range(20, Some(10), 100, MAX_BLOCK_RANGE).is_err());
+        assert!(resolve_block_range(0, Some(51), 100, MAX_BLOCK_RANGE).is_err());
+        assert!(resolve_block_range(0, Some(1000), 10_000, BlocksFormat::Bytes.max_range(false)).is_ok());
+        assert!(resolve_block_range(0, Some(1001), 10_000, BlocksFormat::Ndjson.max_range(false)).is_err());
+        // Ensure an explicit range may end at the latest block, but not past it.
+        assert_eq!(resolve_block_range(90, Some(101), 100, MAX_BLOCK_RANGE).ok(), Some(90..101));
+        assert!(resolve_block_range(90, Some(102), 100, MAX_BLOCK_RANGE).is_err());
+        assert!(resolve_block_range(200, Some(200), 100, MAX_BLOCK_RANGE).is_err());
+    }
+
+    #[test]
+    fn test_page_entries() {
+        let entries = (0..5u8).map(|key| (key, key as u64 * 10)).collect::<IndexMap<_, _>>();
+
+        // Ensure the entries are paginated by key.
+        let (page, cursor) = page_entries(&entries, None, 2).unwrap();
+        assert_eq!((page, cursor), (vec![(0, 0), (1, 10)], Some(1)));
+        let (page, cursor) = page_entries(&entries, Some(&1), 2).unwrap();
+        assert_eq!((page, cursor), (vec![(2, 20), (3, 30)], Some(3)));
+        let (page, cursor) = page_entries(&entries, Some(&3), 2).unwrap();
+        assert_eq!((page, cursor), (vec![(4, 40)], None));
+        // Ensure the last page has no cursor, and an unknown cursor is rejected.
+        assert_eq!(page_entries(&entries, Some(&4), 2).unwrap(), (vec![], None));
+        assert!(page_entries(&entries, Some(&9), 2).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stream_blocks() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let ledger =
+            Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis.clone(), StorageMode::Production)
+                .unwrap();
+
+        // Ensure the blocks are streamed as length-prefixed bytes.
+        let response =
+            stream_blocks(ledger.clone(), 0..1, |height, ledger| length_prefixed(&ledger.get_block(height)?));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body[..4], u32::try_from(body.len() - 4).unwrap().to_le_bytes());
+        assert_eq!(Block::<CurrentNetwork>::from_bytes_le(&body[4..]).unwrap(), genesis);
+
+        // Ensure the blocks are streamed as newline-delimited JSON.
+        let response = stream_blocks(ledger.clone(), 0..1, |height, ledger| {
+            let mut line = serde_json::to_vec(&ledger.get_block(height)?)?;
+            line.push(b'\n');
+            Ok(line)
+        });
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let lines = body.split(|byte| *byte == b'\n').filter(|line| !line.is_empty()).collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(serde_json::from_slice::<Block<CurrentNetwork>>(lines[0]).unwrap(), genesis);
+
+        // Ensure the stream fails if a block is missing.
+        let response = stream_blocks(ledger, 0..2, |height, ledger| length_prefixed(&ledger.get_block(height)?));
+        assert!(axum::body::to_bytes(response.into_body(), usize::MAX).await.is_err());
+    }
 }
@@ -0,0 +1,273 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use snarkvm::{
+    prelude::block::{ConfirmedTransaction, Transaction},
+    synthesizer::{
+        process::{deployment_cost, execution_cost},
+        program::FinalizeGlobalState,
+    },
+};
+
+use anyhow::bail;
+use serde::Serialize;
+
+/// The maximum number of transactions that may be simulated concurrently.
+pub(crate) const MAX_CONCURRENT_SIMULATIONS: usize = 2;
+
+/// The verdict of a transaction simulation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulationStatus {
+    /// The transaction would be accepted.
+    Accepted,
+    /// The transaction would be rejected in finalize, and only its fee would be consumed.
+    Rejected,
+    /// The transaction would be aborted, and not included in a block.
+    Aborted,
+    /// The transaction is invalid, and would not be added to the memory pool.
+    Invalid,
+}
+
+/// The fees of a simulated transaction, in microcredits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FeeEstimate {
+    /// The base fee paid by the transaction.
+    pub base_fee: u64,
+    /// The priority fee paid by the transaction.
+    pub priority_fee: u64,
+    /// The minimum base fee required by the transaction (i.e. its storage and finalize cost).
+    pub minimum_base_fee: u64,
+}
+
+impl FeeEstimate {
+    /// Returns the fees of the given transaction.
+    fn new<C: ConsensusStorage<N>, N: Network>(ledger: &Ledger<N, C>, transaction: &Transaction<N>) -> Result<Self> {
+        let minimum_base_fee = match transaction {
+            Transaction::Deploy(_, _, deployment, _) => deployment_cost(deployment)?.0,
+            Transaction::Execute(_, execution, _) => execution_cost(&ledger.vm().process().read(), execution)?.0,
+            Transaction::Fee(..) => 0,
+        };
+        Ok(Self {
+            base_fee: *transaction.base_fee_amount()?,
+            priority_fee: *transaction.priority_fee_amount()?,
+            minimum_base_fee,
+        })
+    }
+}
+
+/// The result of simulating a transaction against the current state of the ledger.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct Simulation<N: Network> {
+    /// The transaction ID.
+    pub transaction_id: N::TransactionID,
+    /// The verdict.
+    pub status: SimulationStatus,
+    /// The approximate reason the transaction would not be accepted, if any.
+    /// Note: The speculation of the VM does not return the error of a rejected transaction,
+    /// so its reason only names the call that failed in finalize, instead of the actual error.
+    pub approximate_reason: Option<String>,
+    /// The fees of the transaction, if they could be determined.
+    pub fee: Option<FeeEstimate>,
+}
+
+impl<N: Network> Simulation<N> {
+    /// Simulates the given transaction, by running the checks of the ledger,
+    /// and speculatively finalizing it on top of the latest block, without committing the state.
+    pub fn new<C: ConsensusStorage<N>>(ledger: &Ledger<N, C>, transaction: &Transaction<N>) -> Result<Self> {
+        let transaction_id = transaction.id();
+        let fee = FeeEstimate::new(ledger, transaction).ok();
+
+        // Run the checks of the ledger, that are performed before the transaction is added to the memory pool.
+        if let Err(error) = ledger.check_transaction_basic(transaction, None, &mut rand::thread_rng()) {
+            return Ok(Self {
+                transaction_id,
+                status: SimulationStatus::Invalid,
+                approximate_reason: Some(error.to_string()),
+                fee,
+            });
+        }
+
+        // Prepare the state of the next block.
+        let latest_block = ledger.latest_block();
+        let state = FinalizeGlobalState::new::<N>(
+            latest_block.round().saturating_add(1),
+            latest_block.height().saturating_add(1),
+            latest_block.cumulative_weight(),
+            latest_block.cumulative_proof_target(),
+            latest_block.hash(),
+        )?;
+        // Speculatively finalize the transaction.
+        let (_, confirmed, aborted, _) = ledger.vm().speculate(state, vec![], None, [transaction].into_iter())?;
+
+        // Determine the verdict.
+        let (status, approximate_reason) = verdict(transaction, &confirmed, aborted)?;
+        Ok(Self { transaction_id, status, approximate_reason, fee })
+    }
+}
+
+/// Returns the verdict and the reason of the given speculation of a transaction.
+fn verdict<N: Network>(
+    transaction: &Transaction<N>,
+    confirmed: &[ConfirmedTransaction<N>],
+    aborted: Vec<(Transaction<N>, String)>,
+) -> Result<(SimulationStatus, Option<String>)> {
+    if let Some((_, reason)) = aborted.into_iter().next() {
+        return Ok((SimulationStatus::Aborted, Some(reason)));
+    }
+    match confirmed.first() {
+        Some(confirmed) if confirmed.is_accepted() => Ok((SimulationStatus::Accepted, None)),
+        Some(_) => Ok((SimulationStatus::Rejected, Some(rejection_reason(transaction)))),
+        None => bail!("The speculation of transaction '{}' returned no verdict", transaction.id()),
+    }
+}
+
+/// Returns the approximate reason the given transaction was rejected in finalize, i.e. the call that failed.
+fn rejection_reason<N: Network>(transaction: &Transaction<N>) -> String {
+    match transaction {
+        Transaction::Deploy(_, _, deployment, _) => {
+            format!(
+                "The deployment of '{}' failed in finalize, and only its fee would be consumed",
+                deployment.program_id()
+            )
+        }
+        Transaction::Execute(_, execution, _) => match execution.peek() {
+            Ok(transition) => format!(
+                "The finalize of '{}/{}' failed, and only its fee would be consumed",
+                transition.program_id(),
+                transition.function_name()
+            ),
+            Err(_) => "The execution failed in finalize, and only its fee would be consumed".to_string(),
+        },
+        Transaction::Fee(..) => "The fee failed in finalize".to_string(),
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    // POST /mainnet/transaction/simulate
+    pub(crate) async fn transaction_simulate(
+        State(rest): State<Self>,
+        Json(tx): Json<Transaction<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        // Do not speculate on the VM of a validator, as it competes with the finalization of the blocks.
+        if rest.consensus.is_some() {
+            return Err(RestError("Transaction simulations are disabled on validators".to_string()));
+        }
+        // Bound the number of concurrent simulations, as each one runs the finalize logic of the transaction.
+        let Ok(permit) = rest.simulations.clone().try_acquire_owned() else {
+            return Err(RestError("Too many transaction simulations are in progress, please retry later".to_string()));
+        };
+
+        // Simulate the transaction, without blocking the runtime.
+        // Note: The transaction is not added to the memory pool, nor propagated.
+        let ledger = rest.ledger.clone();
+        let simulation = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            Simulation::new(&ledger, &tx)
+        })
+        .await
+        .map_err(|error| RestError(format!("Failed to simulate the transaction - {error}")))??;
+        Ok(ErasedJson::pretty(simulation))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{
+        store::{helpers::memory::ConsensusMemory, ConsensusStore},
+        Address,
+        Literal,
+        MainnetV0,
+        PrivateKey,
+        TestRng,
+        Value,
+        U64,
+        VM,
+    };
+
+    use aleo_std::StorageMode;
+
+    type CurrentNetwork = MainnetV0;
+    type CurrentLedger = Ledger<CurrentNetwork, ConsensusMemory<CurrentNetwork>>;
+
+    /// Returns a ledger, whose genesis block funds the given private key.
+    fn sample_ledger(private_key: &PrivateKey<CurrentNetwork>, rng: &mut TestRng) -> CurrentLedger {
+        let store = ConsensusStore::<_, ConsensusMemory<_>>::open(None).unwrap();
+        let genesis = VM::from(store).unwrap().genesis_beacon(private_key, rng).unwrap();
+        CurrentLedger::load(genesis, StorageMode::Production).unwrap()
+    }
+
+    /// Returns a public transfer of the given amount from the given private key.
+    fn sample_transfer(
+        ledger: &CurrentLedger,
+        private_key: &PrivateKey<CurrentNetwork>,
+        amount: u64,
+        rng: &mut TestRng,
+    ) -> Transaction<CurrentNetwork> {
+        let recipient = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let inputs = [Value::from(Literal::Address(recipient)), Value::from(Literal::U64(U64::new(amount)))];
+        let locator = ("credits.aleo", "transfer_public");
+        ledger.vm().execute(private_key, locator, inputs.into_iter(), None, 0, None, rng).unwrap()
+    }
+
+    #[test]
+    fn test_simulate_accepted_and_rejected() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let ledger = sample_ledger(&private_key, rng);
+
+        // Ensure a transfer within the balance is accepted.
+        let transaction = sample_transfer(&ledger, &private_key, 1, rng);
+        let simulation = Simulation::new(&ledger, &transaction).unwrap();
+        assert_eq!(simulation.transaction_id, transaction.id());
+        assert_eq!(simulation.status, SimulationStatus::Accepted);
+        assert_eq!(simulation.approximate_reason, None);
+        let fee = simulation.fee.unwrap();
+        assert!(fee.base_fee >= fee.minimum_base_fee);
+
+        // Ensure a transfer beyond the balance is rejected in finalize.
+        let transaction = sample_transfer(&ledger, &private_key, u64::MAX, rng);
+        let simulation = Simulation::new(&ledger, &transaction).unwrap();
+        assert_eq!(simulation.status, SimulationStatus::Rejected);
+        assert!(simulation.approximate_reason.unwrap().contains("credits.aleo/transfer_public"));
+
+        // Ensure the simulations did not change the state of the ledger.
+        assert_eq!(ledger.latest_height(), 0);
+        assert_eq!(Simulation::new(&ledger, &transaction).unwrap().status, SimulationStatus::Rejected);
+    }
+
+    #[test]
+    fn test_verdict() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let ledger = sample_ledger(&private_key, rng);
+        let transaction = sample_transfer(&ledger, &private_key, 1, rng);
+
+        // Ensure an aborted transaction returns the reason of the abort.
+        let aborted = vec![(transaction.clone(), "The fee could not be paid".to_string())];
+        let (status, reason) = verdict(&transaction, &[], aborted).unwrap();
+        assert_eq!(status, SimulationStatus::Aborted);
+        assert_eq!(reason.as_deref(), Some("The fee could not be paid"));
+
+        // Ensure an accepted transaction has no reason.
+        let confirmed = ConfirmedTransaction::accepted_execute(0, transaction.clone(), vec![]).unwrap();
+        assert_eq!(verdict(&transaction, &[confirmed], vec![]).unwrap(), (SimulationStatus::Accepted, None));
+
+        // Ensure a speculation without a verdict fails.
+        assert!(verdict(&transaction, &[], vec![]).is_err());
+    }
+}
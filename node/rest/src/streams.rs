@@ -0,0 +1,465 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use snarkos_node_consensus::ConsensusEvent;
+use snarkos_node_router::{messages::NodeType, PeerEvent};
+use snarkvm::{
+    ledger::coinbase::ProverSolution,
+    prelude::{
+        block::{Block, ConfirmedTransaction, Transaction},
+        Address,
+    },
+};
+
+use axum::{
+    extract::ws::{self, WebSocket, WebSocketUpgrade},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, convert::Infallible, str::FromStr};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
+    Stream,
+    StreamExt,
+};
+
+/// The maximum number of stream events buffered for each subscriber.
+pub(crate) const MAX_STREAM_EVENTS: usize = 1024;
+
+/// An event that is pushed to the subscribers of the streaming endpoints.
+#[derive(Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case", bound = "")]
+pub enum StreamEvent<N: Network> {
+    /// The ledger advanced to a new block.
+    Block(Block<N>),
+    /// A transaction was confirmed in a new block.
+    ConfirmedTransaction { height: u32, transaction: ConfirmedTransaction<N> },
+    /// An unconfirmed transaction was added to the memory pool.
+    UnconfirmedTransaction(Transaction<N>),
+    /// An unconfirmed solution was added to the memory pool.
+    UnconfirmedSolution(ProverSolution<N>),
+    /// A peer connected to the node.
+    PeerConnected { peer_ip: SocketAddr, address: Address<N>, node_type: NodeType },
+    /// A peer disconnected from the node.
+    PeerDisconnected { peer_ip: SocketAddr },
+}
+
+impl<N: Network> StreamEvent<N> {
+    /// Returns the events for the given block, followed by the events for each of its confirmed transactions.
+    pub fn from_block(block: Block<N>) -> Vec<Self> {
+        // Retrieve the block height.
+        let height = block.height();
+        // Prepare the confirmed transaction events.
+        let transactions = block
+            .transactions()
+            .iter()
+            .map(|transaction| Self::ConfirmedTransaction { height, transaction: transaction.clone() })
+            .collect::<Vec<_>>();
+        // Return the block event, followed by the confirmed transaction events.
+        std::iter::once(Self::Block(block)).chain(transactions).collect()
+    }
+
+    /// Returns the topic of the event.
+    pub const fn topic(&self) -> StreamTopic {
+        match self {
+            Self::Block(..) => StreamTopic::Blocks,
+            Self::ConfirmedTransaction { .. } => StreamTopic::Transactions,
+            Self::UnconfirmedTransaction(..) | Self::UnconfirmedSolution(..) => StreamTopic::MemoryPool,
+            Self::PeerConnected { .. } | Self::PeerDisconnected { .. } => StreamTopic::Peers,
+        }
+    }
+
+    /// Returns the name of the event.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Block(..) => "block",
+            Self::ConfirmedTransaction { .. } => "confirmed_transaction",
+            Self::UnconfirmedTransaction(..) => "unconfirmed_transaction",
+            Self::UnconfirmedSolution(..) => "unconfirmed_solution",
+            Self::PeerConnected { .. } => "peer_connected",
+            Self::PeerDisconnected { .. } => "peer_disconnected",
+        }
+    }
+}
+
+impl<N: Network> From<PeerEvent<N>> for StreamEvent<N> {
+    fn from(event: PeerEvent<N>) -> Self {
+        match event {
+            PeerEvent::Connected { peer_ip, address, node_type } => Self::PeerConnected { peer_ip, address, node_type },
+            PeerEvent::Disconnected { peer_ip } => Self::PeerDisconnected { peer_ip },
+        }
+    }
+}
+
+/// The topics that can be subscribed to on the streaming endpoints.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StreamTopic {
+    /// New blocks.
+    Blocks,
+    /// Transactions confirmed in new blocks.
+    Transactions,
+    /// Transactions and solutions added to the memory pool.
+    MemoryPool,
+    /// Peer connects and disconnects.
+    Peers,
+}
+
+impl FromStr for StreamTopic {
+    type Err = RestError;
+
+    fn from_str(topic: &str) -> Result<Self, Self::Err> {
+        match topic {
+            "blocks" => Ok(Self::Blocks),
+            "transactions" => Ok(Self::Transactions),
+            "mempool" => Ok(Self::MemoryPool),
+            "peers" => Ok(Self::Peers),
+            _ => Err(RestError(format!("Invalid stream topic '{topic}'"))),
+        }
+    }
+}
+
+/// The query object for the streaming endpoints.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct StreamQuery {
+    /// The comma-separated list of topics to subscribe to (default: all topics).
+    topics: Option<String>,
+    /// The program ID to filter the transactions by.
+    program_id: Option<String>,
+    /// The transition ID to filter the transactions by.
+    transition_id: Option<String>,
+}
+
+/// The filter for the streaming endpoints.
+///
+/// The program ID and transition ID filters only apply to events that carry transactions.
+/// A block matches if any of its transactions match.
+#[derive(Clone)]
+pub(crate) struct StreamFilter<N: Network> {
+    /// The subscribed topics.
+    topics: HashSet<StreamTopic>,
+    /// The program ID to filter the transactions by.
+    program_id: Option<ProgramID<N>>,
+    /// The transition ID to filter the transactions by.
+    transition_id: Option<N::TransitionID>,
+}
+
+impl<N: Network> TryFrom<StreamQuery> for StreamFilter<N> {
+    type Error = RestError;
+
+    fn try_from(query: StreamQuery) -> Result<Self, Self::Error> {
+        // Parse the topics, defaulting to all topics.
+        let topics = match query.topics {
+            Some(topics) => topics.split(',').map(|topic| topic.trim().parse()).collect::<Result<HashSet<_>, _>>()?,
+            None => [StreamTopic::Blocks, StreamTopic::Transactions, StreamTopic::MemoryPool, StreamTopic::Peers]
+                .into_iter()
+                .collect(),
+        };
+        // Parse the program ID.
+        let program_id = match query.program_id {
+            Some(program_id) => Some(
+                ProgramID::from_str(&program_id)
+                    .map_err(|_| RestError(format!("Invalid program ID '{program_id}'")))?,
+            ),
+            None => None,
+        };
+        // Parse the transition ID.
+        let transition_id = match query.transition_id {
+            Some(transition_id) => Some(
+                N::TransitionID::from_str(&transition_id)
+                    .map_err(|_| RestError(format!("Invalid transition ID '{transition_id}'")))?,
+            ),
+            None => None,
+        };
+        Ok(Self { topics, program_id, transition_id })
+    }
+}
+
+impl<N: Network> StreamFilter<N> {
+    /// Returns `true` if the given event passes the filter.
+    pub(crate) fn matches(&self, event: &StreamEvent<N>) -> bool {
+        // Ensure the event is for a subscribed topic.
+        if !self.topics.contains(&event.topic()) {
+            return false;
+        }
+        match event {
+            StreamEvent::Block(block) => {
+                (self.program_id.is_none() && self.transition_id.is_none())
+                    || block
+                        .transactions()
+                        .iter()
+                        .any(|transaction| self.matches_transaction(transaction.transaction()))
+            }
+            StreamEvent::ConfirmedTransaction { transaction, .. } => {
+                self.matches_transaction(transaction.transaction())
+            }
+            StreamEvent::UnconfirmedTransaction(transaction) => self.matches_transaction(transaction),
+            StreamEvent::UnconfirmedSolution(..)
+            | StreamEvent::PeerConnected { .. }
+            | StreamEvent::PeerDisconnected { .. } => true,
+        }
+    }
+
+    /// Returns `true` if the given transaction passes the program ID and transition ID filters.
+    fn matches_transaction(&self, transaction: &Transaction<N>) -> bool {
+        // Check the program ID filter.
+        let is_program_match = self.program_id.map_or(true, |program_id| {
+            transaction.transitions().any(|transition| *transition.program_id() == program_id)
+        });
+        // Check the transition ID filter.
+        let is_transition_match = self.transition_id.map_or(true, |transition_id| {
+            transaction.transitions().any(|transition| *transition.id() == transition_id)
+        });
+        is_program_match && is_transition_match
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    /// Spawns the tasks that forward the node notifications to the stream subscribers.
+    pub(crate) fn spawn_stream_forwarders(&self, blocks: Option<broadcast::Receiver<Block<N>>>) {
+        // Forward the peer events from the router.
+        self.spawn_stream_forwarder(self.routing.router().subscribe_peer_events(), |event| {
+            vec![StreamEvent::from(event)]
+        });
+        // Forward the memory pool and block events from consensus.
+        if let Some(consensus) = &self.consensus {
+            self.spawn_stream_forwarder(consensus.subscribe(), |event| match event {
+                ConsensusEvent::UnconfirmedSolution(solution) => vec![StreamEvent::UnconfirmedSolution(solution)],
+                ConsensusEvent::UnconfirmedTransaction(transaction) => {
+                    vec![StreamEvent::UnconfirmedTransaction(transaction)]
+                }
+                ConsensusEvent::Block(block) => StreamEvent::from_block(block),
+            });
+        }
+        // Forward the blocks from the sync module.
+        if let Some(blocks) = blocks {
+            self.spawn_stream_forwarder(blocks, StreamEvent::from_block);
+        }
+    }
+
+    /// Spawns a task that maps each notification from the given receiver into stream events.
+    fn spawn_stream_forwarder<T: Clone + Send + 'static>(
+        &self,
+        mut receiver: broadcast::Receiver<T>,
+        map: impl Fn(T) -> Vec<StreamEvent<N>> + Send + 'static,
+    ) {
+        let streams = self.streams.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(notification) => {
+                        for event in map(notification) {
+                            // Note: This only fails if there are no subscribers, in which case the event is dropped.
+                            let _ = streams.send(event);
+                        }
+                    }
+                    Err(RecvError::Lagged(num_skipped)) => {
+                        warn!("The REST stream forwarder skipped {num_skipped} notifications");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }));
+    }
+
+    // GET /mainnet/stream/sse?topics={topics}&program_id={programID}&transition_id={transitionID}
+    pub(crate) async fn stream_sse(
+        State(rest): State<Self>,
+        Query(query): Query<StreamQuery>,
+    ) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, RestError> {
+        // Parse the filter.
+        let filter = StreamFilter::<N>::try_from(query)?;
+        // Subscribe to the stream events.
+        let stream = BroadcastStream::new(rest.streams.subscribe()).filter_map(move |event| match event {
+            Ok(event) if filter.matches(&event) => match Event::default().event(event.name()).json_data(&event) {
+                Ok(sse_event) => Some(Ok::<_, Infallible>(sse_event)),
+                Err(error) => {
+                    warn!("Failed to serialize the '{}' stream event - {error}", event.name());
+                    None
+                }
+            },
+            Ok(_) => None,
+            Err(BroadcastStreamRecvError::Lagged(num_skipped)) => {
+                warn!("A REST stream subscriber skipped {num_skipped} events");
+                None
+            }
+        });
+        Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+    }
+
+    // GET /mainnet/stream/ws?topics={topics}&program_id={programID}&transition_id={transitionID}
+    pub(crate) async fn stream_ws(
+        State(rest): State<Self>,
+        Query(query): Query<StreamQuery>,
+        upgrade: WebSocketUpgrade,
+    ) -> Result<impl IntoResponse, RestError> {
+        // Parse the filter.
+        let filter = StreamFilter::<N>::try_from(query)?;
+        // Subscribe to the stream events.
+        let receiver = rest.streams.subscribe();
+        // Upgrade the connection to a WebSocket.
+        Ok(upgrade.on_upgrade(move |socket| Self::stream_to_websocket(socket, receiver, filter)))
+    }
+
+    /// Sends the matching stream events to the given WebSocket, until either side closes.
+    async fn stream_to_websocket(
+        mut socket: WebSocket,
+        mut receiver: broadcast::Receiver<StreamEvent<N>>,
+        filter: StreamFilter<N>,
+    ) {
+        loop {
+            tokio::select! {
+                event = receiver.recv() => match event {
+                    Ok(event) => {
+                        // Skip the event if it does not pass the filter.
+                        if !filter.matches(&event) {
+                            continue;
+                        }
+                        // Serialize the event.
+                        let json = match serde_json::to_string(&event) {
+                            Ok(json) => json,
+                            Err(error) => {
+                                warn!("Failed to serialize the '{}' stream event - {error}", event.name());
+                                continue;
+                            }
+                        };
+                        // Send the event, and stop if the client went away.
+                        if socket.send(ws::Message::Text(json)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(num_skipped)) => warn!("A REST stream subscriber skipped {num_skipped} events"),
+                    Err(RecvError::Closed) => break,
+                },
+                message = socket.recv() => match message {
+                    // Stop if the client closed the connection.
+                    Some(Ok(ws::Message::Close(_)) | Err(_)) | None => break,
+                    // Ignore the other messages from the client.
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns the genesis block of the current network.
+    fn sample_genesis_block() -> Block<CurrentNetwork> {
+        Block::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap()
+    }
+
+    /// Returns the stream filter for the given query parameters, if they are valid.
+    fn sample_filter(
+        topics: Option<&str>,
+        program_id: Option<&str>,
+        transition_id: Option<String>,
+    ) -> Option<StreamFilter<CurrentNetwork>> {
+        StreamFilter::try_from(StreamQuery {
+            topics: topics.map(str::to_string),
+            program_id: program_id.map(str::to_string),
+            transition_id,
+        })
+        .ok()
+    }
+
+    #[test]
+    fn test_stream_event_from_block() {
+        let block = sample_genesis_block();
+        let num_transactions = block.transactions().len();
+
+        // Ensure the block event is followed by an event for each of its transactions.
+        let events = StreamEvent::from_block(block);
+        assert_eq!(events.len(), 1 + num_transactions);
+        assert_eq!(events[0].topic(), StreamTopic::Blocks);
+        assert_eq!(events[0].name(), "block");
+        for event in &events[1..] {
+            assert_eq!(event.topic(), StreamTopic::Transactions);
+            assert!(matches!(event, StreamEvent::ConfirmedTransaction { height: 0, .. }));
+        }
+    }
+
+    #[test]
+    fn test_stream_event_serialization() {
+        let event = StreamEvent::<CurrentNetwork>::PeerDisconnected { peer_ip: "127.0.0.1:4130".parse().unwrap() };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json, serde_json::json!({ "type": "peer_disconnected", "data": { "peer_ip": "127.0.0.1:4130" } }));
+    }
+
+    #[test]
+    fn test_stream_filter_topics() {
+        let block = StreamEvent::Block(sample_genesis_block());
+        let peer = StreamEvent::PeerDisconnected { peer_ip: "127.0.0.1:4130".parse().unwrap() };
+
+        // Ensure all topics are subscribed to by default.
+        let filter = sample_filter(None, None, None).unwrap();
+        assert!(filter.matches(&block));
+        assert!(filter.matches(&peer));
+
+        // Ensure only the given topics are subscribed to.
+        let filter = sample_filter(Some("peers, mempool"), None, None).unwrap();
+        assert!(!filter.matches(&block));
+        assert!(filter.matches(&peer));
+
+        // Ensure an unknown topic is rejected.
+        assert!(sample_filter(Some("blocks,unknown"), None, None).is_none());
+    }
+
+    #[test]
+    fn test_stream_filter_program_id() {
+        let block = sample_genesis_block();
+        let events = StreamEvent::from_block(block);
+        let peer = StreamEvent::PeerDisconnected { peer_ip: "127.0.0.1:4130".parse().unwrap() };
+
+        // Ensure the genesis block and its transactions match on `credits.aleo`.
+        let filter = sample_filter(None, Some("credits.aleo"), None).unwrap();
+        assert!(events.iter().all(|event| filter.matches(event)));
+
+        // Ensure nothing in the genesis block matches on another program, but the peer events still pass.
+        let filter = sample_filter(None, Some("hello.aleo"), None).unwrap();
+        assert!(events.iter().all(|event| !filter.matches(event)));
+        assert!(filter.matches(&peer));
+
+        // Ensure an invalid program ID is rejected.
+        assert!(sample_filter(None, Some("credits"), None).is_none());
+    }
+
+    #[test]
+    fn test_stream_filter_transition_id() {
+        let block = sample_genesis_block();
+        // Select a transition ID from the first transaction.
+        let transaction = block.transactions().iter().next().unwrap().transaction().clone();
+        let transition_id = *transaction.transitions().next().unwrap().id();
+
+        // Ensure only the transaction with the transition, and its block, match.
+        let filter = sample_filter(None, None, Some(transition_id.to_string())).unwrap();
+        let events = StreamEvent::from_block(block);
+        assert!(filter.matches(&events[0]));
+        assert!(filter.matches(&events[1]));
+        assert!(events[2..].iter().all(|event| !filter.matches(event)));
+        assert!(filter.matches(&StreamEvent::UnconfirmedTransaction(transaction)));
+
+        // Ensure an invalid transition ID is rejected.
+        assert!(sample_filter(None, None, Some("au1invalid".to_string())).is_none());
+    }
+}
@@ -20,10 +20,10 @@ use core::marker::PhantomData;
 use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
 
 /// The maximum size of a message that can be transmitted during the handshake.
-const MAXIMUM_HANDSHAKE_MESSAGE_SIZE: usize = 1024 * 1024; // 1 MiB
+pub const MAXIMUM_HANDSHAKE_MESSAGE_SIZE: usize = 1024 * 1024; // 1 MiB
 
 /// The maximum size of a message that can be transmitted in the network.
-pub(crate) const MAXIMUM_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
+pub const MAXIMUM_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
 
 /// The codec used to decode and encode network `Message`s.
 pub struct MessageCodec<N: Network> {
@@ -52,6 +52,10 @@ impl<N: Network> Encoder<Message<N>> for MessageCodec<N> {
     type Error = std::io::Error;
 
     fn encode(&mut self, message: Message<N>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        // Retrieve the message type, to label the metrics.
+        #[cfg(feature = "metrics")]
+        let message_type = message.type_name();
+
         // Serialize the payload directly into dst.
         message
             .write_le(&mut dst.writer())
@@ -60,6 +64,17 @@ impl<N: Network> Encoder<Message<N>> for MessageCodec<N> {
 
         let serialized_message = dst.split_to(dst.len()).freeze();
 
+        #[cfg(feature = "metrics")]
+        {
+            let labels = [("type", message_type.to_string())];
+            metrics::increment_counter_with_labels(metrics::router::MESSAGES_OUTBOUND, 1, &labels);
+            metrics::increment_counter_with_labels(
+                metrics::router::MESSAGES_OUTBOUND_BYTES,
+                serialized_message.len() as u64,
+                &labels,
+            );
+        }
+
         self.codec.encode(serialized_message, dst)
     }
 }
@@ -75,10 +90,26 @@ impl<N: Network> Decoder for MessageCodec<N> {
             None => return Ok(None),
         };
 
+        // Retrieve the number of bytes, to record the metrics.
+        #[cfg(feature = "metrics")]
+        let num_bytes = bytes.len();
+
         // Convert the bytes to a message, or fail if it is not valid.
         let reader = bytes.reader();
         match Message::read_le(reader) {
-            Ok(message) => Ok(Some(message)),
+            Ok(message) => {
+                #[cfg(feature = "metrics")]
+                {
+                    let labels = [("type", message.type_name().to_string())];
+                    metrics::increment_counter_with_labels(metrics::router::MESSAGES_INBOUND, 1, &labels);
+                    metrics::increment_counter_with_labels(
+                        metrics::router::MESSAGES_INBOUND_BYTES,
+                        num_bytes as u64,
+                        &labels,
+                    );
+                }
+                Ok(Some(message))
+            }
             Err(error) => {
                 warn!("Failed to deserialize a message - {}", error);
                 Err(std::io::ErrorKind::InvalidData.into())
@@ -111,7 +111,7 @@ impl<N: Network> From<DisconnectReason> for Message<N> {
 
 impl<N: Network> Message<N> {
     /// The version of the network protocol; it can be incremented in order to force users to update.
-    pub const VERSION: u32 = 14;
+    pub const VERSION: u32 = 15;
 
     /// Returns the message name.
     #[inline]
@@ -133,6 +133,26 @@ impl<N: Network> Message<N> {
         }
     }
 
+    /// Returns the message type name, without any message-specific details.
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::BlockRequest(..) => "BlockRequest",
+            Self::BlockResponse(..) => "BlockResponse",
+            Self::ChallengeRequest(..) => "ChallengeRequest",
+            Self::ChallengeResponse(..) => "ChallengeResponse",
+            Self::Disconnect(..) => "Disconnect",
+            Self::PeerRequest(..) => "PeerRequest",
+            Self::PeerResponse(..) => "PeerResponse",
+            Self::Ping(..) => "Ping",
+            Self::Pong(..) => "Pong",
+            Self::PuzzleRequest(..) => "PuzzleRequest",
+            Self::PuzzleResponse(..) => "PuzzleResponse",
+            Self::UnconfirmedSolution(..) => "UnconfirmedSolution",
+            Self::UnconfirmedTransaction(..) => "UnconfirmedTransaction",
+        }
+    }
+
     /// Returns the message ID.
     #[inline]
     pub fn id(&self) -> u16 {
@@ -13,11 +13,20 @@
 // limitations under the License.
 
 use crate::{
-    messages::{ChallengeRequest, ChallengeResponse, DisconnectReason, Message, MessageCodec, MessageTrait},
+    messages::{
+        ChallengeRequest,
+        ChallengeResponse,
+        DisconnectReason,
+        Message,
+        MessageCodec,
+        MessageTrait,
+        MAXIMUM_HANDSHAKE_MESSAGE_SIZE,
+        MAXIMUM_MESSAGE_SIZE,
+    },
     Peer,
     Router,
 };
-use snarkos_node_tcp::{ConnectionSide, Tcp, P2P};
+use snarkos_node_tcp::{noise_handshake, ConnectionSide, NoiseCodec, NoiseState, Tcp, P2P};
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{block::Header, error, Address, Network},
@@ -69,9 +78,15 @@ macro_rules! expect_message {
     };
 }
 
+/// Returns the data signed in a challenge response, from the given nonces and the handshake hash of the encrypted connection.
+/// Note: The handshake hash binds the Aleo address of the signer to the encrypted connection.
+pub fn challenge_data(request_nonce: u64, response_nonce: u64, handshake_hash: &[u8]) -> Vec<u8> {
+    [&request_nonce.to_le_bytes()[..], &response_nonce.to_le_bytes(), handshake_hash].concat()
+}
+
 /// Send the given message to the peer.
 async fn send<N: Network>(
-    framed: &mut Framed<&mut TcpStream, MessageCodec<N>>,
+    framed: &mut Framed<&mut TcpStream, NoiseCodec<MessageCodec<N>>>,
     peer_addr: SocketAddr,
     message: Message<N>,
 ) -> io::Result<()> {
@@ -87,7 +102,7 @@ impl<N: Network> Router<N> {
         stream: &'a mut TcpStream,
         peer_side: ConnectionSide,
         genesis_header: Header<N>,
-    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
+    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, NoiseCodec<MessageCodec<N>>>)> {
         // If this is an inbound connection, we log it, but don't know the listening address yet.
         // Otherwise, we can immediately register the listening address.
         let mut peer_ip = if peer_side == ConnectionSide::Initiator {
@@ -110,8 +125,11 @@ impl<N: Network> Router<N> {
             self.connecting_peers.lock().remove(&ip);
         }
 
-        // If the handshake succeeded, announce it.
-        if let Ok((ref peer_ip, _)) = handshake_result {
+        // If the handshake succeeded, store the state of the encrypted connection for its codecs, and announce it.
+        if let Ok((ref peer_ip, ref framed)) = handshake_result {
+            if let Some(noise_state) = framed.codec().state() {
+                self.tcp.noise_states().insert(peer_addr, noise_state.clone());
+            }
             info!("Connected to '{peer_ip}'");
         }
 
@@ -125,11 +143,13 @@ impl<N: Network> Router<N> {
         peer_ip: &mut Option<SocketAddr>,
         stream: &'a mut TcpStream,
         genesis_header: Header<N>,
-    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
+    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, NoiseCodec<MessageCodec<N>>>)> {
         // This value is immediately guaranteed to be present, so it can be unwrapped.
         let peer_ip = peer_ip.unwrap();
+        // Perform the Noise handshake, to encrypt the connection.
+        let (noise_state, handshake_hash) = noise_handshake(stream, ConnectionSide::Initiator).await?;
         // Construct the stream.
-        let mut framed = Framed::new(stream, MessageCodec::<N>::handshake());
+        let mut framed = Framed::new(stream, Self::handshake_codec(noise_state));
 
         // Initialize an RNG.
         let rng = &mut OsRng;
@@ -151,7 +171,14 @@ impl<N: Network> Router<N> {
 
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self
-            .verify_challenge_response(peer_addr, peer_request.address, peer_response, genesis_header, our_nonce)
+            .verify_challenge_response(
+                peer_addr,
+                peer_request.address,
+                peer_response,
+                genesis_header,
+                our_nonce,
+                &handshake_hash,
+            )
             .await
         {
             send(&mut framed, peer_addr, reason.into()).await?;
@@ -165,7 +192,7 @@ impl<N: Network> Router<N> {
         /* Step 3: Send the challenge response. */
 
         let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+        let data = challenge_data(peer_request.nonce, response_nonce, &handshake_hash);
         // Sign the counterparty nonce.
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
@@ -188,9 +215,11 @@ impl<N: Network> Router<N> {
         peer_ip: &mut Option<SocketAddr>,
         stream: &'a mut TcpStream,
         genesis_header: Header<N>,
-    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, MessageCodec<N>>)> {
+    ) -> io::Result<(SocketAddr, Framed<&mut TcpStream, NoiseCodec<MessageCodec<N>>>)> {
+        // Perform the Noise handshake, to encrypt the connection.
+        let (noise_state, handshake_hash) = noise_handshake(stream, ConnectionSide::Responder).await?;
         // Construct the stream.
-        let mut framed = Framed::new(stream, MessageCodec::<N>::handshake());
+        let mut framed = Framed::new(stream, Self::handshake_codec(noise_state));
 
         /* Step 1: Receive the challenge request. */
 
@@ -217,7 +246,7 @@ impl<N: Network> Router<N> {
 
         // Sign the counterparty nonce.
         let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+        let data = challenge_data(peer_request.nonce, response_nonce, &handshake_hash);
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
@@ -238,7 +267,14 @@ impl<N: Network> Router<N> {
         let peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self
-            .verify_challenge_response(peer_addr, peer_request.address, peer_response, genesis_header, our_nonce)
+            .verify_challenge_response(
+                peer_addr,
+                peer_request.address,
+                peer_response,
+                genesis_header,
+                our_nonce,
+                &handshake_hash,
+            )
             .await
         {
             send(&mut framed, peer_addr, reason.into()).await?;
@@ -250,6 +286,16 @@ impl<N: Network> Router<N> {
         Ok((peer_ip, framed))
     }
 
+    /// Returns the codec of the encrypted connection with the given peer address, once the handshake is complete.
+    pub fn codec(&self, peer_addr: SocketAddr) -> NoiseCodec<MessageCodec<N>> {
+        NoiseCodec::new(MessageCodec::default(), self.tcp.noise_states().get(peer_addr), MAXIMUM_MESSAGE_SIZE)
+    }
+
+    /// Returns the codec of the encrypted connection during the handshake.
+    fn handshake_codec(noise_state: NoiseState) -> NoiseCodec<MessageCodec<N>> {
+        NoiseCodec::new(MessageCodec::handshake(), Some(noise_state), MAXIMUM_HANDSHAKE_MESSAGE_SIZE)
+    }
+
     /// Ensure the peer is allowed to connect.
     fn ensure_peer_is_allowed(&self, peer_ip: SocketAddr) -> Result<()> {
         // Ensure the peer IP is not this node.
@@ -271,9 +317,10 @@ impl<N: Network> Router<N> {
         // Ensure the peer is not spamming connection attempts.
         if !peer_ip.ip().is_loopback() {
             // Add this connection attempt and retrieve the number of attempts.
-            let num_attempts = self.cache.insert_inbound_connection(peer_ip.ip(), Self::RADIO_SILENCE_IN_SECS as i64);
+            let num_attempts =
+                self.cache.insert_inbound_connection(peer_ip.ip(), self.config.radio_silence_in_secs as i64);
             // Ensure the connecting peer has not surpassed the connection attempt limit.
-            if num_attempts > Self::MAXIMUM_CONNECTION_FAILURES {
+            if num_attempts > self.config.maximum_connection_failures {
                 // Restrict the peer.
                 self.insert_restricted_peer(peer_ip);
                 bail!("Dropping connection request from '{peer_ip}' (tried {num_attempts} times)")
@@ -307,6 +354,7 @@ impl<N: Network> Router<N> {
         response: ChallengeResponse<N>,
         expected_genesis_header: Header<N>,
         expected_nonce: u64,
+        handshake_hash: &[u8],
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge response.
         let ChallengeResponse { genesis_header, signature, nonce } = response;
@@ -322,7 +370,7 @@ impl<N: Network> Router<N> {
             return Some(DisconnectReason::InvalidChallengeResponse);
         };
         // Verify the signature.
-        if !signature.verify_bytes(&peer_address, &[expected_nonce.to_le_bytes(), nonce.to_le_bytes()].concat()) {
+        if !signature.verify_bytes(&peer_address, &challenge_data(expected_nonce, nonce, handshake_hash)) {
             warn!("Handshake with '{peer_addr}' failed (invalid signature)");
             return Some(DisconnectReason::InvalidChallengeResponse);
         }
@@ -15,37 +15,19 @@
 use crate::{
     messages::{DisconnectReason, Message, PeerRequest},
     Outbound,
-    Router,
 };
 use snarkvm::prelude::Network;
 
 use colored::Colorize;
-use rand::{prelude::IteratorRandom, rngs::OsRng};
-
-/// A helper function to compute the maximum of two numbers.
-/// See Rust issue 92391: https://github.com/rust-lang/rust/issues/92391.
-pub const fn max(a: usize, b: usize) -> usize {
-    match a > b {
-        true => a,
-        false => b,
-    }
-}
+use rand::{
+    prelude::{IteratorRandom, SliceRandom},
+    rngs::OsRng,
+};
 
+/// Note: The peer limits and the heartbeat policy are read from the [`RouterConfig`](crate::RouterConfig).
 pub trait Heartbeat<N: Network>: Outbound<N> {
-    /// The duration in seconds to sleep in between heartbeat executions.
-    const HEARTBEAT_IN_SECS: u64 = 25; // 25 seconds
-    /// The minimum number of peers required to maintain connections with.
-    const MINIMUM_NUMBER_OF_PEERS: usize = 3;
-    /// The median number of peers to maintain connections with.
-    const MEDIAN_NUMBER_OF_PEERS: usize = max(Self::MAXIMUM_NUMBER_OF_PEERS / 2, Self::MINIMUM_NUMBER_OF_PEERS);
-    /// The maximum number of peers permitted to maintain connections with.
-    const MAXIMUM_NUMBER_OF_PEERS: usize = 21;
-    /// The maximum number of provers to maintain connections with.
-    const MAXIMUM_NUMBER_OF_PROVERS: usize = Self::MAXIMUM_NUMBER_OF_PEERS / 4;
-
     /// Handles the heartbeat request.
     fn heartbeat(&self) {
-        self.safety_check_minimum_number_of_peers();
         self.log_connected_peers();
 
         // Remove any stale connected peers.
@@ -60,17 +42,8 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         self.handle_trusted_peers();
         // Keep the puzzle request up to date.
         self.handle_puzzle_request();
-    }
-
-    /// TODO (howardwu): Consider checking minimum number of validators, to exclude clients and provers.
-    /// This function performs safety checks on the setting for the minimum number of peers.
-    fn safety_check_minimum_number_of_peers(&self) {
-        // Perform basic sanity checks on the configuration for the number of peers.
-        assert!(Self::MINIMUM_NUMBER_OF_PEERS >= 1, "The minimum number of peers must be at least 1.");
-        assert!(Self::MINIMUM_NUMBER_OF_PEERS <= Self::MAXIMUM_NUMBER_OF_PEERS);
-        assert!(Self::MINIMUM_NUMBER_OF_PEERS <= Self::MEDIAN_NUMBER_OF_PEERS);
-        assert!(Self::MEDIAN_NUMBER_OF_PEERS <= Self::MAXIMUM_NUMBER_OF_PEERS);
-        assert!(Self::MAXIMUM_NUMBER_OF_PROVERS <= Self::MAXIMUM_NUMBER_OF_PEERS);
+        // Persist the peer book.
+        self.handle_peer_book();
     }
 
     /// This function logs the connected peers.
@@ -91,7 +64,7 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         for peer in self.router().get_connected_peers() {
             // Disconnect if the peer has not communicated back within the predefined time.
             let elapsed = peer.last_seen().elapsed().as_secs();
-            if elapsed > Router::<N>::RADIO_SILENCE_IN_SECS {
+            if elapsed > self.router().config().radio_silence_in_secs {
                 warn!("Peer {} has not communicated in {elapsed} seconds", peer.ip());
                 // Disconnect from this peer.
                 self.router().disconnect(peer.ip());
@@ -103,7 +76,7 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
     /// This function only triggers if the router is above the minimum number of connected peers.
     fn remove_oldest_connected_peer(&self) {
         // Skip if the router is at or below the minimum number of connected peers.
-        if self.router().number_of_connected_peers() <= Self::MINIMUM_NUMBER_OF_PEERS {
+        if self.router().number_of_connected_peers() <= self.router().config().minimum_number_of_peers {
             return;
         }
 
@@ -136,12 +109,13 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         // Obtain the number of connected peers.
         let num_connected = self.router().number_of_connected_peers();
         // Compute the total number of surplus peers.
-        let num_surplus_peers = num_connected.saturating_sub(Self::MAXIMUM_NUMBER_OF_PEERS);
+        let num_surplus_peers = num_connected.saturating_sub(self.router().config().maximum_number_of_peers);
 
         // Obtain the number of connected provers.
         let num_connected_provers = self.router().number_of_connected_provers();
         // Compute the number of surplus provers.
-        let num_surplus_provers = num_connected_provers.saturating_sub(Self::MAXIMUM_NUMBER_OF_PROVERS);
+        let num_surplus_provers =
+            num_connected_provers.saturating_sub(self.router().config().maximum_number_of_provers);
         // Compute the number of provers remaining connected.
         let num_remaining_provers = num_connected_provers.saturating_sub(num_surplus_provers);
         // Compute the number of surplus clients and validators.
@@ -160,18 +134,20 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
             // Initialize an RNG.
             let rng = &mut OsRng;
 
-            // Determine the provers to disconnect from.
-            let prover_ips_to_disconnect = self
+            // Determine the provers to disconnect from, starting with the lowest-scoring provers.
+            let mut prover_ips = self
                 .router()
                 .connected_provers()
                 .into_iter()
                 .filter(|peer_ip| !trusted.contains(peer_ip) && !bootstrap.contains(peer_ip))
-                .choose_multiple(rng, num_surplus_provers);
+                .collect::<Vec<_>>();
+            // Shuffle the provers, so that ties in the score are broken at random.
+            prover_ips.shuffle(rng);
+            let prover_ips_to_disconnect = self.router().peer_book().worst_peers(prover_ips, num_surplus_provers);
 
             // TODO (howardwu): As a validator, prioritize disconnecting from clients.
-            //  Remove RNG, pick the `n` oldest nodes.
-            // Determine the clients and validators to disconnect from.
-            let peer_ips_to_disconnect = self
+            // Determine the clients and validators to disconnect from, starting with the lowest-scoring peers.
+            let mut peer_ips = self
                 .router()
                 .get_connected_peers()
                 .into_iter()
@@ -183,7 +159,11 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
                         None
                     }
                 })
-                .choose_multiple(rng, num_surplus_clients_validators);
+                .collect::<Vec<_>>();
+            // Shuffle the peers, so that ties in the score are broken at random.
+            peer_ips.shuffle(rng);
+            let peer_ips_to_disconnect =
+                self.router().peer_book().worst_peers(peer_ips, num_surplus_clients_validators);
 
             // Proceed to send disconnect requests to these peers.
             for peer_ip in peer_ips_to_disconnect.into_iter().chain(prover_ips_to_disconnect) {
@@ -206,14 +186,17 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         // Obtain the number of connected peers.
         let num_connected = self.router().number_of_connected_peers();
         // Compute the number of deficit peers.
-        let num_deficient = Self::MEDIAN_NUMBER_OF_PEERS.saturating_sub(num_connected);
+        let num_deficient = self.router().config().median_number_of_peers().saturating_sub(num_connected);
 
         if num_deficient > 0 {
             // Initialize an RNG.
             let rng = &mut OsRng;
 
-            // Attempt to connect to more peers.
-            for peer_ip in self.router().candidate_peers().into_iter().choose_multiple(rng, num_deficient) {
+            // Retrieve the candidate peers, shuffled so that ties in the score are broken at random.
+            let mut candidate_peers = self.router().candidate_peers().into_iter().collect::<Vec<_>>();
+            candidate_peers.shuffle(rng);
+            // Attempt to connect to the highest-scoring candidate peers.
+            for peer_ip in self.router().peer_book().best_peers(candidate_peers, num_deficient) {
                 self.router().connect(peer_ip);
             }
             // Request more peers from the connected peers.
@@ -263,9 +246,9 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         // Ensure that the trusted nodes are connected.
         for peer_ip in self.router().trusted_peers() {
             // If the peer is not connected, attempt to connect to it.
-            if !self.router().is_connected(peer_ip) {
+            if !self.router().is_connected(&peer_ip) {
                 // Attempt to connect to the trusted peer.
-                self.router().connect(*peer_ip);
+                self.router().connect(peer_ip);
             }
         }
     }
@@ -274,4 +257,15 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
     fn handle_puzzle_request(&self) {
         // No-op
     }
+
+    /// This function persists the peer book, if persistence is enabled.
+    fn handle_peer_book(&self) {
+        // Save the peer book without blocking the runtime.
+        let router = self.router().clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(error) = router.save_peer_book() {
+                warn!("Unable to save the peer book - {error}");
+            }
+        });
+    }
 }
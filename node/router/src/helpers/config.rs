@@ -0,0 +1,142 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::NodeType;
+
+use anyhow::{ensure, Result};
+
+/// The configuration of the peer limits and the heartbeat policy of the router.
+/// See the source of [`RouterConfig::default`] for the defaults.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouterConfig {
+    /// The duration in seconds to sleep in between heartbeat executions.
+    pub heartbeat_in_secs: u64,
+    /// The minimum number of peers required to maintain connections with.
+    pub minimum_number_of_peers: usize,
+    /// The maximum number of peers permitted to maintain connections with.
+    pub maximum_number_of_peers: usize,
+    /// The maximum number of provers to maintain connections with.
+    pub maximum_number_of_provers: usize,
+    /// The duration in seconds after which a connected peer is considered inactive or
+    /// disconnected if no message has been received in the meantime.
+    pub radio_silence_in_secs: u64,
+    /// The maximum number of connection failures permitted by an inbound connecting peer.
+    pub maximum_connection_failures: usize,
+    /// The maximum number of messages accepted from a peer within `message_limit_time_frame_in_secs`.
+    pub message_limit: usize,
+    /// The time frame in seconds to enforce the `message_limit`.
+    pub message_limit_time_frame_in_secs: u64,
+}
+
+impl RouterConfig {
+    /// The default maximum number of peers of a validator.
+    pub const VALIDATOR_MAXIMUM_NUMBER_OF_PEERS: usize = 200;
+
+    /// Initializes a new router configuration with the given maximum number of peers, and the default values.
+    /// The minimum number of peers is lowered to the maximum, and the maximum number of provers is a quarter
+    /// of the maximum number of peers.
+    pub fn new(maximum_number_of_peers: usize) -> Self {
+        let default = Self::default();
+        Self {
+            minimum_number_of_peers: default.minimum_number_of_peers.min(maximum_number_of_peers.max(1)),
+            maximum_number_of_peers,
+            maximum_number_of_provers: maximum_number_of_peers / 4,
+            ..default
+        }
+    }
+
+    /// Initializes a new router configuration with the default values for the given node type.
+    pub fn for_node_type(node_type: NodeType) -> Self {
+        match node_type {
+            NodeType::Validator => Self::new(Self::VALIDATOR_MAXIMUM_NUMBER_OF_PEERS),
+            NodeType::Prover | NodeType::Client => Self::default(),
+        }
+    }
+
+    /// Returns the median number of peers to maintain connections with.
+    pub fn median_number_of_peers(&self) -> usize {
+        (self.maximum_number_of_peers / 2).max(self.minimum_number_of_peers)
+    }
+
+    /// Ensures the configuration is consistent.
+    pub fn check(&self) -> Result<()> {
+        ensure!(self.heartbeat_in_secs >= 1, "The heartbeat interval must be at least 1 second");
+        ensure!(self.minimum_number_of_peers >= 1, "The minimum number of peers must be at least 1");
+        ensure!(
+            self.minimum_number_of_peers <= self.maximum_number_of_peers,
+            "The minimum number of peers ({}) must not exceed the maximum number of peers ({})",
+            self.minimum_number_of_peers,
+            self.maximum_number_of_peers
+        );
+        ensure!(
+            self.maximum_number_of_peers <= u16::MAX as usize,
+            "The maximum number of peers must not exceed {}",
+            u16::MAX
+        );
+        ensure!(
+            self.maximum_number_of_provers <= self.maximum_number_of_peers,
+            "The maximum number of provers ({}) must not exceed the maximum number of peers ({})",
+            self.maximum_number_of_provers,
+            self.maximum_number_of_peers
+        );
+        ensure!(self.maximum_connection_failures >= 1, "The maximum number of connection failures must be at least 1");
+        ensure!(self.radio_silence_in_secs >= 1, "The radio silence duration must be at least 1 second");
+        ensure!(self.message_limit >= 1, "The message limit must be at least 1");
+        ensure!(self.message_limit_time_frame_in_secs >= 1, "The message limit time frame must be at least 1 second");
+        Ok(())
+    }
+}
+
+impl Default for RouterConfig {
+    /// Initializes a new router configuration with the default values.
+    fn default() -> Self {
+        Self {
+            heartbeat_in_secs: 25,
+            minimum_number_of_peers: 3,
+            maximum_number_of_peers: 21,
+            maximum_number_of_provers: 21 / 4,
+            radio_silence_in_secs: 150, // 2.5 minutes
+            maximum_connection_failures: 5,
+            message_limit: 500,
+            message_limit_time_frame_in_secs: 5,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        // Ensure the defaults are consistent.
+        RouterConfig::default().check().unwrap();
+        RouterConfig::for_node_type(NodeType::Validator).check().unwrap();
+        assert_eq!(RouterConfig::default().median_number_of_peers(), 10);
+
+        // Ensure a small maximum lowers the minimum number of peers.
+        let config = RouterConfig::new(1);
+        config.check().unwrap();
+        assert_eq!(config.minimum_number_of_peers, 1);
+        assert_eq!(config.maximum_number_of_provers, 0);
+
+        // Ensure the inconsistent configurations are rejected.
+        assert!(RouterConfig { minimum_number_of_peers: 0, ..Default::default() }.check().is_err());
+        assert!(RouterConfig { minimum_number_of_peers: 22, ..Default::default() }.check().is_err());
+        assert!(RouterConfig { maximum_number_of_provers: 22, ..Default::default() }.check().is_err());
+        assert!(RouterConfig { maximum_number_of_peers: 100_000, ..Default::default() }.check().is_err());
+        assert!(RouterConfig { heartbeat_in_secs: 0, ..Default::default() }.check().is_err());
+        assert!(RouterConfig { maximum_connection_failures: 0, ..Default::default() }.check().is_err());
+    }
+}
@@ -15,8 +15,17 @@
 mod cache;
 pub use cache::Cache;
 
+mod config;
+pub use config::*;
+
 mod peer;
 pub use peer::*;
 
+mod peer_event;
+pub use peer_event::*;
+
+mod peer_rules;
+pub use peer_rules::*;
+
 mod resolver;
 pub use resolver::*;
@@ -0,0 +1,39 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::NodeType;
+use snarkvm::prelude::{Address, Network};
+
+use std::net::SocketAddr;
+
+/// The maximum number of peer events buffered for each subscriber.
+pub const MAX_PEER_EVENTS: usize = 256;
+
+/// A notification for a change in the connected peers of the router.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PeerEvent<N: Network> {
+    /// The peer completed the handshake and is now connected.
+    Connected { peer_ip: SocketAddr, address: Address<N>, node_type: NodeType },
+    /// The peer was disconnected.
+    Disconnected { peer_ip: SocketAddr },
+}
+
+impl<N: Network> PeerEvent<N> {
+    /// Returns the IP address of the peer.
+    pub const fn peer_ip(&self) -> SocketAddr {
+        match self {
+            Self::Connected { peer_ip, .. } | Self::Disconnected { peer_ip } => *peer_ip,
+        }
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub use snarkos_node_tcp::BannedIp;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    net::{IpAddr, SocketAddr},
+    path::Path,
+};
+
+/// The file name of the persisted peer rules.
+pub const PEER_RULES_FILE_NAME: &str = "peer-rules.json";
+
+/// The administrative changes to the peers of the router, which may be persisted to survive a restart.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PeerRules {
+    /// The trusted peers.
+    pub trusted_peers: Vec<SocketAddr>,
+    /// The banned IPs.
+    pub banned_ips: Vec<BannedIp>,
+}
+
+impl PeerRules {
+    /// Loads the peer rules from the given path, returning the default rules if the file does not exist.
+    /// If the file is corrupt, it is kept aside with a `.bak` extension, and the default rules are returned.
+    pub fn load(path: &Path) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+        match std::fs::read(path)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|e| e.to_string()))
+        {
+            Ok(rules) => rules,
+            Err(error) => {
+                // Keep the corrupt file, so that its rules may be recovered by hand.
+                let backup_path = path.with_extension("json.bak");
+                warn!(
+                    "Discarding the peer rules at '{}' (kept at '{}') - {error}",
+                    path.display(),
+                    backup_path.display()
+                );
+                if let Err(error) = std::fs::rename(path, &backup_path) {
+                    warn!("Unable to keep the peer rules at '{}' - {error}", backup_path.display());
+                }
+                Self::default()
+            }
+        }
+    }
+
+    /// Inserts the given trusted peer, if it is not already trusted.
+    pub fn insert_trusted_peer(&mut self, peer_ip: SocketAddr) {
+        if !self.trusted_peers.contains(&peer_ip) {
+            self.trusted_peers.push(peer_ip);
+        }
+    }
+
+    /// Removes the given trusted peer.
+    pub fn remove_trusted_peer(&mut self, peer_ip: SocketAddr) {
+        self.trusted_peers.retain(|trusted_ip| *trusted_ip != peer_ip);
+    }
+
+    /// Inserts the given ban, replacing the existing ban on the same IP.
+    pub fn insert_banned_ip(&mut self, banned: BannedIp) {
+        self.remove_banned_ip(banned.ip);
+        self.banned_ips.push(banned);
+    }
+
+    /// Removes the ban on the given IP.
+    pub fn remove_banned_ip(&mut self, ip: IpAddr) {
+        self.banned_ips.retain(|banned| banned.ip != ip);
+    }
+
+    /// Saves the peer rules to the given path.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        // Ensure the parent directory exists.
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Write to a temporary file first, so that a crash does not leave a partially-written file behind.
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(temp_path, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_update() {
+        let peer_ip = SocketAddr::from(([1, 2, 3, 4], 4130));
+        let ip = IpAddr::from([5, 6, 7, 8]);
+        let mut rules = PeerRules::default();
+
+        // Ensure the trusted peers are not duplicated.
+        rules.insert_trusted_peer(peer_ip);
+        rules.insert_trusted_peer(peer_ip);
+        assert_eq!(rules.trusted_peers, vec![peer_ip]);
+        rules.remove_trusted_peer(peer_ip);
+        assert!(rules.trusted_peers.is_empty());
+
+        // Ensure a new ban replaces the existing ban on the same IP.
+        rules.insert_banned_ip(BannedIp::new(ip, Some(Duration::from_secs(60))));
+        rules.insert_banned_ip(BannedIp::new(ip, None));
+        assert_eq!(rules.banned_ips, vec![BannedIp::new(ip, None)]);
+        rules.remove_banned_ip(ip);
+        assert!(rules.banned_ips.is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path =
+            std::env::temp_dir().join(format!("snarkos-test-{}", rand::random::<u64>())).join(PEER_RULES_FILE_NAME);
+
+        // Ensure a missing file loads the default rules.
+        assert_eq!(PeerRules::load(&path), PeerRules::default());
+
+        // Save and reload the rules.
+        let rules = PeerRules {
+            trusted_peers: vec!["1.2.3.4:4130".parse().unwrap()],
+            banned_ips: vec![BannedIp::new(IpAddr::from([5, 6, 7, 8]), None)],
+        };
+        rules.save(&path).unwrap();
+        assert_eq!(PeerRules::load(&path), rules);
+
+        // Ensure a corrupt file loads the default rules, and is kept aside.
+        std::fs::write(&path, b"{ corrupt").unwrap();
+        assert_eq!(PeerRules::load(&path), PeerRules::default());
+        assert!(!path.exists());
+        assert_eq!(std::fs::read(path.with_extension("json.bak")).unwrap(), b"{ corrupt");
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -48,10 +48,6 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
     const MAXIMUM_PUZZLE_REQUESTS_PER_INTERVAL: usize = 5;
     /// The duration in seconds to sleep in between ping requests with a connected peer.
     const PING_SLEEP_IN_SECS: u64 = 20; // 20 seconds
-    /// The time frame to enforce the `MESSAGE_LIMIT`.
-    const MESSAGE_LIMIT_TIME_FRAME_IN_SECS: i64 = 5;
-    /// The maximum number of messages accepted within `MESSAGE_LIMIT_TIME_FRAME_IN_SECS`.
-    const MESSAGE_LIMIT: usize = 500;
 
     /// Handles the inbound message from the peer.
     async fn inbound(&self, peer_addr: SocketAddr, message: Message<N>) -> Result<()> {
@@ -61,10 +57,13 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
             None => bail!("Unable to resolve the (ambiguous) peer address '{peer_addr}'"),
         };
 
-        // Drop the peer, if they have sent more than `MESSAGE_LIMIT` messages
-        // in the last `MESSAGE_LIMIT_TIME_FRAME_IN_SECS` seconds.
-        let num_messages = self.router().cache.insert_inbound_message(peer_ip, Self::MESSAGE_LIMIT_TIME_FRAME_IN_SECS);
-        if num_messages > Self::MESSAGE_LIMIT {
+        // Drop the peer, if they have sent more than the configured message limit
+        // within the configured time frame.
+        let config = self.router().config();
+        let num_messages =
+            self.router().cache.insert_inbound_message(peer_ip, config.message_limit_time_frame_in_secs as i64);
+        if num_messages > config.message_limit {
+            self.router().peer_book().record_misbehaviour(peer_ip);
             bail!("Dropping '{peer_ip}' for spamming messages (num_messages = {num_messages})")
         }
 
@@ -96,6 +95,7 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
 
                 // Remove the block request, checking if this node previously sent a block request to this peer.
                 if !self.router().cache.remove_outbound_block_request(peer_ip, &request) {
+                    self.router().peer_book().record_misbehaviour(peer_ip);
                     bail!("Peer '{peer_ip}' is not following the protocol (unexpected block response)")
                 }
                 // Perform the deferred non-blocking deserialization of the blocks.
@@ -105,7 +105,10 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
 
                 // Process the block response.
                 let node = self.clone();
-                match spawn_blocking(move || node.block_response(peer_ip, blocks.0)).await? {
+                let is_success = spawn_blocking(move || node.block_response(peer_ip, blocks.0)).await?;
+                // Record the outcome of the block response in the peer book.
+                self.router().peer_book().record_block_response(peer_ip, is_success);
+                match is_success {
                     true => Ok(()),
                     false => bail!("Peer '{peer_ip}' sent an invalid block response"),
                 }
@@ -167,10 +170,30 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     false => bail!("Peer '{peer_ip}' sent an invalid ping"),
                 }
             }
-            Message::Pong(message) => match self.pong(peer_ip, message) {
-                true => Ok(()),
-                false => bail!("Peer '{peer_ip}' sent an invalid pong"),
-            },
+            Message::Pong(message) => {
+                // Record the round-trip latency of the last ping sent to this peer.
+                if let Some(timestamp) = self.router().cache.remove_outbound_ping(peer_ip) {
+                    let latency = time::OffsetDateTime::now_utc() - timestamp;
+                    if let Ok(latency) = std::time::Duration::try_from(latency) {
+                        self.router().peer_book().record_latency(peer_ip, latency);
+                    }
+                    #[cfg(feature = "metrics")]
+                    if let Some(peer) = self.router().get_connected_peer(&peer_ip) {
+                        let labels = [("node_type", peer.node_type().to_string())];
+                        metrics::histogram_with_labels(
+                            metrics::router::PING_LATENCY,
+                            latency.as_seconds_f64(),
+                            &labels,
+                        );
+                        metrics::record_peer_latency(metrics::router::PEER_LATENCY, peer_ip, latency.as_seconds_f64());
+                    }
+                }
+                // Process the pong.
+                match self.pong(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid pong"),
+                }
+            }
             Message::PuzzleRequest(..) => {
                 // Insert the puzzle request for the peer, and fetch the recent frequency.
                 let frequency = self.router().cache.insert_inbound_puzzle_request(peer_ip);
@@ -22,6 +22,7 @@ extern crate tracing;
 pub use snarkos_node_router_messages as messages;
 
 mod handshake;
+pub use handshake::challenge_data;
 
 mod heartbeat;
 pub use heartbeat::*;
@@ -40,7 +41,7 @@ pub use routing::*;
 
 use crate::messages::NodeType;
 use snarkos_account::Account;
-use snarkos_node_tcp::{is_bogon_ip, is_unspecified_or_broadcast_ip, Config, Tcp};
+use snarkos_node_tcp::{is_bogon_ip, is_unspecified_or_broadcast_ip, BanList, BannedIp, Config, PeerBook, Tcp};
 use snarkvm::prelude::{Address, Network, PrivateKey, ViewKey};
 
 use anyhow::{bail, Result};
@@ -48,13 +49,14 @@ use parking_lot::{Mutex, RwLock};
 use std::{
     collections::{HashMap, HashSet},
     future::Future,
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     ops::Deref,
+    path::PathBuf,
     str::FromStr,
     sync::Arc,
-    time::Instant,
+    time::{Duration, Instant},
 };
-use tokio::task::JoinHandle;
+use tokio::{sync::broadcast, task::JoinHandle};
 
 #[derive(Clone)]
 pub struct Router<N: Network>(Arc<InnerRouter<N>>);
@@ -72,6 +74,8 @@ pub struct InnerRouter<N: Network> {
     tcp: Tcp,
     /// The node type.
     node_type: NodeType,
+    /// The configuration of the peer limits and the heartbeat policy.
+    config: RouterConfig,
     /// The account of the node.
     account: Account<N>,
     /// The cache.
@@ -79,7 +83,7 @@ pub struct InnerRouter<N: Network> {
     /// The resolver.
     resolver: Resolver,
     /// The set of trusted peers.
-    trusted_peers: HashSet<SocketAddr>,
+    trusted_peers: RwLock<HashSet<SocketAddr>>,
     /// The map of connected peer IPs to their peer handlers.
     connected_peers: RwLock<HashMap<SocketAddr, Peer<N>>>,
     /// The set of handshaking peers. While `Tcp` already recognizes the connecting IP addresses
@@ -91,6 +95,15 @@ pub struct InnerRouter<N: Network> {
     candidate_peers: RwLock<HashSet<SocketAddr>>,
     /// The set of restricted peer IPs.
     restricted_peers: RwLock<HashMap<SocketAddr, Instant>>,
+    /// The banned IPs, which are shared with the gateway on validators.
+    ban_list: Arc<BanList>,
+    /// The path to the persisted peer rules and the persisted rules, if persistence is enabled.
+    /// Note: The persisted rules only include the changes that were requested to be persisted.
+    persisted_peer_rules: RwLock<Option<(PathBuf, PeerRules)>>,
+    /// The book of the peers seen by the node, used to score the peers.
+    peer_book: PeerBook,
+    /// The sender for the peer connect and disconnect notifications.
+    peer_events: broadcast::Sender<PeerEvent<N>>,
     /// The spawned handles.
     handles: Mutex<Vec<JoinHandle<()>>>,
     /// The boolean flag for the development mode.
@@ -100,11 +113,6 @@ pub struct InnerRouter<N: Network> {
 impl<N: Network> Router<N> {
     /// The maximum number of candidate peers permitted to be stored in the node.
     const MAXIMUM_CANDIDATE_PEERS: usize = 10_000;
-    /// The maximum number of connection failures permitted by an inbound connecting peer.
-    const MAXIMUM_CONNECTION_FAILURES: usize = 5;
-    /// The duration in seconds after which a connected peer is considered inactive or
-    /// disconnected if no message has been received in the meantime.
-    const RADIO_SILENCE_IN_SECS: u64 = 150; // 2.5 minutes
 }
 
 impl<N: Network> Router<N> {
@@ -114,23 +122,30 @@ impl<N: Network> Router<N> {
         node_type: NodeType,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
-        max_peers: u16,
+        config: RouterConfig,
         is_dev: bool,
     ) -> Result<Self> {
+        // Ensure the router configuration is consistent.
+        config.check()?;
         // Initialize the TCP stack.
-        let tcp = Tcp::new(Config::new(node_ip, max_peers));
+        let tcp = Tcp::new(Config::new(node_ip, config.maximum_number_of_peers as u16));
         // Initialize the router.
         Ok(Self(Arc::new(InnerRouter {
             tcp,
             node_type,
+            config,
             account,
             cache: Default::default(),
             resolver: Default::default(),
-            trusted_peers: trusted_peers.iter().copied().collect(),
+            trusted_peers: RwLock::new(trusted_peers.iter().copied().collect()),
             connected_peers: Default::default(),
             connecting_peers: Default::default(),
             candidate_peers: Default::default(),
             restricted_peers: Default::default(),
+            ban_list: Default::default(),
+            persisted_peer_rules: Default::default(),
+            peer_book: Default::default(),
+            peer_events: broadcast::channel(MAX_PEER_EVENTS).0,
             handles: Default::default(),
             is_dev,
         })))
@@ -146,6 +161,9 @@ impl<N: Network> Router<N> {
             return None;
         }
 
+        // Record the connection attempt in the peer book.
+        self.peer_book.record_connection_attempt(peer_ip);
+
         let router = self.clone();
         Some(tokio::spawn(async move {
             // Attempt to connect to the candidate peer.
@@ -153,6 +171,8 @@ impl<N: Network> Router<N> {
                 // Remove the peer from the candidate peers.
                 Ok(()) => {
                     router.remove_candidate_peer(peer_ip);
+                    // Record the successful connection in the peer book.
+                    router.peer_book.record_connection_success(peer_ip);
                     true
                 }
                 // If the connection was not allowed, log the error.
@@ -231,6 +251,11 @@ impl<N: Network> Router<N> {
         self.node_type
     }
 
+    /// Returns the configuration of the peer limits and the heartbeat policy.
+    pub fn config(&self) -> &RouterConfig {
+        &self.config
+    }
+
     /// Returns the account private key of the node.
     pub fn private_key(&self) -> &PrivateKey<N> {
         self.account.private_key()
@@ -288,11 +313,23 @@ impl<N: Network> Router<N> {
 
     /// Returns `true` if the given IP is restricted.
     pub fn is_restricted(&self, ip: &SocketAddr) -> bool {
-        self.restricted_peers
-            .read()
-            .get(ip)
-            .map(|time| time.elapsed().as_secs() < Self::RADIO_SILENCE_IN_SECS)
-            .unwrap_or(false)
+        self.is_banned(&ip.ip())
+            || self
+                .restricted_peers
+                .read()
+                .get(ip)
+                .map(|time| time.elapsed().as_secs() < self.config.radio_silence_in_secs)
+                .unwrap_or(false)
+    }
+
+    /// Returns `true` if the given IP is banned.
+    pub fn is_banned(&self, ip: &IpAddr) -> bool {
+        self.ban_list.is_banned(ip)
+    }
+
+    /// Returns `true` if the given peer IP is trusted.
+    pub fn is_trusted(&self, peer_ip: &SocketAddr) -> bool {
+        self.trusted_peers.read().contains(peer_ip)
     }
 
     /// Returns the maximum number of connected peers.
@@ -370,9 +407,31 @@ impl<N: Network> Router<N> {
         self.restricted_peers.read().keys().copied().collect()
     }
 
+    /// Returns the list of restricted peers, with the time left on each restriction.
+    pub fn restricted_peers_with_time_left(&self) -> Vec<(SocketAddr, Duration)> {
+        let restriction = Duration::from_secs(self.config.radio_silence_in_secs);
+        self.restricted_peers
+            .read()
+            .iter()
+            .filter_map(|(peer_ip, time)| {
+                restriction.checked_sub(time.elapsed()).map(|time_left| (*peer_ip, time_left))
+            })
+            .collect()
+    }
+
+    /// Returns the list of banned IPs, excluding the expired bans.
+    pub fn banned_ips(&self) -> Vec<BannedIp> {
+        self.ban_list.banned_ips()
+    }
+
+    /// Returns the banned IPs, to share them with the gateway.
+    pub fn ban_list(&self) -> &Arc<BanList> {
+        &self.ban_list
+    }
+
     /// Returns the list of trusted peers.
-    pub fn trusted_peers(&self) -> &HashSet<SocketAddr> {
-        &self.trusted_peers
+    pub fn trusted_peers(&self) -> HashSet<SocketAddr> {
+        self.trusted_peers.read().clone()
     }
 
     /// Returns the list of bootstrap peers.
@@ -389,6 +448,16 @@ impl<N: Network> Router<N> {
         }
     }
 
+    /// Returns the book of the peers seen by the node.
+    pub fn peer_book(&self) -> &PeerBook {
+        &self.peer_book
+    }
+
+    /// Returns a new receiver for the peer connect and disconnect notifications.
+    pub fn subscribe_peer_events(&self) -> broadcast::Receiver<PeerEvent<N>> {
+        self.peer_events.subscribe()
+    }
+
     /// Returns the list of metrics for the connected peers.
     pub fn connected_metrics(&self) -> Vec<(SocketAddr, NodeType)> {
         self.connected_peers.read().iter().map(|(ip, peer)| (*ip, peer.node_type())).collect()
@@ -404,6 +473,8 @@ impl<N: Network> Router<N> {
     /// Inserts the given peer into the connected peers.
     pub fn insert_connected_peer(&self, peer: Peer<N>, peer_addr: SocketAddr) {
         let peer_ip = peer.ip();
+        // Prepare the peer event.
+        let peer_event = PeerEvent::Connected { peer_ip, address: peer.address(), node_type: peer.node_type() };
         // Adds a bidirectional map between the listener address and (ambiguous) peer address.
         self.resolver.insert_peer(peer_ip, peer_addr);
         // Add an entry for this `Peer` in the connected peers.
@@ -414,6 +485,8 @@ impl<N: Network> Router<N> {
         self.restricted_peers.write().remove(&peer_ip);
         #[cfg(feature = "metrics")]
         self.update_metrics();
+        // Notify the subscribers, if there are any.
+        let _ = self.peer_events.send(peer_event);
     }
 
     /// Inserts the given peer IPs to the set of candidate peers.
@@ -440,6 +513,8 @@ impl<N: Network> Router<N> {
 
     /// Inserts the given peer into the restricted peers.
     pub fn insert_restricted_peer(&self, peer_ip: SocketAddr) {
+        // Record the misbehaviour in the peer book.
+        self.peer_book.record_misbehaviour(peer_ip);
         // Remove this peer from the candidate peers, if it exists.
         self.candidate_peers.write().remove(&peer_ip);
         // Add the peer to the restricted peers.
@@ -448,6 +523,83 @@ impl<N: Network> Router<N> {
         self.update_metrics();
     }
 
+    /// Inserts the given peer into the trusted peers, returning `true` if the peer was not already trusted.
+    pub fn insert_trusted_peer(&self, peer_ip: SocketAddr) -> bool {
+        // Remove this peer from the restricted peers, if it exists.
+        self.restricted_peers.write().remove(&peer_ip);
+        // Add the peer to the trusted peers.
+        self.trusted_peers.write().insert(peer_ip)
+    }
+
+    /// Removes the given peer from the trusted peers, returning `true` if the peer was trusted.
+    pub fn remove_trusted_peer(&self, peer_ip: SocketAddr) -> bool {
+        self.trusted_peers.write().remove(&peer_ip)
+    }
+
+    /// Bans the given IP for the given duration, or indefinitely if `None`.
+    /// Note: This does not disconnect the connected peers with the given IP.
+    pub fn ban_ip(&self, ip: IpAddr, duration: Option<Duration>) {
+        // Remove the peers with this IP from the candidate peers.
+        self.candidate_peers.write().retain(|peer_ip| peer_ip.ip() != ip);
+        // Add the IP to the banned IPs.
+        self.ban_list.ban(ip, duration);
+        #[cfg(feature = "metrics")]
+        self.update_metrics();
+    }
+
+    /// Unbans the given IP, returning `true` if the IP was banned.
+    pub fn unban_ip(&self, ip: IpAddr) -> bool {
+        self.ban_list.unban(&ip)
+    }
+
+    /// Loads the peer rules from the given path, and persists the subsequent changes to this path
+    /// whenever `persist_peer_rules` is called.
+    /// Note: Corrupt peer rules are discarded with a warning, so that they do not prevent the node from starting.
+    pub fn load_peer_rules(&self, path: PathBuf) {
+        // Load the peer rules.
+        let rules = PeerRules::load(&path);
+        // Add the persisted trusted peers.
+        self.trusted_peers.write().extend(rules.trusted_peers.iter().copied());
+        // Add the persisted bans that have not expired.
+        self.ban_list.extend(rules.banned_ips.iter().cloned());
+        // Set the persisted peer rules.
+        *self.persisted_peer_rules.write() = Some((path, rules));
+    }
+
+    /// Applies the given change to the persisted peer rules, and saves them, so that the change survives a restart.
+    /// Note: The changes that were not persisted through this method are not saved.
+    pub fn persist_peer_rules(&self, update: impl FnOnce(&mut PeerRules)) -> Result<()> {
+        // Retrieve the persisted peer rules.
+        let mut persisted_peer_rules = self.persisted_peer_rules.write();
+        let Some((path, rules)) = persisted_peer_rules.as_mut() else {
+            bail!("Persistence of the peer rules is not enabled on this node")
+        };
+        // Update the peer rules, dropping the persisted bans that have expired.
+        update(rules);
+        rules.banned_ips.retain(|banned| !banned.is_expired());
+        // Save the peer rules.
+        rules.save(path)
+    }
+
+    /// Loads the peer book from the given path, and persists the peer book to this path
+    /// whenever `save_peer_book` is called. The known-good peers are added to the candidate peers.
+    pub fn load_peer_book(&self, path: PathBuf) {
+        // Load the peer book.
+        self.peer_book.load(path);
+        // Add the known-good peers to the candidate peers.
+        // Note: The listener may not be enabled yet, so the self-connection check is deferred to `connect`.
+        let known_good_peers = self.peer_book.known_good_peers();
+        self.candidate_peers.write().extend(known_good_peers.into_iter().take(Self::MAXIMUM_CANDIDATE_PEERS));
+        #[cfg(feature = "metrics")]
+        self.update_metrics();
+    }
+
+    /// Persists the peer book, if persistence is enabled.
+    /// Note: This function writes to the file system, and should not be called on the async runtime.
+    pub fn save_peer_book(&self) -> Result<()> {
+        Ok(self.peer_book.save()?)
+    }
+
     /// Updates the connected peer with the given function.
     pub fn update_connected_peer<Fn: FnMut(&mut Peer<N>)>(
         &self,
@@ -472,11 +624,20 @@ impl<N: Network> Router<N> {
         // Removes the bidirectional map between the listener address and (ambiguous) peer address.
         self.resolver.remove_peer(&peer_ip);
         // Remove this peer from the connected peers, if it exists.
-        self.connected_peers.write().remove(&peer_ip);
+        let peer = self.connected_peers.write().remove(&peer_ip);
         // Add the peer to the candidate peers.
         self.candidate_peers.write().insert(peer_ip);
+        // Forget any ping still awaiting a pong from this peer.
+        self.cache.remove_outbound_ping(peer_ip);
         #[cfg(feature = "metrics")]
-        self.update_metrics();
+        {
+            metrics::remove_peer_latency(peer_ip);
+            self.update_metrics();
+        }
+        // If the peer was connected, notify the subscribers, if there are any.
+        if peer.is_some() {
+            let _ = self.peer_events.send(PeerEvent::Disconnected { peer_ip });
+        }
     }
 
     #[cfg(feature = "test")]
@@ -501,6 +662,10 @@ impl<N: Network> Router<N> {
     /// Shuts down the router.
     pub async fn shut_down(&self) {
         info!("Shutting down the router...");
+        // Persist the peer book.
+        if let Err(error) = self.save_peer_book() {
+            warn!("Unable to save the peer book - {error}");
+        }
         // Abort the tasks.
         self.handles.lock().iter().for_each(|handle| handle.abort());
         // Close the listener.
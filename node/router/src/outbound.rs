@@ -63,6 +63,10 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         if matches!(message, Message::PeerRequest(_)) {
             self.router().cache.increment_outbound_peer_requests(peer_ip);
         }
+        // If the message type is a ping, record the time it was sent to measure the peer latency.
+        if matches!(message, Message::Ping(_)) {
+            self.router().cache.insert_outbound_ping(peer_ip);
+        }
         // Retrieve the message name.
         let name = message.name();
         // Send the message to the peer.
@@ -51,8 +51,8 @@ pub trait Routing<N: Network>:
             loop {
                 // Process a heartbeat in the router.
                 self_clone.heartbeat();
-                // Sleep for `HEARTBEAT_IN_SECS` seconds.
-                tokio::time::sleep(Duration::from_secs(Self::HEARTBEAT_IN_SECS)).await;
+                // Sleep for the configured heartbeat interval.
+                tokio::time::sleep(Duration::from_secs(self_clone.router().config().heartbeat_in_secs)).await;
             }
         });
     }
@@ -23,7 +23,7 @@ use std::{
 };
 
 use snarkos_account::Account;
-use snarkos_node_router::{messages::NodeType, Router};
+use snarkos_node_router::{messages::NodeType, Router, RouterConfig};
 use snarkvm::prelude::{block::Block, FromBytes, MainnetV0 as CurrentNetwork, Network};
 
 /// A helper macro to print the TCP listening address, along with the connected and connecting peers.
@@ -76,7 +76,7 @@ pub async fn client(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNe
         NodeType::Client,
         sample_account(),
         &[],
-        max_peers,
+        RouterConfig::new(max_peers as usize),
         true,
     )
     .await
@@ -92,7 +92,7 @@ pub async fn prover(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNe
         NodeType::Prover,
         sample_account(),
         &[],
-        max_peers,
+        RouterConfig::new(max_peers as usize),
         true,
     )
     .await
@@ -108,7 +108,7 @@ pub async fn validator(listening_port: u16, max_peers: u16) -> TestRouter<Curren
         NodeType::Validator,
         sample_account(),
         &[],
-        max_peers,
+        RouterConfig::new(max_peers as usize),
         true,
     )
     .await
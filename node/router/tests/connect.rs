@@ -15,9 +15,14 @@
 mod common;
 use common::*;
 
+use snarkos_node_router::challenge_data;
 use snarkos_node_tcp::{protocols::Handshake, P2P};
 
 use core::time::Duration;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
 
 #[tokio::test]
 async fn test_connect_without_handshake() {
@@ -150,6 +155,77 @@ async fn test_connect_with_handshake() {
     }
 }
 
+#[tokio::test]
+async fn test_connect_with_encrypted_handshake() {
+    // Create 2 routers.
+    let node0 = validator(0, 2).await;
+    let node1 = client(0, 2).await;
+
+    // Enable handshake protocol.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Check the router level.
+    assert_eq!(node0.number_of_connected_peers(), 1);
+    assert_eq!(node1.number_of_connected_peers(), 1);
+
+    // Ensure the connection is encrypted.
+    assert!(node0.tcp().is_encrypted(node1.local_ip()));
+    // Ensure the state of the encrypted connection is not retained after the handshake.
+    assert!(node0.tcp().noise_states().get(node1.local_ip()).is_none());
+}
+
+#[tokio::test]
+async fn test_connect_rejects_unencrypted_peer() {
+    // Create a router.
+    let node = client(0, 2).await;
+
+    // Enable handshake protocol.
+    node.enable_handshake().await;
+
+    // Start listening.
+    node.tcp().enable_listener().await.unwrap();
+
+    // Connect to the router, and send a plaintext message instead of the Noise handshake.
+    let mut stream = TcpStream::connect(node.local_ip()).await.unwrap();
+    let message = b"plaintext challenge request";
+    stream.write_u16_le(message.len() as u16).await.unwrap();
+    stream.write_all(message).await.unwrap();
+
+    // Ensure the router closes the connection.
+    let mut buffer = [0u8; 64];
+    let result = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buffer)).await.unwrap();
+    assert!(matches!(result, Ok(0) | Err(_)));
+
+    // Check the TCP and router levels.
+    assert_eq!(node.tcp().num_connected(), 0);
+    assert_eq!(node.number_of_connected_peers(), 0);
+}
+
+#[test]
+fn test_challenge_data_binds_handshake_hash() {
+    let rng = &mut rand::thread_rng();
+    let account = sample_account();
+
+    // Sign the challenge with the handshake hash of an encrypted connection.
+    let signature = account.sign_bytes(&challenge_data(1, 2, &[0u8; 32]), rng).unwrap();
+    assert!(signature.verify_bytes(&account.address(), &challenge_data(1, 2, &[0u8; 32])));
+
+    // Ensure the signature is rejected on a connection with another key (e.g. a relayed connection).
+    assert!(!signature.verify_bytes(&account.address(), &challenge_data(1, 2, &[1u8; 32])));
+    // Ensure the signature is rejected for other nonces.
+    assert!(!signature.verify_bytes(&account.address(), &challenge_data(2, 1, &[0u8; 32])));
+}
+
 #[ignore]
 #[tokio::test]
 async fn test_connect_simultaneously_with_handshake() {
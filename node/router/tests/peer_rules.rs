@@ -0,0 +1,89 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::{BannedIp, PEER_RULES_FILE_NAME};
+
+use core::time::Duration;
+use std::net::SocketAddr;
+
+#[tokio::test]
+async fn test_ban_and_unban() {
+    let router = client(0, 1).await;
+    let peer_ip = SocketAddr::from(([1, 2, 3, 4], 4130));
+
+    // Ban the IP, which restricts every port on it.
+    router.ban_ip(peer_ip.ip(), Some(Duration::from_secs(60)));
+    assert!(router.is_banned(&peer_ip.ip()));
+    assert!(router.is_restricted(&peer_ip));
+    assert!(router.is_restricted(&SocketAddr::from(([1, 2, 3, 4], 4131))));
+    assert_eq!(router.banned_ips().len(), 1);
+
+    // Unban the IP.
+    assert!(router.unban_ip(peer_ip.ip()));
+    assert!(!router.unban_ip(peer_ip.ip()));
+    assert!(!router.is_restricted(&peer_ip));
+    assert!(router.banned_ips().is_empty());
+}
+
+#[tokio::test]
+async fn test_trusted_peers() {
+    let router = client(0, 1).await;
+    let peer_ip = SocketAddr::from(([1, 2, 3, 4], 4130));
+
+    // Restrict the peer, and ensure trusting it lifts the restriction.
+    router.insert_restricted_peer(peer_ip);
+    assert!(router.is_restricted(&peer_ip));
+    assert_eq!(router.restricted_peers_with_time_left().len(), 1);
+    assert!(router.insert_trusted_peer(peer_ip));
+    assert!(!router.insert_trusted_peer(peer_ip));
+    assert!(router.is_trusted(&peer_ip));
+    assert!(!router.is_restricted(&peer_ip));
+
+    // Remove the trusted peer.
+    assert!(router.remove_trusted_peer(peer_ip));
+    assert!(!router.is_trusted(&peer_ip));
+}
+
+#[tokio::test]
+async fn test_persisted_peer_rules() {
+    let directory = std::env::temp_dir().join(format!("snarkos-test-{}", rand::random::<u64>()));
+    let path = directory.join(PEER_RULES_FILE_NAME);
+    let peer_ip = SocketAddr::from(([1, 2, 3, 4], 4130));
+    let banned_ip = SocketAddr::from(([5, 6, 7, 8], 4130)).ip();
+    let unpersisted_ip = SocketAddr::from(([9, 10, 11, 12], 4130)).ip();
+
+    // Ensure the peer rules cannot be persisted before persistence is enabled.
+    let router = client(0, 1).await;
+    assert!(router.persist_peer_rules(|rules| rules.insert_trusted_peer(peer_ip)).is_err());
+
+    // Enable persistence, and update the rules, only persisting some of the changes.
+    router.load_peer_rules(path.clone());
+    router.insert_trusted_peer(peer_ip);
+    router.persist_peer_rules(|rules| rules.insert_trusted_peer(peer_ip)).unwrap();
+    router.ban_ip(banned_ip, None);
+    router.ban_ip(unpersisted_ip, None);
+    router.persist_peer_rules(|rules| rules.insert_banned_ip(BannedIp::new(banned_ip, None))).unwrap();
+
+    // Ensure a new router only reloads the persisted changes.
+    let router = client(0, 1).await;
+    router.load_peer_rules(path);
+    assert!(router.is_trusted(&peer_ip));
+    assert!(router.is_banned(&banned_ip));
+    assert!(!router.is_banned(&unpersisted_ip));
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
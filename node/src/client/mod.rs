@@ -24,6 +24,7 @@ use snarkos_node_router::{
     Inbound,
     Outbound,
     Router,
+    RouterConfig,
     Routing,
 };
 use snarkos_node_sync::{BlockSync, BlockSyncMode};
@@ -78,8 +79,11 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
         node_ip: SocketAddr,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        rest_index: bool,
+        rest_mapping_history: bool,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        router_config: RouterConfig,
         genesis: Block<N>,
         cdn: Option<String>,
         storage_mode: StorageMode,
@@ -93,11 +97,15 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
         // Initialize the ledger.
         let ledger = Ledger::<N, C>::load(genesis.clone(), storage_mode.clone())?;
 
+        // Initialize the CDN sync metrics.
+        #[cfg(feature = "metrics")]
+        let cdn_metrics = crate::start_cdn_metrics_loop();
         // Initialize the CDN.
         if let Some(base_url) = cdn {
-            // Sync the ledger with the CDN.
+            // Sync the ledger with the CDN, caching the bundles that are not yet processed.
+            let cache_dir = Some(crate::cdn_cache_path::<N>(&storage_mode));
             if let Err((_, error)) =
-                snarkos_node_cdn::sync_ledger_with_cdn(&base_url, ledger.clone(), shutdown.clone()).await
+                snarkos_node_cdn::sync_ledger_with_cdn(&base_url, ledger.clone(), cache_dir, shutdown.clone()).await
             {
                 crate::log_clean_error(&storage_mode);
                 return Err(error);
@@ -115,10 +123,14 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
             NodeType::Client,
             account,
             trusted_peers,
-            Self::MAXIMUM_NUMBER_OF_PEERS as u16,
+            router_config,
             matches!(storage_mode, StorageMode::Development(_)),
         )
         .await?;
+        // Load the persisted peer rules.
+        router.load_peer_rules(crate::peer_rules_path::<N>(&storage_mode));
+        // Load the persisted peer book.
+        router.load_peer_book(crate::peer_book_path::<N>(&storage_mode));
         // Load the coinbase puzzle.
         let coinbase_puzzle = CoinbasePuzzle::<N>::load()?;
         // Initialize the node.
@@ -135,7 +147,21 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
 
         // Initialize the REST server.
         if let Some(rest_ip) = rest_ip {
-            node.rest = Some(Rest::start(rest_ip, rest_rps, None, ledger.clone(), Arc::new(node.clone())).await?);
+            // Note: The client advances its ledger through the sync module, so the REST streams follow its blocks.
+            let blocks = Some(node.sync.subscribe_blocks());
+            node.rest = Some(
+                Rest::start(
+                    rest_ip,
+                    rest_rps,
+                    None,
+                    ledger.clone(),
+                    Arc::new(node.clone()),
+                    blocks,
+                    rest_index,
+                    rest_mapping_history,
+                )
+                .await?,
+            );
         }
         // Initialize the routing.
         node.initialize_routing().await;
@@ -143,6 +169,11 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
         node.initialize_sync();
         // Initialize the notification message loop.
         node.handles.lock().push(crate::start_notification_message_loop());
+        // Initialize the ledger size metric.
+        #[cfg(feature = "metrics")]
+        node.handles.lock().push(crate::start_ledger_size_metric_loop::<N>(&storage_mode));
+        #[cfg(feature = "metrics")]
+        node.handles.lock().push(cdn_metrics);
         // Pass the node to the signal handler.
         let _ = signal_node.set(node.clone());
         // Return the node.
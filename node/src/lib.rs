@@ -46,6 +46,41 @@ mod traits;
 pub use traits::*;
 
 use aleo_std::StorageMode;
+use snarkos_node_bft::helpers::ROUND_STATE_FILE_NAME;
+use snarkos_node_router::PEER_RULES_FILE_NAME;
+use snarkos_node_tcp::PEER_BOOK_FILE_NAME;
+use snarkvm::prelude::Network;
+use std::path::PathBuf;
+
+/// The name of the directory containing the cache of the CDN bundles, within the ledger directory.
+const CDN_CACHE_DIR_NAME: &str = "cdn-cache";
+/// The file name of the persisted peer book of the BFT gateway, within the ledger directory.
+const BFT_PEER_BOOK_FILE_NAME: &str = "bft-peer-book.json";
+
+/// Returns the path to the persisted peer rules (i.e. the trusted peers and banned IPs) of the node.
+pub fn peer_rules_path<N: Network>(storage_mode: &StorageMode) -> PathBuf {
+    aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone()).join(PEER_RULES_FILE_NAME)
+}
+
+/// Returns the path to the persisted peer book (i.e. the scored peers) of the node router.
+pub fn peer_book_path<N: Network>(storage_mode: &StorageMode) -> PathBuf {
+    aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone()).join(PEER_BOOK_FILE_NAME)
+}
+
+/// Returns the path to the persisted peer book (i.e. the scored validators) of the BFT gateway.
+pub fn bft_peer_book_path<N: Network>(storage_mode: &StorageMode) -> PathBuf {
+    aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone()).join(BFT_PEER_BOOK_FILE_NAME)
+}
+
+/// Returns the path to the persisted round state (i.e. the signed and proposed batches) of the BFT primary.
+pub fn bft_round_state_path<N: Network>(storage_mode: &StorageMode) -> PathBuf {
+    aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone()).join(ROUND_STATE_FILE_NAME)
+}
+
+/// Returns the path to the cache of the CDN bundles that were downloaded, but not yet processed, by the node.
+pub fn cdn_cache_path<N: Network>(storage_mode: &StorageMode) -> PathBuf {
+    aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone()).join(CDN_CACHE_DIR_NAME)
+}
 
 /// A helper to log instructions to recover.
 pub fn log_clean_error(storage_mode: &StorageMode) {
@@ -72,6 +107,60 @@ pub fn start_notification_message_loop() -> tokio::task::JoinHandle<()> {
     })
 }
 
+/// The interval at which the size of the ledger is recorded in the metrics.
+#[cfg(feature = "metrics")]
+const LEDGER_SIZE_INTERVAL_IN_SECS: u64 = 60;
+
+/// Starts a loop that periodically records the size of the ledger (i.e. its RocksDB directory) in the metrics.
+#[cfg(feature = "metrics")]
+pub fn start_ledger_size_metric_loop<N: Network>(storage_mode: &StorageMode) -> tokio::task::JoinHandle<()> {
+    let ledger_dir = aleo_std::aleo_ledger_dir(N::ID, storage_mode.clone());
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(LEDGER_SIZE_INTERVAL_IN_SECS));
+        loop {
+            interval.tick().await;
+            // Compute the size of the ledger directory, without blocking the runtime.
+            let path = ledger_dir.clone();
+            match tokio::task::spawn_blocking(move || directory_size(&path)).await {
+                Ok(size) => metrics::gauge(metrics::storage::LEDGER_SIZE, size as f64),
+                Err(error) => warn!("Failed to compute the size of the ledger - {error}"),
+            }
+        }
+    })
+}
+
+/// Starts a loop that records the progress of the CDN sync in the metrics.
+#[cfg(feature = "metrics")]
+pub fn start_cdn_metrics_loop() -> tokio::task::JoinHandle<()> {
+    let mut receiver = snarkos_node_cdn::subscribe_progress();
+    tokio::spawn(async move {
+        while receiver.changed().await.is_ok() {
+            let progress = receiver.borrow_and_update().clone();
+            metrics::gauge(metrics::cdn::HEIGHT, progress.current_height as f64);
+            metrics::gauge(metrics::cdn::TARGET_HEIGHT, progress.end_height as f64);
+            metrics::gauge(metrics::cdn::DOWNLOADED_BYTES, progress.downloaded_bytes as f64);
+            metrics::gauge(metrics::cdn::CACHED_BUNDLES, progress.cached_bundles as f64);
+            metrics::gauge(metrics::cdn::CONCURRENT_REQUESTS, progress.concurrency as f64);
+        }
+    })
+}
+
+/// Returns the total size of the files in the given directory, in bytes.
+#[cfg(feature = "metrics")]
+fn directory_size(path: &std::path::Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => directory_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
 /// Returns the notification message as a string.
 pub fn notification_message() -> String {
     use colored::Colorize;
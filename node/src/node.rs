@@ -12,9 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{traits::NodeInterface, Client, Prover, Validator};
+use crate::{traits::NodeInterface, Client, PoolMode, Prover, ProverStats, Validator};
 use snarkos_account::Account;
-use snarkos_node_router::messages::NodeType;
+use snarkos_node_router::{messages::NodeType, RouterConfig};
 use snarkvm::prelude::{
     block::Block,
     store::helpers::{memory::ConsensusMemory, rocksdb::ConsensusDB},
@@ -26,7 +26,7 @@ use snarkvm::prelude::{
 
 use aleo_std::StorageMode;
 use anyhow::Result;
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 pub enum Node<N: Network> {
     /// A validator is a full node, capable of validating blocks.
@@ -44,12 +44,17 @@ impl<N: Network> Node<N> {
         bft_ip: Option<SocketAddr>,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        rest_index: bool,
+        rest_mapping_history: bool,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
         trusted_validators: &[SocketAddr],
+        num_workers: u8,
+        router_config: RouterConfig,
         genesis: Block<N>,
         cdn: Option<String>,
         storage_mode: StorageMode,
+        graceful_shutdown: Option<Duration>,
     ) -> Result<Self> {
         Ok(Self::Validator(Arc::new(
             Validator::new(
@@ -57,12 +62,17 @@ impl<N: Network> Node<N> {
                 bft_ip,
                 rest_ip,
                 rest_rps,
+                rest_index,
+                rest_mapping_history,
                 account,
                 trusted_peers,
                 trusted_validators,
+                num_workers,
+                router_config,
                 genesis,
                 cdn,
                 storage_mode,
+                graceful_shutdown,
             )
             .await?,
         )))
@@ -73,10 +83,27 @@ impl<N: Network> Node<N> {
         node_ip: SocketAddr,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        router_config: RouterConfig,
         genesis: Block<N>,
         storage_mode: StorageMode,
+        pool_mode: Option<PoolMode>,
+        num_puzzle_instances: Option<u8>,
+        puzzle_cores: Vec<usize>,
     ) -> Result<Self> {
-        Ok(Self::Prover(Arc::new(Prover::new(node_ip, account, trusted_peers, genesis, storage_mode).await?)))
+        Ok(Self::Prover(Arc::new(
+            Prover::new(
+                node_ip,
+                account,
+                trusted_peers,
+                router_config,
+                genesis,
+                storage_mode,
+                pool_mode,
+                num_puzzle_instances,
+                puzzle_cores,
+            )
+            .await?,
+        )))
     }
 
     /// Initializes a new client node.
@@ -84,14 +111,30 @@ impl<N: Network> Node<N> {
         node_ip: SocketAddr,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        rest_index: bool,
+        rest_mapping_history: bool,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        router_config: RouterConfig,
         genesis: Block<N>,
         cdn: Option<String>,
         storage_mode: StorageMode,
     ) -> Result<Self> {
         Ok(Self::Client(Arc::new(
-            Client::new(node_ip, rest_ip, rest_rps, account, trusted_peers, genesis, cdn, storage_mode).await?,
+            Client::new(
+                node_ip,
+                rest_ip,
+                rest_rps,
+                rest_index,
+                rest_mapping_history,
+                account,
+                trusted_peers,
+                router_config,
+                genesis,
+                cdn,
+                storage_mode,
+            )
+            .await?,
         )))
     }
 
@@ -131,6 +174,14 @@ impl<N: Network> Node<N> {
         }
     }
 
+    /// Returns the statistics of the coinbase puzzle, if the node is a prover.
+    pub fn prover_stats(&self) -> Option<Arc<ProverStats>> {
+        match self {
+            Self::Prover(node) => Some(node.stats().clone()),
+            Self::Validator(_) | Self::Client(_) => None,
+        }
+    }
+
     /// Returns `true` if the node is in development mode.
     pub fn is_dev(&self) -> bool {
         match self {
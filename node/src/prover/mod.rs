@@ -12,8 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod pool;
+use pool::Pool;
+pub use pool::{PoolMessage, PoolMode};
+
 mod router;
 
+mod stats;
+pub use stats::{InstanceStats, ProverStats, HASHRATE_WINDOWS_IN_SECS};
+
+mod threads;
+use threads::PuzzleThreads;
+
 use crate::traits::NodeInterface;
 use snarkos_account::Account;
 use snarkos_node_bft::ledger_service::ProverLedgerService;
@@ -23,6 +33,7 @@ use snarkos_node_router::{
     Inbound,
     Outbound,
     Router,
+    RouterConfig,
     Routing,
 };
 use snarkos_node_sync::{BlockSync, BlockSyncMode};
@@ -36,12 +47,13 @@ use snarkvm::{
         block::{Block, Header},
         coinbase::{CoinbasePuzzle, EpochChallenge, ProverSolution},
         store::ConsensusStorage,
+        Address,
         Network,
     },
 };
 
 use aleo_std::StorageMode;
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use colored::Colorize;
 use core::{marker::PhantomData, time::Duration};
 use parking_lot::{Mutex, RwLock};
@@ -55,6 +67,10 @@ use std::{
 };
 use tokio::task::JoinHandle;
 
+/// The interval at which the statistics of the prover are recorded in the metrics.
+#[cfg(feature = "metrics")]
+const PROVER_METRICS_INTERVAL_IN_SECS: u64 = 10;
+
 /// A prover is a light node, capable of producing proofs for consensus.
 #[derive(Clone)]
 pub struct Prover<N: Network, C: ConsensusStorage<N>> {
@@ -74,6 +90,12 @@ pub struct Prover<N: Network, C: ConsensusStorage<N>> {
     puzzle_instances: Arc<AtomicU8>,
     /// The maximum number of puzzle instances.
     max_puzzle_instances: u8,
+    /// The dedicated thread pools of the coinbase puzzle instances.
+    puzzle_threads: Arc<PuzzleThreads>,
+    /// The statistics of the coinbase puzzle instances.
+    stats: Arc<ProverStats>,
+    /// The mining pool, if the prover serves a pool or is a pool worker.
+    pool: Option<Arc<Pool<N>>>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The shutdown signal.
@@ -88,8 +110,12 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         node_ip: SocketAddr,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        router_config: RouterConfig,
         genesis: Block<N>,
         storage_mode: StorageMode,
+        pool_mode: Option<PoolMode>,
+        num_puzzle_instances: Option<u8>,
+        puzzle_cores: Vec<usize>,
     ) -> Result<Self> {
         // Prepare the shutdown flag.
         let shutdown: Arc<AtomicBool> = Default::default();
@@ -108,14 +134,27 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
             NodeType::Prover,
             account,
             trusted_peers,
-            Self::MAXIMUM_NUMBER_OF_PEERS as u16,
+            router_config,
             matches!(storage_mode, StorageMode::Development(_)),
         )
         .await?;
+        // Load the persisted peer rules.
+        router.load_peer_rules(crate::peer_rules_path::<N>(&storage_mode));
+        // Load the persisted peer book.
+        router.load_peer_book(crate::peer_book_path::<N>(&storage_mode));
         // Load the coinbase puzzle.
         let coinbase_puzzle = CoinbasePuzzle::<N>::load()?;
-        // Compute the maximum number of puzzle instances.
-        let max_puzzle_instances = num_cpus::get().saturating_sub(2).clamp(1, 6);
+        // Compute the maximum number of puzzle instances, unless it is specified (or implied by the puzzle cores).
+        let max_puzzle_instances = match (num_puzzle_instances, puzzle_cores.len()) {
+            (Some(num_puzzle_instances), _) => {
+                ensure!(num_puzzle_instances > 0, "The number of puzzle instances must be greater than zero");
+                num_puzzle_instances as usize
+            }
+            (None, 0) => num_cpus::get().saturating_sub(2).clamp(1, 6),
+            (None, num_cores) => num_cores,
+        };
+        // Build the dedicated thread pools of the coinbase puzzle.
+        let puzzle_threads = PuzzleThreads::new(max_puzzle_instances, &puzzle_cores)?;
         // Initialize the node.
         let node = Self {
             router,
@@ -126,14 +165,26 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
             latest_block_header: Default::default(),
             puzzle_instances: Default::default(),
             max_puzzle_instances: u8::try_from(max_puzzle_instances)?,
+            puzzle_threads: Arc::new(puzzle_threads),
+            stats: Arc::new(ProverStats::new(max_puzzle_instances)),
+            pool: pool_mode.as_ref().map(|mode| Arc::new(Pool::new(mode))),
             handles: Default::default(),
             shutdown,
             _phantom: Default::default(),
         };
-        // Initialize the routing.
-        node.initialize_routing().await;
+        // Initialize the routing, unless the prover is a pool worker, which only connects to its pool.
+        if !matches!(pool_mode, Some(PoolMode::Worker { .. })) {
+            node.initialize_routing().await;
+        }
+        // Initialize the pool.
+        if let Some(pool_mode) = pool_mode {
+            node.initialize_pool(pool_mode).await?;
+        }
         // Initialize the coinbase puzzle.
         node.initialize_coinbase_puzzle().await;
+        // Initialize the prover metrics.
+        #[cfg(feature = "metrics")]
+        node.initialize_metrics();
         // Initialize the notification message loop.
         node.handles.lock().push(crate::start_notification_message_loop());
         // Pass the node to the signal handler.
@@ -165,21 +216,62 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Prover<N, C> {
 }
 
 impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
+    /// Returns the statistics of the coinbase puzzle instances.
+    pub fn stats(&self) -> &Arc<ProverStats> {
+        &self.stats
+    }
+
     /// Initialize a new instance of the coinbase puzzle.
     async fn initialize_coinbase_puzzle(&self) {
-        for _ in 0..self.max_puzzle_instances {
+        for instance in 0..self.max_puzzle_instances {
             let prover = self.clone();
             self.handles.lock().push(tokio::spawn(async move {
-                prover.coinbase_puzzle_loop().await;
+                prover.coinbase_puzzle_loop(instance as usize).await;
             }));
         }
     }
 
+    /// Initializes a loop that periodically records the statistics of the prover in the metrics.
+    #[cfg(feature = "metrics")]
+    fn initialize_metrics(&self) {
+        let stats = self.stats.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(PROVER_METRICS_INTERVAL_IN_SECS));
+            loop {
+                interval.tick().await;
+                // Record the hashrate over each window.
+                let [window_1m, window_15m, window_1h] = HASHRATE_WINDOWS_IN_SECS;
+                metrics::gauge(metrics::prover::HASHRATE_1M, stats.hashrate(window_1m));
+                metrics::gauge(metrics::prover::HASHRATE_15M, stats.hashrate(window_15m));
+                metrics::gauge(metrics::prover::HASHRATE_1H, stats.hashrate(window_1h));
+                // Record the statistics of each puzzle instance.
+                for (instance, instance_stats) in stats.instances().iter().enumerate() {
+                    let labels = [("instance", instance.to_string())];
+                    metrics::gauge_with_labels(
+                        metrics::prover::PROOFS_ATTEMPTED,
+                        instance_stats.proofs_attempted as f64,
+                        &labels,
+                    );
+                    metrics::gauge_with_labels(
+                        metrics::prover::SOLUTIONS_FOUND,
+                        instance_stats.solutions_found as f64,
+                        &labels,
+                    );
+                    metrics::gauge_with_labels(
+                        metrics::prover::BEST_TARGET,
+                        instance_stats.best_target as f64,
+                        &labels,
+                    );
+                }
+            }
+        }));
+    }
+
     /// Executes an instance of the coinbase puzzle.
-    async fn coinbase_puzzle_loop(&self) {
+    async fn coinbase_puzzle_loop(&self, instance: usize) {
         loop {
-            // If the node is not connected to any peers, then skip this iteration.
-            if self.router.number_of_connected_peers() == 0 {
+            // If the node is not connected to any peers (or to its pool), then skip this iteration.
+            if self.pool_worker().is_none() && self.router.number_of_connected_peers() == 0 {
                 trace!("Skipping an iteration of the coinbase puzzle (no connected peers)");
                 tokio::time::sleep(Duration::from_secs(N::ANCHOR_TIME as u64)).await;
                 continue;
@@ -192,29 +284,46 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
                 continue;
             }
 
-            // Read the latest epoch challenge.
-            let latest_epoch_challenge = self.latest_epoch_challenge.read().clone();
-            // Read the latest state.
-            let latest_state = self
-                .latest_block_header
-                .read()
-                .as_ref()
-                .map(|header| (header.coinbase_target(), header.proof_target()));
-
-            // If the latest epoch challenge and latest state exists, then proceed to generate a prover solution.
-            if let (Some(challenge), Some((coinbase_target, proof_target))) = (latest_epoch_challenge, latest_state) {
-                // Execute the coinbase puzzle.
+            // If the latest puzzle state exists, then proceed to generate a prover solution.
+            if let Some((challenge, address, coinbase_target, proof_target)) = self.latest_puzzle_state() {
+                // Execute the coinbase puzzle, on the dedicated thread pool of this instance.
+                // Note: The puzzle instances are counted here, so that a panicking iteration is not left counted.
+                self.increment_puzzle_instances();
                 let prover = self.clone();
-                let result = tokio::task::spawn_blocking(move || {
-                    prover.coinbase_puzzle_iteration(&challenge, coinbase_target, proof_target, &mut OsRng)
-                })
-                .await;
-
-                // If the prover found a solution, then broadcast it.
-                if let Ok(Some((solution_target, solution))) = result {
-                    info!("Found a Solution '{}' (Proof Target {solution_target})", solution.commitment());
-                    // Broadcast the prover solution.
-                    self.broadcast_prover_solution(solution);
+                let result = self
+                    .puzzle_threads
+                    .execute(instance, move || {
+                        prover.coinbase_puzzle_iteration(
+                            instance,
+                            &challenge,
+                            address,
+                            coinbase_target,
+                            proof_target,
+                            &mut OsRng,
+                        )
+                    })
+                    .await;
+                self.decrement_puzzle_instances();
+
+                match result {
+                    // If the prover found a solution, then submit it to the pool, or broadcast it.
+                    Ok(Some((solution_target, solution))) => {
+                        if self.pool_worker().is_some() {
+                            debug!("Found a Share '{}' (Proof Target {solution_target})", solution.commitment());
+                            // Submit the share to the pool.
+                            self.submit_pool_share(solution);
+                        } else {
+                            info!("Found a Solution '{}' (Proof Target {solution_target})", solution.commitment());
+                            // Broadcast the prover solution.
+                            self.broadcast_prover_solution(solution);
+                        }
+                    }
+                    Ok(None) => (),
+                    // If the iteration failed, then back off for a brief period of time, before retrying.
+                    Err(error) => {
+                        warn!("Failed to execute the coinbase puzzle - {error}");
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
                 }
             } else {
                 // Otherwise, sleep for a brief period of time, to await for puzzle state.
@@ -229,17 +338,30 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         }
     }
 
-    /// Performs one iteration of the coinbase puzzle.
+    /// Returns the latest puzzle state, as `(epoch challenge, address, coinbase target, proof target)`.
+    /// If the prover is a pool worker, the puzzle state is the job of its pool, for the pool address.
+    fn latest_puzzle_state(&self) -> Option<(Arc<EpochChallenge<N>>, Address<N>, u64, u64)> {
+        if self.pool_worker().is_some() {
+            return self.pool_job();
+        }
+        // Read the latest epoch challenge.
+        let latest_epoch_challenge = self.latest_epoch_challenge.read().clone()?;
+        // Read the latest state.
+        let (coinbase_target, proof_target) =
+            self.latest_block_header.read().as_ref().map(|header| (header.coinbase_target(), header.proof_target()))?;
+        Some((latest_epoch_challenge, self.address(), coinbase_target, proof_target))
+    }
+
+    /// Performs one iteration of the coinbase puzzle, for the given address.
     fn coinbase_puzzle_iteration<R: Rng + CryptoRng>(
         &self,
+        instance: usize,
         epoch_challenge: &EpochChallenge<N>,
+        address: Address<N>,
         coinbase_target: u64,
         proof_target: u64,
         rng: &mut R,
     ) -> Option<(u64, ProverSolution<N>)> {
-        // Increment the puzzle instances.
-        self.increment_puzzle_instances();
-
         trace!(
             "Proving 'CoinbasePuzzle' {}",
             format!(
@@ -249,17 +371,21 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
             .dimmed()
         );
 
-        // Compute the prover solution.
+        // Compute the prover solution, without a minimum target, in order to record the best target.
         let result = self
             .coinbase_puzzle
-            .prove(epoch_challenge, self.address(), rng.gen(), Some(proof_target))
+            .prove(epoch_challenge, address, rng.gen(), None)
             .ok()
             .and_then(|solution| solution.to_target().ok().map(|solution_target| (solution_target, solution)));
-
-        // Decrement the puzzle instances.
-        self.decrement_puzzle_instances();
-        // Return the result.
-        result
+        // Record the attempt in the statistics.
+        self.stats.record_attempt(
+            instance,
+            epoch_challenge.epoch_number(),
+            result.as_ref().map(|(solution_target, _)| *solution_target),
+            proof_target,
+        );
+        // Only keep the solution if it meets the proof target.
+        result.filter(|(solution_target, _)| *solution_target >= proof_target)
     }
 
     /// Broadcasts the prover solution to the network.
@@ -0,0 +1,669 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::coinbase::{CoinbasePuzzle, CoinbaseVerifyingKey, PuzzleCommitment};
+
+use anyhow::{anyhow, bail, ensure};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, Semaphore},
+};
+use tokio_util::codec::{Framed, LinesCodec};
+
+/// The maximum length of a pool message, in bytes.
+const MAX_POOL_MESSAGE_LENGTH: usize = 64 * 1024;
+/// The divisor of the network proof target, which determines the proof target of the pool shares.
+/// Note: A lower share target lets the pool account for the work of each worker, between solutions.
+const SHARE_TARGET_DIVISOR: u64 = 8;
+/// The interval at which the pool server checks for a new job, in milliseconds.
+const POOL_JOB_INTERVAL_IN_MS: u64 = 1000;
+/// The delay before a pool worker reconnects to the pool server, in seconds.
+const POOL_RECONNECT_DELAY_IN_SECS: u64 = 5;
+/// The maximum number of outbound messages buffered for each pool connection.
+const MAX_POOL_QUEUE_DEPTH: usize = 64;
+/// The maximum number of concurrent connections to the pool server.
+const MAX_POOL_WORKERS: usize = 256;
+/// The time a connection to the pool server has to subscribe, in seconds.
+const POOL_SUBSCRIBE_TIMEOUT_IN_SECS: u64 = 10;
+
+/// The mode of a prover in a mining pool.
+/// Note: The pool workers authenticate to the pool server with a shared token.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PoolMode {
+    /// The prover serves the pool at the given address, handing out jobs to remote pool workers.
+    Server { listener_ip: SocketAddr, token: String },
+    /// The prover is a pool worker, which connects to the pool server at the given address,
+    /// instead of the P2P network.
+    Worker { server_ip: SocketAddr, token: String },
+}
+
+/// A message of the pool protocol, sent as one line of JSON over TCP.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", bound = "")]
+pub enum PoolMessage<N: Network> {
+    /// A worker subscribes to the jobs of the pool, with the token of the pool.
+    Subscribe { worker_name: String, token: String },
+    /// The pool hands out a job, for which the worker computes solutions for the pool address.
+    Job {
+        epoch_number: u32,
+        epoch_block_hash: N::BlockHash,
+        degree: u32,
+        pool_address: Address<N>,
+        coinbase_target: u64,
+        proof_target: u64,
+    },
+    /// A worker submits a share.
+    Submit { solution: ProverSolution<N> },
+    /// The pool accepted the share.
+    Accepted { commitment: PuzzleCommitment<N> },
+    /// The pool rejected the share.
+    Rejected { reason: String },
+}
+
+/// The job of a pool worker, as `(epoch challenge, pool address, coinbase target, proof target)`.
+type PoolJob<N> = (Arc<EpochChallenge<N>>, Address<N>, u64, u64);
+
+/// The state of a pool worker, as seen by the pool server.
+struct PoolWorkerInfo<N: Network> {
+    /// The name of the worker.
+    name: String,
+    /// The sender for the outbound messages to the worker.
+    sender: mpsc::Sender<PoolMessage<N>>,
+    /// The number of accepted shares.
+    num_shares: u64,
+}
+
+/// The state of the pool server.
+pub(crate) struct PoolServer<N: Network> {
+    /// The token that authenticates the workers.
+    token: String,
+    /// The current job, as `(job message, epoch challenge, share target, network proof target)`.
+    job: RwLock<Option<(PoolMessage<N>, Arc<EpochChallenge<N>>, u64, u64)>>,
+    /// The connected workers.
+    workers: RwLock<HashMap<SocketAddr, PoolWorkerInfo<N>>>,
+    /// The shares accepted in the current epoch.
+    shares: Mutex<HashSet<PuzzleCommitment<N>>>,
+    /// The permits of the connections, bounding the number of concurrent connections.
+    connections: Arc<Semaphore>,
+}
+
+/// The state of a pool worker.
+pub(crate) struct PoolWorker<N: Network> {
+    /// The token that authenticates the worker to the pool server.
+    token: String,
+    /// The current job.
+    job: RwLock<Option<PoolJob<N>>>,
+    /// The sender for the shares to submit, if the worker is connected.
+    sender: RwLock<Option<mpsc::Sender<ProverSolution<N>>>>,
+}
+
+/// The pool of a prover.
+pub(crate) enum Pool<N: Network> {
+    /// The pool server.
+    Server(Arc<PoolServer<N>>),
+    /// The pool worker.
+    Worker(PoolWorker<N>),
+}
+
+impl<N: Network> Pool<N> {
+    /// Initializes the pool for the given mode.
+    pub(crate) fn new(mode: &PoolMode) -> Self {
+        match mode {
+            PoolMode::Server { token, .. } => Self::Server(Arc::new(PoolServer::new(token.clone()))),
+            PoolMode::Worker { token, .. } => Self::Worker(PoolWorker::new(token.clone())),
+        }
+    }
+}
+
+impl<N: Network> PoolServer<N> {
+    /// Initializes the pool server, with the given token.
+    fn new(token: String) -> Self {
+        Self {
+            token,
+            job: Default::default(),
+            workers: Default::default(),
+            shares: Default::default(),
+            connections: Arc::new(Semaphore::new(MAX_POOL_WORKERS)),
+        }
+    }
+
+    /// Returns `true` if the given token matches the token of the pool.
+    fn authenticate(&self, token: &str) -> bool {
+        // Note: The tokens are compared in constant time, to avoid leaking the token through the timing.
+        self.token.len() == token.len()
+            && self.token.bytes().zip(token.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+
+    /// Updates the job of the pool, and returns the job message if it changed.
+    fn update_job(
+        &self,
+        epoch_challenge: Arc<EpochChallenge<N>>,
+        pool_address: Address<N>,
+        coinbase_target: u64,
+        proof_target: u64,
+    ) -> Option<PoolMessage<N>> {
+        // Prepare the job.
+        let share_target = (proof_target / SHARE_TARGET_DIVISOR).max(1);
+        let job = PoolMessage::Job {
+            epoch_number: epoch_challenge.epoch_number(),
+            epoch_block_hash: epoch_challenge.epoch_block_hash(),
+            degree: epoch_challenge.degree(),
+            pool_address,
+            coinbase_target,
+            proof_target: share_target,
+        };
+        let mut current = self.job.write();
+        // If the job is unchanged, return early.
+        if current.as_ref().map(|(job, ..)| job) == Some(&job) {
+            return None;
+        }
+        // If the epoch changed, reset the shares.
+        if current.as_ref().map(|(_, challenge, ..)| challenge.epoch_number()) != Some(epoch_challenge.epoch_number()) {
+            self.shares.lock().clear();
+        }
+        // Store the job.
+        *current = Some((job.clone(), epoch_challenge, share_target, proof_target));
+        Some(job)
+    }
+
+    /// Validates the given share for the current job, and records it.
+    /// Returns `true` if the share also meets the network proof target.
+    fn check_share(
+        &self,
+        solution: &ProverSolution<N>,
+        pool_address: Address<N>,
+        verifying_key: &CoinbaseVerifyingKey<N>,
+    ) -> Result<bool> {
+        let Some((_, epoch_challenge, share_target, proof_target)) = self.job.read().clone() else {
+            bail!("The pool has no job")
+        };
+        // Ensure the share is for the pool address.
+        ensure!(solution.address() == pool_address, "The share is not for the pool address");
+        // Ensure the share is new.
+        let commitment = solution.commitment();
+        ensure!(!self.shares.lock().contains(&commitment), "The share is a duplicate");
+        // Ensure the share is valid for the share target.
+        ensure!(
+            solution.verify(verifying_key, &epoch_challenge, share_target)?,
+            "The share is invalid for the current job"
+        );
+        // Record the share.
+        ensure!(self.shares.lock().insert(commitment), "The share is a duplicate");
+        // Return whether the share meets the network proof target.
+        Ok(solution.to_target()? >= proof_target)
+    }
+}
+
+impl<N: Network> PoolServer<N> {
+    /// Accepts the pool workers on the given listener, and handles their connections.
+    /// The shares of the workers are verified with the given coinbase puzzle, for the given pool address,
+    /// and the shares that meet the network proof target are sent to the given sender, as solutions.
+    async fn serve(
+        self: Arc<Self>,
+        listener: TcpListener,
+        coinbase_puzzle: CoinbasePuzzle<N>,
+        pool_address: Address<N>,
+        solutions: mpsc::Sender<ProverSolution<N>>,
+    ) {
+        loop {
+            let (stream, worker_addr) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(error) => {
+                    warn!("Failed to accept a pool worker - {error}");
+                    continue;
+                }
+            };
+            // Ensure the number of connected workers is bounded.
+            let Ok(permit) = self.connections.clone().try_acquire_owned() else {
+                debug!("Dropping pool worker '{worker_addr}' (too many pool workers)");
+                continue;
+            };
+            let server = self.clone();
+            let coinbase_puzzle = coinbase_puzzle.clone();
+            let solutions = solutions.clone();
+            tokio::spawn(async move {
+                if let Err(error) =
+                    server.handle_worker(stream, worker_addr, &coinbase_puzzle, pool_address, &solutions).await
+                {
+                    debug!("Pool worker '{worker_addr}' disconnected - {error}");
+                }
+                server.remove_worker(worker_addr);
+                drop(permit);
+            });
+        }
+    }
+
+    /// Handles the connection of a pool worker.
+    async fn handle_worker(
+        self: &Arc<Self>,
+        stream: TcpStream,
+        worker_addr: SocketAddr,
+        coinbase_puzzle: &CoinbasePuzzle<N>,
+        pool_address: Address<N>,
+        solutions: &mpsc::Sender<ProverSolution<N>>,
+    ) -> Result<()> {
+        let mut framed = Framed::new(stream, LinesCodec::new_with_max_length(MAX_POOL_MESSAGE_LENGTH));
+
+        // Receive the subscription, ensuring an idle connection does not hold a slot of the pool.
+        let subscription = tokio::time::timeout(
+            Duration::from_secs(POOL_SUBSCRIBE_TIMEOUT_IN_SECS),
+            read_pool_message::<N>(&mut framed),
+        )
+        .await
+        .map_err(|_| anyhow!("Pool worker '{worker_addr}' did not subscribe in time"))??;
+        let worker_name = match subscription {
+            PoolMessage::Subscribe { worker_name, token } => {
+                // Ensure the worker is authenticated.
+                if !self.authenticate(&token) {
+                    let reason = "Invalid pool token".to_string();
+                    write_pool_message(&mut framed, &PoolMessage::<N>::Rejected { reason }).await?;
+                    bail!("Pool worker '{worker_addr}' sent an invalid pool token");
+                }
+                worker_name
+            }
+            message => bail!("Expected a subscription, received {message:?}"),
+        };
+        info!("Pool worker '{worker_name}' connected from '{worker_addr}'");
+
+        // Register the worker, and send it the current job.
+        let (sender, mut receiver) = mpsc::channel(MAX_POOL_QUEUE_DEPTH);
+        if let Some((job, ..)) = self.job.read().as_ref() {
+            let _ = sender.try_send(job.clone());
+        }
+        self.workers.write().insert(worker_addr, PoolWorkerInfo { name: worker_name, sender, num_shares: 0 });
+
+        loop {
+            tokio::select! {
+                // Send the outbound messages.
+                Some(message) = receiver.recv() => write_pool_message(&mut framed, &message).await?,
+                // Process the submitted shares.
+                message = read_pool_message::<N>(&mut framed) => {
+                    let response = match message? {
+                        PoolMessage::Submit { solution } => {
+                            match self.process_share(worker_addr, solution, coinbase_puzzle, pool_address, solutions).await {
+                                Ok(()) => PoolMessage::Accepted { commitment: solution.commitment() },
+                                Err(error) => PoolMessage::Rejected { reason: error.to_string() },
+                            }
+                        }
+                        message => bail!("Expected a share, received {message:?}"),
+                    };
+                    write_pool_message(&mut framed, &response).await?;
+                }
+            }
+        }
+    }
+
+    /// Removes the given pool worker.
+    fn remove_worker(&self, worker_addr: SocketAddr) {
+        if let Some(worker) = self.workers.write().remove(&worker_addr) {
+            info!("Pool worker '{}' disconnected ({} shares)", worker.name, worker.num_shares);
+        }
+    }
+
+    /// Validates the given share, and sends it as a solution if it meets the network proof target.
+    async fn process_share(
+        self: &Arc<Self>,
+        worker_addr: SocketAddr,
+        solution: ProverSolution<N>,
+        coinbase_puzzle: &CoinbasePuzzle<N>,
+        pool_address: Address<N>,
+        solutions: &mpsc::Sender<ProverSolution<N>>,
+    ) -> Result<()> {
+        // Validate and record the share.
+        let server = self.clone();
+        let coinbase_puzzle = coinbase_puzzle.clone();
+        let is_solution = tokio::task::spawn_blocking(move || {
+            server.check_share(&solution, pool_address, coinbase_puzzle.coinbase_verifying_key())
+        })
+        .await??;
+        if let Some(worker) = self.workers.write().get_mut(&worker_addr) {
+            worker.num_shares += 1;
+            trace!("Accepted a share from pool worker '{}' ({} shares)", worker.name, worker.num_shares);
+        }
+        // If the share meets the network proof target, send it as a solution.
+        if is_solution {
+            info!("Pool worker '{worker_addr}' found a Solution '{}'", solution.commitment());
+            if let Err(error) = solutions.try_send(solution) {
+                warn!("Failed to propagate the solution of pool worker '{worker_addr}' - {error}");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> PoolWorker<N> {
+    /// Initializes the pool worker, with the given token.
+    fn new(token: String) -> Self {
+        Self { token, job: Default::default(), sender: Default::default() }
+    }
+
+    /// Connects to the pool server under the given name, and processes the jobs until the connection is lost.
+    async fn connect(&self, server_ip: SocketAddr, worker_name: String) -> Result<()> {
+        let stream = TcpStream::connect(server_ip).await?;
+        let mut framed = Framed::new(stream, LinesCodec::new_with_max_length(MAX_POOL_MESSAGE_LENGTH));
+
+        // Subscribe to the jobs of the pool.
+        let subscribe = PoolMessage::<N>::Subscribe { worker_name, token: self.token.clone() };
+        write_pool_message(&mut framed, &subscribe).await?;
+        info!("Connected to the pool '{server_ip}'");
+
+        // Prepare the sender for the shares.
+        let (sender, mut receiver) = mpsc::channel(MAX_POOL_QUEUE_DEPTH);
+        self.sender.write().replace(sender);
+
+        loop {
+            tokio::select! {
+                // Submit the shares.
+                Some(solution) = receiver.recv() => {
+                    write_pool_message(&mut framed, &PoolMessage::Submit { solution }).await?;
+                }
+                // Process the messages of the pool.
+                message = read_pool_message::<N>(&mut framed) => match message? {
+                    PoolMessage::Job { epoch_number, epoch_block_hash, degree, pool_address, coinbase_target, proof_target } => {
+                        let epoch_challenge = tokio::task::spawn_blocking(move || {
+                            EpochChallenge::new(epoch_number, epoch_block_hash, degree)
+                        })
+                        .await??;
+                        info!(
+                            "Pool Job (Epoch {epoch_number}, Coinbase Target {coinbase_target}, Proof Target {proof_target})"
+                        );
+                        self.job.write().replace((Arc::new(epoch_challenge), pool_address, coinbase_target, proof_target));
+                    }
+                    PoolMessage::Accepted { commitment } => debug!("The pool accepted the share '{commitment}'"),
+                    PoolMessage::Rejected { reason } => warn!("The pool rejected a share - {reason}"),
+                    message => bail!("Unexpected pool message {message:?}"),
+                },
+            }
+        }
+    }
+
+    /// Clears the state of the connection to the pool server.
+    fn disconnect(&self) {
+        self.job.write().take();
+        self.sender.write().take();
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
+    /// Returns the pool worker state, if the prover is a pool worker.
+    pub(crate) fn pool_worker(&self) -> Option<&PoolWorker<N>> {
+        match self.pool.as_deref() {
+            Some(Pool::Worker(worker)) => Some(worker),
+            _ => None,
+        }
+    }
+
+    /// Returns the pool server state, if the prover serves a pool.
+    fn pool_server(&self) -> Option<&Arc<PoolServer<N>>> {
+        match self.pool.as_deref() {
+            Some(Pool::Server(server)) => Some(server),
+            _ => None,
+        }
+    }
+
+    /// Returns the current job of the pool worker.
+    pub(crate) fn pool_job(&self) -> Option<PoolJob<N>> {
+        self.pool_worker().and_then(|worker| worker.job.read().clone())
+    }
+
+    /// Submits the given share to the pool server.
+    pub(crate) fn submit_pool_share(&self, solution: ProverSolution<N>) {
+        let sender = self.pool_worker().and_then(|worker| worker.sender.read().clone());
+        match sender {
+            Some(sender) => {
+                if let Err(error) = sender.try_send(solution) {
+                    warn!("Failed to submit a share to the pool - {error}");
+                }
+            }
+            None => warn!("Failed to submit a share to the pool - not connected"),
+        }
+    }
+
+    /// Initializes the pool, for the given mode.
+    pub(crate) async fn initialize_pool(&self, mode: PoolMode) -> Result<()> {
+        match mode {
+            PoolMode::Server { listener_ip, .. } => {
+                let Some(server) = self.pool_server().cloned() else { bail!("The prover does not serve a pool") };
+                // Bind the pool server.
+                let listener = TcpListener::bind(listener_ip).await?;
+                info!("Listening for pool workers at '{}'", listener.local_addr()?);
+                // Accept the pool workers.
+                let (solutions_sender, mut solutions_receiver) = mpsc::channel(MAX_POOL_QUEUE_DEPTH);
+                let serve = server.serve(listener, self.coinbase_puzzle.clone(), self.address(), solutions_sender);
+                self.handles.lock().push(tokio::spawn(serve));
+                // Propagate the solutions found by the pool workers.
+                let prover = self.clone();
+                self.handles.lock().push(tokio::spawn(async move {
+                    while let Some(solution) = solutions_receiver.recv().await {
+                        prover.broadcast_prover_solution(solution);
+                    }
+                }));
+                // Hand out the jobs to the pool workers.
+                let prover = self.clone();
+                self.handles.lock().push(tokio::spawn(async move {
+                    loop {
+                        prover.update_pool_job();
+                        tokio::time::sleep(Duration::from_millis(POOL_JOB_INTERVAL_IN_MS)).await;
+                    }
+                }));
+            }
+            PoolMode::Worker { server_ip, .. } => {
+                // Connect to the pool server, and reconnect whenever the connection is lost.
+                let prover = self.clone();
+                self.handles.lock().push(tokio::spawn(async move {
+                    let Some(worker) = prover.pool_worker() else { return };
+                    loop {
+                        if let Err(error) = worker.connect(server_ip, prover.address().to_string()).await {
+                            warn!("Disconnected from the pool '{server_ip}' - {error}");
+                        }
+                        // Clear the state of the connection.
+                        worker.disconnect();
+                        tokio::time::sleep(Duration::from_secs(POOL_RECONNECT_DELAY_IN_SECS)).await;
+                    }
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the job of the pool from the latest puzzle state, and hands it out to the pool workers if it changed.
+    fn update_pool_job(&self) {
+        let Some(server) = self.pool_server() else { return };
+        // Retrieve the latest puzzle state.
+        let Some(epoch_challenge) = self.latest_epoch_challenge.read().clone() else { return };
+        let Some((coinbase_target, proof_target)) =
+            self.latest_block_header.read().as_ref().map(|header| (header.coinbase_target(), header.proof_target()))
+        else {
+            return;
+        };
+        // Update the job, and hand it out to the workers if it changed.
+        let Some(job) = server.update_job(epoch_challenge, self.address(), coinbase_target, proof_target) else {
+            return;
+        };
+        for (worker_addr, worker) in server.workers.read().iter() {
+            if let Err(error) = worker.sender.try_send(job.clone()) {
+                warn!("Failed to send a job to pool worker '{worker_addr}' - {error}");
+            }
+        }
+    }
+}
+
+/// Reads the next pool message from the given stream.
+async fn read_pool_message<N: Network>(framed: &mut Framed<TcpStream, LinesCodec>) -> Result<PoolMessage<N>> {
+    let line = framed.next().await.ok_or_else(|| anyhow!("The connection was closed"))??;
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// Writes the given pool message to the given stream.
+async fn write_pool_message<N: Network>(
+    framed: &mut Framed<TcpStream, LinesCodec>,
+    message: &PoolMessage<N>,
+) -> Result<()> {
+    framed.send(serde_json::to_string(message)?).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{
+        coinbase::{CoinbasePuzzle, PuzzleConfig},
+        MainnetV0,
+        PrivateKey,
+        TestRng,
+    };
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns a coinbase puzzle and an epoch challenge of a small degree, for the given epoch.
+    fn sample_puzzle(epoch_number: u32) -> (CoinbasePuzzle<CurrentNetwork>, Arc<EpochChallenge<CurrentNetwork>>) {
+        let degree = (1 << 8) - 1;
+        let srs = CoinbasePuzzle::<CurrentNetwork>::setup(PuzzleConfig { degree: 1 << 10 }).unwrap();
+        let coinbase_puzzle = CoinbasePuzzle::<CurrentNetwork>::trim(&srs, PuzzleConfig { degree }).unwrap();
+        let epoch_challenge = EpochChallenge::new(epoch_number, Default::default(), degree).unwrap();
+        (coinbase_puzzle, Arc::new(epoch_challenge))
+    }
+
+    #[test]
+    fn test_pool_message_serialization() {
+        let rng = &mut TestRng::default();
+        let pool_address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+
+        let messages = [
+            PoolMessage::Subscribe { worker_name: "worker".to_string(), token: "secret".to_string() },
+            PoolMessage::Job {
+                epoch_number: 1,
+                epoch_block_hash: Default::default(),
+                degree: 8191,
+                pool_address,
+                coinbase_target: 1 << 20,
+                proof_target: 1 << 10,
+            },
+            PoolMessage::Rejected { reason: "The share is a duplicate".to_string() },
+        ];
+        for message in messages {
+            let line = serde_json::to_string(&message).unwrap();
+            assert!(!line.contains('\n'));
+            assert_eq!(serde_json::from_str::<PoolMessage<CurrentNetwork>>(&line).unwrap(), message);
+        }
+        assert_eq!(
+            serde_json::to_string(&PoolMessage::<CurrentNetwork>::Subscribe {
+                worker_name: "worker".to_string(),
+                token: "secret".to_string()
+            })
+            .unwrap(),
+            r#"{"type":"subscribe","worker_name":"worker","token":"secret"}"#
+        );
+    }
+
+    #[test]
+    fn test_pool_server_authenticate() {
+        let server = PoolServer::<CurrentNetwork>::new("secret".to_string());
+        assert!(server.authenticate("secret"));
+        assert!(!server.authenticate("secreT"));
+        assert!(!server.authenticate("secret2"));
+        assert!(!server.authenticate(""));
+    }
+
+    #[test]
+    fn test_pool_server_shares() {
+        let rng = &mut TestRng::default();
+        let pool_address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let other_address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let (coinbase_puzzle, epoch_challenge) = sample_puzzle(1);
+        let verifying_key = coinbase_puzzle.coinbase_verifying_key();
+        let server = PoolServer::<CurrentNetwork>::new("secret".to_string());
+
+        // Ensure a share is rejected if the pool has no job.
+        let share = coinbase_puzzle.prove(&epoch_challenge, pool_address, rng.gen(), None).unwrap();
+        assert!(server.check_share(&share, pool_address, verifying_key).is_err());
+
+        // Ensure the job is only handed out when it changes.
+        let job = server.update_job(epoch_challenge.clone(), pool_address, 1 << 10, 1).unwrap();
+        assert!(matches!(job, PoolMessage::Job { epoch_number: 1, proof_target: 1, .. }));
+        assert!(server.update_job(epoch_challenge.clone(), pool_address, 1 << 10, 1).is_none());
+
+        // Ensure a valid share is accepted once, and meets the network proof target of 1.
+        assert!(server.check_share(&share, pool_address, verifying_key).unwrap());
+        assert!(server.check_share(&share, pool_address, verifying_key).is_err());
+
+        // Ensure a share for another address is rejected.
+        let share = coinbase_puzzle.prove(&epoch_challenge, other_address, rng.gen(), None).unwrap();
+        assert!(server.check_share(&share, pool_address, verifying_key).is_err());
+
+        // Ensure a share of the previous epoch is rejected, once the job moves to a new epoch.
+        let share = coinbase_puzzle.prove(&epoch_challenge, pool_address, rng.gen(), None).unwrap();
+        let (_, next_epoch_challenge) = sample_puzzle(2);
+        assert!(server.update_job(next_epoch_challenge.clone(), pool_address, 1 << 10, 1).is_some());
+        assert!(server.shares.lock().is_empty());
+        assert!(server.check_share(&share, pool_address, verifying_key).is_err());
+        let share = coinbase_puzzle.prove(&next_epoch_challenge, pool_address, rng.gen(), None).unwrap();
+        assert!(server.check_share(&share, pool_address, verifying_key).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_pool_server_and_worker() {
+        let rng = &mut TestRng::default();
+        let pool_address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let (coinbase_puzzle, epoch_challenge) = sample_puzzle(1);
+
+        // Serve a pool with a job, whose network proof target is 1.
+        let server = Arc::new(PoolServer::<CurrentNetwork>::new("secret".to_string()));
+        server.update_job(epoch_challenge, pool_address, 1 << 10, 1).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_ip = listener.local_addr().unwrap();
+        let (solutions_sender, mut solutions_receiver) = mpsc::channel(1);
+        tokio::spawn(server.clone().serve(listener, coinbase_puzzle.clone(), pool_address, solutions_sender));
+
+        // Ensure a worker with an invalid token is rejected, and disconnected.
+        let worker = PoolWorker::<CurrentNetwork>::new("wrong".to_string());
+        let result = tokio::time::timeout(Duration::from_secs(10), worker.connect(server_ip, "intruder".to_string()));
+        assert!(result.await.unwrap().is_err());
+        assert!(worker.job.read().is_none());
+        assert!(server.workers.read().is_empty());
+
+        // Connect a worker with the token of the pool, and wait for its job.
+        let worker = Arc::new(PoolWorker::<CurrentNetwork>::new("secret".to_string()));
+        let worker_ = worker.clone();
+        tokio::spawn(async move { worker_.connect(server_ip, "worker".to_string()).await });
+        let job = tokio::time::timeout(Duration::from_secs(60), async {
+            loop {
+                if let Some(job) = worker.job.read().clone() {
+                    break job;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        let (epoch_challenge, job_address, coinbase_target, share_target) = job;
+        assert_eq!(epoch_challenge.epoch_number(), 1);
+        assert_eq!((job_address, coinbase_target, share_target), (pool_address, 1 << 10, 1));
+        assert_eq!(server.workers.read().len(), 1);
+
+        // Submit a share, which meets the network proof target, and is propagated as a solution.
+        let share = coinbase_puzzle.prove(&epoch_challenge, pool_address, rng.gen(), None).unwrap();
+        let sender = worker.sender.read().clone().unwrap();
+        sender.send(share).await.unwrap();
+        let solution = tokio::time::timeout(Duration::from_secs(60), solutions_receiver.recv()).await.unwrap();
+        assert_eq!(solution, Some(share));
+        assert!(server.shares.lock().contains(&share.commitment()));
+        assert_eq!(server.workers.read().values().next().unwrap().num_shares, 1);
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::Mutex;
+use std::{collections::VecDeque, time::Instant};
+
+/// The windows (in seconds) over which the hashrate of the prover is reported.
+pub const HASHRATE_WINDOWS_IN_SECS: [u64; 3] = [60, 15 * 60, 60 * 60];
+
+/// The statistics of a single instance of the coinbase puzzle.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstanceStats {
+    /// The number of proofs attempted.
+    pub proofs_attempted: u64,
+    /// The number of solutions found (i.e. proofs that met the proof target).
+    pub solutions_found: u64,
+    /// The epoch number of the best target.
+    pub epoch_number: u32,
+    /// The best target achieved in the epoch.
+    pub best_target: u64,
+}
+
+/// The statistics of the prover, used to report its hashrate and solutions.
+pub struct ProverStats {
+    /// The statistics of each instance of the coinbase puzzle.
+    instances: Vec<Mutex<InstanceStats>>,
+    /// The number of proofs attempted per second, as `(seconds since start, number of proofs)`.
+    attempts: Mutex<VecDeque<(u64, u64)>>,
+    /// The time at which the statistics were initialized.
+    start: Instant,
+}
+
+impl ProverStats {
+    /// Initializes the statistics for the given number of puzzle instances.
+    pub fn new(num_instances: usize) -> Self {
+        Self {
+            instances: (0..num_instances).map(|_| Default::default()).collect(),
+            attempts: Default::default(),
+            start: Instant::now(),
+        }
+    }
+
+    /// Returns the statistics of each instance of the coinbase puzzle.
+    pub fn instances(&self) -> Vec<InstanceStats> {
+        self.instances.iter().map(|instance| *instance.lock()).collect()
+    }
+
+    /// Returns the total number of proofs attempted.
+    pub fn proofs_attempted(&self) -> u64 {
+        self.instances.iter().map(|instance| instance.lock().proofs_attempted).sum()
+    }
+
+    /// Returns the total number of solutions found.
+    pub fn solutions_found(&self) -> u64 {
+        self.instances.iter().map(|instance| instance.lock().solutions_found).sum()
+    }
+
+    /// Returns the number of proofs per second over the given window, in seconds.
+    pub fn hashrate(&self, window_in_secs: u64) -> f64 {
+        let now = self.start.elapsed().as_secs();
+        // Sum the proofs attempted within the window.
+        let num_proofs: u64 = self
+            .attempts
+            .lock()
+            .iter()
+            .filter(|(second, _)| now.saturating_sub(*second) < window_in_secs)
+            .map(|(_, num_proofs)| num_proofs)
+            .sum();
+        // If the prover has not yet run for the full window, only count the elapsed time.
+        let elapsed = window_in_secs.min(now + 1);
+        num_proofs as f64 / elapsed as f64
+    }
+
+    /// Records a proof attempted by the given puzzle instance, with its target, if the proof succeeded.
+    pub fn record_attempt(&self, instance: usize, epoch_number: u32, target: Option<u64>, proof_target: u64) {
+        // Update the statistics of the instance.
+        if let Some(stats) = self.instances.get(instance) {
+            let mut stats = stats.lock();
+            stats.proofs_attempted += 1;
+            // If the epoch changed, then reset the best target.
+            if stats.epoch_number != epoch_number {
+                stats.epoch_number = epoch_number;
+                stats.best_target = 0;
+            }
+            if let Some(target) = target {
+                stats.best_target = stats.best_target.max(target);
+                if target >= proof_target {
+                    stats.solutions_found += 1;
+                }
+            }
+        }
+
+        // Update the number of proofs attempted in the current second.
+        let now = self.start.elapsed().as_secs();
+        let mut attempts = self.attempts.lock();
+        match attempts.back_mut() {
+            Some((second, num_proofs)) if *second == now => *num_proofs += 1,
+            _ => attempts.push_back((now, 1)),
+        }
+        // Prune the attempts that fall outside of the largest window.
+        let max_window = HASHRATE_WINDOWS_IN_SECS[HASHRATE_WINDOWS_IN_SECS.len() - 1];
+        while attempts.front().map_or(false, |(second, _)| now.saturating_sub(*second) >= max_window) {
+            attempts.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_attempt() {
+        let stats = ProverStats::new(2);
+
+        // Record a failed attempt, a proof below the proof target, and a solution.
+        stats.record_attempt(0, 1, None, 100);
+        stats.record_attempt(0, 1, Some(50), 100);
+        stats.record_attempt(1, 1, Some(150), 100);
+
+        let instances = stats.instances();
+        assert_eq!(instances[0], InstanceStats {
+            proofs_attempted: 2,
+            solutions_found: 0,
+            epoch_number: 1,
+            best_target: 50
+        });
+        assert_eq!(instances[1], InstanceStats {
+            proofs_attempted: 1,
+            solutions_found: 1,
+            epoch_number: 1,
+            best_target: 150
+        });
+        assert_eq!(stats.proofs_attempted(), 3);
+        assert_eq!(stats.solutions_found(), 1);
+        assert!(stats.hashrate(HASHRATE_WINDOWS_IN_SECS[0]) > 0.0);
+
+        // Ensure the best target is reset in a new epoch.
+        stats.record_attempt(0, 2, Some(10), 100);
+        assert_eq!(stats.instances()[0].best_target, 10);
+
+        // Ensure an unknown instance is ignored.
+        stats.record_attempt(5, 2, Some(10), 100);
+        assert_eq!(stats.proofs_attempted(), 4);
+    }
+}
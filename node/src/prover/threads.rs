@@ -0,0 +1,138 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, ensure, Result};
+use core_affinity::CoreId;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::Arc;
+
+/// The stack size of each puzzle thread.
+const PUZZLE_THREAD_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// The dedicated thread pools of the coinbase puzzle, one per puzzle instance.
+/// Note: Each proof runs entirely on the pool of its instance, including its parallel parts,
+/// so that the instances do not contend on the global rayon pool, and every proving thread is pinned.
+pub(crate) struct PuzzleThreads {
+    /// The thread pools, indexed by puzzle instance.
+    pools: Vec<Arc<ThreadPool>>,
+}
+
+impl PuzzleThreads {
+    /// Builds a thread pool for each of the given number of puzzle instances.
+    /// If cores are given, they are split across the instances in a round-robin order,
+    /// and each thread of an instance is pinned to one of its cores.
+    /// Otherwise, the available cores are split evenly across the instances.
+    pub(crate) fn new(num_instances: usize, cores: &[usize]) -> Result<Self> {
+        ensure!(num_instances > 0, "The number of puzzle instances must be greater than zero");
+
+        // Resolve the core IDs to pin the threads to.
+        let core_ids = match cores.is_empty() {
+            true => vec![],
+            false => {
+                let available = core_affinity::get_core_ids().unwrap_or_default();
+                cores
+                    .iter()
+                    .map(|core| match available.iter().find(|core_id| core_id.id == *core) {
+                        Some(core_id) => Ok(*core_id),
+                        None => bail!("Core {core} is not available (found {} cores)", available.len()),
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            }
+        };
+
+        let pools = (0..num_instances)
+            .map(|instance| {
+                // Select the cores of this instance; if there are fewer cores than instances, the cores are shared.
+                let instance_cores = match core_ids.len() {
+                    0 => vec![],
+                    num_cores if num_cores >= num_instances => {
+                        core_ids.iter().skip(instance).step_by(num_instances).copied().collect()
+                    }
+                    num_cores => vec![core_ids[instance % num_cores]],
+                };
+                Ok(Arc::new(Self::build(instance, (num_cpus::get() / num_instances).max(1), instance_cores)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { pools })
+    }
+
+    /// Builds the thread pool of the given instance, with one thread per core, or the given number of threads if no cores are given.
+    fn build(instance: usize, default_num_threads: usize, cores: Vec<CoreId>) -> Result<ThreadPool> {
+        let num_threads = match cores.is_empty() {
+            true => default_num_threads,
+            false => cores.len(),
+        };
+        Ok(ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .stack_size(PUZZLE_THREAD_STACK_SIZE)
+            .thread_name(move |index| format!("puzzle-{instance}-{index}"))
+            .start_handler(move |index| {
+                // Pin the thread to its core.
+                if let Some(core_id) = cores.get(index) {
+                    match core_affinity::set_for_current(*core_id) {
+                        true => debug!("Pinned thread {index} of puzzle instance {instance} to core {}", core_id.id),
+                        false => {
+                            warn!("Failed to pin thread {index} of puzzle instance {instance} to core {}", core_id.id)
+                        }
+                    }
+                }
+            })
+            .build()?)
+    }
+
+    /// Executes the given function on the thread pool of the given instance, and returns its output.
+    pub(crate) async fn execute<T: Send + 'static>(
+        &self,
+        instance: usize,
+        function: impl FnOnce() -> T + Send + 'static,
+    ) -> Result<T> {
+        let Some(pool) = self.pools.get(instance).cloned() else {
+            bail!("Puzzle instance {instance} does not exist");
+        };
+        // Run the function inside the pool, so that its parallel parts also run on the threads of this instance.
+        // Note: A panicking function is propagated to the calling thread, and the pool keeps its threads.
+        match tokio::task::spawn_blocking(move || pool.install(function)).await {
+            Ok(output) => Ok(output),
+            Err(error) => bail!("A task of puzzle instance {instance} failed - {error}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[tokio::test]
+    async fn test_puzzle_threads() {
+        let threads = PuzzleThreads::new(2, &[]).unwrap();
+
+        // Ensure each instance executes on its own pool, including its parallel parts.
+        let name = threads.execute(1, || thread::current().name().map(str::to_string)).await.unwrap();
+        assert!(name.unwrap().starts_with("puzzle-1-"));
+        let num_threads = threads.execute(0, rayon::current_num_threads).await.unwrap();
+        assert_eq!(num_threads, (num_cpus::get() / 2).max(1));
+
+        // Ensure a panicking task fails, and the pool keeps executing the next tasks.
+        assert!(threads.execute(0, || panic!("test panic")).await.is_err());
+        assert_eq!(threads.execute(0, || 2 + 2).await.unwrap(), 4);
+
+        // Ensure an unknown instance is rejected.
+        assert!(threads.execute(2, || ()).await.is_err());
+        // Ensure an unknown core is rejected.
+        assert!(PuzzleThreads::new(1, &[usize::MAX]).is_err());
+        // Ensure there is at least one instance.
+        assert!(PuzzleThreads::new(0, &[]).is_err());
+    }
+}
@@ -25,6 +25,7 @@ use snarkos_node_router::{
     Inbound,
     Outbound,
     Router,
+    RouterConfig,
     Routing,
 };
 use snarkos_node_sync::{BlockSync, BlockSyncMode};
@@ -68,6 +69,8 @@ pub struct Validator<N: Network, C: ConsensusStorage<N>> {
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The shutdown signal.
     shutdown: Arc<AtomicBool>,
+    /// The maximum duration to wait on shutdown for the pending batch proposal, if graceful shutdown is enabled.
+    graceful_shutdown: Option<Duration>,
 }
 
 impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
@@ -77,12 +80,17 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         bft_ip: Option<SocketAddr>,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        rest_index: bool,
+        rest_mapping_history: bool,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
         trusted_validators: &[SocketAddr],
+        num_workers: u8,
+        router_config: RouterConfig,
         genesis: Block<N>,
         cdn: Option<String>,
         storage_mode: StorageMode,
+        graceful_shutdown: Option<Duration>,
     ) -> Result<Self> {
         // Prepare the shutdown flag.
         let shutdown: Arc<AtomicBool> = Default::default();
@@ -93,11 +101,15 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         // Initialize the ledger.
         let ledger = Ledger::load(genesis, storage_mode.clone())?;
 
+        // Initialize the CDN sync metrics.
+        #[cfg(feature = "metrics")]
+        let cdn_metrics = crate::start_cdn_metrics_loop();
         // Initialize the CDN.
         if let Some(base_url) = cdn {
-            // Sync the ledger with the CDN.
+            // Sync the ledger with the CDN, caching the bundles that are not yet processed.
+            let cache_dir = Some(crate::cdn_cache_path::<N>(&storage_mode));
             if let Err((_, error)) =
-                snarkos_node_cdn::sync_ledger_with_cdn(&base_url, ledger.clone(), shutdown.clone()).await
+                snarkos_node_cdn::sync_ledger_with_cdn(&base_url, ledger.clone(), cache_dir, shutdown.clone()).await
             {
                 crate::log_clean_error(&storage_mode);
                 return Err(error);
@@ -110,8 +122,18 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         let sync = BlockSync::new(BlockSyncMode::Gateway, ledger_service.clone());
 
         // Initialize the consensus.
-        let mut consensus =
-            Consensus::new(account.clone(), ledger_service, bft_ip, trusted_validators, storage_mode.clone())?;
+        let mut consensus = Consensus::new(
+            account.clone(),
+            ledger_service,
+            bft_ip,
+            trusted_validators,
+            num_workers,
+            storage_mode.clone(),
+        )?;
+        // Load the persisted peer book of the gateway.
+        consensus.bft().primary().gateway().load_peer_book(crate::bft_peer_book_path::<N>(&storage_mode));
+        // Load the persisted round state of the primary.
+        consensus.bft().primary().load_round_state(crate::bft_round_state_path::<N>(&storage_mode)).await?;
         // Initialize the primary channels.
         let (primary_sender, primary_receiver) = init_primary_channels::<N>();
         // Start the consensus.
@@ -123,10 +145,16 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
             NodeType::Validator,
             account,
             trusted_peers,
-            Self::MAXIMUM_NUMBER_OF_PEERS as u16,
+            router_config,
             matches!(storage_mode, StorageMode::Development(_)),
         )
         .await?;
+        // Load the persisted peer rules.
+        router.load_peer_rules(crate::peer_rules_path::<N>(&storage_mode));
+        // Share the banned IPs of the router with the gateway.
+        consensus.bft().primary().gateway().set_ban_list(router.ban_list().clone())?;
+        // Load the persisted peer book.
+        router.load_peer_book(crate::peer_book_path::<N>(&storage_mode));
 
         // Initialize the node.
         let mut node = Self {
@@ -137,14 +165,34 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
             sync,
             handles: Default::default(),
             shutdown,
+            graceful_shutdown,
         };
+        // Initialize the ledger size metric.
+        #[cfg(feature = "metrics")]
+        node.handles.lock().push(crate::start_ledger_size_metric_loop::<N>(&storage_mode));
+        #[cfg(feature = "metrics")]
+        node.handles.lock().push(cdn_metrics);
         // Initialize the transaction pool.
         node.initialize_transaction_pool(storage_mode)?;
 
         // Initialize the REST server.
         if let Some(rest_ip) = rest_ip {
-            node.rest =
-                Some(Rest::start(rest_ip, rest_rps, Some(consensus), ledger.clone(), Arc::new(node.clone())).await?);
+            // Note: Consensus notifies the blocks it commits, so the REST streams also follow the blocks
+            // that the BFT sync advances the ledger to.
+            let blocks = Some(consensus.bft().primary().sync().subscribe_blocks());
+            node.rest = Some(
+                Rest::start(
+                    rest_ip,
+                    rest_rps,
+                    Some(consensus),
+                    ledger.clone(),
+                    Arc::new(node.clone()),
+                    blocks,
+                    rest_index,
+                    rest_mapping_history,
+                )
+                .await?,
+            );
         }
         // Initialize the routing.
         node.initialize_routing().await;
@@ -430,6 +478,12 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Validator<N, C> {
     async fn shut_down(&self) {
         info!("Shutting down...");
 
+        // If graceful shutdown is enabled, finish the current round of the primary.
+        if let Some(timeout) = self.graceful_shutdown {
+            trace!("Finishing the current round...");
+            self.consensus.bft().primary().finish_round(timeout).await;
+        }
+
         // Shut down the node.
         trace!("Shutting down the node...");
         self.shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
@@ -490,12 +544,17 @@ mod tests {
             None,
             Some(rest),
             10,
+            false,
+            false,
             account,
             &[],
             &[],
+            1,
+            RouterConfig::new(RouterConfig::VALIDATOR_MAXIMUM_NUMBER_OF_PEERS),
             genesis,
             None,
             storage_mode,
+            None,
         )
         .await
         .unwrap();
@@ -24,7 +24,7 @@ use snarkos_node_router::messages::{
     Pong,
     UnconfirmedTransaction,
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_tcp::{Connection, ConnectionSide, NoiseCodec, Tcp};
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{block::Transaction, coinbase::EpochChallenge, error, Network},
@@ -88,25 +88,25 @@ impl<N: Network, C: ConsensusStorage<N>> Disconnect for Validator<N, C> {
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Writing for Validator<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = NoiseCodec<MessageCodec<N>>;
     type Message = Message<N>;
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().codec(addr)
     }
 }
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Reading for Validator<N, C> {
-    type Codec = MessageCodec<N>;
+    type Codec = NoiseCodec<MessageCodec<N>>;
     type Message = Message<N>;
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router().codec(peer_addr)
     }
 
     /// Processes a message received from the network.
@@ -127,10 +127,7 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Validator<N, C> {
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Routing<N> for Validator<N, C> {}
 
-impl<N: Network, C: ConsensusStorage<N>> Heartbeat<N> for Validator<N, C> {
-    /// The maximum number of peers permitted to maintain connections with.
-    const MAXIMUM_NUMBER_OF_PEERS: usize = 200;
-}
+impl<N: Network, C: ConsensusStorage<N>> Heartbeat<N> for Validator<N, C> {}
 
 impl<N: Network, C: ConsensusStorage<N>> Outbound<N> for Validator<N, C> {
     /// Returns a reference to the router.
@@ -35,6 +35,7 @@ use std::{
     },
     time::Instant,
 };
+use tokio::sync::broadcast;
 
 #[cfg(not(test))]
 pub const REDUNDANCY_FACTOR: usize = 1;
@@ -47,6 +48,9 @@ const BLOCK_REQUEST_TIMEOUT_IN_SECS: u64 = 600; // 600 seconds
 const MAX_BLOCK_REQUESTS: usize = 50; // 50 requests
 const MAX_BLOCK_REQUEST_TIMEOUTS: usize = 5; // 5 timeouts
 
+/// The maximum number of advanced blocks buffered for each subscriber.
+const MAX_BLOCK_NOTIFICATIONS: usize = 64;
+
 /// The maximum number of blocks tolerated before the primary is considered behind its peers.
 pub const MAX_BLOCKS_BEHIND: u32 = 1; // blocks
 
@@ -111,6 +115,8 @@ pub struct BlockSync<N: Network> {
     is_block_synced: Arc<AtomicBool>,
     /// The lock to guarantee advance_with_sync_blocks() is called only once at a time.
     advance_with_sync_blocks_lock: Arc<Mutex<()>>,
+    /// The sender for the blocks that were advanced to from the sync pool.
+    block_notifications: broadcast::Sender<Block<N>>,
 }
 
 impl<N: Network> BlockSync<N> {
@@ -127,6 +133,7 @@ impl<N: Network> BlockSync<N> {
             request_timeouts: Default::default(),
             is_block_synced: Default::default(),
             advance_with_sync_blocks_lock: Default::default(),
+            block_notifications: broadcast::channel(MAX_BLOCK_NOTIFICATIONS).0,
         }
     }
 
@@ -136,6 +143,11 @@ impl<N: Network> BlockSync<N> {
         self.mode
     }
 
+    /// Returns a new receiver for the blocks that are advanced to from the sync pool.
+    pub fn subscribe_blocks(&self) -> broadcast::Receiver<Block<N>> {
+        self.block_notifications.subscribe()
+    }
+
     /// Returns `true` if the node is synced up to the latest block (within the given tolerance).
     #[inline]
     pub fn is_block_synced(&self) -> bool {
@@ -324,6 +336,8 @@ impl<N: Network> BlockSync<N> {
             }
             // Update the latest height.
             current_height = self.canon.latest_block_height();
+            // Notify the subscribers, if there are any.
+            let _ = self.block_notifications.send(block);
         }
     }
 }
@@ -441,6 +455,12 @@ impl<N: Network> BlockSync<N> {
         let is_synced = num_blocks_behind <= max_blocks_behind;
         // Update the sync status.
         self.is_block_synced.store(is_synced, Ordering::SeqCst);
+
+        #[cfg(feature = "metrics")]
+        {
+            metrics::gauge(metrics::sync::GREATEST_PEER_HEIGHT, greatest_peer_height as f64);
+            metrics::gauge(metrics::sync::BLOCKS_BEHIND, num_blocks_behind as f64);
+        }
     }
 
     /// Inserts a block request for the given height.
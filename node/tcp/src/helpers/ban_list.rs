@@ -0,0 +1,130 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+/// A banned IP, and the UNIX timestamp (in seconds) at which the ban expires, if any.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BannedIp {
+    /// The banned IP.
+    pub ip: IpAddr,
+    /// The UNIX timestamp (in seconds) at which the ban expires, or `None` if the ban is indefinite.
+    pub expires_at: Option<u64>,
+}
+
+impl BannedIp {
+    /// Initializes a new banned IP, banned for the given duration, or indefinitely if `None`.
+    pub fn new(ip: IpAddr, duration: Option<Duration>) -> Self {
+        Self { ip, expires_at: duration.map(|duration| now().saturating_add(duration.as_secs())) }
+    }
+
+    /// Returns the time left on the ban, or `None` if the ban is indefinite.
+    pub fn time_left(&self) -> Option<Duration> {
+        self.expires_at.map(|expires_at| Duration::from_secs(expires_at.saturating_sub(now())))
+    }
+
+    /// Returns `true` if the ban has expired.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.map_or(false, |expires_at| expires_at <= now())
+    }
+}
+
+/// The banned IPs of a node, which are shared by the router and the gateway.
+#[derive(Debug, Default)]
+pub struct BanList {
+    /// The map of banned IPs to their bans.
+    banned_ips: RwLock<HashMap<IpAddr, BannedIp>>,
+}
+
+impl BanList {
+    /// Returns `true` if the given IP is banned.
+    pub fn is_banned(&self, ip: &IpAddr) -> bool {
+        self.banned_ips.read().get(ip).map_or(false, |banned| !banned.is_expired())
+    }
+
+    /// Returns the list of banned IPs, excluding the expired bans.
+    pub fn banned_ips(&self) -> Vec<BannedIp> {
+        self.banned_ips.read().values().filter(|banned| !banned.is_expired()).cloned().collect()
+    }
+
+    /// Bans the given IP for the given duration, or indefinitely if `None`.
+    pub fn ban(&self, ip: IpAddr, duration: Option<Duration>) {
+        self.banned_ips.write().insert(ip, BannedIp::new(ip, duration));
+    }
+
+    /// Unbans the given IP, returning `true` if the IP was banned.
+    pub fn unban(&self, ip: &IpAddr) -> bool {
+        self.banned_ips.write().remove(ip).is_some()
+    }
+
+    /// Adds the given bans, skipping the bans that have expired.
+    pub fn extend(&self, bans: impl IntoIterator<Item = BannedIp>) {
+        self.banned_ips
+            .write()
+            .extend(bans.into_iter().filter(|banned| !banned.is_expired()).map(|banned| (banned.ip, banned)));
+    }
+}
+
+/// Returns the current UNIX timestamp, in seconds.
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_banned_ip() {
+        let ip = IpAddr::from([1, 2, 3, 4]);
+
+        let banned = BannedIp::new(ip, None);
+        assert!(!banned.is_expired());
+        assert_eq!(banned.time_left(), None);
+
+        let banned = BannedIp::new(ip, Some(Duration::from_secs(60)));
+        assert!(!banned.is_expired());
+        assert!(banned.time_left().unwrap() <= Duration::from_secs(60));
+
+        let banned = BannedIp::new(ip, Some(Duration::ZERO));
+        assert!(banned.is_expired());
+        assert_eq!(banned.time_left(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_ban_list() {
+        let ban_list = BanList::default();
+        let ip = IpAddr::from([1, 2, 3, 4]);
+
+        // Ban and unban the IP.
+        ban_list.ban(ip, None);
+        assert!(ban_list.is_banned(&ip));
+        assert_eq!(ban_list.banned_ips(), vec![BannedIp::new(ip, None)]);
+        assert!(ban_list.unban(&ip));
+        assert!(!ban_list.unban(&ip));
+        assert!(!ban_list.is_banned(&ip));
+
+        // Ensure the expired bans are neither added nor reported.
+        ban_list.extend([BannedIp::new(ip, Some(Duration::ZERO)), BannedIp::new(IpAddr::from([5, 6, 7, 8]), None)]);
+        assert!(!ban_list.is_banned(&ip));
+        assert_eq!(ban_list.banned_ips().len(), 1);
+    }
+}
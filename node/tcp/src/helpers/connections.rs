@@ -47,6 +47,11 @@ impl Connections {
         self.0.write().remove(&addr)
     }
 
+    /// Returns `true` if the connection with the given address is encrypted.
+    pub(crate) fn is_encrypted(&self, addr: SocketAddr) -> bool {
+        self.0.read().get(&addr).map_or(false, |conn| conn.is_encrypted)
+    }
+
     /// Returns the number of connected addresses.
     pub(crate) fn num_connected(&self) -> usize {
         self.0.read().len()
@@ -83,6 +88,8 @@ pub struct Connection {
     pub(crate) readiness_notifier: Option<oneshot::Sender<()>>,
     /// Handles to tasks spawned for the connection.
     pub(crate) tasks: Vec<JoinHandle<()>>,
+    /// Whether the connection is encrypted with the Noise protocol.
+    pub(crate) is_encrypted: bool,
 }
 
 impl Connection {
@@ -96,6 +103,7 @@ impl Connection {
             readiness_notifier: None,
             side,
             tasks: Default::default(),
+            is_encrypted: false,
         }
     }
 
@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod ban_list;
+pub use ban_list::{BanList, BannedIp};
+
 mod config;
 pub use config::Config;
 
@@ -21,6 +24,12 @@ pub use connections::{Connection, ConnectionSide};
 mod known_peers;
 pub use known_peers::KnownPeers;
 
+mod noise;
+pub use noise::{noise_handshake, NoiseCodec, NoiseState, NoiseStates, NOISE_HANDSHAKE_TYPE};
+
+mod peer_book;
+pub use peer_book::{PeerBook, PeerRecord, PEER_BOOK_FILE_NAME};
+
 mod stats;
 pub use stats::Stats;
 
@@ -0,0 +1,321 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::HashMap, io, net::SocketAddr, sync::Arc};
+
+use bytes::BytesMut;
+use parking_lot::Mutex;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
+use tracing::*;
+
+use crate::ConnectionSide;
+
+/// The Noise protocol used to encrypt the connections.
+pub const NOISE_HANDSHAKE_TYPE: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+
+/// The maximum size of a Noise message, including the authentication tag.
+const MAX_NOISE_MESSAGE_LEN: usize = 65535;
+/// The size of the authentication tag of a Noise transport message.
+const NOISE_TAG_LEN: usize = 16;
+
+/// Converts a Noise error into an I/O error.
+fn noise_error(error: snow::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("noise error: {error}"))
+}
+
+/// The state of an encrypted connection, after the Noise handshake.
+///
+/// The state may be cloned for the reading and writing halves of the connection,
+/// as each half only advances its own nonce.
+#[derive(Clone)]
+pub struct NoiseState {
+    /// The transport state of the Noise session.
+    state: Arc<snow::StatelessTransportState>,
+    /// The nonce of the next outbound message.
+    tx_nonce: u64,
+    /// The nonce of the next inbound message.
+    rx_nonce: u64,
+}
+
+/// Performs a Noise handshake on the given stream, from the given side of the connection.
+///
+/// On success, this function returns the state of the encrypted connection, along with the handshake hash,
+/// which both sides may sign to bind their identities to the encrypted connection.
+///
+/// Note: The handshake messages are read exactly, so that no subsequent (encrypted) bytes are consumed from the stream.
+pub async fn noise_handshake<T: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut T,
+    own_side: ConnectionSide,
+) -> io::Result<(NoiseState, Vec<u8>)> {
+    // Initialize the handshake state, with an ephemeral static key.
+    let params: snow::params::NoiseParams = NOISE_HANDSHAKE_TYPE.parse().map_err(noise_error)?;
+    let builder = snow::Builder::new(params);
+    let keypair = builder.generate_keypair().map_err(noise_error)?;
+    let builder = builder.local_private_key(&keypair.private);
+    let mut noise = match own_side {
+        ConnectionSide::Initiator => builder.build_initiator(),
+        ConnectionSide::Responder => builder.build_responder(),
+    }
+    .map_err(noise_error)?;
+
+    // Exchange the handshake messages, each prefixed with its length.
+    let mut buffer = vec![0u8; MAX_NOISE_MESSAGE_LEN];
+    while !noise.is_handshake_finished() {
+        if noise.is_my_turn() {
+            let len = noise.write_message(&[], &mut buffer).map_err(noise_error)?;
+            stream.write_u16_le(len as u16).await?;
+            stream.write_all(&buffer[..len]).await?;
+            stream.flush().await?;
+        } else {
+            let len = stream.read_u16_le().await? as usize;
+            let mut message = vec![0u8; len];
+            stream.read_exact(&mut message).await?;
+            noise.read_message(&message, &mut buffer).map_err(noise_error)?;
+        }
+    }
+
+    // Retrieve the handshake hash, and switch to the transport mode.
+    let handshake_hash = noise.get_handshake_hash().to_vec();
+    let state = noise.into_stateless_transport_mode().map_err(noise_error)?;
+    Ok((NoiseState { state: Arc::new(state), tx_nonce: 0, rx_nonce: 0 }, handshake_hash))
+}
+
+/// A codec that encrypts the frames of the given inner codec, using the state of a Noise session.
+///
+/// Each frame is split into chunks that fit in a Noise message, which are encrypted individually.
+/// If the state is missing (i.e. the connection did not perform the Noise handshake), every frame is rejected.
+pub struct NoiseCodec<C> {
+    /// The codec delimiting the encrypted frames.
+    codec: LengthDelimitedCodec,
+    /// The codec of the plaintext frames.
+    inner: C,
+    /// The state of the Noise session.
+    state: Option<NoiseState>,
+}
+
+impl<C> NoiseCodec<C> {
+    /// Initializes a new codec with the given inner codec, accepting plaintext frames of up to the given size.
+    pub fn new(inner: C, state: Option<NoiseState>, max_frame_length: usize) -> Self {
+        // Account for the authentication tag of each chunk.
+        let num_chunks = max_frame_length.div_ceil(MAX_NOISE_MESSAGE_LEN - NOISE_TAG_LEN);
+        let codec = LengthDelimitedCodec::builder()
+            .max_frame_length(max_frame_length + num_chunks * NOISE_TAG_LEN)
+            .little_endian()
+            .new_codec();
+        Self { codec, inner, state }
+    }
+
+    /// Returns the state of the Noise session.
+    pub fn state(&self) -> Option<&NoiseState> {
+        self.state.as_ref()
+    }
+
+    /// Returns the state of the Noise session, or an error if it is missing.
+    fn state_mut(&mut self) -> io::Result<&mut NoiseState> {
+        self.state.as_mut().ok_or_else(|| io::Error::new(io::ErrorKind::NotConnected, "missing the Noise state"))
+    }
+}
+
+impl<M, C: Encoder<M, Error = io::Error>> Encoder<M> for NoiseCodec<C> {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: M, dst: &mut BytesMut) -> io::Result<()> {
+        #[cfg(feature = "metrics")]
+        let timer = std::time::Instant::now();
+
+        // Encode the item with the inner codec.
+        let mut plaintext = BytesMut::new();
+        self.inner.encode(item, &mut plaintext)?;
+        let state = self.state_mut()?;
+
+        #[cfg(feature = "metrics")]
+        metrics::histogram(metrics::tcp::NOISE_CODEC_ENCRYPTION_SIZE, plaintext.len() as f64);
+
+        // Encrypt the plaintext, in chunks that fit in a Noise message.
+        let mut ciphertext = BytesMut::with_capacity(plaintext.len() + NOISE_TAG_LEN);
+        let mut buffer = vec![0u8; MAX_NOISE_MESSAGE_LEN];
+        for chunk in plaintext.chunks(MAX_NOISE_MESSAGE_LEN - NOISE_TAG_LEN) {
+            let len = state.state.write_message(state.tx_nonce, chunk, &mut buffer).map_err(|error| {
+                error!("Failed to encrypt a message - {error}");
+                noise_error(error)
+            })?;
+            state.tx_nonce += 1;
+            ciphertext.extend_from_slice(&buffer[..len]);
+        }
+
+        // Delimit the ciphertext.
+        self.codec.encode(ciphertext.freeze(), dst)?;
+
+        #[cfg(feature = "metrics")]
+        metrics::histogram(metrics::tcp::NOISE_CODEC_ENCRYPTION_TIME, timer.elapsed().as_micros() as f64);
+        Ok(())
+    }
+}
+
+impl<C: Decoder<Error = io::Error>> Decoder for NoiseCodec<C> {
+    type Error = io::Error;
+    type Item = C::Item;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<Self::Item>> {
+        #[cfg(feature = "metrics")]
+        let timer = std::time::Instant::now();
+
+        // Decode a frame containing the ciphertext.
+        let Some(ciphertext) = self.codec.decode(src)? else {
+            return Ok(None);
+        };
+        let state = self.state_mut()?;
+
+        // Decrypt the ciphertext, in chunks of the size of a Noise message.
+        let mut plaintext = BytesMut::with_capacity(ciphertext.len());
+        let mut buffer = vec![0u8; MAX_NOISE_MESSAGE_LEN];
+        for chunk in ciphertext.chunks(MAX_NOISE_MESSAGE_LEN) {
+            let len = state.state.read_message(state.rx_nonce, chunk, &mut buffer).map_err(|error| {
+                warn!("Failed to decrypt a message - {error}");
+                noise_error(error)
+            })?;
+            state.rx_nonce += 1;
+            plaintext.extend_from_slice(&buffer[..len]);
+        }
+
+        #[cfg(feature = "metrics")]
+        metrics::histogram(metrics::tcp::NOISE_CODEC_DECRYPTION_SIZE, plaintext.len() as f64);
+
+        // Decode the plaintext with the inner codec, which must contain exactly one item.
+        let item = match self.inner.decode(&mut plaintext)? {
+            Some(item) if plaintext.is_empty() => item,
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid encrypted frame")),
+        };
+
+        #[cfg(feature = "metrics")]
+        metrics::histogram(metrics::tcp::NOISE_CODEC_DECRYPTION_TIME, timer.elapsed().as_micros() as f64);
+        Ok(Some(item))
+    }
+}
+
+/// The states of the encrypted connections that completed their handshake,
+/// which are retained until the codecs of the connection are created.
+#[derive(Default)]
+pub struct NoiseStates(Mutex<HashMap<SocketAddr, NoiseState>>);
+
+impl NoiseStates {
+    /// Inserts the state of the encrypted connection with the given address.
+    pub fn insert(&self, addr: SocketAddr, state: NoiseState) {
+        self.0.lock().insert(addr, state);
+    }
+
+    /// Returns the state of the encrypted connection with the given address.
+    pub fn get(&self, addr: SocketAddr) -> Option<NoiseState> {
+        self.0.lock().get(&addr).cloned()
+    }
+
+    /// Removes the state of the encrypted connection with the given address.
+    pub fn remove(&self, addr: SocketAddr) -> Option<NoiseState> {
+        self.0.lock().remove(&addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bytes::Bytes;
+    use futures_util::{SinkExt, TryStreamExt};
+    use tokio::io::duplex;
+    use tokio_util::codec::Framed;
+
+    #[tokio::test]
+    async fn test_noise_codec() {
+        let (mut initiator, mut responder) = duplex(MAX_NOISE_MESSAGE_LEN);
+
+        // Perform the handshake.
+        let (initiator_result, responder_result) = tokio::join!(
+            noise_handshake(&mut initiator, ConnectionSide::Initiator),
+            noise_handshake(&mut responder, ConnectionSide::Responder)
+        );
+        let (initiator_state, initiator_hash) = initiator_result.unwrap();
+        let (responder_state, responder_hash) = responder_result.unwrap();
+        assert_eq!(initiator_hash, responder_hash);
+
+        // Send a frame that spans several Noise messages.
+        const MAX_FRAME_LENGTH: usize = 1024 * 1024;
+        let mut writer = Framed::new(
+            initiator,
+            NoiseCodec::new(LengthDelimitedCodec::new(), Some(initiator_state), MAX_FRAME_LENGTH),
+        );
+        let mut reader = Framed::new(
+            responder,
+            NoiseCodec::new(LengthDelimitedCodec::new(), Some(responder_state), MAX_FRAME_LENGTH),
+        );
+        let message = Bytes::from(vec![7u8; 3 * MAX_NOISE_MESSAGE_LEN]);
+        let (sent, received) = tokio::join!(writer.send(message.clone()), reader.try_next());
+        sent.unwrap();
+        assert_eq!(received.unwrap().unwrap().freeze(), message);
+        assert_eq!(writer.codec().state().unwrap().tx_nonce, 4);
+        assert_eq!(reader.codec().state().unwrap().rx_nonce, 4);
+
+        // Send a second frame, to ensure the nonces are in sync.
+        let (sent, received) = tokio::join!(writer.send(Bytes::from_static(b"hello")), reader.try_next());
+        sent.unwrap();
+        assert_eq!(received.unwrap().unwrap().freeze(), Bytes::from_static(b"hello"));
+    }
+
+    /// Performs a Noise handshake over an in-memory stream, returning the states of both sides.
+    async fn sample_states() -> (NoiseState, NoiseState) {
+        let (mut initiator, mut responder) = duplex(MAX_NOISE_MESSAGE_LEN);
+        let (initiator_result, responder_result) = tokio::join!(
+            noise_handshake(&mut initiator, ConnectionSide::Initiator),
+            noise_handshake(&mut responder, ConnectionSide::Responder)
+        );
+        (initiator_result.unwrap().0, responder_result.unwrap().0)
+    }
+
+    #[tokio::test]
+    async fn test_noise_codec_rejects_mismatched_keys() {
+        const MAX_FRAME_LENGTH: usize = 1024;
+
+        // Perform two independent handshakes.
+        let (initiator_state, responder_state) = sample_states().await;
+        let (_, other_responder_state) = sample_states().await;
+
+        // Encrypt a frame with the first session.
+        let mut writer = NoiseCodec::new(LengthDelimitedCodec::new(), Some(initiator_state), MAX_FRAME_LENGTH);
+        let mut frame = BytesMut::new();
+        writer.encode(Bytes::from_static(b"hello"), &mut frame).unwrap();
+
+        // Ensure the frame is rejected by the other session.
+        let mut reader = NoiseCodec::new(LengthDelimitedCodec::new(), Some(other_responder_state), MAX_FRAME_LENGTH);
+        assert!(reader.decode(&mut frame.clone()).is_err());
+        // Ensure the frame is rejected without a session.
+        let mut reader = NoiseCodec::new(LengthDelimitedCodec::new(), None, MAX_FRAME_LENGTH);
+        assert!(reader.decode(&mut frame.clone()).is_err());
+        // Ensure the frame is accepted by its own session.
+        let mut reader = NoiseCodec::new(LengthDelimitedCodec::new(), Some(responder_state), MAX_FRAME_LENGTH);
+        assert_eq!(reader.decode(&mut frame).unwrap().unwrap().freeze(), Bytes::from_static(b"hello"));
+    }
+
+    #[tokio::test]
+    async fn test_noise_handshake_rejects_invalid_message() {
+        let (mut initiator, mut responder) = duplex(MAX_NOISE_MESSAGE_LEN);
+
+        // Send a plaintext message instead of the first handshake message.
+        let message = b"plaintext";
+        initiator.write_u16_le(message.len() as u16).await.unwrap();
+        initiator.write_all(message).await.unwrap();
+
+        // Ensure the responder rejects the handshake.
+        assert!(noise_handshake(&mut responder, ConnectionSide::Responder).await.is_err());
+    }
+}
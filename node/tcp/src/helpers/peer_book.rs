@@ -0,0 +1,415 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::HashMap,
+    io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// The file name of the persisted peer book.
+pub const PEER_BOOK_FILE_NAME: &str = "peer-book.json";
+
+/// The record of a peer's past behaviour, used to score the peer.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PeerRecord {
+    /// The number of outbound connection attempts.
+    pub connection_attempts: u32,
+    /// The number of outbound connection attempts that succeeded.
+    pub connection_successes: u32,
+    /// The moving average of the round-trip latency in milliseconds, if it was measured.
+    pub latency_ms: Option<u64>,
+    /// The number of block responses that were processed successfully.
+    pub block_responses: u32,
+    /// The number of block responses that failed to be processed.
+    pub block_response_failures: u32,
+    /// The number of times the peer violated the protocol, decayed up to the last violation.
+    pub misbehaviours: u32,
+    /// The UNIX timestamp (in seconds) at which the peer last violated the protocol.
+    #[serde(default)]
+    pub last_misbehaviour: u64,
+    /// The UNIX timestamp (in seconds) at which the peer was last seen.
+    pub last_seen: u64,
+}
+
+impl PeerRecord {
+    /// The weight of the block response success rate in the score.
+    const BLOCK_RESPONSE_WEIGHT: f64 = 30.0;
+    /// The weight of the connection success rate in the score.
+    const CONNECTION_WEIGHT: f64 = 40.0;
+    /// The weight of the latency in the score.
+    const LATENCY_WEIGHT: f64 = 20.0;
+    /// The duration in seconds after which the penalty of a misbehaviour is halved.
+    const MISBEHAVIOUR_HALF_LIFE_IN_SECS: u64 = 24 * 60 * 60;
+    /// The penalty applied to the score for each misbehaviour.
+    const MISBEHAVIOUR_PENALTY: f64 = 25.0;
+    /// The duration in seconds during which a sighting of the peer counts as recent.
+    const RECENCY_IN_SECS: u64 = 24 * 60 * 60;
+    /// The weight of a recent sighting of the peer in the score.
+    const RECENCY_WEIGHT: f64 = 10.0;
+    /// The latency in milliseconds at which the latency score is halved.
+    const REFERENCE_LATENCY_MS: f64 = 200.0;
+
+    /// Returns the number of misbehaviours of the peer, decayed by the time since the last misbehaviour.
+    pub fn recent_misbehaviours(&self) -> f64 {
+        let elapsed = now().saturating_sub(self.last_misbehaviour) as f64;
+        self.misbehaviours as f64 * 0.5f64.powf(elapsed / Self::MISBEHAVIOUR_HALF_LIFE_IN_SECS as f64)
+    }
+
+    /// Returns the score of the peer, where a higher score indicates a better peer.
+    /// A peer without any record has a neutral score, so that new peers are tried before known-bad peers.
+    pub fn score(&self) -> f64 {
+        // Compute the success rates, smoothed so that a single observation does not dominate.
+        let rate = |successes: u32, total: u32| (successes as f64 + 1.0) / (total as f64 + 2.0);
+        let connection_rate = rate(self.connection_successes, self.connection_attempts);
+        let block_response_rate =
+            rate(self.block_responses, self.block_responses.saturating_add(self.block_response_failures));
+        // Compute the latency score, which is neutral if the latency was not measured.
+        let latency = match self.latency_ms {
+            Some(latency_ms) => 1.0 / (1.0 + latency_ms as f64 / Self::REFERENCE_LATENCY_MS),
+            None => 0.5,
+        };
+        // Compute the recency score.
+        let recency = match now().saturating_sub(self.last_seen) < Self::RECENCY_IN_SECS {
+            true => 1.0,
+            false => 0.0,
+        };
+
+        Self::CONNECTION_WEIGHT * connection_rate
+            + Self::LATENCY_WEIGHT * latency
+            + Self::BLOCK_RESPONSE_WEIGHT * block_response_rate
+            + Self::RECENCY_WEIGHT * recency
+            - Self::MISBEHAVIOUR_PENALTY * self.recent_misbehaviours()
+    }
+}
+
+/// A book of the peers seen by the node, which may be persisted to survive a restart.
+#[derive(Default)]
+pub struct PeerBook {
+    /// The map of peer IPs to their records.
+    records: RwLock<HashMap<SocketAddr, PeerRecord>>,
+    /// The path to the persisted peer book, if persistence is enabled.
+    path: RwLock<Option<PathBuf>>,
+    /// Whether the records changed since the peer book was last persisted.
+    is_dirty: AtomicBool,
+    /// The lock that serializes the writes of the persisted peer book.
+    save_lock: Mutex<()>,
+}
+
+impl PeerBook {
+    /// The weight of the latest latency measurement in the moving average.
+    const LATENCY_SMOOTHING: f64 = 0.2;
+    /// The maximum number of peers in the peer book.
+    pub const MAXIMUM_PEERS: usize = 10_000;
+
+    /// Loads the peer book from the given path, and persists the peer book to this path
+    /// whenever `save` is called. A missing or corrupt file loads an empty peer book.
+    pub fn load(&self, path: PathBuf) {
+        if path.exists() {
+            match std::fs::read(&path).map_err(|e| e.to_string()).and_then(|bytes| {
+                serde_json::from_slice::<HashMap<SocketAddr, PeerRecord>>(&bytes).map_err(|e| e.to_string())
+            }) {
+                Ok(records) => self.records.write().extend(records),
+                Err(error) => warn!("Discarding the peer book at '{}' - {error}", path.display()),
+            }
+            self.prune();
+        }
+        *self.path.write() = Some(path);
+    }
+
+    /// Persists the peer book, if persistence is enabled and the records changed since the last save.
+    /// Note: This function writes to the file system, and should not be called on the async runtime.
+    pub fn save(&self) -> io::Result<()> {
+        // Retrieve the path to the persisted peer book.
+        let Some(path) = self.path.read().clone() else {
+            return Ok(());
+        };
+        // Serialize the writes, so that concurrent saves do not race on the temporary file.
+        let _lock = self.save_lock.lock();
+        // Return early if the records did not change.
+        if !self.is_dirty.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let bytes = serde_json::to_vec(&*self.records.read())?;
+        save_to_file(&path, &bytes).map_err(|error| {
+            // Ensure the records are saved again on the next attempt.
+            self.is_dirty.store(true, Ordering::SeqCst);
+            error
+        })
+    }
+
+    /// Returns the record of the given peer, if it exists.
+    pub fn get(&self, peer_ip: &SocketAddr) -> Option<PeerRecord> {
+        self.records.read().get(peer_ip).copied()
+    }
+
+    /// Returns the number of peers in the peer book.
+    pub fn len(&self) -> usize {
+        self.records.read().len()
+    }
+
+    /// Returns `true` if the peer book is empty.
+    pub fn is_empty(&self) -> bool {
+        self.records.read().is_empty()
+    }
+
+    /// Returns the score of the given peer, which is neutral if the peer is unknown.
+    pub fn score(&self, peer_ip: &SocketAddr) -> f64 {
+        self.get(peer_ip).unwrap_or_default().score()
+    }
+
+    /// Returns the known peers that connected successfully in the past, from the highest score to the lowest.
+    pub fn known_good_peers(&self) -> Vec<SocketAddr> {
+        let good_peers = self
+            .records
+            .read()
+            .iter()
+            .filter(|(_, record)| record.connection_successes > 0 && record.recent_misbehaviours() < 0.5)
+            .map(|(ip, _)| *ip)
+            .collect::<Vec<_>>();
+        self.best_peers(good_peers, usize::MAX)
+    }
+
+    /// Returns up to `num_peers` of the given peers, from the highest score to the lowest.
+    /// Peers with equal scores retain their given order.
+    pub fn best_peers(&self, peers: impl IntoIterator<Item = SocketAddr>, num_peers: usize) -> Vec<SocketAddr> {
+        let mut peers = self.with_scores(peers);
+        peers.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        peers.into_iter().take(num_peers).map(|(ip, _)| ip).collect()
+    }
+
+    /// Returns up to `num_peers` of the given peers, from the lowest score to the highest.
+    /// Peers with equal scores retain their given order.
+    pub fn worst_peers(&self, peers: impl IntoIterator<Item = SocketAddr>, num_peers: usize) -> Vec<SocketAddr> {
+        let mut peers = self.with_scores(peers);
+        peers.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        peers.into_iter().take(num_peers).map(|(ip, _)| ip).collect()
+    }
+
+    /// Records an outbound connection attempt to the given peer.
+    pub fn record_connection_attempt(&self, peer_ip: SocketAddr) {
+        self.update(peer_ip, |record| record.connection_attempts = record.connection_attempts.saturating_add(1));
+    }
+
+    /// Records a successful outbound connection to the given peer.
+    pub fn record_connection_success(&self, peer_ip: SocketAddr) {
+        self.update(peer_ip, |record| {
+            record.connection_successes = record.connection_successes.saturating_add(1);
+            record.last_seen = now();
+        });
+    }
+
+    /// Records a round-trip latency measurement for the given peer.
+    pub fn record_latency(&self, peer_ip: SocketAddr, latency: Duration) {
+        let latency_ms = latency.as_millis().min(u64::MAX as u128) as u64;
+        self.update(peer_ip, |record| {
+            record.latency_ms = Some(match record.latency_ms {
+                Some(average) => ((1.0 - Self::LATENCY_SMOOTHING) * average as f64
+                    + Self::LATENCY_SMOOTHING * latency_ms as f64)
+                    .round() as u64,
+                None => latency_ms,
+            });
+            record.last_seen = now();
+        });
+    }
+
+    /// Records the outcome of processing a block response from the given peer.
+    pub fn record_block_response(&self, peer_ip: SocketAddr, is_success: bool) {
+        self.update(peer_ip, |record| {
+            match is_success {
+                true => record.block_responses = record.block_responses.saturating_add(1),
+                false => record.block_response_failures = record.block_response_failures.saturating_add(1),
+            }
+            record.last_seen = now();
+        });
+    }
+
+    /// Records a protocol violation by the given peer.
+    pub fn record_misbehaviour(&self, peer_ip: SocketAddr) {
+        self.update(peer_ip, |record| {
+            record.misbehaviours = (record.recent_misbehaviours().round() as u32).saturating_add(1);
+            record.last_misbehaviour = now();
+        });
+    }
+
+    /// Updates the record of the given peer with the given function, inserting a new record if needed.
+    fn update(&self, peer_ip: SocketAddr, write_fn: impl FnOnce(&mut PeerRecord)) {
+        let is_new = {
+            let mut records = self.records.write();
+            let is_new = !records.contains_key(&peer_ip);
+            write_fn(records.entry(peer_ip).or_default());
+            is_new
+        };
+        self.is_dirty.store(true, Ordering::SeqCst);
+        // Bound the number of records, if a new peer was added.
+        if is_new {
+            self.prune();
+        }
+    }
+
+    /// Removes the lowest-scoring records, if the peer book exceeds the maximum number of peers.
+    fn prune(&self) {
+        let mut records = self.records.write();
+        if records.len() > Self::MAXIMUM_PEERS {
+            // Note: The records are pruned down to 90% of the maximum, so that pruning is not repeated on every new peer.
+            let num_retained = Self::MAXIMUM_PEERS * 9 / 10;
+            let mut scores: Vec<_> = records.iter().map(|(ip, record)| (*ip, record.score())).collect();
+            scores.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+            for (ip, _) in scores.into_iter().skip(num_retained) {
+                records.remove(&ip);
+            }
+            self.is_dirty.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Returns the given peers with their scores.
+    fn with_scores(&self, peers: impl IntoIterator<Item = SocketAddr>) -> Vec<(SocketAddr, f64)> {
+        let records = self.records.read();
+        peers.into_iter().map(|ip| (ip, records.get(&ip).copied().unwrap_or_default().score())).collect()
+    }
+}
+
+/// Writes the given bytes to the given path, through a temporary file so that a crash
+/// does not leave a partially-written file behind.
+fn save_to_file(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    // Ensure the parent directory exists.
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, bytes)?;
+    std::fs::rename(temp_path, path)
+}
+
+/// Returns the current UNIX timestamp, in seconds.
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoring() {
+        let book = PeerBook::default();
+        let good: SocketAddr = "1.2.3.4:4130".parse().unwrap();
+        let unknown: SocketAddr = "1.2.3.5:4130".parse().unwrap();
+        let bad: SocketAddr = "1.2.3.6:4130".parse().unwrap();
+
+        // Record a reliable, fast peer.
+        book.record_connection_attempt(good);
+        book.record_connection_success(good);
+        book.record_latency(good, Duration::from_millis(50));
+        book.record_block_response(good, true);
+        // Record an unreachable, misbehaving peer.
+        book.record_connection_attempt(bad);
+        book.record_connection_attempt(bad);
+        book.record_block_response(bad, false);
+        book.record_misbehaviour(bad);
+
+        // Ensure the peers are ranked by score, with the unknown peer in the middle.
+        assert!(book.score(&good) > book.score(&unknown));
+        assert!(book.score(&unknown) > book.score(&bad));
+        assert_eq!(book.best_peers([bad, unknown, good], 2), vec![good, unknown]);
+        assert_eq!(book.worst_peers([good, unknown, bad], 1), vec![bad]);
+        assert_eq!(book.known_good_peers(), vec![good]);
+
+        // Ensure the latency is averaged.
+        book.record_latency(good, Duration::from_millis(100));
+        assert_eq!(book.get(&good).unwrap().latency_ms, Some(60));
+    }
+
+    #[test]
+    fn test_misbehaviour_decay() {
+        let half_life = PeerRecord::MISBEHAVIOUR_HALF_LIFE_IN_SECS;
+        let record = PeerRecord { misbehaviours: 4, last_misbehaviour: now(), ..Default::default() };
+        assert_eq!(record.recent_misbehaviours().round(), 4.0);
+
+        // Ensure the misbehaviours are halved after each half-life.
+        let record = PeerRecord { last_misbehaviour: now() - 2 * half_life, ..record };
+        assert_eq!(record.recent_misbehaviours().round(), 1.0);
+        // Ensure old misbehaviours are forgiven, and no longer outweigh a neutral record.
+        let record = PeerRecord { last_misbehaviour: now() - 10 * half_life, ..record };
+        assert!(record.recent_misbehaviours() < 0.5);
+        assert!(record.score() > PeerRecord::default().score() - PeerRecord::MISBEHAVIOUR_PENALTY / 2.0);
+
+        // Ensure a new misbehaviour adds to the decayed misbehaviours.
+        let book = PeerBook::default();
+        let peer_ip: SocketAddr = "1.2.3.4:4130".parse().unwrap();
+        book.records.write().insert(peer_ip, PeerRecord { last_misbehaviour: now() - 2 * half_life, ..record });
+        book.record_misbehaviour(peer_ip);
+        assert_eq!(book.get(&peer_ip).unwrap().misbehaviours, 2);
+    }
+
+    #[test]
+    fn test_prune() {
+        let book = PeerBook::default();
+        let bad: SocketAddr = "1.2.3.4:4130".parse().unwrap();
+        book.record_misbehaviour(bad);
+        // Ensure the number of records is bounded, and the lowest-scoring records are removed first.
+        for i in 0..PeerBook::MAXIMUM_PEERS as u32 {
+            book.record_connection_success(SocketAddr::from((std::net::Ipv4Addr::from(0x0a00_0000 + i), 4130)));
+        }
+        assert_eq!(book.len(), PeerBook::MAXIMUM_PEERS * 9 / 10);
+        assert!(book.get(&bad).is_none());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir()
+            .join(format!("snarkos-test-peer-book-{}", std::process::id()))
+            .join(PEER_BOOK_FILE_NAME);
+        let peer_ip: SocketAddr = "1.2.3.4:4130".parse().unwrap();
+
+        // Ensure saving without a path is a no-op.
+        let book = PeerBook::default();
+        book.record_connection_success(peer_ip);
+        book.save().unwrap();
+        assert!(!path.exists());
+
+        // Ensure loading a missing file retains the existing records.
+        book.load(path.clone());
+        assert_eq!(book.len(), 1);
+        book.save().unwrap();
+
+        // Ensure the records survive a reload.
+        let reloaded = PeerBook::default();
+        reloaded.load(path.clone());
+        assert_eq!(reloaded.get(&peer_ip), book.get(&peer_ip));
+
+        // Ensure an unchanged peer book is not saved again.
+        std::fs::remove_file(&path).unwrap();
+        book.save().unwrap();
+        assert!(!path.exists());
+        book.record_misbehaviour(peer_ip);
+        book.save().unwrap();
+        assert!(path.exists());
+
+        // Ensure a corrupt file loads an empty peer book.
+        std::fs::write(&path, b"{ corrupt").unwrap();
+        let reloaded = PeerBook::default();
+        reloaded.load(path.clone());
+        assert!(reloaded.is_empty());
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
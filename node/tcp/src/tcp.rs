@@ -41,6 +41,7 @@ use crate::{
     protocols::{Protocol, Protocols},
     Config,
     KnownPeers,
+    NoiseStates,
     Stats,
 };
 
@@ -75,6 +76,8 @@ pub struct InnerTcp {
     connections: Connections,
     /// Collects statistics related to the node's peers.
     known_peers: KnownPeers,
+    /// Contains the states of the encrypted connections that are being set up.
+    noise_states: NoiseStates,
     /// Collects statistics related to the node itself.
     stats: Stats,
     /// The node's tasks.
@@ -101,6 +104,7 @@ impl Tcp {
             connecting: Default::default(),
             connections: Default::default(),
             known_peers: Default::default(),
+            noise_states: Default::default(),
             stats: Default::default(),
             tasks: Default::default(),
         }));
@@ -134,6 +138,11 @@ impl Tcp {
         self.connections.is_connected(addr)
     }
 
+    /// Checks whether the connection with the provided address is encrypted.
+    pub fn is_encrypted(&self, addr: SocketAddr) -> bool {
+        self.connections.is_encrypted(addr)
+    }
+
     /// Checks if Tcp is currently setting up a connection with the provided address.
     pub fn is_connecting(&self, addr: SocketAddr) -> bool {
         self.connecting.lock().contains(&addr)
@@ -165,6 +174,12 @@ impl Tcp {
         &self.known_peers
     }
 
+    /// Returns a reference to the states of the encrypted connections that are being set up.
+    #[inline]
+    pub fn noise_states(&self) -> &NoiseStates {
+        &self.noise_states
+    }
+
     /// Returns a reference to the statistics.
     #[inline]
     pub fn stats(&self) -> &Stats {
@@ -433,7 +448,11 @@ impl Tcp {
         let connection = Connection::new(peer_addr, stream, !own_side);
 
         // Enact the enabled protocols.
-        let mut connection = self.enable_protocols(connection).await?;
+        let connection = self.enable_protocols(connection).await;
+        // Remove the state of the encrypted connection, as the codecs were created with it.
+        let is_encrypted = self.noise_states.remove(peer_addr).is_some();
+        let mut connection = connection?;
+        connection.is_encrypted = is_encrypted;
 
         // if Reading is enabled, we'll notify the related task when the connection is fully ready.
         let conn_ready_tx = connection.readiness_notifier.take();
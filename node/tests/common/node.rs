@@ -14,7 +14,7 @@
 
 use crate::common::test_peer::sample_genesis_block;
 use snarkos_account::Account;
-use snarkos_node::{Client, Prover, Validator};
+use snarkos_node::{router::RouterConfig, Client, Prover, Validator};
 use snarkvm::prelude::{store::helpers::memory::ConsensusMemory, MainnetV0 as CurrentNetwork};
 
 use aleo_std::StorageMode;
@@ -25,8 +25,11 @@ pub async fn client() -> Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>>
         "127.0.0.1:0".parse().unwrap(),
         None,
         10,
+        false, // No index.
+        false, // No mapping history.
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
+        RouterConfig::default(),
         sample_genesis_block(),
         None, // No CDN.
         StorageMode::Production,
@@ -40,8 +43,12 @@ pub async fn prover() -> Prover<CurrentNetwork, ConsensusMemory<CurrentNetwork>>
         "127.0.0.1:0".parse().unwrap(),
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
+        RouterConfig::default(),
         sample_genesis_block(),
         StorageMode::Production,
+        None, // No pool.
+        None, // Default number of puzzle instances.
+        vec![],
     )
     .await
     .expect("couldn't create prover instance")
@@ -53,12 +60,17 @@ pub async fn validator() -> Validator<CurrentNetwork, ConsensusMemory<CurrentNet
         None,
         None,
         10,
+        false, // No index.
+        false, // No mapping history.
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
         &[],
+        1, // One worker.
+        RouterConfig::new(RouterConfig::VALIDATOR_MAXIMUM_NUMBER_OF_PEERS),
         sample_genesis_block(), // Should load the current network's genesis block.
         None,                   // No CDN.
         StorageMode::Production,
+        None, // No graceful shutdown.
     )
     .await
     .expect("couldn't create validator instance")
@@ -14,9 +14,20 @@
 
 use snarkos_account::Account;
 use snarkos_node_router::{
+    challenge_data,
     expect_message,
-    messages::{ChallengeRequest, ChallengeResponse, Message, MessageCodec, MessageTrait, NodeType},
+    messages::{
+        ChallengeRequest,
+        ChallengeResponse,
+        Message,
+        MessageCodec,
+        MessageTrait,
+        NodeType,
+        MAXIMUM_HANDSHAKE_MESSAGE_SIZE,
+        MAXIMUM_MESSAGE_SIZE,
+    },
 };
+use snarkos_node_tcp::{noise_handshake, NoiseCodec, NoiseStates};
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{block::Block, error, Address, FromBytes, MainnetV0 as CurrentNetwork, Network, TestRng},
@@ -26,6 +37,7 @@ use std::{
     io,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     str::FromStr,
+    sync::Arc,
 };
 
 use futures_util::{sink::SinkExt, TryStreamExt};
@@ -58,6 +70,7 @@ pub struct TestPeer {
     node: Node,
     node_type: NodeType,
     account: Account<CurrentNetwork>,
+    noise_states: Arc<NoiseStates>,
 }
 
 impl Pea2Pea for TestPeer {
@@ -88,6 +101,7 @@ impl TestPeer {
             }),
             node_type,
             account,
+            noise_states: Default::default(),
         };
 
         peer.enable_handshake().await;
@@ -123,7 +137,19 @@ impl Handshake for TestPeer {
         let peer_addr = conn.addr();
         let node_side = !conn.side();
         let stream = self.borrow_stream(&mut conn);
-        let mut framed = Framed::new(stream, MessageCodec::<CurrentNetwork>::default());
+
+        // Perform the Noise handshake, to encrypt the connection.
+        let own_side = match node_side {
+            ConnectionSide::Initiator => snarkos_node_tcp::ConnectionSide::Initiator,
+            ConnectionSide::Responder => snarkos_node_tcp::ConnectionSide::Responder,
+        };
+        let (noise_state, handshake_hash) = noise_handshake(stream, own_side).await?;
+        let codec = NoiseCodec::new(
+            MessageCodec::<CurrentNetwork>::handshake(),
+            Some(noise_state),
+            MAXIMUM_HANDSHAKE_MESSAGE_SIZE,
+        );
+        let mut framed = Framed::new(stream, codec);
 
         // Retrieve the genesis block header.
         let genesis_header = *sample_genesis_block().header();
@@ -141,7 +167,7 @@ impl Handshake for TestPeer {
 
                 // Sign the nonce.
                 let response_nonce: u64 = rng.gen();
-                let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+                let data = challenge_data(peer_request.nonce, response_nonce, &handshake_hash);
                 let signature = self.account().sign_bytes(&data, rng).unwrap();
 
                 // Send the challenge response.
@@ -155,7 +181,7 @@ impl Handshake for TestPeer {
 
                 // Sign the nonce.
                 let response_nonce: u64 = rng.gen();
-                let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+                let data = challenge_data(peer_request.nonce, response_nonce, &handshake_hash);
                 let signature = self.account().sign_bytes(&data, rng).unwrap();
 
                 // Send our challenge bundle.
@@ -170,27 +196,32 @@ impl Handshake for TestPeer {
             }
         }
 
+        // Store the state of the encrypted connection for its codecs.
+        if let Some(noise_state) = framed.codec().state() {
+            self.noise_states.insert(peer_addr, noise_state.clone());
+        }
+
         Ok(conn)
     }
 }
 
 #[async_trait::async_trait]
 impl Writing for TestPeer {
-    type Codec = MessageCodec<CurrentNetwork>;
+    type Codec = NoiseCodec<MessageCodec<CurrentNetwork>>;
     type Message = Message<CurrentNetwork>;
 
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        NoiseCodec::new(MessageCodec::default(), self.noise_states.get(addr), MAXIMUM_MESSAGE_SIZE)
     }
 }
 
 #[async_trait::async_trait]
 impl Reading for TestPeer {
-    type Codec = MessageCodec<CurrentNetwork>;
+    type Codec = NoiseCodec<MessageCodec<CurrentNetwork>>;
     type Message = Message<CurrentNetwork>;
 
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        NoiseCodec::new(MessageCodec::default(), self.noise_states.get(peer_addr), MAXIMUM_MESSAGE_SIZE)
     }
 
     async fn process_message(&self, _peer_ip: SocketAddr, _message: Self::Message) -> io::Result<()> {
@@ -200,5 +231,8 @@ impl Reading for TestPeer {
 
 #[async_trait::async_trait]
 impl Disconnect for TestPeer {
-    async fn handle_disconnect(&self, _peer_addr: SocketAddr) {}
+    async fn handle_disconnect(&self, peer_addr: SocketAddr) {
+        // Remove the state of the encrypted connection.
+        self.noise_states.remove(peer_addr);
+    }
 }
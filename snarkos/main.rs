@@ -14,7 +14,6 @@
 
 use snarkos_cli::{commands::CLI, helpers::Updater};
 
-use clap::Parser;
 use std::process::exit;
 
 #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
@@ -25,8 +24,14 @@ use tikv_jemallocator::Jemalloc;
 static GLOBAL: Jemalloc = Jemalloc;
 
 fn main() -> anyhow::Result<()> {
-    // Parse the given arguments.
-    let cli = CLI::parse();
+    // Parse the given arguments, and the configuration file.
+    let cli = match CLI::parse_with_config() {
+        Ok(cli) => cli,
+        Err(error) => {
+            println!("⚠️  {error}\n");
+            exit(1);
+        }
+    };
     // Run the updater.
     println!("{}", Updater::print_cli());
     // Run the CLI.